@@ -0,0 +1,6 @@
+// `cargo bench --features bench`: mide el rasterizador de la biblioteca con
+// criterion (ver src/bench.rs), sin compilar la ventana ni el resto del juego.
+
+fn main() {
+    std::process::exit(proyecto_nave::bench::run());
+}
@@ -0,0 +1,92 @@
+// Ambientes sonoros de los planetas: cada tipo de planeta tiene un sonido
+// continuo (retumbo volcánico, olas, viento helado) que aparece con un
+// fundido cuando la nave se acerca y se desvanece al alejarse.
+
+use nalgebra_glm::Vec3;
+use serde::{Deserialize, Serialize};
+
+use crate::audio::{AudioOutput, Tone, Waveform};
+use crate::scene::Scene;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AmbientConfig {
+    // Distancia a la superficie desde la que empieza a oírse el ambiente
+    pub radius: f32,
+    // Volumen máximo, al rozar la superficie
+    pub volume: f32,
+    // Duración del fundido de entrada/salida en unidades de tiempo de simulación
+    pub fade: f32,
+}
+
+impl Default for AmbientConfig {
+    fn default() -> Self {
+        AmbientConfig {
+            radius: 25.0,
+            volume: 0.3,
+            fade: 0.5,
+        }
+    }
+}
+
+// Identificadores de los sonidos continuos en el mezclador; los primeros
+// quedan para otros usos (el motor de la nave fantasma usa el 1)
+const FIRST_LOOP_ID: u32 = 100;
+
+// Sonido de cada tipo de planeta según su shader: ruido filtrado con la
+// frecuencia de corte y la oscilación de volumen que lo caracterizan
+fn ambient_tone(shader: &str) -> Option<Tone> {
+    match shader {
+        // Retumbo grave que late despacio
+        "volcanic" => Some(Tone::new(70.0, 0.0, 1.0, Waveform::Noise).with_tremolo(0.4)),
+        // Olas: ruido suave que sube y baja cada pocos segundos
+        "ocean" => Some(Tone::new(450.0, 0.0, 0.8, Waveform::Noise).with_tremolo(0.12)),
+        // Viento agudo en ráfagas
+        "ice" => Some(Tone::new(1400.0, 0.0, 0.5, Waveform::Noise).with_tremolo(0.25)),
+        _ => None,
+    }
+}
+
+const SOUND_SHADERS: [&str; 3] = ["volcanic", "ocean", "ice"];
+
+pub struct Soundscape {
+    config: AmbientConfig,
+    // Volumen actual (0-1) de cada sonido de `SOUND_SHADERS`
+    levels: [f32; 3],
+}
+
+impl Soundscape {
+    pub fn new(config: AmbientConfig) -> Self {
+        Soundscape { config, levels: [0.0; 3] }
+    }
+
+    // Acerca el volumen de cada ambiente al que corresponde por la distancia
+    // al planeta más cercano de ese tipo y actualiza los sonidos continuos
+    pub fn update(&mut self, dt: f32, scene: &Scene, ship_position: Vec3, audio: &AudioOutput) {
+        let step = if self.config.fade > 0.0 { dt / self.config.fade } else { 1.0 };
+
+        for (index, shader) in SOUND_SHADERS.iter().enumerate() {
+            let target = scene.bodies
+                .iter()
+                .zip(&scene.states)
+                .filter(|(body, _)| body.shader == *shader)
+                .map(|(body, state)| {
+                    let distance = ((state.position - ship_position).magnitude() - body.scale).max(0.0);
+                    1.0 - (distance / self.config.radius).min(1.0)
+                })
+                .fold(0.0, f32::max);
+
+            let level = &mut self.levels[index];
+            *level += (target - *level).clamp(-step, step);
+
+            let id = FIRST_LOOP_ID + index as u32;
+            match ambient_tone(shader) {
+                Some(tone) if *level > 0.001 => {
+                    let volume = tone.volume * self.config.volume * *level;
+                    audio.set_loop(id, Tone { volume, ..tone });
+                }
+                _ => audio.stop_loop(id),
+            }
+        }
+    }
+}
@@ -0,0 +1,310 @@
+// Modelos animados por fotogramas clave importados de glTF: cada nodo tiene
+// pistas de traslación, rotación y escala (TRS) que se interpolan en el
+// tiempo y se componen con la jerarquía de nodos para obtener la matriz de
+// modelo de cada malla (p. ej. el anillo giratorio de una estación). Un OBJ
+// se carga como un modelo quieto de un solo nodo; su `[[prop]]` lo puede
+// hacer girar entero con `spin`.
+
+use std::path::Path;
+
+use gltf::animation::util::ReadOutputs;
+use gltf::animation::Interpolation;
+use nalgebra_glm::{self as glm, Mat4, Quat, Vec2, Vec3};
+use serde::{Deserialize, Serialize};
+
+use crate::culling::bounding_radius;
+use crate::obj::{is_gltf_path, ModelError, Obj};
+use crate::scene::BodyState;
+use crate::vertex::Vertex;
+
+// Objeto animado de la escena (`[[prop]]`)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PropConfig {
+    pub name: String,
+    // Archivo .gltf/.glb con las mallas y la animación, o un .obj quieto
+    pub model: String,
+    // Posición en el mundo o, con `parent`, respecto al cuerpo al empezar
+    pub position: [f32; 3],
+    // Cuerpo alrededor del cual orbita, en el plano horizontal
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    // Unidades de tiempo por vuelta alrededor del padre (0 = quieto a su lado)
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub orbit_period: f32,
+    // Giro propio alrededor del eje vertical, en grados por unidad de tiempo
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub spin: f32,
+    #[serde(default = "default_one")]
+    pub scale: f32,
+    // Segundos de animación por unidad de tiempo de simulación
+    #[serde(default = "default_one")]
+    pub speed: f32,
+    // Permite acoplarse y entrar al hangar (Enter cerca del objeto)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dockable: bool,
+    // Zona de acoplamiento: centro respecto al objeto (en unidades del
+    // modelo, gira con él) y radio en unidades del mundo
+    #[serde(default)]
+    pub dock_offset: [f32; 3],
+    #[serde(default = "default_dock_radius")]
+    pub dock_radius: f32,
+    // Mensaje de radio al acercarse la nave (ver `transmissions`); sin él la
+    // estación usa un saludo genérico
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub greeting: Option<String>,
+    // Índice de `parent` en `Scene::bodies`, resuelto al cargar
+    #[serde(skip)]
+    pub parent_index: Option<usize>,
+}
+
+fn default_one() -> f32 {
+    1.0
+}
+
+fn default_dock_radius() -> f32 {
+    2.5
+}
+
+fn is_zero(value: &f32) -> bool {
+    *value == 0.0
+}
+
+// `offset` girado `angle` radianes alrededor de Y, como la matriz de modelo
+pub fn rotate_y(offset: Vec3, angle: f32) -> Vec3 {
+    let (sin, cos) = angle.sin_cos();
+    Vec3::new(cos * offset.x + sin * offset.z, offset.y, -sin * offset.x + cos * offset.z)
+}
+
+impl PropConfig {
+    // Posición y giro en el instante `time`; `center` es la posición del
+    // padre (o el origen si no tiene)
+    pub fn state(&self, time: f32, center: Vec3) -> BodyState {
+        let orbit = if self.orbit_period > 0.0 { time / self.orbit_period * std::f32::consts::TAU } else { 0.0 };
+        BodyState {
+            position: center + rotate_y(Vec3::from(self.position), orbit),
+            rotation: Vec3::new(0.0, (time * self.spin).to_radians(), 0.0),
+        }
+    }
+
+    // Centro de la zona de acoplamiento con el objeto en `state`
+    pub fn dock_port(&self, state: &BodyState) -> Vec3 {
+        state.position + rotate_y(Vec3::from(self.dock_offset) * self.scale, state.rotation.y)
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Transform {
+    translation: Vec3,
+    rotation: Quat,
+    scale: Vec3,
+}
+
+impl Transform {
+    fn matrix(&self) -> Mat4 {
+        glm::translation(&self.translation) * glm::quat_to_mat4(&self.rotation) * glm::scaling(&self.scale)
+    }
+}
+
+enum Keys {
+    Translation(Vec<Vec3>),
+    Rotation(Vec<Quat>),
+    Scale(Vec<Vec3>),
+}
+
+struct Track {
+    node: usize,
+    times: Vec<f32>,
+    keys: Keys,
+    // Interpolación escalonada (STEP) en lugar de lineal
+    step: bool,
+}
+
+impl Track {
+    // Fotograma anterior a `time` y fracción hacia el siguiente
+    fn locate(&self, time: f32) -> (usize, usize, f32) {
+        let last = self.times.len() - 1;
+        if time <= self.times[0] {
+            return (0, 0, 0.0);
+        }
+        if time >= self.times[last] {
+            return (last, last, 0.0);
+        }
+        let next = self.times.partition_point(|&t| t <= time);
+        let previous = next - 1;
+        let span = self.times[next] - self.times[previous];
+        let alpha = if self.step || span <= 0.0 { 0.0 } else { (time - self.times[previous]) / span };
+        (previous, next, alpha)
+    }
+
+    fn apply(&self, time: f32, transform: &mut Transform) {
+        let (a, b, alpha) = self.locate(time);
+        match &self.keys {
+            Keys::Translation(values) => transform.translation = values[a].lerp(&values[b], alpha),
+            Keys::Rotation(values) => transform.rotation = glm::quat_slerp(&values[a], &values[b], alpha),
+            Keys::Scale(values) => transform.scale = values[a].lerp(&values[b], alpha),
+        }
+    }
+}
+
+pub struct AnimatedMesh {
+    node: usize,
+    pub vertices: Vec<Vertex>,
+    pub indices: Vec<u32>,
+    // Radio envolvente respecto al origen del nodo, para el descarte por frustum
+    pub radius: f32,
+}
+
+pub struct AnimatedModel {
+    rest: Vec<Transform>,
+    children: Vec<Vec<usize>>,
+    roots: Vec<usize>,
+    tracks: Vec<Track>,
+    pub meshes: Vec<AnimatedMesh>,
+    duration: f32,
+}
+
+impl AnimatedModel {
+    // glTF con su animación, o un OBJ quieto, según la extensión
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ModelError> {
+        let path = path.as_ref();
+        if !is_gltf_path(path) {
+            return Obj::load(&path.to_string_lossy()).map(|model| Self::still(&model));
+        }
+        let (document, buffers, _) = gltf::import(path).map_err(ModelError::Gltf)?;
+
+        let rest = document
+            .nodes()
+            .map(|node| {
+                let (translation, rotation, scale) = node.transform().decomposed();
+                Transform {
+                    translation: Vec3::from(translation),
+                    rotation: glm::quat(rotation[0], rotation[1], rotation[2], rotation[3]),
+                    scale: Vec3::from(scale),
+                }
+            })
+            .collect();
+        let children = document
+            .nodes()
+            .map(|node| node.children().map(|child| child.index()).collect())
+            .collect();
+        let roots = match document.default_scene().or_else(|| document.scenes().next()) {
+            Some(scene) => scene.nodes().map(|node| node.index()).collect(),
+            None => Vec::new(),
+        };
+
+        let mut meshes = Vec::new();
+        for node in document.nodes() {
+            let Some(mesh) = node.mesh() else { continue };
+            for primitive in mesh.primitives().filter(|p| p.mode() == gltf::mesh::Mode::Triangles) {
+                let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+                let Some(positions) = reader.read_positions() else { continue };
+                let positions: Vec<[f32; 3]> = positions.collect();
+                let normals: Vec<[f32; 3]> = reader.read_normals().map(|n| n.collect()).unwrap_or_default();
+                let tex_coords: Vec<[f32; 2]> = reader
+                    .read_tex_coords(0)
+                    .map(|t| t.into_f32().collect())
+                    .unwrap_or_default();
+                let vertices: Vec<Vertex> = positions
+                    .iter()
+                    .enumerate()
+                    .map(|(i, position)| {
+                        Vertex::new(
+                            Vec3::from(*position),
+                            normals.get(i).map_or(Vec3::new(0.0, 1.0, 0.0), |n| Vec3::from(*n)),
+                            tex_coords.get(i).map_or(Vec2::new(0.0, 0.0), |t| Vec2::from(*t)),
+                        )
+                    })
+                    .collect();
+                let indices = match reader.read_indices() {
+                    Some(indices) => indices.into_u32().collect(),
+                    None => (0..vertices.len() as u32).collect(),
+                };
+                meshes.push(AnimatedMesh {
+                    node: node.index(),
+                    radius: bounding_radius(&vertices),
+                    vertices,
+                    indices,
+                });
+            }
+        }
+
+        // Se combinan los canales de todas las animaciones del archivo
+        let mut tracks = Vec::new();
+        for animation in document.animations() {
+            for channel in animation.channels() {
+                let reader = channel.reader(|buffer| Some(&buffers[buffer.index()]));
+                let (Some(inputs), Some(outputs)) = (reader.read_inputs(), reader.read_outputs()) else {
+                    continue;
+                };
+                let times: Vec<f32> = inputs.collect();
+                let interpolation = channel.sampler().interpolation();
+                // CUBICSPLINE guarda (tangente de entrada, valor, tangente de salida):
+                // se usa solo el valor y se interpola linealmente
+                let is_value = |index: usize| interpolation != Interpolation::CubicSpline || index % 3 == 1;
+                let keys = match outputs {
+                    ReadOutputs::Translations(values) => Keys::Translation(
+                        values.enumerate().filter(|(i, _)| is_value(*i)).map(|(_, v)| Vec3::from(v)).collect(),
+                    ),
+                    ReadOutputs::Rotations(values) => Keys::Rotation(
+                        values
+                            .into_f32()
+                            .enumerate()
+                            .filter(|(i, _)| is_value(*i))
+                            .map(|(_, r)| glm::quat(r[0], r[1], r[2], r[3]))
+                            .collect(),
+                    ),
+                    ReadOutputs::Scales(values) => Keys::Scale(
+                        values.enumerate().filter(|(i, _)| is_value(*i)).map(|(_, v)| Vec3::from(v)).collect(),
+                    ),
+                    ReadOutputs::MorphTargetWeights(_) => continue,
+                };
+                let key_count = match &keys {
+                    Keys::Translation(values) | Keys::Scale(values) => values.len(),
+                    Keys::Rotation(values) => values.len(),
+                };
+                if times.is_empty() || key_count != times.len() {
+                    continue;
+                }
+                tracks.push(Track {
+                    node: channel.target().node().index(),
+                    times,
+                    keys,
+                    step: interpolation == Interpolation::Step,
+                });
+            }
+        }
+        let duration = tracks.iter().filter_map(|track| track.times.last().copied()).fold(0.0, f32::max);
+
+        Ok(AnimatedModel { rest, children, roots, tracks, meshes, duration })
+    }
+
+    // Un solo nodo sin pistas, con una malla por material del modelo
+    fn still(model: &Obj) -> Self {
+        let rest = Transform { translation: Vec3::zeros(), rotation: glm::quat_identity(), scale: Vec3::repeat(1.0) };
+        let meshes = model
+            .get_submeshes()
+            .into_iter()
+            .map(|part| AnimatedMesh { node: 0, radius: bounding_radius(&part.vertices), vertices: part.vertices, indices: part.indices })
+            .collect();
+        AnimatedModel { rest: vec![rest], children: vec![Vec::new()], roots: vec![0], tracks: Vec::new(), meshes, duration: 0.0 }
+    }
+
+    // Matriz de modelo (relativa al origen del modelo) de cada malla en el
+    // instante `time`; la animación se repite en bucle
+    pub fn pose(&self, time: f32) -> Vec<Mat4> {
+        let time = if self.duration > 0.0 { time.rem_euclid(self.duration) } else { 0.0 };
+        let mut local = self.rest.clone();
+        for track in &self.tracks {
+            track.apply(time, &mut local[track.node]);
+        }
+
+        let mut world = vec![Mat4::identity(); local.len()];
+        let mut stack: Vec<(usize, Mat4)> = self.roots.iter().map(|&root| (root, Mat4::identity())).collect();
+        while let Some((node, parent)) = stack.pop() {
+            world[node] = parent * local[node].matrix();
+            stack.extend(self.children[node].iter().map(|&child| (child, world[node])));
+        }
+
+        self.meshes.iter().map(|mesh| world[mesh.node]).collect()
+    }
+}
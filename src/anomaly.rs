@@ -0,0 +1,186 @@
+// Anomalías: objetos especiales que el generador (`--generate`) reparte de
+// vez en cuando en los huecos entre órbitas, y que una escena también puede
+// declarar con `[[anomaly]]`. Un agujero de gusano es un remolino luminoso
+// que siempre mira a la cámara y, si tiene par, lleva hasta él (ver
+// `wormhole`); una nave abandonada es un casco quemado que
+// deriva despacio alrededor de su posición dando tumbos. No chocan con la
+// nave: se encuentran con el escáner (Y) y son el objetivo de la misión
+// "Investigar anomalías".
+
+use nalgebra_glm::{Mat4, Vec3, Vec4};
+use serde::{Deserialize, Serialize};
+
+use crate::framebuffer::Framebuffer;
+use crate::projected_radius;
+use crate::scene::{BodyState, Scene};
+use crate::scanner::{Contact, ContactKind};
+use crate::shaders::shade_wormhole;
+
+// Velocidad angular de la deriva de las naves abandonadas (radianes por
+// unidad de tiempo), lenta para que se note que se mueven sin irse lejos
+const DRIFT_SPEED: f32 = 0.08;
+// Distancia a la superficie de la anomalía a la que se da por investigada
+const INVESTIGATE_RANGE: f32 = 3.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnomalyKind {
+    Wormhole,
+    Derelict,
+}
+
+impl AnomalyKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            AnomalyKind::Wormhole => "agujero de gusano",
+            AnomalyKind::Derelict => "nave abandonada",
+        }
+    }
+}
+
+fn default_size() -> f32 {
+    1.0
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Anomaly {
+    pub name: String,
+    pub kind: AnomalyKind,
+    pub position: [f32; 3],
+    // Radio del remolino, o escala del casco de la nave abandonada
+    #[serde(default = "default_size")]
+    pub size: f32,
+    // Nave abandonada: radio de la deriva alrededor de `position` y vueltas
+    // de los tumbos por unidad de tiempo
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub drift: f32,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub tumble: f32,
+    // Agujero de gusano: nombre del otro agujero por el que se sale; el par
+    // funciona en los dos sentidos
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit: Option<String>,
+}
+
+fn is_zero(value: &f32) -> bool {
+    *value == 0.0
+}
+
+impl Anomaly {
+    // Posición y rotación en el instante `time`
+    pub fn state(&self, time: f32) -> BodyState {
+        let angle = time * DRIFT_SPEED;
+        let drift = Vec3::new(angle.cos(), (angle * 1.3).sin() * 0.3, angle.sin()) * self.drift;
+        let turns = time * self.tumble * std::f32::consts::TAU;
+        BodyState {
+            position: Vec3::from(self.position) + drift,
+            rotation: Vec3::new(turns, turns * 0.7, turns * 0.4),
+        }
+    }
+}
+
+// Índice del agujero de gusano por el que sale quien entra en `index`: el
+// que nombra su `exit` o, si no tiene, el que lo nombra a él
+pub fn wormhole_exit(anomalies: &[Anomaly], index: usize) -> Option<usize> {
+    let name = &anomalies[index].name;
+    match &anomalies[index].exit {
+        Some(exit) => anomalies.iter().position(|anomaly| &anomaly.name == exit),
+        None => anomalies.iter().position(|anomaly| anomaly.exit.as_ref() == Some(name)),
+    }
+}
+
+// Agujero de gusano: un disco de frente a la cámara con la profundidad de su
+// centro. La garganta es opaca y el remolino se funde con el fondo hacia el
+// borde, así no tapa las estrellas con un disco negro.
+pub fn render_wormhole(
+    framebuffer: &mut Framebuffer,
+    center: Vec3,
+    radius: f32,
+    time: f32,
+    view_matrix: &Mat4,
+    projection_matrix: &Mat4,
+    viewport_matrix: &Mat4,
+) {
+    let center_view = view_matrix * Vec4::new(center.x, center.y, center.z, 1.0);
+    let view_depth = -center_view.z;
+    if view_depth <= 0.1 {
+        return;
+    }
+    let clip = projection_matrix * center_view;
+    let depth = clip.z / clip.w;
+    let screen = viewport_matrix * Vec4::new(clip.x / clip.w, clip.y / clip.w, 0.0, 1.0);
+    let radius_px = projected_radius(radius, view_depth, projection_matrix, viewport_matrix);
+    if radius_px < 0.5 || depth <= -1.0 || depth >= 1.0 {
+        return;
+    }
+
+    let min_x = (screen.x - radius_px).floor().max(0.0) as usize;
+    let max_x = (screen.x + radius_px).ceil().min(framebuffer.width as f32 - 1.0).max(0.0) as usize;
+    let min_y = (screen.y - radius_px).floor().max(0.0) as usize;
+    let max_y = (screen.y + radius_px).ceil().min(framebuffer.height as f32 - 1.0).max(0.0) as usize;
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            let dx = (x as f32 + 0.5 - screen.x) / radius_px;
+            let dy = -(y as f32 + 0.5 - screen.y) / radius_px;
+            let dist_sq = dx * dx + dy * dy;
+            if dist_sq > 1.0 {
+                continue;
+            }
+            let color = shade_wormhole(Vec3::new(dx, dy, (1.0 - dist_sq).sqrt()), time, 1.0 / radius_px);
+            let alpha = ((1.0 - dist_sq.sqrt()) / 0.7).clamp(0.0, 1.0);
+            framebuffer.point_hdr(x, y, depth, color, alpha);
+        }
+    }
+}
+
+// Misión "Investigar anomalías": el escáner localiza las anomalías y hay
+// que acercarse a cada una. El objetivo es siempre la localizada más cercana
+// que falta visitar.
+pub struct Investigation {
+    located: Vec<bool>,
+    investigated: Vec<bool>,
+    target: Option<usize>,
+}
+
+impl Investigation {
+    pub fn new(scene: &Scene) -> Self {
+        let count = scene.anomalies.len();
+        Investigation { located: vec![false; count], investigated: vec![false; count], target: None }
+    }
+
+    // Marca las anomalías que el escáner encontró, elige el objetivo y
+    // devuelve la que se acaba de investigar, si alguna
+    pub fn update(&mut self, scene: &Scene, contacts: &[Contact], ship_position: Vec3) -> Option<usize> {
+        for contact in contacts.iter().filter(|contact| contact.kind == ContactKind::Anomaly) {
+            if let Some(index) = scene.anomalies.iter().position(|anomaly| anomaly.name == contact.name) {
+                self.located[index] = true;
+            }
+        }
+        let distance = |index: usize| (scene.anomaly_states[index].position - ship_position).magnitude() - scene.anomalies[index].size;
+        self.target = (0..scene.anomalies.len())
+            .filter(|&index| self.located[index] && !self.investigated[index])
+            .min_by(|&a, &b| distance(a).total_cmp(&distance(b)));
+        let reached = self.target.filter(|&index| distance(index) < INVESTIGATE_RANGE)?;
+        self.investigated[reached] = true;
+        Some(reached)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.investigated.iter().all(|done| *done)
+    }
+
+    pub fn hud_lines(&self, scene: &Scene, ship_position: Vec3) -> Vec<String> {
+        let done = self.investigated.iter().filter(|done| **done).count();
+        let mut lines = vec![format!("Anomalías investigadas: {} / {}", done, self.investigated.len())];
+        match self.target {
+            Some(index) => {
+                let anomaly = &scene.anomalies[index];
+                let distance = (scene.anomaly_states[index].position - ship_position).magnitude();
+                lines.push(format!("Investigar: {} ({}) a {:.1}", anomaly.name, anomaly.kind.label(), distance));
+            }
+            None if !self.is_finished() => lines.push("Usa el escáner (Y) para localizar anomalías".to_string()),
+            None => lines.push("Todas las anomalías investigadas".to_string()),
+        }
+        lines
+    }
+}
@@ -0,0 +1,139 @@
+// Antialiasing (`--aa`): con supersampling la escena se dibuja a 2 o 4 veces
+// la resolución de la ventana en cada eje y cada píxel final es el promedio
+// de su bloque de muestras; con FXAA se dibuja a la resolución normal y un
+// filtro en pantalla suaviza los bordes donde el brillo cambia de golpe. En
+// ambos casos el HUD se dibuja después, ya a la resolución de la ventana.
+
+use nalgebra_glm::Vec3;
+
+use crate::framebuffer::{pack_color, unpack_color, Framebuffer};
+
+// Diferencia de brillo mínima para tratar un píxel como borde: absoluta y
+// relativa al vecino más brillante
+const EDGE_MIN: f32 = 0.0312;
+const EDGE_THRESHOLD: f32 = 0.125;
+// Cuánto del vecino se mezcla en un borde: lo mínimo en un borde recto y lo
+// máximo en un píxel aislado
+const MIN_BLEND: f32 = 0.25;
+const MAX_BLEND: f32 = 0.6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Antialias {
+    #[default]
+    Off,
+    Fxaa,
+    Ssaa2,
+    Ssaa4,
+}
+
+impl Antialias {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "off" => Some(Antialias::Off),
+            "fxaa" => Some(Antialias::Fxaa),
+            "ssaa2" => Some(Antialias::Ssaa2),
+            "ssaa4" => Some(Antialias::Ssaa4),
+            _ => None,
+        }
+    }
+
+    // Muestras por píxel en cada eje al dibujar la escena
+    pub fn scale(self) -> usize {
+        match self {
+            Antialias::Ssaa2 => 2,
+            Antialias::Ssaa4 => 4,
+            _ => 1,
+        }
+    }
+}
+
+fn luma(color: u32) -> f32 {
+    let color = unpack_color(color);
+    color.x * 0.299 + color.y * 0.587 + color.z * 0.114
+}
+
+pub struct Resolver {
+    pub mode: Antialias,
+    // Copia de la imagen que lee el FXAA mientras escribe en la de salida
+    source: Vec<u32>,
+}
+
+impl Resolver {
+    pub fn new(mode: Antialias) -> Self {
+        Resolver { mode, source: Vec::new() }
+    }
+
+    // Lleva la escena dibujada en `scene` (a `mode.scale()` veces el tamaño de
+    // `display`) a la imagen que se muestra en la ventana
+    pub fn resolve(&mut self, scene: &Framebuffer, display: &mut Framebuffer) {
+        let scale = self.mode.scale();
+        if scale == 1 {
+            display.buffer.copy_from_slice(&scene.buffer);
+        } else {
+            downsample(scene, display, scale);
+        }
+        if self.mode == Antialias::Fxaa {
+            self.source.clear();
+            self.source.extend_from_slice(&display.buffer);
+            fxaa(&self.source, display);
+        }
+    }
+}
+
+// Promedio de cada bloque de `scale` x `scale` muestras
+fn downsample(scene: &Framebuffer, display: &mut Framebuffer, scale: usize) {
+    let samples = (scale * scale) as f32;
+    for y in 0..display.height {
+        for x in 0..display.width {
+            let mut sum = Vec3::zeros();
+            for sy in 0..scale {
+                for sx in 0..scale {
+                    sum += unpack_color(scene.buffer[scene.index(x * scale + sx, y * scale + sy)]);
+                }
+            }
+            let index = display.index(x, y);
+            display.buffer[index] = pack_color(sum / samples);
+        }
+    }
+}
+
+// FXAA simplificado: en los píxeles con mucho contraste local se busca hacia
+// dónde cambia más el brillo (arriba/abajo o izquierda/derecha) y se mezcla
+// el píxel con ese vecino, más cuanto más se aparta del promedio de los cuatro
+fn fxaa(source: &[u32], display: &mut Framebuffer) {
+    let (width, height) = (display.width, display.height);
+    let index_of = display.indexer();
+    for y in 1..height.saturating_sub(1) {
+        for x in 1..width.saturating_sub(1) {
+            let index = index_of(x, y);
+            let (north_index, south_index) = (index_of(x, y - 1), index_of(x, y + 1));
+            let (west_index, east_index) = (index_of(x - 1, y), index_of(x + 1, y));
+            let center = luma(source[index]);
+            let north = luma(source[north_index]);
+            let south = luma(source[south_index]);
+            let west = luma(source[west_index]);
+            let east = luma(source[east_index]);
+            let max = center.max(north).max(south).max(west).max(east);
+            let min = center.min(north).min(south).min(west).min(east);
+            let range = max - min;
+            if range < EDGE_MIN.max(max * EDGE_THRESHOLD) {
+                continue;
+            }
+
+            // Borde horizontal si el brillo cambia más en vertical
+            let horizontal = (north + south - 2.0 * center).abs() >= (east + west - 2.0 * center).abs();
+            let neighbour = if horizontal {
+                if (north - center).abs() >= (south - center).abs() { north_index } else { south_index }
+            } else if (west - center).abs() >= (east - center).abs() {
+                west_index
+            } else {
+                east_index
+            };
+            let average = (north + south + west + east) * 0.25;
+            let subpixel = ((average - center).abs() / range).clamp(0.0, 1.0);
+            let blend = MIN_BLEND + (MAX_BLEND - MIN_BLEND) * subpixel * subpixel * (3.0 - 2.0 * subpixel);
+            let color = unpack_color(source[index]).lerp(&unpack_color(source[neighbour]), blend);
+            display.buffer[index] = pack_color(color);
+        }
+    }
+}
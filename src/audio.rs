@@ -0,0 +1,303 @@
+// Síntesis de tonos simples y ruido filtrado para las señales de navegación
+// y los sonidos ambientales. El mezclador
+// siempre se compila; la salida a la tarjeta de sonido usa cpal y solo
+// existe con la feature `audio` (sin ella los sonidos se descartan).
+
+use std::f32::consts::PI;
+use std::sync::{Arc, Mutex};
+
+use nalgebra_glm::Vec3;
+
+// Velocidad del "sonido" para el efecto Doppler, en unidades del mundo por
+// unidad de tiempo; mucho menor que la real para que se note al pasar
+const SPEED_OF_SOUND: f32 = 60.0;
+// Distancia hasta la que un emisor suena a volumen completo
+const REFERENCE_DISTANCE: f32 = 5.0;
+const ROLLOFF: f32 = 0.15;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Waveform {
+    Sine,
+    Square,
+    // Ruido blanco con un filtro pasa bajos; `frequency` es la frecuencia de corte
+    Noise,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Tone {
+    pub frequency: f32,
+    // Duración en segundos reales
+    pub duration: f32,
+    pub volume: f32,
+    pub waveform: Waveform,
+    // -1 = izquierda, 0 = centro, 1 = derecha
+    pub pan: f32,
+    // Frecuencia (Hz) de una oscilación lenta del volumen, como olas o ráfagas; 0 = constante
+    pub tremolo: f32,
+}
+
+impl Tone {
+    pub fn new(frequency: f32, duration: f32, volume: f32, waveform: Waveform) -> Self {
+        Tone { frequency, duration, volume, waveform, pan: 0.0, tremolo: 0.0 }
+    }
+
+    pub fn with_tremolo(self, tremolo: f32) -> Self {
+        Tone { tremolo, ..self }
+    }
+
+    // Aplica la posición relativa al oyente: volumen, paneo y Doppler
+    pub fn spatialized(self, spatial: &Spatial) -> Self {
+        Tone {
+            frequency: self.frequency * spatial.pitch,
+            volume: self.volume * spatial.gain,
+            pan: spatial.pan,
+            ..self
+        }
+    }
+}
+
+// Quien escucha: la cámara, con su vector derecho para el paneo
+#[derive(Debug, Clone, Copy)]
+pub struct Listener {
+    pub position: Vec3,
+    pub right: Vec3,
+    pub velocity: Vec3,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Emitter {
+    pub position: Vec3,
+    pub velocity: Vec3,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Spatial {
+    pub gain: f32,
+    pub pan: f32,
+    pub pitch: f32,
+}
+
+pub fn spatialize(listener: &Listener, emitter: &Emitter) -> Spatial {
+    let offset = emitter.position - listener.position;
+    let distance = offset.magnitude();
+    if distance < 1e-4 {
+        return Spatial { gain: 1.0, pan: 0.0, pitch: 1.0 };
+    }
+    let direction = offset / distance;
+
+    // Atenuación por distancia inversa a partir de la distancia de referencia
+    let gain = REFERENCE_DISTANCE / (REFERENCE_DISTANCE + ROLLOFF * (distance - REFERENCE_DISTANCE).max(0.0));
+    let pan = direction.dot(&listener.right).clamp(-1.0, 1.0);
+
+    // Doppler: acercarse sube el tono, alejarse lo baja
+    let listener_speed = listener.velocity.dot(&direction);
+    let emitter_speed = emitter.velocity.dot(&direction);
+    let pitch = ((SPEED_OF_SOUND + listener_speed) / (SPEED_OF_SOUND + emitter_speed).max(1.0)).clamp(0.5, 2.0);
+
+    Spatial { gain, pan, pitch }
+}
+
+// Estado de la forma de onda de una voz
+struct Oscillator {
+    phase: f32,
+    tremolo_phase: f32,
+    rng_state: u32,
+    filtered: f32,
+}
+
+impl Oscillator {
+    fn new() -> Self {
+        Oscillator { phase: 0.0, tremolo_phase: 0.0, rng_state: 0x9E37_79B9, filtered: 0.0 }
+    }
+
+    // Siguiente muestra del tono, sin aplicar volumen
+    fn next(&mut self, tone: &Tone, dt: f32) -> f32 {
+        let mut sample = match tone.waveform {
+            Waveform::Sine => (self.phase * 2.0 * PI).sin(),
+            Waveform::Square => if self.phase < 0.5 { 1.0 } else { -1.0 },
+            Waveform::Noise => {
+                self.rng_state ^= self.rng_state << 13;
+                self.rng_state ^= self.rng_state >> 17;
+                self.rng_state ^= self.rng_state << 5;
+                let white = (self.rng_state as f32 / u32::MAX as f32) * 2.0 - 1.0;
+                // Pasa bajos de un polo; se compensa la energía que quita el filtro
+                let alpha = 1.0 - (-2.0 * PI * tone.frequency * dt).exp();
+                self.filtered += alpha * (white - self.filtered);
+                self.filtered * (2.0 / alpha.max(1e-4)).sqrt() * 0.5
+            }
+        };
+        self.phase = (self.phase + tone.frequency * dt).fract();
+
+        if tone.tremolo > 0.0 {
+            sample *= 0.6 + 0.4 * (self.tremolo_phase * 2.0 * PI).sin();
+            self.tremolo_phase = (self.tremolo_phase + tone.tremolo * dt).fract();
+        }
+        sample
+    }
+}
+
+struct Voice {
+    tone: Tone,
+    oscillator: Oscillator,
+    elapsed: f32,
+}
+
+// Sonido continuo (p. ej. el zumbido de un motor) que se actualiza cada cuadro
+struct LoopVoice {
+    id: u32,
+    tone: Tone,
+    oscillator: Oscillator,
+}
+
+// Ganancias izquierda/derecha de potencia constante
+fn pan_gains(pan: f32) -> (f32, f32) {
+    let angle = (pan.clamp(-1.0, 1.0) + 1.0) * PI / 4.0;
+    (angle.cos(), angle.sin())
+}
+
+// Rampa de entrada y salida para evitar chasquidos al cortar la onda
+const FADE_SECONDS: f32 = 0.005;
+
+#[derive(Default)]
+pub struct Mixer {
+    voices: Vec<Voice>,
+    loops: Vec<LoopVoice>,
+}
+
+impl Mixer {
+    pub fn play(&mut self, tone: Tone) {
+        self.voices.push(Voice { tone, oscillator: Oscillator::new(), elapsed: 0.0 });
+    }
+
+    // Inicia o actualiza el sonido continuo `id`; se ignora la duración del tono
+    pub fn set_loop(&mut self, id: u32, tone: Tone) {
+        match self.loops.iter_mut().find(|voice| voice.id == id) {
+            Some(voice) => voice.tone = tone,
+            None => self.loops.push(LoopVoice { id, tone, oscillator: Oscillator::new() }),
+        }
+    }
+
+    pub fn stop_loop(&mut self, id: u32) {
+        self.loops.retain(|voice| voice.id != id);
+    }
+
+    // Llena `out` (muestras intercaladas de `channels` canales) sumando las
+    // voces activas y descarta las que ya terminaron
+    #[cfg_attr(not(feature = "audio"), allow(dead_code))]
+    pub fn fill(&mut self, out: &mut [f32], channels: usize, sample_rate: f32) {
+        let dt = 1.0 / sample_rate;
+        for frame in out.chunks_mut(channels.max(1)) {
+            let (mut left, mut right) = (0.0, 0.0);
+            for voice in &mut self.voices {
+                if voice.elapsed >= voice.tone.duration {
+                    continue;
+                }
+                let remaining = voice.tone.duration - voice.elapsed;
+                let envelope = (voice.elapsed / FADE_SECONDS).min(remaining / FADE_SECONDS).min(1.0);
+                let sample = voice.oscillator.next(&voice.tone, dt) * envelope * voice.tone.volume;
+                let (gain_left, gain_right) = pan_gains(voice.tone.pan);
+                left += sample * gain_left;
+                right += sample * gain_right;
+                voice.elapsed += dt;
+            }
+            for voice in &mut self.loops {
+                let sample = voice.oscillator.next(&voice.tone, dt) * voice.tone.volume;
+                let (gain_left, gain_right) = pan_gains(voice.tone.pan);
+                left += sample * gain_left;
+                right += sample * gain_right;
+            }
+
+            // Estéreo en los dos primeros canales; en mono se mezclan ambos
+            match frame.len() {
+                1 => frame[0] = ((left + right) * 0.5).clamp(-1.0, 1.0),
+                _ => {
+                    frame.fill(0.0);
+                    frame[0] = left.clamp(-1.0, 1.0);
+                    frame[1] = right.clamp(-1.0, 1.0);
+                }
+            }
+        }
+        self.voices.retain(|voice| voice.elapsed < voice.tone.duration);
+    }
+}
+
+pub struct AudioOutput {
+    mixer: Arc<Mutex<Mixer>>,
+    enabled: bool,
+    #[cfg(feature = "audio")]
+    _stream: Option<cpal::Stream>,
+}
+
+impl AudioOutput {
+    #[cfg(feature = "audio")]
+    pub fn new() -> Self {
+        let mixer = Arc::new(Mutex::new(Mixer::default()));
+        match open_stream(Arc::clone(&mixer)) {
+            Ok(stream) => AudioOutput { mixer, enabled: true, _stream: Some(stream) },
+            Err(e) => {
+                eprintln!("No se pudo abrir la salida de audio: {} (sin sonido)", e);
+                AudioOutput { mixer, enabled: false, _stream: None }
+            }
+        }
+    }
+
+    #[cfg(not(feature = "audio"))]
+    pub fn new() -> Self {
+        AudioOutput {
+            mixer: Arc::new(Mutex::new(Mixer::default())),
+            enabled: false,
+        }
+    }
+
+    fn with_mixer(&self, action: impl FnOnce(&mut Mixer)) {
+        if !self.enabled {
+            return;
+        }
+        if let Ok(mut mixer) = self.mixer.lock() {
+            action(&mut mixer);
+        }
+    }
+
+    pub fn play(&self, tone: Tone) {
+        self.with_mixer(|mixer| mixer.play(tone));
+    }
+
+    pub fn set_loop(&self, id: u32, tone: Tone) {
+        self.with_mixer(|mixer| mixer.set_loop(id, tone));
+    }
+
+    pub fn stop_loop(&self, id: u32) {
+        self.with_mixer(|mixer| mixer.stop_loop(id));
+    }
+}
+
+#[cfg(feature = "audio")]
+fn open_stream(mixer: Arc<Mutex<Mixer>>) -> Result<cpal::Stream, String> {
+    use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+
+    let host = cpal::default_host();
+    let device = host.default_output_device().ok_or("no hay dispositivo de salida")?;
+    let config = device.default_output_config().map_err(|e| e.to_string())?;
+    if config.sample_format() != cpal::SampleFormat::F32 {
+        return Err(format!("formato de muestra no soportado: {:?}", config.sample_format()));
+    }
+
+    let config: cpal::StreamConfig = config.into();
+    let channels = config.channels as usize;
+    let sample_rate = config.sample_rate.0 as f32;
+    let stream = device
+        .build_output_stream(
+            &config,
+            move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
+                match mixer.lock() {
+                    Ok(mut mixer) => mixer.fill(data, channels, sample_rate),
+                    Err(_) => data.fill(0.0),
+                }
+            },
+            |e| eprintln!("Error en la salida de audio: {}", e),
+            None,
+        )
+        .map_err(|e| e.to_string())?;
+    stream.play().map_err(|e| e.to_string())?;
+    Ok(stream)
+}
@@ -0,0 +1,247 @@
+// Banco de pruebas del rasterizador (`--bench`): sin abrir la ventana dibuja
+// unas escenas fijas (el planeta con varios shaders y la nave) en un
+// framebuffer fuera de pantalla y mide por separado las etapas del dibujo:
+// el sombreado de vértices, el rasterizado (armado de los triángulos y
+// generación de fragmentos), el sombreado de fragmentos y el cuadro completo
+// con `render_model`. Así un cambio en `triangle.rs` o en `shaders.rs` se ve
+// en la etapa que le toca.
+//
+// Sin la feature `bench` se imprime el promedio de cada etapa; con ella
+// (`cargo bench --features bench`) las mide criterion, que guarda cada
+// corrida en target/criterion y avisa si una etapa cambió respecto de la
+// anterior.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use nalgebra_glm::{look_at, perspective, Mat4, Vec3};
+
+use crate::clipping::{is_inside, FAR_PLANE, NEAR_PLANE};
+use crate::culling::{bounding_radius, Frustum};
+use crate::debug_view::DebugView;
+use crate::fragment::Fragment;
+use crate::framebuffer::Framebuffer;
+use crate::lighting::{Light, Material};
+use crate::obj::Obj;
+use crate::render::{create_viewport_matrix, render_model, shade_fragments, RasterScratch, Uniforms};
+use crate::shaders::{
+    Climate, GasGiantShader, OceanShader, PlanetShader, RockyShader, SpaceshipShader, Surface, VolcanicShader, BARE_SURFACE,
+};
+use crate::triangle::{setup_triangle, triangle};
+use crate::vertex::Vertex;
+use crate::vertex_stage::transform_vertices;
+
+const WIDTH: usize = 800;
+const HEIGHT: usize = 600;
+const CAMERA_DISTANCE: f32 = 3.0;
+// Repeticiones de cada etapa sin criterion
+#[cfg(not(feature = "bench"))]
+const ITERATIONS: u64 = 20;
+// Shaders de planeta que se miden, con su nombre en la escena: uno por
+// familia de ruido
+const PLANET_SHADERS: [(&str, &dyn PlanetShader); 4] =
+    [("rocky", &RockyShader), ("gas_giant", &GasGiantShader), ("ocean", &OceanShader), ("volcanic", &VolcanicShader)];
+
+#[derive(Debug, Clone, Copy)]
+enum Stage {
+    Vertices,
+    Raster,
+    Fragments,
+    Frame,
+}
+
+impl Stage {
+    const ALL: [Stage; 4] = [Stage::Vertices, Stage::Raster, Stage::Fragments, Stage::Frame];
+
+    fn name(self) -> &'static str {
+        match self {
+            Stage::Vertices => "vertices",
+            Stage::Raster => "rasterizado",
+            Stage::Fragments => "fragmentos",
+            Stage::Frame => "cuadro",
+        }
+    }
+}
+
+// Una escena fija: un modelo con su shader, centrado y escalado para que
+// ocupe buena parte de la pantalla
+struct Fixture {
+    name: String,
+    vertices: Vec<Vertex>,
+    indices: Vec<u32>,
+    radius: f32,
+    shader: &'static dyn PlanetShader,
+    surface: Surface,
+    material: Material,
+    model_matrix: Mat4,
+    // Resultados de las etapas anteriores, para medir cada una por separado
+    transformed: Vec<Vertex>,
+    fragments: Vec<Fragment>,
+}
+
+impl Fixture {
+    fn new(name: String, model: &Obj, shader: &'static dyn PlanetShader, surface: Surface, material: Material) -> Self {
+        let (vertices, indices) = model.get_vertex_and_index_arrays();
+        let radius = bounding_radius(&vertices);
+        let scale = 1.0 / radius.max(1e-6);
+        let model_matrix = Mat4::new_rotation(Vec3::new(0.3, 0.6, 0.0)) * Mat4::new_scaling(scale);
+        let mut fixture = Fixture {
+            name,
+            vertices,
+            indices,
+            radius,
+            shader,
+            surface,
+            material,
+            model_matrix,
+            transformed: Vec::new(),
+            fragments: Vec::new(),
+        };
+        fixture.transformed = fixture.shade_vertices();
+        let mut fragments = Vec::new();
+        fixture.rasterize(|batch| fragments.extend_from_slice(batch));
+        fixture.fragments = fragments;
+        fixture
+    }
+
+    fn uniforms(&self) -> Uniforms<'_> {
+        let eye = Vec3::new(0.0, 0.0, CAMERA_DISTANCE);
+        let view_matrix = look_at(&eye, &Vec3::zeros(), &Vec3::new(0.0, 1.0, 0.0));
+        let projection_matrix = perspective(WIDTH as f32 / HEIGHT as f32, 45f32.to_radians(), 0.1, 100.0);
+        Uniforms {
+            model_matrix: self.model_matrix,
+            view_matrix,
+            projection_matrix,
+            viewport_matrix: create_viewport_matrix(WIDTH as f32, HEIGHT as f32),
+            frustum: Frustum::from_matrix(&(projection_matrix * view_matrix)),
+            shader_time: 0.0,
+            shader: self.shader,
+            seed: 0,
+            alpha: 1.0,
+            texture: None,
+            surface: &self.surface,
+            light: Light::new(Vec3::new(10.0, 10.0, 30.0)),
+            material: self.material,
+            camera_position: eye,
+            debug_view: DebugView::Shaded,
+            screen_lighting: false,
+            detail: 0.0,
+        }
+    }
+
+    fn shade_vertices(&self) -> Vec<Vertex> {
+        let mut transformed = Vec::new();
+        transform_vertices(&self.vertices, &self.uniforms(), &mut transformed);
+        transformed
+    }
+
+    // Arma los triángulos ya sombreados y pasa los fragmentos de cada uno a
+    // `emit`; los modelos quedan enteros dentro del frustum, así que no hace
+    // falta recortar
+    fn rasterize(&self, mut emit: impl FnMut(&[Fragment])) {
+        let uniforms = self.uniforms();
+        let clip_planes = [NEAR_PLANE, FAR_PLANE];
+        let screen = (0, 0, WIDTH - 1, HEIGHT - 1);
+        let mut fragments = Vec::new();
+        for face in self.indices.chunks_exact(3) {
+            let [v1, v2, v3] = [0, 1, 2].map(|corner| &self.transformed[face[corner] as usize]);
+            if !(is_inside(v1, &clip_planes) && is_inside(v2, &clip_planes) && is_inside(v3, &clip_planes)) {
+                continue;
+            }
+            if let Some(setup) = setup_triangle(v1, v2, v3, &uniforms) {
+                triangle([v1, v2, v3], &setup, screen, &mut fragments);
+                emit(&fragments);
+            }
+        }
+    }
+
+    // Tiempo de `iterations` pasadas de la etapa. El framebuffer se limpia
+    // fuera de la medición antes de cada una para que el test de
+    // profundidad no descarte los fragmentos de la pasada anterior.
+    fn measure(&self, framebuffer: &mut Framebuffer, scratch: &mut RasterScratch, stage: Stage, iterations: u64) -> Duration {
+        let uniforms = self.uniforms();
+        let mut total = Duration::ZERO;
+        for _ in 0..iterations {
+            framebuffer.clear();
+            if let Stage::Fragments = stage {
+                scratch.fragments.clone_from(&self.fragments);
+            }
+            let start = Instant::now();
+            match stage {
+                Stage::Vertices => {
+                    black_box(self.shade_vertices());
+                }
+                Stage::Raster => {
+                    let mut count = 0;
+                    self.rasterize(|fragments| count += fragments.len());
+                    black_box(count);
+                }
+                Stage::Fragments => shade_fragments(framebuffer, &uniforms, scratch),
+                Stage::Frame => render_model(framebuffer, &uniforms, &self.vertices, &self.indices, self.radius),
+            }
+            total += start.elapsed();
+        }
+        total
+    }
+}
+
+fn fixtures() -> Result<Vec<Fixture>, String> {
+    let load = |path: &str| Obj::load(path).map_err(|e| format!("No se pudo cargar {}: {}", path, e));
+    let planet = load("assets/planeta.obj")?;
+    let ship = load("assets/CazaTie.obj")?;
+    let mut fixtures = Vec::new();
+    for (name, shader) in PLANET_SHADERS {
+        let surface = Surface { craters: Vec::new(), climate: Climate::TEMPERATE, ring_palette: Vec::new(), params: Vec::new() };
+        fixtures.push(Fixture::new(format!("planeta_{}", name), &planet, shader, surface, Material::new(false, 0.5, 32.0)));
+    }
+    fixtures.push(Fixture::new("nave".to_string(), &ship, &SpaceshipShader, BARE_SURFACE.clone(), Material::new(false, 0.8, 64.0)));
+    Ok(fixtures)
+}
+
+// Corre el banco y devuelve el código de salida
+pub fn run() -> i32 {
+    let fixtures = match fixtures() {
+        Ok(fixtures) => fixtures,
+        Err(e) => {
+            eprintln!("{}", e);
+            return 1;
+        }
+    };
+    let mut framebuffer = Framebuffer::new(WIDTH, HEIGHT);
+    let mut scratch = RasterScratch::default();
+    measure_all(&fixtures, &mut framebuffer, &mut scratch);
+    0
+}
+
+#[cfg(not(feature = "bench"))]
+fn measure_all(fixtures: &[Fixture], framebuffer: &mut Framebuffer, scratch: &mut RasterScratch) {
+    println!("Banco del rasterizador a {}x{}, promedio de {} pasadas por etapa en ms", WIDTH, HEIGHT, ITERATIONS);
+    let mut header = format!("{:<20}", "escena");
+    for stage in Stage::ALL {
+        header.push_str(&format!(" {:>12}", stage.name()));
+    }
+    println!("{} {:>11} {:>11}", header, "triángulos", "fragmentos");
+    for fixture in fixtures {
+        let millis = Stage::ALL.map(|stage| fixture.measure(framebuffer, scratch, stage, ITERATIONS).as_secs_f64() * 1000.0 / ITERATIONS as f64);
+        let mut line = format!("{:<20}", fixture.name);
+        for value in millis {
+            line.push_str(&format!(" {:>12.3}", value));
+        }
+        println!("{} {:>11} {:>11}", line, fixture.indices.len() / 3, fixture.fragments.len());
+    }
+}
+
+#[cfg(feature = "bench")]
+fn measure_all(fixtures: &[Fixture], framebuffer: &mut Framebuffer, scratch: &mut RasterScratch) {
+    use criterion::Criterion;
+
+    let mut criterion = Criterion::default().sample_size(20).configure_from_args();
+    for fixture in fixtures {
+        let mut group = criterion.benchmark_group(fixture.name.clone());
+        for stage in Stage::ALL {
+            group.bench_function(stage.name(), |bencher| bencher.iter_custom(|iterations| fixture.measure(framebuffer, scratch, stage, iterations)));
+        }
+        group.finish();
+    }
+    criterion.final_summary();
+}
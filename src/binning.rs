@@ -0,0 +1,37 @@
+// Reparto de los triángulos de un modelo en los bloques del framebuffer (ver
+// `framebuffer`): cada triángulo se anota en los bloques que toca su
+// rectángulo en pantalla y después se rasteriza bloque por bloque, así cada
+// bloque se escribe entero mientras está en caché. Dentro de un bloque los
+// triángulos conservan el orden del modelo, de modo que las mezclas y los
+// empates de profundidad dan lo mismo que dibujándolos de a uno.
+
+use crate::framebuffer::{Framebuffer, TILE_SIZE};
+
+#[derive(Default)]
+pub struct TileBins {
+    // (bloque, triángulo) en el orden en que se anotaron
+    entries: Vec<(u32, u32)>,
+}
+
+impl TileBins {
+    // Anota el triángulo en los bloques que toca `bounds` (min_x, min_y,
+    // max_x, max_y en píxeles, con los máximos incluidos)
+    pub fn insert(&mut self, framebuffer: &Framebuffer, triangle: usize, bounds: (usize, usize, usize, usize)) {
+        let (min_x, min_y, max_x, max_y) = bounds;
+        for tile_y in min_y / TILE_SIZE..=max_y / TILE_SIZE {
+            for tile_x in min_x / TILE_SIZE..=max_x / TILE_SIZE {
+                let tile = framebuffer.tile_at(tile_x * TILE_SIZE, tile_y * TILE_SIZE);
+                self.entries.push((tile as u32, triangle as u32));
+            }
+        }
+    }
+
+    // Los bloques con algún triángulo, en orden, cada uno con sus triángulos
+    pub fn by_tile(&mut self) -> impl Iterator<Item = (usize, impl Iterator<Item = usize> + '_)> + '_ {
+        // Orden estable: los triángulos de cada bloque quedan como se anotaron
+        self.entries.sort_by_key(|&(tile, _)| tile);
+        self.entries
+            .chunk_by(|a, b| a.0 == b.0)
+            .map(|chunk| (chunk[0].0 as usize, chunk.iter().map(|&(_, triangle)| triangle as usize)))
+    }
+}
@@ -0,0 +1,158 @@
+use nalgebra_glm::{Vec3, Mat4, look_at};
+
+// Elevación de la cámara que sigue a la nave, en grados: se detiene un poco
+// antes de los polos, donde el giro alrededor de la nave se vuelve brusco
+pub const MIN_PITCH: f32 = -85.0;
+pub const MAX_PITCH: f32 = 85.0;
+
+// Cámara en tercera persona que orbita alrededor de la nave
+pub struct Camera {
+    yaw: f32,
+    pitch: f32,
+    distance: f32, // Distancia desde la nave
+    min_distance: f32,
+    max_distance: f32,
+}
+
+impl Camera {
+    pub fn new() -> Self {
+        Self {
+            yaw: 62.0, // Cámara directamente detrás de la nave
+            pitch: 10.0, // Ángulo de elevación suave
+            distance: 12.0, // Distancia por defecto (tercera persona) - más lejos
+            min_distance: 1.5, // Zoom mínimo para ver la nave completa
+            max_distance: 20.0, // Máximo zoom out aumentado
+        }
+    }
+
+    pub fn get_position(&self, target: &Vec3, ship_yaw: f32) -> Vec3 {
+        let combined_yaw = (self.yaw + ship_yaw).to_radians();
+        let pitch_rad = self.pitch.to_radians();
+        
+        // Calcular posición de la cámara alrededor de la nave
+        Vec3::new(
+            target.x + self.distance * combined_yaw.cos() * pitch_rad.cos(),
+            target.y + self.distance * pitch_rad.sin(),
+            target.z + self.distance * combined_yaw.sin() * pitch_rad.cos(),
+        )
+    }
+
+    // Vista hacia la nave desde cualquier punto de la esfera que la rodea. La
+    // vertical de la cámara es la tangente a la esfera en la dirección en que
+    // sube la elevación: siempre es perpendicular a la mirada, así que no se
+    // degenera cerca de los polos como el eje Y fijo, y en el ecuador es Y.
+    pub fn get_view_matrix(&self, target: &Vec3, ship_yaw: f32) -> Mat4 {
+        let camera_pos = self.get_position(target, ship_yaw);
+        let combined_yaw = (self.yaw + ship_yaw).to_radians();
+        let pitch_rad = self.pitch.to_radians();
+        let up = Vec3::new(
+            -combined_yaw.cos() * pitch_rad.sin(),
+            pitch_rad.cos(),
+            -combined_yaw.sin() * pitch_rad.sin(),
+        );
+        look_at(&camera_pos, target, &up)
+    }
+
+    // Sube o baja la cámara alrededor de la nave (grados)
+    pub fn tilt(&mut self, delta: f32) {
+        self.pitch = (self.pitch + delta).clamp(MIN_PITCH, MAX_PITCH);
+    }
+
+    // Devuelve true si se sigue acercando cuando ya estaba en el zoom
+    // mínimo: ahí la cámara pasa a la cabina
+    pub fn zoom(&mut self, delta: f32) -> bool {
+        let at_minimum = self.distance <= self.min_distance;
+        self.distance -= delta * 0.5;
+        self.distance = self.distance.clamp(self.min_distance, self.max_distance);
+        at_minimum && delta > 0.0
+    }
+
+    // Fija la elevación (grados) y la distancia que se indiquen, con los
+    // mismos límites que `tilt` y `zoom`
+    pub fn set_view(&mut self, pitch: Option<f32>, distance: Option<f32>) {
+        if let Some(pitch) = pitch {
+            self.pitch = pitch.clamp(MIN_PITCH, MAX_PITCH);
+        }
+        if let Some(distance) = distance {
+            self.distance = distance.clamp(self.min_distance, self.max_distance);
+        }
+    }
+}
+
+// Cámara libre de depuración: se separa de la nave y vuela por el sistema
+// con WASD y mirada con el mouse
+pub struct FreeCamera {
+    pub position: Vec3,
+    yaw: f32,   // grados, 0 = mirando hacia +X
+    pitch: f32, // grados, limitado para no voltear la vista
+    pub speed: f32,
+}
+
+// Vista en primera persona desde la cabina: en `anchor` (adelante y arriba
+// del centro de la nave, ver `ships`) y mirando hacia donde vuela, con el
+// alabeo (`roll`) y el cabeceo (`pitch`) de la nave en radianes. Devuelve la
+// matriz de vista y la posición de la cámara.
+pub fn cockpit_view(ship_position: Vec3, forward: Vec3, roll: f32, pitch: f32, anchor: [f32; 2]) -> (Mat4, Vec3) {
+    let up = Vec3::new(0.0, 1.0, 0.0);
+    let eye = ship_position + forward * anchor[0] + up * anchor[1];
+    // La cámara se inclina con la nave, así el horizonte se mueve al girar
+    let right = forward.cross(&up);
+    let look = forward * pitch.cos() + up * pitch.sin();
+    let tilted_up = up * roll.cos() + right * roll.sin();
+    (look_at(&eye, &(eye + look), &tilted_up), eye)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CameraMode {
+    Follow,
+    Free,
+}
+
+impl FreeCamera {
+    // Crea la cámara libre en `position` mirando hacia `target`
+    pub fn looking_at(position: Vec3, target: Vec3) -> Self {
+        let direction = (target - position).normalize();
+        Self {
+            position,
+            yaw: direction.z.atan2(direction.x).to_degrees(),
+            pitch: direction.y.clamp(-1.0, 1.0).asin().to_degrees(),
+            speed: 0.4,
+        }
+    }
+
+    pub fn forward(&self) -> Vec3 {
+        let yaw = self.yaw.to_radians();
+        let pitch = self.pitch.to_radians();
+        Vec3::new(yaw.cos() * pitch.cos(), pitch.sin(), yaw.sin() * pitch.cos())
+    }
+
+    pub fn right(&self) -> Vec3 {
+        self.forward().cross(&Vec3::new(0.0, 1.0, 0.0)).normalize()
+    }
+
+    pub fn get_view_matrix(&self) -> Mat4 {
+        look_at(&self.position, &(self.position + self.forward()), &Vec3::new(0.0, 1.0, 0.0))
+    }
+
+    pub fn look(&mut self, delta_x: f32, delta_y: f32) {
+        self.turn(delta_x * 0.3, -delta_y * 0.3);
+    }
+
+    // Gira la vista `yaw` grados a la derecha y `pitch` grados hacia arriba
+    pub fn turn(&mut self, yaw: f32, pitch: f32) {
+        self.yaw += yaw;
+        self.pitch = (self.pitch + pitch).clamp(-89.0, 89.0);
+    }
+
+    // Mueve la cámara en su sistema local (adelante, derecha, arriba) escalado por `multiplier`
+    pub fn translate(&mut self, forward: f32, right: f32, up: f32, multiplier: f32) {
+        let step = self.speed * multiplier;
+        self.position += self.forward() * forward * step
+            + self.right() * right * step
+            + Vec3::new(0.0, 1.0, 0.0) * up * step;
+    }
+
+    pub fn adjust_speed(&mut self, scroll: f32) {
+        self.speed = (self.speed * (1.0 + scroll * 0.1)).clamp(0.05, 5.0);
+    }
+}
@@ -0,0 +1,73 @@
+// Recorte de triángulos en espacio de clip (antes de la división de perspectiva)
+// con el algoritmo de Sutherland–Hodgman.
+
+use nalgebra_glm::Vec4;
+use crate::vertex::Vertex;
+
+// Plano del frustum expresado como coeficientes sobre (x, y, z, w):
+// un punto está dentro si dot(plano, posición) >= 0
+pub const NEAR_PLANE: Vec4 = Vec4::new(0.0, 0.0, 1.0, 1.0);
+pub const FAR_PLANE: Vec4 = Vec4::new(0.0, 0.0, -1.0, 1.0);
+
+fn distance(plane: &Vec4, vertex: &Vertex) -> f32 {
+    plane.dot(&vertex.transformed_position)
+}
+
+// Interpola todos los atributos del vértice; en espacio de clip la
+// interpolación lineal es correcta porque aún no se dividió entre w
+fn lerp_vertex(a: &Vertex, b: &Vertex, t: f32) -> Vertex {
+    Vertex {
+        position: a.position.lerp(&b.position, t),
+        normal: a.normal.lerp(&b.normal, t),
+        tex_coords: a.tex_coords.lerp(&b.tex_coords, t),
+        color: a.color,
+        transformed_position: a.transformed_position.lerp(&b.transformed_position, t),
+        transformed_normal: a.transformed_normal.lerp(&b.transformed_normal, t),
+        world_position: a.world_position.lerp(&b.world_position, t),
+    }
+}
+
+// Recorta un polígono convexo contra un plano
+fn clip_polygon(polygon: &[Vertex], plane: &Vec4) -> Vec<Vertex> {
+    let mut output = Vec::with_capacity(polygon.len() + 1);
+    if polygon.is_empty() {
+        return output;
+    }
+
+    for i in 0..polygon.len() {
+        let current = &polygon[i];
+        let next = &polygon[(i + 1) % polygon.len()];
+        let d_current = distance(plane, current);
+        let d_next = distance(plane, next);
+
+        if d_current >= 0.0 {
+            output.push(current.clone());
+        }
+        // El lado cruza el plano: agregar el punto de intersección
+        if (d_current >= 0.0) != (d_next >= 0.0) {
+            let t = d_current / (d_current - d_next);
+            output.push(lerp_vertex(current, next, t));
+        }
+    }
+
+    output
+}
+
+pub fn is_inside(vertex: &Vertex, planes: &[Vec4]) -> bool {
+    planes.iter().all(|plane| distance(plane, vertex) >= 0.0)
+}
+
+// Recorta el triángulo contra los planos dados y lo devuelve como abanico de triángulos
+pub fn clip_triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex, planes: &[Vec4]) -> Vec<[Vertex; 3]> {
+    let mut polygon = vec![v1.clone(), v2.clone(), v3.clone()];
+    for plane in planes {
+        polygon = clip_polygon(&polygon, plane);
+        if polygon.len() < 3 {
+            return Vec::new();
+        }
+    }
+
+    (1..polygon.len() - 1)
+        .map(|i| [polygon[0].clone(), polygon[i].clone(), polygon[i + 1].clone()])
+        .collect()
+}
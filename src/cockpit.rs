@@ -0,0 +1,366 @@
+// HUD holográfico de la cabina (vista en primera persona): corchetes sobre
+// el objetivo, cinta de velocidad y escalera de cabeceo respecto al plano
+// orbital (Y = 0). Todo se dibuja en verde translúcido, con una curvatura
+// leve de visor y líneas de barrido que parpadean. Debajo va el marco de la
+// ventana de la cabina y un horizonte artificial en el tablero.
+
+use nalgebra_glm::{Mat4, Vec2, Vec3, Vec4};
+
+use crate::framebuffer::{pack_color, Framebuffer};
+use crate::scene::Scene;
+use crate::{project_to_screen, projected_radius};
+
+const HOLO_COLOR: Vec3 = Vec3::new(0.35, 1.0, 0.55);
+// El mismo verde empaquetado, para las líneas de texto del HUD
+pub const HOLO_TEXT_COLOR: u32 = 0x59FF8C;
+// Abombamiento del visor: cuánto se separan del centro los bordes
+const CURVATURE: f32 = 0.06;
+const HOLO_ALPHA: f32 = 0.8;
+// Píxeles por unidad de velocidad en la cinta
+const TAPE_SCALE: f32 = 12.0;
+const TAPE_HALF_HEIGHT: f32 = 110.0;
+const LADDER_STEP: usize = 10;
+
+// Marco de la cabina: ventana octogonal con un aro interior y ocho montantes
+// del aro al borde, como en un caza TIE
+const FRAME_COLOR: Vec3 = Vec3::new(0.09, 0.1, 0.12);
+const FRAME_EDGE_COLOR: Vec3 = Vec3::new(0.28, 0.3, 0.34);
+// Semiejes de la ventana respecto a la mitad del ancho y del alto
+const WINDOW_HALF_WIDTH: f32 = 0.94;
+const WINDOW_HALF_HEIGHT: f32 = 0.86;
+// Radio del aro interior respecto a la mitad del alto
+const RING_RADIUS: f32 = 0.42;
+// Grosores en píxeles
+const RING_WIDTH: f32 = 2.0;
+const STRUT_WIDTH: f32 = 3.0;
+const EDGE_WIDTH: f32 = 2.0;
+
+// Horizonte artificial: radio respecto al alto de la pantalla
+const ATTITUDE_RADIUS: f32 = 0.1;
+const SKY_COLOR: Vec3 = Vec3::new(0.17, 0.44, 0.72);
+const GROUND_COLOR: Vec3 = Vec3::new(0.48, 0.29, 0.13);
+const AIRCRAFT_COLOR: u32 = 0xFFB020;
+
+// Lo que necesita el HUD de la cámara de este cuadro
+pub struct CockpitView<'a> {
+    pub view_matrix: &'a Mat4,
+    pub projection_matrix: &'a Mat4,
+    pub viewport_matrix: &'a Mat4,
+    pub camera_position: Vec3,
+    pub time: f32,
+}
+
+// Pincel del HUD: aplica la curvatura a cada punto y modula la opacidad con
+// las líneas de barrido y el parpadeo
+struct Holo<'a> {
+    framebuffer: &'a mut Framebuffer,
+    center: Vec2,
+    half_height: f32,
+    time: f32,
+    flicker: f32,
+}
+
+impl Holo<'_> {
+    fn curve(&self, point: Vec2) -> Vec2 {
+        let offset = (point - self.center) / self.half_height;
+        self.center + offset * (1.0 + CURVATURE * offset.magnitude_squared()) * self.half_height
+    }
+
+    fn plot(&mut self, point: Vec2) {
+        let point = self.curve(point);
+        if point.x < 0.0 || point.y < 0.0 {
+            return;
+        }
+        // Una de cada tres filas más tenue, y la banda que baja más brillante
+        let row = point.y as usize;
+        let scanline = match row % 3 {
+            0 => 0.45,
+            _ => 1.0,
+        };
+        let sweep = ((point.y / self.half_height - self.time * 0.6).rem_euclid(2.0) - 1.0).abs();
+        let band = 1.0 + 0.4 * (1.0 - sweep * 8.0).max(0.0);
+        let alpha = (HOLO_ALPHA * scanline * self.flicker * band).min(1.0);
+        self.framebuffer.overlay_blend(point.x as usize, row, pack_color(HOLO_COLOR), alpha);
+    }
+
+    fn line(&mut self, from: Vec2, to: Vec2) {
+        let steps = (to - from).abs().max().ceil().min(4096.0) as usize;
+        for step in 0..=steps {
+            let t = if steps == 0 { 0.0 } else { step as f32 / steps as f32 };
+            self.plot(from.lerp(&to, t));
+        }
+    }
+
+    fn text(&mut self, anchor: Vec2, text: &str) {
+        let point = self.curve(anchor);
+        if point.x >= 0.0 && point.y >= 0.0 {
+            self.framebuffer.draw_text(point.x as usize, point.y as usize, text, pack_color(HOLO_COLOR * self.flicker));
+        }
+    }
+}
+
+pub fn render_cockpit_hud(
+    framebuffer: &mut Framebuffer,
+    view: &CockpitView,
+    scene: &Scene,
+    forward: Vec3,
+    speed: f32,
+    target: Option<usize>,
+) {
+    let (width, height) = (framebuffer.width as f32, framebuffer.height as f32);
+    let time = view.time;
+    let level = Vec3::new(forward.x, 0.0, forward.z).try_normalize(1e-6).unwrap_or_else(|| Vec3::new(1.0, 0.0, 0.0));
+    render_frame(framebuffer);
+    render_attitude(framebuffer, ladder_row(view, level, 0.0));
+
+    // Parpadeo irregular: dos senos desfasados y, de vez en cuando, un bajón
+    let glitch = if (time * 3.7).sin() > 0.97 { 0.6 } else { 1.0 };
+    let mut holo = Holo {
+        framebuffer,
+        center: Vec2::new(width * 0.5, height * 0.5),
+        half_height: height * 0.5,
+        time,
+        flicker: (0.9 + 0.05 * (time * 41.0).sin() + 0.05 * (time * 17.0).sin()) * glitch,
+    };
+
+    // Retícula fija en el eje de la nave: cruz abierta con un punto al centro
+    let center = holo.center;
+    for direction in [Vec2::new(1.0, 0.0), Vec2::new(-1.0, 0.0), Vec2::new(0.0, 1.0), Vec2::new(0.0, -1.0)] {
+        holo.line(center + direction * 6.0, center + direction * 18.0);
+    }
+    holo.plot(center);
+
+    render_ladder(&mut holo, view, level);
+    render_speed_tape(&mut holo, speed);
+    if let Some(index) = target {
+        render_target(&mut holo, view, scene, index);
+    }
+}
+
+// Marco opaco de la ventana: el metal se aclara un poco hacia arriba y los
+// bordes del marco, del aro y de los montantes tienen un filo más claro
+fn render_frame(framebuffer: &mut Framebuffer) {
+    let (width, height) = (framebuffer.width, framebuffer.height);
+    let half = Vec2::new(width as f32 * 0.5, height as f32 * 0.5);
+    let window = Vec2::new(half.x * WINDOW_HALF_WIDTH, half.y * WINDOW_HALF_HEIGHT);
+    let ring = half.y * RING_RADIUS;
+    // Los montantes siguen las diagonales del octágono (22.5° + k·45°)
+    let struts = [22.5f32, 67.5, 112.5, 157.5].map(|angle| {
+        let angle = angle.to_radians();
+        Vec2::new(angle.cos(), angle.sin())
+    });
+    for y in 0..height {
+        let metal = FRAME_COLOR * (1.15 - 0.3 * y as f32 / height as f32);
+        for x in 0..width {
+            let offset = Vec2::new(x as f32 + 0.5, y as f32 + 0.5) - half;
+            let (u, v) = ((offset.x / window.x).abs(), (offset.y / window.y).abs());
+            // Distancia octogonal normalizada: 1 en el borde de la ventana
+            let window_distance = u.max(v).max((u + v) / 1.4);
+            let color = if window_distance >= 1.0 {
+                if (window_distance - 1.0) * window.y < EDGE_WIDTH {
+                    FRAME_EDGE_COLOR
+                } else {
+                    metal
+                }
+            } else {
+                let (ax, ay) = (offset.x.abs(), offset.y.abs());
+                let octagon = ax.max(ay).max((ax + ay) * std::f32::consts::FRAC_1_SQRT_2);
+                let ring_distance = (octagon - ring).abs();
+                let strut_distance = struts
+                    .iter()
+                    .map(|direction| (offset.x * direction.y - offset.y * direction.x).abs())
+                    .fold(f32::INFINITY, f32::min);
+                if ring_distance < RING_WIDTH {
+                    FRAME_EDGE_COLOR.lerp(&metal, ring_distance / RING_WIDTH)
+                } else if octagon > ring && strut_distance < STRUT_WIDTH {
+                    FRAME_EDGE_COLOR.lerp(&metal, strut_distance / STRUT_WIDTH)
+                } else {
+                    continue;
+                }
+            };
+            framebuffer.overlay_pixel(x, y, pack_color(color));
+        }
+    }
+}
+
+// Horizonte artificial del tablero, abajo al centro: cielo y suelo separados
+// por el mismo horizonte que se ve por la ventana (con su alabeo y su altura)
+// y la silueta fija de la nave encima
+fn render_attitude(framebuffer: &mut Framebuffer, horizon: Option<(Vec2, Vec2)>) {
+    let (width, height) = (framebuffer.width as f32, framebuffer.height as f32);
+    let screen_center = Vec2::new(width * 0.5, height * 0.5);
+    let radius = height * ATTITUDE_RADIUS;
+    let center = Vec2::new(width * 0.5, height - radius - 8.0);
+    let (point, axis) = horizon.unwrap_or((screen_center, Vec2::new(1.0, 0.0)));
+    let down = Vec2::new(-axis.y, axis.x);
+    // Altura del horizonte en la ventana, llevada a la escala del instrumento
+    let offset = (point - screen_center).dot(&down) * radius / screen_center.y;
+    let bezel = radius - 3.0;
+
+    let min_x = (center.x - radius).max(0.0) as usize;
+    let max_x = (center.x + radius).min(width - 1.0).max(0.0) as usize;
+    let min_y = (center.y - radius).max(0.0) as usize;
+    let max_y = (center.y + radius).min(height - 1.0).max(0.0) as usize;
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            let relative = Vec2::new(x as f32 + 0.5, y as f32 + 0.5) - center;
+            let distance = relative.magnitude();
+            if distance > radius {
+                continue;
+            }
+            let below = relative.dot(&down) - offset;
+            let color = if distance > bezel {
+                pack_color(FRAME_EDGE_COLOR)
+            } else if below.abs() < 1.0 {
+                0xFFFFFF
+            } else if below < 0.0 {
+                pack_color(SKY_COLOR)
+            } else {
+                pack_color(GROUND_COLOR)
+            };
+            framebuffer.overlay_pixel(x, y, color);
+        }
+    }
+
+    // Alas a los lados y un punto al centro, fijos respecto a la nave
+    let wing = radius * 0.55;
+    let gap = radius * 0.2;
+    for x in (center.x - wing) as usize..=(center.x + wing) as usize {
+        let dx = (x as f32 - center.x).abs();
+        if dx > gap || dx < 2.0 {
+            for y in center.y as usize - 1..=center.y as usize + 1 {
+                framebuffer.overlay_pixel(x, y, AIRCRAFT_COLOR);
+            }
+        }
+    }
+}
+
+// Punto en pantalla de la línea de la escalera a `pitch` grados sobre el
+// plano orbital y su dirección en pantalla, que gira con el alabeo de la cámara
+fn ladder_row(view: &CockpitView, level: Vec3, pitch: f32) -> Option<(Vec2, Vec2)> {
+    let angle = pitch.to_radians();
+    let anchor = view.camera_position + (level * angle.cos() + Vec3::new(0.0, angle.sin(), 0.0)) * 100.0;
+    let side = level.cross(&Vec3::new(0.0, 1.0, 0.0));
+    let project = |point: Vec3| {
+        project_to_screen(point, view.view_matrix, view.projection_matrix, view.viewport_matrix).map(|(x, y, _)| Vec2::new(x, y))
+    };
+    let center = project(anchor)?;
+    let axis = (project(anchor + side * 10.0)? - center).try_normalize(1e-6)?;
+    Some((center, axis))
+}
+
+// Escalera de cabeceo: una línea por cada 10° sobre y bajo el plano orbital;
+// las de abajo van punteadas. A la derecha del horizonte, la altura sobre el plano.
+fn render_ladder(holo: &mut Holo, view: &CockpitView, level: Vec3) {
+    let half_width = holo.half_height * 0.45;
+    for pitch in (-30i32..=30).step_by(LADDER_STEP) {
+        let Some((center, axis)) = ladder_row(view, level, pitch as f32) else {
+            continue;
+        };
+        // Perpendicular a la línea, hacia abajo en pantalla sin alabeo
+        let down = Vec2::new(-axis.y, axis.x);
+        let gap = if pitch == 0 { 30.0 } else { 50.0 };
+        let reach = if pitch == 0 { half_width * 1.6 } else { half_width };
+        for side in [-1.0, 1.0] {
+            let inner = center + axis * (side * gap);
+            let outer = center + axis * (side * reach);
+            if pitch < 0 {
+                // Tramos de 8 píxeles separados por 6
+                let length = reach - gap;
+                let mut start = 0.0;
+                while start < length {
+                    let end = (start + 8.0).min(length);
+                    holo.line(inner.lerp(&outer, start / length), inner.lerp(&outer, end / length));
+                    start += 14.0;
+                }
+            } else {
+                holo.line(inner, outer);
+            }
+            if pitch != 0 {
+                // Patitas hacia el horizonte en los extremos
+                let tick = if pitch > 0 { 6.0 } else { -6.0 };
+                holo.line(outer, outer + down * tick);
+            }
+        }
+        let label = center + axis * (reach + 6.0) - Vec2::new(0.0, 6.0);
+        if pitch != 0 {
+            holo.text(label, &pitch.abs().to_string());
+        } else {
+            let altitude = view.camera_position.y;
+            holo.text(label, &format!("PLANO {:+.1}", altitude));
+        }
+    }
+}
+
+// Cinta vertical de velocidad a la izquierda: marcas cada unidad, números
+// cada 5, y la velocidad actual en una caja al centro
+fn render_speed_tape(holo: &mut Holo, speed: f32) {
+    let x = holo.center.x - holo.half_height * 0.9;
+    let y = holo.center.y;
+    holo.line(Vec2::new(x, y - TAPE_HALF_HEIGHT), Vec2::new(x, y + TAPE_HALF_HEIGHT));
+
+    let visible = TAPE_HALF_HEIGHT / TAPE_SCALE;
+    let first = (speed - visible).ceil().max(0.0) as i32;
+    let last = (speed + visible).floor() as i32;
+    for value in first..=last {
+        let tick_y = y - (value as f32 - speed) * TAPE_SCALE;
+        let length = if value % 5 == 0 { 10.0 } else { 5.0 };
+        holo.line(Vec2::new(x, tick_y), Vec2::new(x + length, tick_y));
+        if value % 5 == 0 && (tick_y - y).abs() > 10.0 {
+            holo.text(Vec2::new(x - 34.0, tick_y - 6.0), &value.to_string());
+        }
+    }
+
+    let corners = [
+        Vec2::new(x - 50.0, y - 10.0),
+        Vec2::new(x - 4.0, y - 10.0),
+        Vec2::new(x - 4.0, y + 10.0),
+        Vec2::new(x - 50.0, y + 10.0),
+    ];
+    for i in 0..4 {
+        holo.line(corners[i], corners[(i + 1) % 4]);
+    }
+    holo.text(Vec2::new(x - 46.0, y - 6.0), &format!("{:.1}", speed));
+    holo.text(Vec2::new(x - 46.0, y + TAPE_HALF_HEIGHT + 8.0), "VEL");
+}
+
+// Corchetes alrededor del objetivo con su nombre y distancia; si queda fuera
+// de la vista, una flecha en el borde del visor apunta hacia él
+fn render_target(holo: &mut Holo, view: &CockpitView, scene: &Scene, index: usize) {
+    let position = scene.states[index].position;
+    let body = &scene.bodies[index];
+    let distance = (position - view.camera_position).magnitude();
+    let label = format!("{} {:.0}", body.name, distance);
+
+    let screen = project_to_screen(position, view.view_matrix, view.projection_matrix, view.viewport_matrix);
+    let on_screen = screen.filter(|(x, y, _)| {
+        let margin = 20.0;
+        *x > margin && *y > margin && *x < holo.center.x * 2.0 - margin && *y < holo.center.y * 2.0 - margin
+    });
+
+    match on_screen {
+        Some((x, y, depth)) => {
+            let radius = projected_radius(body.scale, depth, view.projection_matrix, view.viewport_matrix).max(12.0) + 6.0;
+            let arm = (radius * 0.4).clamp(6.0, 30.0);
+            let center = Vec2::new(x, y);
+            for (sx, sy) in [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)] {
+                let corner = center + Vec2::new(sx * radius, sy * radius);
+                holo.line(corner, corner - Vec2::new(sx * arm, 0.0));
+                holo.line(corner, corner - Vec2::new(0.0, sy * arm));
+            }
+            holo.text(center + Vec2::new(-radius, radius + 6.0), &label);
+        }
+        None => {
+            let relative = view.view_matrix * Vec4::new(position.x, position.y, position.z, 1.0);
+            // En pantalla Y crece hacia abajo
+            let direction = Vec2::new(relative.x, -relative.y).try_normalize(1e-6).unwrap_or_else(|| Vec2::new(0.0, 1.0));
+            let tip = holo.center + direction * holo.half_height * 0.8;
+            let side = Vec2::new(-direction.y, direction.x) * 8.0;
+            let base = tip - direction * 14.0;
+            holo.line(tip, base + side);
+            holo.line(tip, base - side);
+            holo.line(base + side, base - side);
+            holo.text(base - direction * 20.0 - Vec2::new(24.0, 6.0), &label);
+        }
+    }
+}
@@ -7,6 +7,7 @@ pub struct Color {
   b: u8,
 }
 
+#[allow(dead_code)]
 impl Color {
   // Constructor to initialize the color using r, g, b values as u8
   pub fn new(r: u8, g: u8, b: u8) -> Self {
@@ -36,7 +37,7 @@ impl Color {
   }
 
   // Function to return the color as a hex value
-  pub fn to_hex(&self) -> u32 {
+  pub fn to_hex(self) -> u32 {
     ((self.r as u32) << 16) | ((self.g as u32) << 8) | (self.b as u32)
   }
 }
@@ -0,0 +1,163 @@
+// Cometas: cualquier cuerpo puede declarar una cola (`tail = { ... }`),
+// normalmente junto con el shader `comet` y una órbita muy excéntrica. La cola
+// es un chorro de partículas que sale del núcleo en sentido contrario al sol;
+// cerca del sol es larga y brillante, y al alejarse se acorta y se apaga.
+
+use nalgebra_glm::{Mat4, Vec3, Vec4};
+use serde::{Deserialize, Serialize};
+
+use crate::framebuffer::Framebuffer;
+use crate::scene::Scene;
+
+// Vida de cada partícula en unidades de tiempo de simulación
+const LIFETIME: f32 = 0.8;
+// Partículas emitidas por cuadro con la cola completa
+const EMIT_PER_FRAME: f32 = 6.0;
+// Límite de partículas vivas entre todos los cometas
+const MAX_PARTICLES: usize = 3000;
+// Por debajo de esta actividad el cometa ya no suelta cola
+const MIN_ACTIVITY: f32 = 0.02;
+// Ancho de la cola al final de su vida, en radios del núcleo
+const SPREAD: f32 = 2.5;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CometTail {
+    // Largo de la cola en unidades del mundo con el cometa activo
+    pub length: f32,
+    pub color: [f32; 3],
+    // Distancia al sol dentro de la cual la cola está completa; más lejos
+    // se acorta y se apaga con el cuadrado de la distancia
+    pub active_distance: f32,
+}
+
+impl Default for CometTail {
+    fn default() -> Self {
+        CometTail {
+            length: 12.0,
+            color: [0.6, 0.8, 1.0],
+            active_distance: 20.0,
+        }
+    }
+}
+
+impl CometTail {
+    // Actividad 0..1 del cometa a `distance` del sol
+    fn activity(&self, distance: f32) -> f32 {
+        (self.active_distance / distance.max(1e-3)).powi(2).min(1.0)
+    }
+}
+
+struct TailParticle {
+    position: Vec3,
+    velocity: Vec3,
+    age: f32,
+    color: Vec3,
+    // Radio del núcleo que la soltó, para el ancho de la cola
+    size: f32,
+}
+
+#[derive(Default)]
+pub struct CometTails {
+    particles: Vec<TailParticle>,
+    // Posición de cada cuerpo en el cuadro anterior, para heredar su velocidad
+    previous_positions: Vec<Vec3>,
+    rng_state: u32,
+}
+
+impl CometTails {
+    // Número pseudoaleatorio en [-1, 1] (xorshift)
+    fn random(&mut self) -> f32 {
+        if self.rng_state == 0 {
+            self.rng_state = 0x2545_F491;
+        }
+        self.rng_state ^= self.rng_state << 13;
+        self.rng_state ^= self.rng_state >> 17;
+        self.rng_state ^= self.rng_state << 5;
+        (self.rng_state as f32 / u32::MAX as f32) * 2.0 - 1.0
+    }
+
+    pub fn update(&mut self, scene: &Scene, dt: f32) {
+        self.particles.retain_mut(|particle| {
+            particle.age += dt;
+            particle.position += particle.velocity * dt;
+            particle.age < LIFETIME
+        });
+
+        let positions: Vec<Vec3> = scene.states.iter().map(|state| state.position).collect();
+        // Sin cuadro anterior (o con la escena recargada) aún no hay velocidad
+        let previous = std::mem::replace(&mut self.previous_positions, positions.clone());
+        if previous.len() != positions.len() || dt <= 0.0 {
+            return;
+        }
+
+        let sun = scene.star_position();
+        for (index, body) in scene.bodies.iter().enumerate() {
+            let Some(tail) = &body.tail else {
+                continue;
+            };
+            let nucleus = positions[index];
+            let to_sun = sun - nucleus;
+            let activity = tail.activity(to_sun.magnitude());
+            if activity < MIN_ACTIVITY {
+                continue;
+            }
+            let away = -to_sun.try_normalize(1e-6).unwrap_or_else(|| Vec3::new(1.0, 0.0, 0.0));
+            // Las partículas se llevan la velocidad del núcleo, así la cola
+            // apunta lejos del sol y no queda atrás sobre la órbita
+            let inherited = (nucleus - previous[index]) / dt;
+            let speed = tail.length * activity.sqrt() / LIFETIME;
+            let color = Vec3::from(tail.color) * (0.5 + activity);
+            let count = (EMIT_PER_FRAME * activity).ceil() as usize;
+            for _ in 0..count {
+                if self.particles.len() >= MAX_PARTICLES {
+                    self.particles.remove(0);
+                }
+                let jitter = Vec3::new(self.random(), self.random(), self.random());
+                let spread = jitter * (body.scale * SPREAD / LIFETIME);
+                let speed = speed * (1.0 + self.random() * 0.3);
+                self.particles.push(TailParticle {
+                    position: nucleus + jitter * body.scale * 0.5,
+                    velocity: inherited + away * speed + spread,
+                    age: 0.0,
+                    color,
+                    size: body.scale,
+                });
+            }
+        }
+    }
+
+    // Cada partícula es un disco suave en HDR que crece y se apaga con la edad
+    pub fn render(&self, framebuffer: &mut Framebuffer, view_matrix: &Mat4, projection_matrix: &Mat4, viewport_matrix: &Mat4) {
+        for particle in &self.particles {
+            let view = view_matrix * Vec4::new(particle.position.x, particle.position.y, particle.position.z, 1.0);
+            if -view.z <= 0.1 {
+                continue;
+            }
+            let clip = projection_matrix * view;
+            let ndc = clip.xyz() / clip.w;
+            if ndc.z <= -1.0 || ndc.z >= 1.0 {
+                continue;
+            }
+            let center = viewport_matrix * Vec4::new(ndc.x, ndc.y, ndc.z, 1.0);
+
+            let life = particle.age / LIFETIME;
+            let world_radius = particle.size * (0.4 + life);
+            let radius = (world_radius * projection_matrix[(1, 1)] / -view.z * -viewport_matrix[(1, 1)]).clamp(0.7, 12.0);
+            let alpha = (1.0 - life) * 0.35;
+            let min_x = (center.x - radius).max(0.0) as usize;
+            let max_x = (center.x + radius).min(framebuffer.width as f32 - 1.0).max(0.0) as usize;
+            let min_y = (center.y - radius).max(0.0) as usize;
+            let max_y = (center.y + radius).min(framebuffer.height as f32 - 1.0).max(0.0) as usize;
+            for y in min_y..=max_y {
+                for x in min_x..=max_x {
+                    let distance = ((x as f32 - center.x).powi(2) + (y as f32 - center.y).powi(2)).sqrt() / radius;
+                    if distance < 1.0 {
+                        let falloff = 1.0 - distance * distance;
+                        framebuffer.point_hdr(x, y, ndc.z, particle.color, alpha * falloff);
+                    }
+                }
+            }
+        }
+    }
+}
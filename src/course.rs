@@ -0,0 +1,228 @@
+// Circuito de anillos: genera anillos de control (toros emisivos) a lo largo
+// de una curva Catmull-Rom cerrada que pasa sobre los planetas, detecta
+// cuando la nave los atraviesa y lleva el tiempo de cada vuelta.
+
+use std::f32::consts::PI;
+
+use nalgebra_glm::{Mat4, Vec2, Vec3, Vec4};
+use serde::{Deserialize, Serialize};
+
+use crate::scene::Scene;
+use crate::vertex::Vertex;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CourseConfig {
+    // Número de anillos repartidos a lo largo del circuito
+    pub rings: usize,
+    pub laps: usize,
+    pub ring_radius: f32,
+    // Altura de la curva por encima de cada planeta, sumada a su escala
+    pub height: f32,
+}
+
+impl Default for CourseConfig {
+    fn default() -> Self {
+        CourseConfig {
+            rings: 12,
+            laps: 3,
+            ring_radius: 2.5,
+            height: 3.0,
+        }
+    }
+}
+
+pub struct Ring {
+    pub center: Vec3,
+    // Dirección en la que se debe cruzar el anillo (tangente de la curva)
+    pub normal: Vec3,
+    pub radius: f32,
+}
+
+impl Ring {
+    // Matriz de modelo que lleva el toro unitario (eje +Y) a este anillo
+    pub fn model_matrix(&self) -> Mat4 {
+        let reference = if self.normal.y.abs() > 0.99 { Vec3::new(1.0, 0.0, 0.0) } else { Vec3::new(0.0, 1.0, 0.0) };
+        let x_axis = reference.cross(&self.normal).normalize() * self.radius;
+        let y_axis = self.normal * self.radius;
+        let z_axis = x_axis.cross(&self.normal).normalize() * self.radius;
+        Mat4::from_columns(&[
+            Vec4::new(x_axis.x, x_axis.y, x_axis.z, 0.0),
+            Vec4::new(y_axis.x, y_axis.y, y_axis.z, 0.0),
+            Vec4::new(z_axis.x, z_axis.y, z_axis.z, 0.0),
+            Vec4::new(self.center.x, self.center.y, self.center.z, 1.0),
+        ])
+    }
+
+    // ¿El segmento recorrido en este cuadro cruza el plano del anillo por dentro?
+    fn crossed_by(&self, from: Vec3, to: Vec3) -> bool {
+        let d_from = (from - self.center).dot(&self.normal);
+        let d_to = (to - self.center).dot(&self.normal);
+        if (d_from >= 0.0) == (d_to >= 0.0) {
+            return false;
+        }
+        let t = d_from / (d_from - d_to);
+        let hit = from + (to - from) * t;
+        (hit - self.center).magnitude() <= self.radius
+    }
+}
+
+// Punto de una curva Catmull-Rom uniforme entre p1 y p2
+pub fn catmull_rom(p0: Vec3, p1: Vec3, p2: Vec3, p3: Vec3, t: f32) -> Vec3 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    (p1 * 2.0
+        + (p2 - p0) * t
+        + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * t2
+        + (-p0 + p1 * 3.0 - p2 * 3.0 + p3) * t3)
+        * 0.5
+}
+
+// Toro unitario (radio mayor 1) alrededor del eje Y, con caras hacia afuera
+pub fn torus_mesh(tube_radius: f32, segments: usize, sides: usize) -> (Vec<Vertex>, Vec<u32>) {
+    let mut vertices = Vec::with_capacity((segments + 1) * (sides + 1));
+    for i in 0..=segments {
+        let u = i as f32 / segments as f32 * 2.0 * PI;
+        for j in 0..=sides {
+            let v = j as f32 / sides as f32 * 2.0 * PI;
+            let normal = Vec3::new(v.cos() * u.cos(), v.sin(), v.cos() * u.sin());
+            let ring_radius = 1.0 + tube_radius * v.cos();
+            let position = Vec3::new(ring_radius * u.cos(), tube_radius * v.sin(), ring_radius * u.sin());
+            let tex_coords = Vec2::new(i as f32 / segments as f32, j as f32 / sides as f32);
+            vertices.push(Vertex::new(position, normal, tex_coords));
+        }
+    }
+
+    let stride = (sides + 1) as u32;
+    let mut indices = Vec::with_capacity(segments * sides * 6);
+    for i in 0..segments as u32 {
+        for j in 0..sides as u32 {
+            let a = i * stride + j;
+            let b = a + 1;
+            let c = a + stride;
+            let d = c + 1;
+            indices.extend_from_slice(&[a, b, c, c, b, d]);
+        }
+    }
+    (vertices, indices)
+}
+
+pub struct Course {
+    pub rings: Vec<Ring>,
+    laps: usize,
+    next: usize,
+    lap: usize,
+    lap_start: Option<f32>,
+    last_split: Option<f32>,
+    lap_times: Vec<f32>,
+}
+
+impl Course {
+    // Construye el circuito sobre las posiciones actuales de los planetas,
+    // ordenados por su ángulo alrededor del centro para formar un lazo
+    pub fn generate(scene: &Scene, config: &CourseConfig) -> Option<Self> {
+        let mut anchors: Vec<Vec3> = scene.bodies
+            .iter()
+            .zip(&scene.states)
+            .filter(|(body, _)| body.orbit_radius > 0.0 && body.parent.is_none())
+            .map(|(body, state)| state.position + Vec3::new(0.0, body.scale + config.height, 0.0))
+            .collect();
+        if anchors.len() < 2 || config.rings < 2 {
+            return None;
+        }
+        anchors.sort_by(|a, b| a.z.atan2(a.x).total_cmp(&b.z.atan2(b.x)));
+
+        let count = anchors.len();
+        let point = |s: f32| -> Vec3 {
+            let segment = s.floor() as usize;
+            let at = |k: usize| anchors[(segment + k + count - 1) % count];
+            catmull_rom(at(0), at(1), at(2), at(3), s - s.floor())
+        };
+
+        let rings = (0..config.rings)
+            .map(|k| {
+                let s = k as f32 / config.rings as f32 * count as f32;
+                let ahead = point((s + 0.01) % count as f32);
+                let behind = point((s - 0.01 + count as f32) % count as f32);
+                Ring {
+                    center: point(s),
+                    normal: (ahead - behind).normalize(),
+                    radius: config.ring_radius,
+                }
+            })
+            .collect();
+
+        Some(Course {
+            rings,
+            laps: config.laps.max(1),
+            next: 0,
+            lap: 0,
+            lap_start: None,
+            last_split: None,
+            lap_times: Vec::new(),
+        })
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.lap >= self.laps
+    }
+
+    // Revisa si la nave cruzó el siguiente anillo entre `from` y `to`.
+    // Cruzar el primer anillo inicia la vuelta; volver a cruzarlo la cierra.
+    pub fn update(&mut self, time: f32, from: Vec3, to: Vec3) {
+        if self.is_finished() || !self.rings[self.next].crossed_by(from, to) {
+            return;
+        }
+
+        match self.lap_start {
+            Some(start) if self.next == 0 => {
+                self.lap_times.push(time - start);
+                self.lap += 1;
+                self.lap_start = Some(time);
+                self.last_split = None;
+            }
+            Some(start) => self.last_split = Some(time - start),
+            None => self.lap_start = Some(time),
+        }
+        self.next = (self.next + 1) % self.rings.len();
+    }
+
+    // Opacidad de cada anillo: el siguiente se ve sólido, el resto tenue
+    pub fn ring_alpha(&self, index: usize) -> f32 {
+        if self.is_finished() {
+            0.0
+        } else if index == self.next {
+            1.0
+        } else {
+            0.35
+        }
+    }
+
+    pub fn hud_lines(&self, time: f32) -> Vec<String> {
+        let mut lines = Vec::new();
+        match self.lap_start {
+            _ if self.is_finished() => {
+                let total: f32 = self.lap_times.iter().sum();
+                lines.push(format!("Circuito terminado: {:.2}", total));
+            }
+            None => lines.push("Circuito: cruza el primer anillo".to_string()),
+            Some(start) => {
+                lines.push(format!(
+                    "Vuelta {}/{}  anillo {}/{}",
+                    self.lap + 1,
+                    self.laps,
+                    self.next,
+                    self.rings.len()
+                ));
+                lines.push(format!("Tiempo de vuelta: {:.2}", time - start));
+            }
+        }
+        if let Some(split) = self.last_split {
+            lines.push(format!("Parcial: {:.2}", split));
+        }
+        if let Some(best) = self.lap_times.iter().copied().min_by(f32::total_cmp) {
+            lines.push(format!("Mejor vuelta: {:.2}", best));
+        }
+        lines
+    }
+}
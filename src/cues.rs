@@ -0,0 +1,83 @@
+// Señales sonoras de navegación: pitidos tipo contador Geiger que se
+// aceleran al acercarse al cuerpo seleccionado y un tono de alerta cuando
+// la nave se acerca demasiado rápido a cualquier cuerpo.
+
+use nalgebra_glm::Vec3;
+
+use crate::audio::{Tone, Waveform};
+use crate::scene::Scene;
+
+// Distancia (a la superficie) desde la que empiezan a acelerarse los pitidos
+const BEEP_RANGE: f32 = 60.0;
+// Intervalo entre pitidos, en unidades de tiempo de simulación
+const SLOWEST_BEEP: f32 = 0.5;
+const FASTEST_BEEP: f32 = 0.03;
+// Velocidad de acercamiento (unidades por unidad de tiempo) considerada peligrosa
+const SAFE_CLOSING_SPEED: f32 = 8.0;
+const WARNING_RANGE: f32 = 15.0;
+const WARNING_INTERVAL: f32 = 0.12;
+
+// Sonidos producidos en un cuadro: el pitido sale de la posición del
+// objetivo; la alerta es un sonido de cabina, sin posición
+#[derive(Default)]
+pub struct CueTones {
+    pub beep: Option<Tone>,
+    pub warning: Option<Tone>,
+}
+
+pub struct NavigationCues {
+    beep_timer: f32,
+    warning_timer: f32,
+    previous_distances: Vec<f32>,
+    pub warning: bool,
+}
+
+impl NavigationCues {
+    pub fn new() -> Self {
+        NavigationCues {
+            beep_timer: 0.0,
+            warning_timer: 0.0,
+            previous_distances: Vec::new(),
+            warning: false,
+        }
+    }
+
+    // Avanza los temporizadores y devuelve los tonos a reproducir en este cuadro
+    pub fn update(&mut self, dt: f32, scene: &Scene, ship_position: Vec3, target: Option<usize>) -> CueTones {
+        let distances: Vec<f32> = scene.bodies
+            .iter()
+            .zip(&scene.states)
+            .map(|(body, state)| (state.position - ship_position).magnitude() - body.collision_radius)
+            .collect();
+        let mut tones = CueTones::default();
+
+        if let Some(distance) = target.and_then(|index| distances.get(index)) {
+            let closeness = (distance.max(0.0) / BEEP_RANGE).min(1.0);
+            let interval = FASTEST_BEEP + (SLOWEST_BEEP - FASTEST_BEEP) * closeness;
+            self.beep_timer += dt;
+            if self.beep_timer >= interval {
+                self.beep_timer = 0.0;
+                tones.beep = Some(Tone::new(880.0, 0.04, 0.25, Waveform::Sine));
+            }
+        }
+
+        // Alerta si algún cuerpo cercano se aproxima más rápido de lo seguro
+        self.warning = self.previous_distances.len() == distances.len()
+            && distances
+                .iter()
+                .zip(&self.previous_distances)
+                .any(|(now, before)| *now < WARNING_RANGE && (before - now) / dt > SAFE_CLOSING_SPEED);
+        if self.warning {
+            self.warning_timer += dt;
+            if self.warning_timer >= WARNING_INTERVAL {
+                self.warning_timer = 0.0;
+                tones.warning = Some(Tone::new(330.0, 0.15, 0.2, Waveform::Square));
+            }
+        } else {
+            self.warning_timer = WARNING_INTERVAL;
+        }
+
+        self.previous_distances = distances;
+        tones
+    }
+}
@@ -0,0 +1,54 @@
+// Descarte de modelos completos fuera del frustum antes del vertex shader,
+// usando una esfera envolvente por modelo.
+
+use nalgebra_glm::{Mat4, Vec3, Vec4};
+
+use crate::vertex::Vertex;
+
+#[derive(Clone, Copy)]
+pub struct Frustum {
+    // Planos (normal hacia adentro, distancia) normalizados
+    planes: [Vec4; 6],
+}
+
+impl Frustum {
+    // Extrae los seis planos de la matriz vista-proyección (Gribb-Hartmann)
+    pub fn from_matrix(view_projection: &Mat4) -> Self {
+        let row = |i: usize| view_projection.row(i).transpose();
+        let (r0, r1, r2, r3) = (row(0), row(1), row(2), row(3));
+        let normalize = |plane: Vec4| plane / plane.xyz().magnitude();
+        Frustum {
+            planes: [
+                normalize(r3 + r0), // izquierdo
+                normalize(r3 - r0), // derecho
+                normalize(r3 + r1), // inferior
+                normalize(r3 - r1), // superior
+                normalize(r3 + r2), // cercano
+                normalize(r3 - r2), // lejano
+            ],
+        }
+    }
+
+    pub fn contains_sphere(&self, center: Vec3, radius: f32) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.xyz().dot(&center) + plane.w >= -radius)
+    }
+}
+
+// Radio de la esfera centrada en el origen del modelo que contiene todos sus vértices
+pub fn bounding_radius(vertices: &[Vertex]) -> f32 {
+    vertices
+        .iter()
+        .map(|vertex| vertex.position.magnitude())
+        .fold(0.0, f32::max)
+}
+
+// Centro y radio en el mundo de la esfera envolvente tras aplicar `model_matrix`
+pub fn world_sphere(model_matrix: &Mat4, local_radius: f32) -> (Vec3, f32) {
+    let center = (model_matrix * Vec4::new(0.0, 0.0, 0.0, 1.0)).xyz();
+    let max_scale = (0..3)
+        .map(|i| model_matrix.column(i).xyz().magnitude())
+        .fold(0.0, f32::max);
+    (center, local_radius * max_scale)
+}
@@ -0,0 +1,37 @@
+// Shaders de planeta que se agregan por el registro (`shader_registry`) en
+// lugar de por `shaders.rs`: sirven de ejemplo de cómo sumar un tipo de
+// planeta nuevo con sus parámetros sin tocar el resto del pipeline.
+
+use nalgebra_glm::Vec3;
+
+use crate::shader_registry::{ShaderParam, ShaderRegistry};
+use crate::shaders::{fbm_filtered, Surface};
+
+// Registro con los shaders de este módulo, listo para `shader_registry::install`
+pub fn registry() -> ShaderRegistry {
+    let mut registry = ShaderRegistry::default();
+    let banded = vec![
+        // Cantidad de franjas de polo a polo
+        ShaderParam::new("bands", 8.0, 1.0, 40.0),
+        // Velocidad a la que se desplazan las franjas (negativo = al revés)
+        ShaderParam::new("speed", 0.3, -5.0, 5.0),
+        // Cuánto ondula el borde de cada franja
+        ShaderParam::new("turbulence", 0.4, 0.0, 2.0),
+    ];
+    registry
+        .register("banded", banded, shade_banded)
+        .expect("los nombres de los shaders de ejemplo no se repiten");
+    registry
+}
+
+// Franjas de color alternadas por latitud, con bordes ondulados por ruido
+fn shade_banded(point: Vec3, time: f32, footprint: f32, surface: &Surface) -> Vec3 {
+    let [bands, speed, turbulence] = [surface.params[0], surface.params[1], surface.params[2]];
+    let uv = point.normalize();
+    let warp = (fbm_filtered(uv * 3.0 + Vec3::new(time * speed, 0.0, 0.0), 3, 0.5, 2.0, footprint * 3.0) - 0.5) * turbulence;
+    let stripe = ((uv.y + warp) * bands * std::f32::consts::PI).sin() * 0.5 + 0.5;
+
+    let teal = Vec3::new(0.1, 0.45, 0.5);
+    let sand = Vec3::new(0.85, 0.75, 0.55);
+    teal.lerp(&sand, stripe)
+}
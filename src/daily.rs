@@ -0,0 +1,40 @@
+// Modo "sistema del día": la semilla procedural se deriva de la fecha (UTC)
+// para que todos los que lo ejecuten el mismo día exploren el mismo sistema.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Convierte días desde 1970-01-01 a (año, mes, día) en el calendario gregoriano
+// (algoritmo "civil_from_days" de Howard Hinnant)
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+// Semilla del día con el formato AAAAMMDD, fácil de leer y compartir
+pub fn today_seed() -> u32 {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default();
+    let (year, month, day) = civil_from_days(seconds.div_euclid(86_400));
+    year as u32 * 10_000 + month * 100 + day
+}
+
+// Mezcla la semilla del sistema con un índice para obtener valores
+// independientes por cuerpo (hash de enteros de Wang)
+pub fn mix_seed(seed: u32, index: u32) -> u32 {
+    let mut h = seed ^ index.wrapping_mul(0x9E37_79B9);
+    h = (h ^ 61) ^ (h >> 16);
+    h = h.wrapping_add(h << 3);
+    h ^= h >> 4;
+    h = h.wrapping_mul(0x27D4_EB2D);
+    h ^ (h >> 15)
+}
@@ -0,0 +1,130 @@
+// Modos de depuración del rasterizador (tecla G): en lugar de la imagen
+// sombreada se ve el z-buffer en grises, las aristas de los triángulos, las
+// normales como color o cuántos fragmentos cayeron en cada píxel. Sirven para
+// revisar el recorte, la profundidad y el costo de cada cuerpo.
+
+use nalgebra_glm::{Mat4, Vec3, Vec4};
+
+use crate::framebuffer::{pack_color, Framebuffer};
+use crate::vertex::Vertex;
+
+const WIREFRAME_COLOR: u32 = 0x40FF80;
+// Fragmentos por píxel que ya se pintan del color más caliente
+const OVERDRAW_MAX: f32 = 8.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DebugView {
+    #[default]
+    Shaded,
+    Depth,
+    Wireframe,
+    Normals,
+    Overdraw,
+}
+
+impl DebugView {
+    pub fn next(self) -> Self {
+        match self {
+            DebugView::Shaded => DebugView::Depth,
+            DebugView::Depth => DebugView::Wireframe,
+            DebugView::Wireframe => DebugView::Normals,
+            DebugView::Normals => DebugView::Overdraw,
+            DebugView::Overdraw => DebugView::Shaded,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DebugView::Shaded => "Sombreado",
+            DebugView::Depth => "Profundidad",
+            DebugView::Wireframe => "Alambre",
+            DebugView::Normals => "Normales",
+            DebugView::Overdraw => "Sobredibujado",
+        }
+    }
+
+    // Reemplaza la imagen por el z-buffer o el conteo de fragmentos; los
+    // demás modos ya se dibujaron así durante el rasterizado
+    pub fn resolve(self, framebuffer: &mut Framebuffer, projection_matrix: &Mat4) {
+        match self {
+            DebugView::Depth => render_depth(framebuffer, projection_matrix),
+            DebugView::Overdraw => render_overdraw(framebuffer),
+            _ => {}
+        }
+    }
+}
+
+// Normal en -1..1 llevada a un color 0..1 (x rojo, y verde, z azul)
+pub fn normal_color(normal: Vec3) -> Vec3 {
+    normal * 0.5 + Vec3::new(0.5, 0.5, 0.5)
+}
+
+// Aristas del triángulo en pantalla, con la profundidad de cada vértice.
+// Las caras de espaldas se omiten igual que en el rasterizador, así se ve
+// exactamente qué triángulos llegan a pintarse.
+pub fn draw_wireframe(framebuffer: &mut Framebuffer, viewport_matrix: &Mat4, vertices: [&Vertex; 3]) {
+    let mut screen = [Vec3::zeros(); 3];
+    for (point, vertex) in screen.iter_mut().zip(vertices) {
+        let clip = vertex.transformed_position;
+        if clip.w.abs() < 1e-6 {
+            return;
+        }
+        let ndc = clip.xyz() / clip.w;
+        let position = viewport_matrix * Vec4::new(ndc.x, ndc.y, ndc.z, 1.0);
+        *point = Vec3::new(position.x, position.y, ndc.z);
+    }
+    let [a, b, c] = screen;
+    let area = (c.x - a.x) * (b.y - a.y) - (c.y - a.y) * (b.x - a.x);
+    if area <= 0.0 {
+        return;
+    }
+    framebuffer.set_current_color(WIREFRAME_COLOR);
+    framebuffer.draw_line(a, b);
+    framebuffer.draw_line(b, c);
+    framebuffer.draw_line(c, a);
+}
+
+// Profundidad lineal (distancia a la cámara) de cada píxel en grises: el
+// más cercano del cuadro en blanco y el más lejano en gris oscuro; el fondo
+// sin geometría queda negro
+fn render_depth(framebuffer: &mut Framebuffer, projection_matrix: &Mat4) {
+    // Inversa de la proyección en z: z_ndc = -P22 - P23 / z_vista
+    let (p22, p23) = (projection_matrix[(2, 2)], projection_matrix[(2, 3)]);
+    let linear = |depth: f32| p23 / (depth + p22);
+    let (near, far) = framebuffer
+        .zbuffer
+        .iter()
+        .filter(|depth| depth.is_finite())
+        .map(|&depth| linear(depth))
+        .fold((f32::INFINITY, 0.0f32), |(near, far), distance| (near.min(distance), far.max(distance)));
+    let range = (far - near).max(1e-6);
+    for (pixel, &depth) in framebuffer.buffer.iter_mut().zip(&framebuffer.zbuffer) {
+        *pixel = if depth.is_finite() {
+            let gray = 1.0 - 0.85 * (linear(depth) - near) / range;
+            pack_color(Vec3::new(gray, gray, gray))
+        } else {
+            0x000000
+        };
+    }
+}
+
+// Mapa de calor de los fragmentos rasterizados por píxel, pasen o no el test
+// de profundidad: azul con uno, luego verde, amarillo y rojo
+fn render_overdraw(framebuffer: &mut Framebuffer) {
+    const RAMP: [Vec3; 4] = [
+        Vec3::new(0.1, 0.2, 1.0),
+        Vec3::new(0.1, 0.9, 0.2),
+        Vec3::new(1.0, 0.9, 0.1),
+        Vec3::new(1.0, 0.1, 0.1),
+    ];
+    for (pixel, &count) in framebuffer.buffer.iter_mut().zip(&framebuffer.overdraw) {
+        if count == 0 {
+            *pixel = 0x000000;
+            continue;
+        }
+        let position = ((count - 1) as f32 / (OVERDRAW_MAX - 1.0)).min(1.0) * (RAMP.len() - 1) as f32;
+        let index = (position as usize).min(RAMP.len() - 2);
+        let t = position - index as f32;
+        *pixel = pack_color(RAMP[index].lerp(&RAMP[index + 1], t));
+    }
+}
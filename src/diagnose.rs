@@ -0,0 +1,312 @@
+// Autodiagnóstico (`--diagnose [carpeta]`): sin abrir la ventana dibuja una
+// serie de escenas de prueba (un triángulo, el test de profundidad, cada
+// shader de planeta, texto y mezcla con transparencia), comprueba en cada
+// una unos pocos píxeles cuyo valor se conoce de antemano y guarda las
+// imágenes como PNG junto con un informe `informe.txt` de lo que pasó y lo
+// que falló. Sirve para verificar una compilación nueva y para adjuntar a un
+// reporte de errores de dibujo que solo aparecen en alguna plataforma.
+
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+
+use nalgebra_glm::{look_at, perspective, Mat4, Vec2, Vec3};
+
+use crate::culling::Frustum;
+use crate::debug_view::DebugView;
+use crate::framebuffer::{pack_color, unpack_color, Framebuffer};
+use crate::lighting::{Light, Material};
+use crate::pixel_format::PixelFormat;
+use crate::scene::{shader_from_name, shader_names, shader_params};
+use crate::shaders::{Climate, PlanetShader, Surface, SpaceshipShader, StarShader, BARE_SURFACE};
+use crate::text::{CHAR_ADVANCE, LINE_HEIGHT};
+use crate::vertex::Vertex;
+use crate::{create_viewport_matrix, render_impostor, render_model, Uniforms};
+
+pub const DEFAULT_DIRECTORY: &str = "diagnostico";
+const SIZE: usize = 128;
+// Diferencia tolerada por canal al comparar colores (un escalón de 8 bits
+// más el redondeo)
+const TOLERANCE: f32 = 2.0 / 255.0;
+const CAMERA_DISTANCE: f32 = 3.0;
+
+// Resultado de una prueba: la imagen que dibujó y el motivo si falló
+struct Check {
+    name: String,
+    image: Framebuffer,
+    result: Result<(), String>,
+    millis: f32,
+}
+
+fn camera() -> (Mat4, Mat4, Mat4, Vec3) {
+    let eye = Vec3::new(0.0, 0.0, CAMERA_DISTANCE);
+    let view_matrix = look_at(&eye, &Vec3::zeros(), &Vec3::new(0.0, 1.0, 0.0));
+    let projection_matrix = perspective(1.0, 45f32.to_radians(), 0.1, 100.0);
+    let viewport_matrix = create_viewport_matrix(SIZE as f32, SIZE as f32);
+    (view_matrix, projection_matrix, viewport_matrix, eye)
+}
+
+// Uniforms de las pruebas: cámara fija y la luz detrás de ella
+fn uniforms<'a>(shader: &'a dyn PlanetShader, surface: &'a Surface, material: Material, model_matrix: Mat4) -> Uniforms<'a> {
+    let (view_matrix, projection_matrix, viewport_matrix, eye) = camera();
+    Uniforms {
+        model_matrix,
+        view_matrix,
+        projection_matrix,
+        viewport_matrix,
+        frustum: Frustum::from_matrix(&(projection_matrix * view_matrix)),
+        shader_time: 0.0,
+        shader,
+        seed: 0,
+        alpha: 1.0,
+        texture: None,
+        surface,
+        light: Light::new(eye * 10.0),
+        material,
+        camera_position: eye,
+        debug_view: DebugView::Shaded,
+        screen_lighting: false,
+        detail: 0.0,
+    }
+}
+
+fn pixel(framebuffer: &Framebuffer, x: usize, y: usize) -> Vec3 {
+    unpack_color(framebuffer.buffer[framebuffer.index(x, y)])
+}
+
+fn same_color(a: Vec3, b: Vec3) -> bool {
+    (a - b).amax() <= TOLERANCE
+}
+
+fn is_black(color: Vec3) -> bool {
+    color == Vec3::zeros()
+}
+
+// Triángulo de frente a la cámara en el plano z = `depth`, con el vértice
+// de arriba en el centro horizontal
+fn triangle_model(depth: f32, offset_x: f32) -> (Vec<Vertex>, Vec<u32>) {
+    let normal = Vec3::new(0.0, 0.0, 1.0);
+    let vertex = |x: f32, y: f32| Vertex::new(Vec3::new(x + offset_x, y, depth), normal, Vec2::zeros());
+    (vec![vertex(-0.8, -0.6), vertex(0.8, -0.6), vertex(0.0, 0.8)], vec![0, 1, 2])
+}
+
+fn draw_triangle(framebuffer: &mut Framebuffer, shader: &dyn PlanetShader, depth: f32, offset_x: f32) {
+    let (vertices, indices) = triangle_model(depth, offset_x);
+    let uniforms = uniforms(shader, &BARE_SURFACE, Material::new(true, 0.0, 1.0), Mat4::identity());
+    render_model(framebuffer, &uniforms, &vertices, &indices, 1.5);
+}
+
+fn new_image() -> Framebuffer {
+    let mut framebuffer = Framebuffer::new(SIZE, SIZE);
+    framebuffer.clear();
+    framebuffer
+}
+
+// Un triángulo: cubre su centro, no las esquinas, y su área en píxeles se
+// parece a la que da la proyección
+fn single_triangle() -> (Framebuffer, Result<(), String>) {
+    let mut framebuffer = new_image();
+    draw_triangle(&mut framebuffer, &SpaceshipShader, 0.0, 0.0);
+    let covered = framebuffer.buffer.iter().filter(|&&color| color != 0).count() as f32;
+    // Base 1.6 y altura 1.4 en el plano a distancia 3, con 45° de campo
+    let pixels_per_unit = SIZE as f32 * 0.5 / (CAMERA_DISTANCE * 22.5f32.to_radians().tan());
+    let expected = 0.5 * 1.6 * 1.4 * pixels_per_unit * pixels_per_unit;
+    let result = if is_black(pixel(&framebuffer, SIZE / 2, SIZE / 2)) {
+        Err("el centro del triángulo quedó vacío".to_string())
+    } else if !is_black(pixel(&framebuffer, 2, 2)) || !is_black(pixel(&framebuffer, SIZE - 3, 2)) {
+        Err("el triángulo cubre las esquinas superiores".to_string())
+    } else if (covered - expected).abs() > expected * 0.1 {
+        Err(format!("cubre {} píxeles, se esperaban unos {:.0}", covered, expected))
+    } else {
+        Ok(())
+    };
+    (framebuffer, result)
+}
+
+// Dos triángulos que se solapan: en la zona común gana el más cercano,
+// sin importar cuál se dibuje primero
+fn depth_test() -> (Framebuffer, Result<(), String>) {
+    let draw = |near_first: bool| {
+        let mut framebuffer = new_image();
+        let near = |framebuffer: &mut Framebuffer| draw_triangle(framebuffer, &SpaceshipShader, 0.5, -0.3);
+        let far = |framebuffer: &mut Framebuffer| draw_triangle(framebuffer, &StarShader, -0.5, 0.3);
+        if near_first {
+            near(&mut framebuffer);
+            far(&mut framebuffer);
+        } else {
+            far(&mut framebuffer);
+            near(&mut framebuffer);
+        }
+        framebuffer
+    };
+    let mut near_alone = new_image();
+    draw_triangle(&mut near_alone, &SpaceshipShader, 0.5, -0.3);
+    let near_first = draw(true);
+    let far_first = draw(false);
+
+    let (x, y) = (SIZE / 2, SIZE / 2);
+    let expected = pixel(&near_alone, x, y);
+    let result = if is_black(expected) {
+        Err("el triángulo cercano no cubre el centro".to_string())
+    } else if !same_color(pixel(&near_first, x, y), expected) {
+        Err("dibujado antes, el triángulo cercano quedó tapado por el lejano".to_string())
+    } else if !same_color(pixel(&far_first, x, y), expected) {
+        Err("dibujado después, el triángulo cercano no tapó al lejano".to_string())
+    } else {
+        Ok(())
+    };
+    (far_first, result)
+}
+
+// Una esfera con el shader: sin colores inválidos, con el centro pintado y
+// las esquinas vacías
+fn planet_shader(name: &str) -> (Framebuffer, Result<(), String>) {
+    let mut framebuffer = new_image();
+    let Some(shader) = shader_from_name(name) else {
+        return (framebuffer, Err(format!("no se encontró el shader '{}'", name)));
+    };
+    let surface = Surface {
+        craters: Vec::new(),
+        climate: Climate::TEMPERATE,
+        ring_palette: Vec::new(),
+        params: shader_params(name).iter().map(|param| param.default).collect(),
+    };
+    let uniforms = uniforms(shader, &surface, Material::new(false, 0.5, 32.0), Mat4::identity());
+    render_impostor(&mut framebuffer, &uniforms, Vec3::zeros(), 1.0);
+
+    let result = if framebuffer.hdr.iter().any(|color| !color.iter().all(|channel| channel.is_finite())) {
+        Err("el shader devolvió colores no finitos (NaN o infinito)".to_string())
+    } else if is_black(pixel(&framebuffer, SIZE / 2, SIZE / 2)) {
+        Err("el centro de la esfera quedó negro".to_string())
+    } else if !is_black(pixel(&framebuffer, 1, 1)) {
+        Err("la esfera se sale de su disco".to_string())
+    } else {
+        Ok(())
+    };
+    (framebuffer, result)
+}
+
+// Texto: pinta algo, con el color pedido, y nada fuera de su caja (más la sombra)
+fn text() -> (Framebuffer, Result<(), String>) {
+    let mut framebuffer = new_image();
+    let label = "PRUEBA 0123";
+    let color = 0x40E0FF;
+    let (left, top) = (4, 8);
+    framebuffer.draw_text(left, top, label, color);
+    let right = left + label.chars().count() * CHAR_ADVANCE + 1;
+    let bottom = top + LINE_HEIGHT + 1;
+    let mut lit = 0;
+    let mut outside = 0;
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let value = framebuffer.buffer[framebuffer.index(x, y)];
+            lit += (value == color) as usize;
+            outside += (value != 0 && !((left..right).contains(&x) && (top..bottom).contains(&y))) as usize;
+        }
+    }
+    let result = if lit == 0 {
+        Err("el texto no pintó ningún píxel".to_string())
+    } else if outside > 0 {
+        Err(format!("{} píxeles pintados fuera de la caja del texto", outside))
+    } else {
+        Ok(())
+    };
+    (framebuffer, result)
+}
+
+// Mezcla: un píxel al 50 % sobre otro da el promedio, tanto en la escena
+// (`point_hdr`, con profundidad) como en el HUD (`overlay_blend`)
+fn blending() -> (Framebuffer, Result<(), String>) {
+    let mut framebuffer = new_image();
+    let (red, blue) = (Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+    let expected = (red + blue) * 0.5;
+    for y in 0..SIZE {
+        for x in 0..SIZE / 2 {
+            framebuffer.point_hdr(x, y, 0.5, red, 1.0);
+            framebuffer.point_hdr(x, y, 0.0, blue, 0.5);
+        }
+        for x in SIZE / 2..SIZE {
+            framebuffer.overlay_pixel(x, y, pack_color(red));
+            framebuffer.overlay_blend(x, y, pack_color(blue), 0.5);
+        }
+    }
+    let scene = pixel(&framebuffer, SIZE / 4, SIZE / 2);
+    let overlay = pixel(&framebuffer, SIZE * 3 / 4, SIZE / 2);
+    let result = if !same_color(scene, expected) {
+        Err(format!("mezcla en la escena: {:.3?}, se esperaba {:.3?}", scene, expected))
+    } else if !same_color(overlay, expected) {
+        Err(format!("mezcla en el HUD: {:.3?}, se esperaba {:.3?}", overlay, expected))
+    } else {
+        Ok(())
+    };
+    (framebuffer, result)
+}
+
+fn timed(name: String, test: impl FnOnce() -> (Framebuffer, Result<(), String>)) -> Check {
+    let start = Instant::now();
+    let (image, result) = test();
+    Check { name, image, result, millis: start.elapsed().as_secs_f32() * 1000.0 }
+}
+
+fn save_png(path: &Path, framebuffer: &mut Framebuffer) -> Result<(), String> {
+    let bytes = PixelFormat::Rgba8.encode(framebuffer.present());
+    let image = image::RgbaImage::from_raw(framebuffer.width as u32, framebuffer.height as u32, bytes).ok_or("tamaño de imagen inválido")?;
+    image.save(path).map_err(|e| e.to_string())
+}
+
+// Corre todas las pruebas, guarda imágenes e informe en `directory` y
+// devuelve el código de salida: 0 si todas pasaron
+pub fn run(directory: &Path) -> i32 {
+    if let Err(e) = fs::create_dir_all(directory) {
+        eprintln!("No se pudo crear la carpeta {}: {}", directory.display(), e);
+        return 1;
+    }
+    let mut checks = vec![
+        timed("triangulo".to_string(), single_triangle),
+        timed("profundidad".to_string(), depth_test),
+    ];
+    for name in shader_names() {
+        checks.push(timed(format!("shader_{}", name), || planet_shader(name)));
+    }
+    checks.push(timed("texto".to_string(), text));
+    checks.push(timed("mezcla".to_string(), blending));
+
+    let mut report = format!(
+        "Autodiagnóstico de {} {} ({} {})\n\n",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+    let mut failures = 0;
+    for check in &mut checks {
+        let image_path = directory.join(format!("{}.png", check.name));
+        let line = match &check.result {
+            Ok(()) => format!("PASA  {:<20} {:>7.2} ms", check.name, check.millis),
+            Err(reason) => {
+                failures += 1;
+                format!("FALLA {:<20} {:>7.2} ms  {}", check.name, check.millis, reason)
+            }
+        };
+        println!("{}", line);
+        report.push_str(&line);
+        report.push('\n');
+        if let Err(e) = save_png(&image_path, &mut check.image) {
+            eprintln!("No se pudo guardar {}: {}", image_path.display(), e);
+        }
+    }
+    let summary = format!("\n{} de {} pruebas pasaron\n", checks.len() - failures, checks.len());
+    print!("{}", summary);
+    report.push_str(&summary);
+
+    let report_path = directory.join("informe.txt");
+    match fs::write(&report_path, report) {
+        Ok(()) => println!("Imágenes e informe en {}", directory.display()),
+        Err(e) => eprintln!("No se pudo escribir {}: {}", report_path.display(), e),
+    }
+    if failures == 0 {
+        0
+    } else {
+        1
+    }
+}
@@ -0,0 +1,129 @@
+// Acoplamiento a las estaciones: al entrar despacio en la zona de
+// acoplamiento de un `[[prop]]` acoplable (`dock_offset` y `dock_radius`) la
+// nave se traba en el collar y acompaña a la estación en su órbita y su giro,
+// sin responder a los controles. La cámara se aleja y da vueltas alrededor de
+// la estación; Enter entra al hangar y U desacopla: la nave se aparta sola del
+// collar y vuelve a volar al salir de la zona.
+
+use nalgebra_glm::{look_at, Mat4, Vec3};
+
+use crate::animation::rotate_y;
+use crate::scene::Scene;
+
+// Rapidez máxima con la que se puede entrar a la zona para acoplarse
+pub const DOCK_SPEED: f32 = 3.0;
+// La zona se anuncia en el HUD desde esta cantidad de radios suyos
+const APPROACH_RANGE: f32 = 4.0;
+// Fracción de la distancia al collar que se cierra por cuadro al trabarse
+const LOCK_EASE: f32 = 0.1;
+// Rapidez con que la nave se aparta del collar al desacoplar
+const UNDOCK_SPEED: f32 = 2.0;
+// Cámara de acoplamiento: distancia a la estación, altura, vueltas por
+// unidad de tiempo (en radianes) y fracción del camino que recorre por cuadro
+const ORBIT_DISTANCE: f32 = 12.0;
+const ORBIT_HEIGHT: f32 = 4.0;
+const ORBIT_RATE: f32 = 0.15;
+const CAMERA_EASE: f32 = 0.05;
+
+pub struct Docking {
+    pub station: usize,
+    // Posición de la nave respecto al collar, en los ejes de la estación
+    offset: Vec3,
+    // Rumbo de la nave respecto al giro de la estación
+    heading: f32,
+    // Ángulo de la cámara alrededor de la estación
+    orbit_angle: f32,
+    // Avance 0..1 de la cámara de acoplamiento
+    blend: f32,
+    leaving: bool,
+}
+
+// Estación acoplable con la zona más cercana a `ship_position`, si está a
+// menos de `range` radios de ella, y la distancia a su centro
+fn nearest_zone(scene: &Scene, ship_position: Vec3, range: f32) -> Option<(usize, f32)> {
+    scene
+        .props
+        .iter()
+        .zip(&scene.prop_states)
+        .enumerate()
+        .filter(|(_, (prop, _))| prop.dockable)
+        .map(|(index, (prop, state))| (index, (prop.dock_port(state) - ship_position).magnitude(), prop.dock_radius))
+        .filter(|(_, distance, radius)| *distance < radius * range)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(index, distance, _)| (index, distance))
+}
+
+impl Docking {
+    // Se acopla a la estación en cuya zona está la nave, si va a menos de
+    // `DOCK_SPEED`. `heading` es el rumbo de la nave.
+    pub fn check(scene: &Scene, ship_position: Vec3, ship_velocity: Vec3, heading: f32) -> Option<Self> {
+        if ship_velocity.magnitude() >= DOCK_SPEED {
+            return None;
+        }
+        let (station, _) = nearest_zone(scene, ship_position, 1.0)?;
+        let (prop, state) = (&scene.props[station], &scene.prop_states[station]);
+        let spin = state.rotation.y;
+        let to_station = state.position - ship_position;
+        Some(Docking {
+            station,
+            offset: rotate_y(ship_position - prop.dock_port(state), -spin),
+            // Girar los ejes `spin` radianes resta `spin` al rumbo
+            heading: heading + spin,
+            // La cámara empieza del lado de la nave
+            orbit_angle: (-to_station.z).atan2(-to_station.x),
+            blend: 0.0,
+            leaving: false,
+        })
+    }
+
+    // Estación acoplable cuya zona está cerca, para el aviso del HUD, y la
+    // distancia hasta ella
+    pub fn approach(scene: &Scene, ship_position: Vec3) -> Option<(usize, f32)> {
+        nearest_zone(scene, ship_position, APPROACH_RANGE)
+    }
+
+    // Lleva la nave al collar (o la aparta al desacoplar) y devuelve su
+    // posición y rumbo en el mundo
+    pub fn update(&mut self, scene: &Scene, dt: f32) -> (Vec3, f32) {
+        let (prop, state) = (&scene.props[self.station], &scene.prop_states[self.station]);
+        if self.leaving {
+            // Hacia afuera por el eje del collar; sin collar, por donde entró
+            let outward = Vec3::from(prop.dock_offset).try_normalize(1e-6).or_else(|| self.offset.try_normalize(1e-6)).unwrap_or_else(Vec3::y);
+            self.offset += outward * UNDOCK_SPEED * dt;
+            let remaining = 1.0 - self.offset.magnitude() / prop.dock_radius;
+            self.blend = self.blend.min(remaining.clamp(0.0, 1.0));
+        } else {
+            self.offset *= 1.0 - LOCK_EASE;
+            self.blend += (1.0 - self.blend) * CAMERA_EASE;
+        }
+        self.orbit_angle += ORBIT_RATE * dt;
+        let spin = state.rotation.y;
+        (prop.dock_port(state) + rotate_y(self.offset, spin), self.heading - spin)
+    }
+
+    // U: apartarse del collar
+    pub fn undock(&mut self) {
+        self.leaving = true;
+    }
+
+    // Trabada en el collar: se puede entrar al hangar
+    pub fn is_docked(&self) -> bool {
+        !self.leaving
+    }
+
+    // La nave ya salió de la zona y vuelve a volar
+    pub fn is_finished(&self, scene: &Scene) -> bool {
+        self.leaving && self.offset.magnitude() >= scene.props[self.station].dock_radius
+    }
+
+    // Cámara que da vueltas alrededor de la estación, mezclada con la de
+    // seguimiento (`follow_eye`) según el avance del acoplamiento. Devuelve la
+    // matriz de vista y la posición.
+    pub fn camera(&self, scene: &Scene, ship_position: Vec3, follow_eye: Vec3) -> (Mat4, Vec3) {
+        let center = scene.prop_states[self.station].position;
+        let orbit_eye = center + Vec3::new(self.orbit_angle.cos() * ORBIT_DISTANCE, ORBIT_HEIGHT, self.orbit_angle.sin() * ORBIT_DISTANCE);
+        let eye = follow_eye.lerp(&orbit_eye, self.blend);
+        let target = ship_position.lerp(&center, self.blend);
+        (look_at(&eye, &target, &Vec3::new(0.0, 1.0, 0.0)), eye)
+    }
+}
@@ -0,0 +1,65 @@
+// Modelo de vuelo con inercia: la nave tiene una velocidad que los controles
+// empujan (empuje) en lugar de moverla directamente. Sin arrastre sigue a la
+// deriva con la velocidad que lleva hasta que se la contrarresta o se frena
+// (Z); con `drag` la velocidad decae sola, como en un vuelo más arcade. El
+// impulso (ver `power`) sube el empuje y la velocidad máxima mientras dura.
+
+use nalgebra_glm::Vec3;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct FlightConfig {
+    // Velocidad máxima en unidades por unidad de tiempo de simulación
+    pub max_speed: f32,
+    // Aceleración de los motores con un control a fondo
+    pub thrust: f32,
+    // Fracción de la velocidad que se pierde por unidad de tiempo (0 = sin
+    // arrastre, vuelo newtoniano)
+    pub drag: f32,
+    // Desaceleración del freno
+    pub brake: f32,
+}
+
+impl Default for FlightConfig {
+    fn default() -> Self {
+        FlightConfig {
+            max_speed: 15.0,
+            thrust: 40.0,
+            drag: 0.0,
+            brake: 80.0,
+        }
+    }
+}
+
+impl FlightConfig {
+    // La configuración de la escena con la velocidad máxima y el empuje de
+    // una nave del hangar (factores sobre los de la escena)
+    pub fn scaled(&self, speed: f32, thrust: f32) -> Self {
+        FlightConfig { max_speed: self.max_speed * speed, thrust: self.thrust * thrust, ..self.clone() }
+    }
+
+    // Velocidad tras `dt` sin empuje: arrastre y límite de velocidad. Con
+    // impulso (`boost` > 1) el límite sube; al soltarlo, lo que lo excede se
+    // pierde con la fuerza del freno en lugar de cortarse de golpe.
+    pub fn coast(&self, velocity: Vec3, dt: f32, boost: f32) -> Vec3 {
+        let velocity = velocity * (1.0 - self.drag * dt).max(0.0);
+        let speed = velocity.magnitude();
+        let limit = self.max_speed * boost;
+        if speed > limit {
+            velocity * ((speed - self.brake * dt).max(limit) / speed)
+        } else {
+            velocity
+        }
+    }
+
+    // Velocidad tras frenar durante `dt`: baja sin cambiar de dirección
+    pub fn braked(&self, velocity: Vec3, dt: f32) -> Vec3 {
+        let speed = velocity.magnitude();
+        if speed <= self.brake * dt {
+            Vec3::zeros()
+        } else {
+            velocity * ((speed - self.brake * dt) / speed)
+        }
+    }
+}
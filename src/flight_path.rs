@@ -0,0 +1,151 @@
+// Grabación de la trayectoria de la nave (posiciones + tiempos) en JSON y
+// reproducción de una trayectoria exportada como estela "fantasma". Los
+// saltos por un agujero de gusano quedan marcados: el fantasma pasa de un
+// lado al otro sin recorrer el espacio entre ambos.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use nalgebra_glm::{Mat4, Vec3, Vec4};
+use serde::{Deserialize, Serialize};
+
+use crate::framebuffer::Framebuffer;
+
+// Distancia mínima entre puntos grabados, para no llenar el archivo con
+// puntos repetidos cuando la nave está quieta
+const MIN_SPACING: f32 = 0.25;
+const GHOST_COLOR: u32 = 0x66CCFF;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PathPoint {
+    // Tiempo de simulación en que se grabó el punto
+    pub time: f32,
+    pub position: [f32; 3],
+    // La nave llegó a este punto saltando desde el anterior
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub jump: bool,
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FlightPath {
+    pub points: Vec<PathPoint>,
+}
+
+impl FlightPath {
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let source = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&source)?)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn record(&mut self, time: f32, position: Vec3) {
+        let moved = match self.points.last() {
+            Some(last) => (Vec3::from(last.position) - position).magnitude() >= MIN_SPACING,
+            None => true,
+        };
+        if moved {
+            self.points.push(PathPoint { time, position: position.into(), jump: false });
+        }
+    }
+
+    // Punto de llegada de un salto, siempre se graba
+    pub fn record_jump(&mut self, time: f32, position: Vec3) {
+        self.points.push(PathPoint { time, position: position.into(), jump: true });
+    }
+
+    // Duración de la trayectoria desde el primer punto grabado
+    pub fn duration(&self) -> f32 {
+        match (self.points.first(), self.points.last()) {
+            (Some(first), Some(last)) => last.time - first.time,
+            _ => 0.0,
+        }
+    }
+
+    // Posición interpolada `elapsed` unidades después del primer punto
+    pub fn position_at(&self, elapsed: f32) -> Option<Vec3> {
+        let first = self.points.first()?;
+        let time = first.time + elapsed;
+        let next = self.points.iter().position(|p| p.time >= time);
+        match next {
+            Some(0) => Some(Vec3::from(first.position)),
+            Some(i) => {
+                let a = &self.points[i - 1];
+                let b = &self.points[i];
+                // Hasta el salto la nave sigue en el punto anterior
+                if b.jump {
+                    return Some(Vec3::from(if b.time <= time { b.position } else { a.position }));
+                }
+                let t = (time - a.time) / (b.time - a.time).max(f32::EPSILON);
+                Some(Vec3::from(a.position).lerp(&Vec3::from(b.position), t))
+            }
+            None => self.points.last().map(|p| Vec3::from(p.position)),
+        }
+    }
+
+    // Dónde está la nave fantasma en el instante `time`, al ritmo en que se
+    // grabó. En bucle vuelve a empezar al terminar; si no, se queda en el final.
+    pub fn replay_position(&self, time: f32, looping: bool) -> Option<Vec3> {
+        let duration = self.duration();
+        let elapsed = if looping && duration > 0.0 { time.rem_euclid(duration) } else { time };
+        self.position_at(elapsed)
+    }
+
+    // Velocidad de la nave fantasma en el instante `time`, medida sobre el
+    // último `dt`; cero si en ese tramo saltó
+    pub fn replay_velocity(&self, time: f32, dt: f32, looping: bool) -> Option<Vec3> {
+        let current = self.replay_position(time, looping)?;
+        let previous = self.replay_position(time - dt, looping)?;
+        let duration = self.duration();
+        let elapsed = |time: f32| if looping && duration > 0.0 { time.rem_euclid(duration) } else { time };
+        let first = self.points.first()?.time;
+        let (from, to) = (first + elapsed(time - dt), first + elapsed(time));
+        let jumped = self.points.iter().any(|point| point.jump && point.time > from && point.time <= to);
+        Some(if jumped || to < from { Vec3::zeros() } else { (current - previous) / dt })
+    }
+
+    // Dibuja la trayectoria como puntos translúcidos
+    pub fn render_ghost(&self, framebuffer: &mut Framebuffer, view_matrix: &Mat4, projection_matrix: &Mat4) {
+        for point in &self.points {
+            draw_marker(framebuffer, Vec3::from(point.position), 0, GHOST_COLOR, 0.35, view_matrix, projection_matrix);
+        }
+    }
+}
+
+// Cuadrado translúcido de (2·half_size + 1) píxeles centrado en `position`
+pub fn draw_marker(
+    framebuffer: &mut Framebuffer,
+    position: Vec3,
+    half_size: i32,
+    color: u32,
+    alpha: f32,
+    view_matrix: &Mat4,
+    projection_matrix: &Mat4,
+) {
+    let view = view_matrix * Vec4::new(position.x, position.y, position.z, 1.0);
+    if -view.z <= 0.1 {
+        return;
+    }
+    let clip = projection_matrix * view;
+    let ndc = clip.xyz() / clip.w;
+    if ndc.z < -1.0 || ndc.z > 1.0 {
+        return;
+    }
+
+    let screen_x = ((ndc.x * 0.5 + 0.5) * framebuffer.width as f32) as i32;
+    let screen_y = ((1.0 - (ndc.y * 0.5 + 0.5)) * framebuffer.height as f32) as i32;
+    for y in (screen_y - half_size)..=(screen_y + half_size) {
+        for x in (screen_x - half_size)..=(screen_x + half_size) {
+            if x >= 0 && y >= 0 {
+                framebuffer.point_blend(x as usize, y as usize, ndc.z, color, alpha);
+            }
+        }
+    }
+}
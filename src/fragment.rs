@@ -1,20 +1,29 @@
 use nalgebra_glm::{Vec2, Vec3};
 use crate::color::Color;
 
+#[derive(Clone)]
 pub struct Fragment {
     pub position: Vec2,
+    #[allow(dead_code)]
     pub color: Color,
     pub depth: f32,
     pub vertex_position: Vec3,
+    pub tex_coords: Vec2,
+    pub normal: Vec3,
+    pub world_position: Vec3,
 }
 
 impl Fragment {
+    #[allow(dead_code)]
     pub fn new(x: f32, y: f32, color: Color, depth: f32) -> Self {
         Fragment {
             position: Vec2::new(x, y),
             color,
             depth,
             vertex_position: Vec3::new(0.0, 0.0, 0.0),
+            tex_coords: Vec2::new(0.0, 0.0),
+            normal: Vec3::new(0.0, 0.0, 0.0),
+            world_position: Vec3::new(0.0, 0.0, 0.0),
         }
     }
 
@@ -24,6 +33,9 @@ impl Fragment {
             color,
             depth,
             vertex_position,
+            tex_coords: Vec2::new(0.0, 0.0),
+            normal: Vec3::new(0.0, 0.0, 0.0),
+            world_position: Vec3::new(0.0, 0.0, 0.0),
         }
     }
 }
\ No newline at end of file
@@ -1,26 +1,96 @@
 // framebuffer.rs
+//
+// Los buffers se guardan por bloques de `TILE_SIZE` x `TILE_SIZE` píxeles,
+// cada bloque seguido en memoria y fila por fila dentro de él, en lugar de
+// filas de toda la pantalla: un triángulo grande escribe en pocos bloques
+// contiguos en vez de saltar de fila en fila. Los píxeles se ubican con
+// `index`; la imagen en filas que esperan minifb y los PNG sale de `present`.
+
+use nalgebra_glm::Vec3;
+
+use crate::pixel_format::PixelFormat;
+
+// Lado de los bloques en píxeles
+pub const TILE_SIZE: usize = 32;
+const TILE_PIXELS: usize = TILE_SIZE * TILE_SIZE;
 
 pub struct Framebuffer {
     pub width: usize,
     pub height: usize,
+    // Bloques a lo ancho; los de los bordes derecho e inferior se guardan
+    // completos aunque sobresalgan de la imagen
+    tiles_x: usize,
     pub buffer: Vec<u32>,
     pub zbuffer: Vec<f32>,
+    // Color sin recortar de los píxeles escritos con `point_hdr`, usado por el
+    // bloom; vale 0 donde el píxel visible no viene de un shader HDR
+    pub hdr: Vec<Vec3>,
+    // Fragmentos rasterizados en cada píxel (modo de depuración de sobredibujado)
+    pub overdraw: Vec<u16>,
+    // Píxeles que tienen el color del shader todavía sin iluminar: los
+    // ilumina después el pase en pantalla (`screen_lighting`)
+    pub unlit: Vec<bool>,
+    // La imagen en filas, armada por `present`
+    linear: Vec<u32>,
     background_color: u32,
     current_color: u32,
 }
 
 impl Framebuffer {
     pub fn new(width: usize, height: usize) -> Self {
+        let (tiles_x, tiles_y) = (width.div_ceil(TILE_SIZE), height.div_ceil(TILE_SIZE));
+        let size = tiles_x * tiles_y * TILE_PIXELS;
         Framebuffer {
             width,
             height,
-            buffer: vec![0; width * height],
-            zbuffer: vec![f32::INFINITY; width * height],
+            tiles_x,
+            buffer: vec![0; size],
+            zbuffer: vec![f32::INFINITY; size],
+            hdr: vec![Vec3::zeros(); size],
+            overdraw: vec![0; size],
+            unlit: vec![false; size],
+            linear: vec![0; width * height],
             background_color: 0x000000,
             current_color: 0xFFFFFF,
         }
     }
 
+    // Posición del píxel (x, y) en los buffers
+    pub fn index(&self, x: usize, y: usize) -> usize {
+        (self.indexer())(x, y)
+    }
+
+    // `index` sin tomar prestado el framebuffer, para recorrer un buffer
+    // mientras se escribe en otro
+    pub fn indexer(&self) -> impl Fn(usize, usize) -> usize + Copy {
+        let tiles_x = self.tiles_x;
+        move |x, y| ((y / TILE_SIZE) * tiles_x + x / TILE_SIZE) * TILE_PIXELS + (y % TILE_SIZE) * TILE_SIZE + x % TILE_SIZE
+    }
+
+    // Bloque que contiene el píxel (x, y)
+    pub fn tile_at(&self, x: usize, y: usize) -> usize {
+        (y / TILE_SIZE) * self.tiles_x + x / TILE_SIZE
+    }
+
+    // Rectángulo de píxeles del bloque dentro de la imagen: (min_x, min_y,
+    // max_x, max_y), con los máximos incluidos
+    pub fn tile_bounds(&self, tile: usize) -> (usize, usize, usize, usize) {
+        let (min_x, min_y) = ((tile % self.tiles_x) * TILE_SIZE, (tile / self.tiles_x) * TILE_SIZE);
+        (min_x, min_y, (min_x + TILE_SIZE).min(self.width) - 1, (min_y + TILE_SIZE).min(self.height) - 1)
+    }
+
+    // La imagen en filas de arriba abajo, como la esperan la ventana y los PNG
+    pub fn present(&mut self) -> &[u32] {
+        for y in 0..self.height {
+            let row = &mut self.linear[y * self.width..(y + 1) * self.width];
+            for (tile_x, chunk) in row.chunks_mut(TILE_SIZE).enumerate() {
+                let start = ((y / TILE_SIZE) * self.tiles_x + tile_x) * TILE_PIXELS + (y % TILE_SIZE) * TILE_SIZE;
+                chunk.copy_from_slice(&self.buffer[start..start + chunk.len()]);
+            }
+        }
+        &self.linear
+    }
+
     pub fn clear(&mut self) {
         for pixel in self.buffer.iter_mut() {
             *pixel = self.background_color;
@@ -28,18 +98,170 @@ impl Framebuffer {
         for depth in self.zbuffer.iter_mut() {
             *depth = f32::INFINITY;
         }
+        self.hdr.fill(Vec3::zeros());
+        self.overdraw.fill(0);
+        self.unlit.fill(false);
+    }
+
+    // Cuenta un fragmento en el píxel, sin tocar el color ni la profundidad
+    pub fn count_fragment(&mut self, x: usize, y: usize) {
+        if x < self.width && y < self.height {
+            let index = self.index(x, y);
+            let count = &mut self.overdraw[index];
+            *count = count.saturating_add(1);
+        }
     }
 
+    #[allow(dead_code)]
     pub fn point(&mut self, x: usize, y: usize, depth: f32) {
         if x < self.width && y < self.height {
-            let index = y * self.width + x;
+            let index = self.index(x, y);
             if self.zbuffer[index] > depth {
                 self.buffer[index] = self.current_color;
                 self.zbuffer[index] = depth;
+                self.hdr[index] = Vec3::zeros();
+                self.unlit[index] = false;
+            }
+        }
+    }
+
+    // Si un fragmento en (x, y) pasaría el test de profundidad: sirve para
+    // descartarlo antes de sombrearlo (early-Z)
+    pub fn passes_depth(&self, x: usize, y: usize, depth: f32) -> bool {
+        x < self.width && y < self.height && self.zbuffer[self.index(x, y)] > depth
+    }
+
+    // Mezcla `color` sobre el píxel existente (source-over) si pasa el test de
+    // profundidad. Un fragmento translúcido no tapa lo que está detrás, así
+    // que no escribe la profundidad: lo que se dibuje después detrás de él se
+    // descarta contra lo opaco y no contra él, y por eso lo translúcido va
+    // después de lo opaco y de atrás hacia adelante. Con alpha 1 es un punto
+    // opaco y sí la escribe.
+    pub fn point_blend(&mut self, x: usize, y: usize, depth: f32, color: u32, alpha: f32) {
+        if x < self.width && y < self.height {
+            let index = self.index(x, y);
+            if self.zbuffer[index] > depth {
+                self.buffer[index] = blend_colors(color, self.buffer[index], alpha);
+                if alpha >= 1.0 {
+                    self.zbuffer[index] = depth;
+                }
+                self.hdr[index] *= 1.0 - alpha;
+                self.unlit[index] = false;
+            }
+        }
+    }
+
+    // Píxel de una línea con la cobertura `alpha`: se mezcla como
+    // `point_blend` pero escribe la profundidad, para que la línea siga tapando
+    // lo que pasa detrás de ella
+    fn point_coverage(&mut self, x: usize, y: usize, depth: f32, color: u32, alpha: f32) {
+        if x < self.width && y < self.height {
+            let index = self.index(x, y);
+            if self.zbuffer[index] > depth {
+                self.point_blend(x, y, depth, color, alpha);
+                self.zbuffer[index] = depth;
             }
         }
     }
 
+    // Escribe un color de shader que puede pasar de 1.0: se muestra recortado
+    // y el valor completo se guarda en `hdr` para el post-procesado
+    pub fn point_hdr(&mut self, x: usize, y: usize, depth: f32, color: Vec3, alpha: f32) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+        let index = self.index(x, y);
+        if self.zbuffer[index] <= depth {
+            return;
+        }
+        let hdr = self.hdr[index];
+        if alpha < 1.0 {
+            self.point_blend(x, y, depth, pack_color(color), alpha);
+            self.hdr[index] = hdr * (1.0 - alpha) + color * alpha;
+        } else {
+            self.buffer[index] = pack_color(color);
+            self.zbuffer[index] = depth;
+            self.hdr[index] = color;
+            self.unlit[index] = false;
+        }
+    }
+
+    // Como `point_hdr` opaco, pero con el color sin iluminar: queda marcado
+    // para que lo ilumine el pase en pantalla
+    pub fn point_unlit(&mut self, x: usize, y: usize, depth: f32, color: Vec3) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+        let index = self.index(x, y);
+        if self.zbuffer[index] > depth {
+            self.buffer[index] = pack_color(color);
+            self.zbuffer[index] = depth;
+            self.hdr[index] = color;
+            self.unlit[index] = true;
+        }
+    }
+
+    // Línea antialias (Xiaolin Wu) del color actual entre dos puntos de pantalla; `z` lleva la
+    // profundidad, que se interpola a lo largo de la línea. Cada paso sobre el
+    // eje mayor reparte el color entre los dos píxeles vecinos según la
+    // cobertura. Solo se recorre el tramo que cae dentro de la pantalla.
+    pub fn draw_line(&mut self, from: Vec3, to: Vec3) {
+        let color = self.current_color;
+        let steep = (to.y - from.y).abs() > (to.x - from.x).abs();
+        let (mut a, mut b) = if steep {
+            (Vec3::new(from.y, from.x, from.z), Vec3::new(to.y, to.x, to.z))
+        } else {
+            (from, to)
+        };
+        if a.x > b.x {
+            std::mem::swap(&mut a, &mut b);
+        }
+        if !(a.x.is_finite() && b.x.is_finite() && a.y.is_finite() && b.y.is_finite()) {
+            return;
+        }
+
+        let dx = b.x - a.x;
+        let gradient = if dx < 1e-6 { 0.0 } else { (b.y - a.y) / dx };
+        let major_limit = if steep { self.height } else { self.width } as f32;
+        let start = a.x.round().max(0.0);
+        let end = b.x.round().min(major_limit - 1.0);
+        if start > end {
+            return;
+        }
+
+        for major in start as usize..=end as usize {
+            let t = if dx < 1e-6 { 0.0 } else { ((major as f32 - a.x) / dx).clamp(0.0, 1.0) };
+            let minor = a.y + gradient * (major as f32 - a.x);
+            let depth = a.z + (b.z - a.z) * t;
+            let base = minor.floor();
+            let coverage = minor - base;
+            for (offset, alpha) in [(0.0, 1.0 - coverage), (1.0, coverage)] {
+                let minor = base + offset;
+                if minor < 0.0 || alpha <= 0.0 {
+                    continue;
+                }
+                let (x, y) = if steep { (minor as usize, major) } else { (major, minor as usize) };
+                self.point_coverage(x, y, depth, color, alpha);
+            }
+        }
+    }
+
+    // Píxel de interfaz: se dibuja encima de todo, sin test de profundidad
+    pub fn overlay_pixel(&mut self, x: usize, y: usize, color: u32) {
+        if x < self.width && y < self.height {
+            let index = self.index(x, y);
+            self.buffer[index] = color;
+        }
+    }
+
+    // Como `overlay_pixel`, pero mezclando con lo que ya hay debajo
+    pub fn overlay_blend(&mut self, x: usize, y: usize, color: u32, alpha: f32) {
+        if x < self.width && y < self.height {
+            let index = self.index(x, y);
+            self.buffer[index] = blend_colors(color, self.buffer[index], alpha);
+        }
+    }
+
     pub fn set_background_color(&mut self, color: u32) {
         self.background_color = color;
     }
@@ -47,4 +269,18 @@ impl Framebuffer {
     pub fn set_current_color(&mut self, color: u32) {
         self.current_color = color;
     }
-}
\ No newline at end of file
+}
+
+// Mezcla source-over de dos colores empaquetados
+fn blend_colors(src: u32, dst: u32, alpha: f32) -> u32 {
+    pack_color(unpack_color(src) * alpha + unpack_color(dst) * (1.0 - alpha))
+}
+
+// Color 0..1 al formato de trabajo del framebuffer (ver `pixel_format`)
+pub fn pack_color(color: Vec3) -> u32 {
+    PixelFormat::Xrgb8888.pack(color)
+}
+
+pub fn unpack_color(color: u32) -> Vec3 {
+    PixelFormat::Xrgb8888.unpack(color)
+}
@@ -0,0 +1,285 @@
+// Mapa galáctico (H): los sistemas de la galaxia como nodos sobre un plano.
+// El primero es el de partida (la escena cargada o la de `--generate`) y los
+// demás salen del generador procedural con semillas derivadas de la de la
+// galaxia (`--galaxy <semilla>`), así la misma semilla da siempre los mismos
+// vecinos. Con las flechas se elige un sistema y Enter viaja: se genera (o se
+// vuelve a cargar) su escena y la nave aparece en el borde, del lado desde el
+// que llegó, mirando hacia la estrella. Mientras el mapa está abierto el
+// sistema queda en pausa, como en el hangar.
+
+use std::f32::consts::TAU;
+use std::path::PathBuf;
+
+use minifb::{Key, KeyRepeat, Window};
+use nalgebra_glm::{Vec2, Vec3};
+
+use crate::framebuffer::{pack_color, Framebuffer};
+use crate::procgen::{self, Rng};
+use crate::scene::Scene;
+use crate::skybox::temperature_color;
+use crate::text;
+
+pub const DEFAULT_SEED: u64 = 1977;
+// Sistemas generados además del de partida
+const SYSTEM_COUNT: usize = 9;
+// Separación mínima entre sistemas, en unidades del mapa (0..1)
+const MIN_SPACING: f32 = 0.16;
+// Años luz por unidad del mapa, para mostrar la distancia del salto
+const LIGHT_YEARS: f32 = 40.0;
+// Un sistema elegido con las flechas tiene que estar dentro de este cono
+// alrededor de la dirección de la flecha (coseno del ángulo)
+const ARROW_CONE: f32 = 0.4;
+// La nave aparece esta distancia más allá de la órbita más lejana, pero nunca
+// a más de `MAX_ARRIVAL_DISTANCE` de la estrella para seguir viéndola
+const ARRIVAL_MARGIN: f32 = 15.0;
+const MAX_ARRIVAL_DISTANCE: f32 = 120.0;
+const BACKGROUND_STARS: usize = 300;
+const MARGIN: usize = 20;
+const TITLE_HEIGHT: usize = 50;
+const LABEL_COLOR: u32 = 0xE0E0E0;
+const INFO_COLOR: u32 = 0xA0A0A0;
+const SELECTED_COLOR: u32 = 0x80FF80;
+const ROUTE_COLOR: u32 = 0x408040;
+
+// De dónde sale la escena de un sistema
+pub enum Origin {
+    File(PathBuf),
+    Generated(u64),
+}
+
+pub struct StarSystem {
+    pub name: String,
+    pub origin: Origin,
+    // Posición en el mapa, en 0..1
+    position: Vec2,
+    star_scale: f32,
+    planets: usize,
+    moons: usize,
+    anomalies: usize,
+}
+
+impl StarSystem {
+    fn new(scene: &Scene, origin: Origin, position: Vec2) -> Self {
+        let planets = scene.bodies.iter().filter(|body| body.orbit_radius > 0.0 && body.parent.is_none()).count();
+        StarSystem {
+            name: scene.bodies.first().map_or_else(String::new, |star| star.name.clone()),
+            origin,
+            position,
+            star_scale: scene.bodies.iter().find(|body| body.emissive).map_or(1.0, |star| star.scale),
+            planets,
+            moons: scene.bodies.iter().filter(|body| body.parent.is_some()).count(),
+            anomalies: scene.anomalies.len(),
+        }
+    }
+
+    // Las estrellas más grandes del generador (escala 5 a 9) son más calientes
+    fn color(&self) -> u32 {
+        let kelvin = 3000.0 + (self.star_scale - 5.0).clamp(0.0, 4.0) / 4.0 * 9000.0;
+        pack_color(temperature_color(kelvin))
+    }
+}
+
+pub struct Galaxy {
+    pub systems: Vec<StarSystem>,
+    // Sistema en el que está la nave y el elegido en el mapa
+    pub current: usize,
+    selected: usize,
+    background: Vec<(Vec2, u32)>,
+    // Semilla de la galaxia mientras los vecinos no se generaron: se generan
+    // al abrir el mapa por primera vez, no al arrancar
+    pending_seed: Option<u64>,
+    // Reloj del mapa, para el aro que late sobre el sistema elegido
+    time: f32,
+}
+
+impl Galaxy {
+    // `home` es la escena de partida, que queda en el centro del mapa
+    pub fn new(seed: u64, home: &Scene, origin: Origin) -> Self {
+        Galaxy {
+            systems: vec![StarSystem::new(home, origin, Vec2::new(0.5, 0.5))],
+            current: 0,
+            selected: 0,
+            background: Vec::new(),
+            pending_seed: Some(seed),
+            time: 0.0,
+        }
+    }
+
+    // Al abrir el mapa queda elegido el sistema actual
+    pub fn open(&mut self) {
+        if let Some(seed) = self.pending_seed.take() {
+            self.generate_neighbours(seed);
+        }
+        self.selected = self.current;
+    }
+
+    // Vecinos y estrellas de fondo de la galaxia `seed`
+    fn generate_neighbours(&mut self, seed: u64) {
+        let mut rng = Rng(seed);
+        // Lugares al azar que no queden pegados a otro sistema
+        let mut attempts = 0;
+        while self.systems.len() <= SYSTEM_COUNT && attempts < 1000 {
+            attempts += 1;
+            let position = Vec2::new(rng.range(0.05, 0.95), rng.range(0.05, 0.95));
+            let system_seed = rng.next_u64();
+            if self.systems.iter().any(|system| (system.position - position).magnitude() < MIN_SPACING) {
+                continue;
+            }
+            match procgen::generate(system_seed) {
+                Ok(scene) => self.systems.push(StarSystem::new(&scene, Origin::Generated(system_seed), position)),
+                Err(e) => eprintln!("No se pudo generar el sistema {}: {}", system_seed, e),
+            }
+        }
+        self.background = (0..BACKGROUND_STARS)
+            .map(|_| {
+                let brightness = rng.range(0.1, 0.45);
+                (Vec2::new(rng.next_f32(), rng.next_f32()), pack_color(Vec3::new(brightness, brightness, brightness * 1.2)))
+            })
+            .collect();
+    }
+
+    // Flechas eligen el sistema más cercano en esa dirección; Enter devuelve
+    // el elegido si no es el actual
+    pub fn update(&mut self, window: &Window, dt: f32) -> Option<usize> {
+        self.time += dt;
+        let arrows = [(Key::Left, Vec2::new(-1.0, 0.0)), (Key::Right, Vec2::new(1.0, 0.0)), (Key::Up, Vec2::new(0.0, -1.0)), (Key::Down, Vec2::new(0.0, 1.0))];
+        for (key, direction) in arrows {
+            if window.is_key_pressed(key, KeyRepeat::Yes) {
+                let from = self.systems[self.selected].position;
+                let next = self
+                    .systems
+                    .iter()
+                    .enumerate()
+                    .map(|(index, system)| (index, system.position - from))
+                    .filter(|(_, offset)| offset.magnitude() > 0.0 && offset.normalize().dot(&direction) > ARROW_CONE)
+                    .min_by(|a, b| a.1.magnitude().total_cmp(&b.1.magnitude()));
+                if let Some((index, _)) = next {
+                    self.selected = index;
+                }
+            }
+        }
+        (window.is_key_pressed(Key::Enter, KeyRepeat::No) && self.selected != self.current).then_some(self.selected)
+    }
+
+    // Escena del sistema `index`, recién cargada o generada
+    pub fn load(&self, index: usize) -> Result<Scene, String> {
+        match &self.systems[index].origin {
+            Origin::File(path) => Scene::load(path).map_err(|e| e.to_string()),
+            Origin::Generated(seed) => procgen::generate(*seed).map_err(|e| e.to_string()),
+        }
+    }
+
+    // Dirección del salto del sistema actual al `index` en el plano XZ, para
+    // que la nave llegue del lado desde el que viene
+    pub fn heading_to(&self, index: usize) -> Vec3 {
+        let offset = self.systems[index].position - self.systems[self.current].position;
+        Vec3::new(offset.x, 0.0, offset.y).try_normalize(1e-6).unwrap_or_else(|| Vec3::new(1.0, 0.0, 0.0))
+    }
+
+    pub fn render(&self, framebuffer: &mut Framebuffer) {
+        let (width, height) = (framebuffer.width, framebuffer.height);
+        let area = (height.saturating_sub(TITLE_HEIGHT + 4 * text::LINE_HEIGHT + MARGIN)).min(width.saturating_sub(2 * MARGIN)) as f32;
+        let origin = Vec2::new((width as f32 - area) * 0.5, TITLE_HEIGHT as f32);
+        let to_screen = |position: Vec2| origin + position * area;
+
+        for (position, color) in &self.background {
+            let point = to_screen(*position);
+            framebuffer.overlay_pixel(point.x as usize, point.y as usize, *color);
+        }
+        framebuffer.draw_text(MARGIN, 16, "MAPA GALÁCTICO", 0xFFD080);
+
+        // Ruta del salto, del sistema actual al elegido
+        let (from, to) = (to_screen(self.systems[self.current].position), to_screen(self.systems[self.selected].position));
+        draw_line(framebuffer, from, to, ROUTE_COLOR);
+
+        for (index, system) in self.systems.iter().enumerate() {
+            let center = to_screen(system.position);
+            let radius = 2.0 + system.star_scale * 0.4;
+            fill_circle(framebuffer, center, radius, system.color());
+            if index == self.current {
+                draw_ring(framebuffer, center, radius + 4.0, 0xFFD080);
+            }
+            if index == self.selected {
+                // El aro del elegido late despacio
+                let pulse = 7.0 + (self.time * 4.0).sin() * 1.5;
+                draw_ring(framebuffer, center, radius + pulse, SELECTED_COLOR);
+            }
+            let color = if index == self.selected { SELECTED_COLOR } else { LABEL_COLOR };
+            let label_x = (center.x - system.name.chars().count() as f32 * 4.0).max(0.0) as usize;
+            framebuffer.draw_text(label_x, (center.y + radius + 10.0) as usize, &system.name, color);
+        }
+
+        let system = &self.systems[self.selected];
+        let info_top = height.saturating_sub(4 * text::LINE_HEIGHT + MARGIN);
+        let title = if self.selected == self.current { format!("{} (sistema actual)", system.name) } else { system.name.clone() };
+        framebuffer.draw_text(MARGIN, info_top, &title, SELECTED_COLOR);
+        let mut details = format!("{} planetas, {} lunas", system.planets, system.moons);
+        if system.anomalies > 0 {
+            details.push_str(&format!(", {} anomalías", system.anomalies));
+        }
+        let origin = match &system.origin {
+            Origin::File(path) => format!("Escena: {}", path.display()),
+            Origin::Generated(seed) => format!("Semilla: {}", seed),
+        };
+        let distance = (system.position - self.systems[self.current].position).magnitude() * LIGHT_YEARS;
+        framebuffer.draw_text(MARGIN, info_top + text::LINE_HEIGHT, &details, INFO_COLOR);
+        framebuffer.draw_text(MARGIN, info_top + 2 * text::LINE_HEIGHT, &format!("{}   Distancia: {:.1} años luz", origin, distance), INFO_COLOR);
+        framebuffer.draw_text(MARGIN, info_top + 3 * text::LINE_HEIGHT, "Flechas: elegir   Enter: viajar   H: volver al vuelo", LABEL_COLOR);
+    }
+}
+
+// Punto de llegada a `scene` (ya actualizada) viajando en la dirección
+// `heading` del plano XZ: más allá de la órbita más lejana, del lado por el
+// que se entra, y mirando a la estrella. Devuelve la posición y el rumbo.
+pub fn arrival(scene: &Scene, heading: Vec3) -> (Vec3, f32) {
+    let center = scene.star_position();
+    let edge = scene
+        .bodies
+        .iter()
+        .filter(|body| body.parent.is_none())
+        .map(|body| body.orbit_radius * (1.0 + body.eccentricity) + body.scale)
+        .fold(0.0, f32::max);
+    let distance = (edge + ARRIVAL_MARGIN).min(MAX_ARRIVAL_DISTANCE);
+    let position = center - heading * distance;
+    let to_star = center - position;
+    (position, to_star.z.atan2(to_star.x))
+}
+
+fn draw_line(framebuffer: &mut Framebuffer, from: Vec2, to: Vec2, color: u32) {
+    let steps = (to - from).abs().max().ceil() as usize;
+    for step in 0..=steps {
+        let t = if steps == 0 { 0.0 } else { step as f32 / steps as f32 };
+        // Trazos cortados, como una ruta todavía no recorrida
+        if (step / 4) % 2 == 1 {
+            continue;
+        }
+        let point = from.lerp(&to, t);
+        framebuffer.overlay_pixel(point.x as usize, point.y as usize, color);
+    }
+}
+
+fn fill_circle(framebuffer: &mut Framebuffer, center: Vec2, radius: f32, color: u32) {
+    let reach = radius.ceil() as i32;
+    for dy in -reach..=reach {
+        for dx in -reach..=reach {
+            if (dx * dx + dy * dy) as f32 > radius * radius {
+                continue;
+            }
+            let (x, y) = (center.x + dx as f32, center.y + dy as f32);
+            if x >= 0.0 && y >= 0.0 {
+                framebuffer.overlay_pixel(x as usize, y as usize, color);
+            }
+        }
+    }
+}
+
+fn draw_ring(framebuffer: &mut Framebuffer, center: Vec2, radius: f32, color: u32) {
+    let segments = (radius * 6.0).ceil() as usize;
+    for segment in 0..segments {
+        let angle = segment as f32 / segments as f32 * TAU;
+        let (x, y) = (center.x + angle.cos() * radius, center.y + angle.sin() * radius);
+        if x >= 0.0 && y >= 0.0 {
+            framebuffer.overlay_pixel(x as usize, y as usize, color);
+        }
+    }
+}
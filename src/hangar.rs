@@ -0,0 +1,254 @@
+// Interior del hangar al acoplarse a una estación: una escena pequeña con
+// sus propias mallas (piso, paredes y plataformas), luz cálida interior y
+// una cámara que solo gira un poco alrededor de las naves. Aquí se elige la
+// nave y la misión antes de volver a salir.
+
+use nalgebra_glm::{look_at, perspective, Mat4, Vec2, Vec3};
+use minifb::{Key, KeyRepeat, Window};
+
+use crate::course::Course;
+use crate::culling::Frustum;
+use crate::debug_view::DebugView;
+use crate::framebuffer::Framebuffer;
+use crate::lighting::{Light, Material};
+use crate::scene::Scene;
+use crate::shaders::{PlanetShader, BARE_SURFACE, CheckpointShader, SpaceshipShader};
+use crate::ships::Ship;
+use crate::text;
+use crate::texture::Texture;
+use crate::vertex::Vertex;
+use crate::{create_model_matrix, create_viewport_matrix, render_model, Uniforms, SHIP_REFLECTIVITY};
+
+// Límite del giro de la cámara a cada lado, en grados
+const CAMERA_YAW_LIMIT: f32 = 35.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mission {
+    Free,
+    Race,
+    Course,
+    Investigate,
+}
+
+impl Mission {
+    pub fn name(self) -> &'static str {
+        match self {
+            Mission::Free => "Vuelo libre",
+            Mission::Race => "Carrera por puntos de control",
+            Mission::Course => "Circuito de anillos",
+            Mission::Investigate => "Investigar anomalías",
+        }
+    }
+}
+
+// Vuelo libre siempre; carrera, circuito e investigación solo si la escena
+// los permite
+pub fn available_missions(scene: &Scene) -> Vec<Mission> {
+    let mut missions = vec![Mission::Free];
+    if scene.race.is_some() {
+        missions.push(Mission::Race);
+    }
+    if Course::generate(scene, &scene.course.clone().unwrap_or_default()).is_some() {
+        missions.push(Mission::Course);
+    }
+    if !scene.anomalies.is_empty() {
+        missions.push(Mission::Investigate);
+    }
+    missions
+}
+
+// Lo elegido al salir del hangar
+pub struct Departure {
+    pub ship: usize,
+    pub mission: Mission,
+}
+
+// Caja centrada en el origen con caras hacia afuera
+fn box_mesh(half: Vec3) -> (Vec<Vertex>, Vec<u32>) {
+    let faces = [
+        (Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 0.0, 1.0)),
+        (Vec3::new(-1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 0.0, -1.0)),
+        (Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 0.0, 1.0), Vec3::new(1.0, 0.0, 0.0)),
+        (Vec3::new(0.0, -1.0, 0.0), Vec3::new(0.0, 0.0, -1.0), Vec3::new(1.0, 0.0, 0.0)),
+        (Vec3::new(0.0, 0.0, 1.0), Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0)),
+        (Vec3::new(0.0, 0.0, -1.0), Vec3::new(-1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0)),
+    ];
+    let mut vertices = Vec::with_capacity(24);
+    let mut indices = Vec::with_capacity(36);
+    for (normal, u, v) in faces {
+        let base = vertices.len() as u32;
+        for (su, sv) in [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)] {
+            let position = (normal + u * su + v * sv).component_mul(&half);
+            vertices.push(Vertex::new(position, normal, Vec2::new((su + 1.0) * 0.5, (sv + 1.0) * 0.5)));
+        }
+        indices.extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
+    }
+    (vertices, indices)
+}
+
+struct Piece {
+    vertices: Vec<Vertex>,
+    indices: Vec<u32>,
+    position: Vec3,
+}
+
+pub struct Hangar {
+    station: String,
+    missions: Vec<Mission>,
+    ship: usize,
+    ship_count: usize,
+    mission: usize,
+    camera_yaw: f32,
+    time: f32,
+    pieces: Vec<Piece>,
+    pad: (Vec<Vertex>, Vec<u32>),
+}
+
+// Las naves esperan en plataformas alineadas sobre el eje X, centradas
+fn pad_position(index: usize, count: usize) -> Vec3 {
+    Vec3::new((index as f32 - (count as f32 - 1.0) * 0.5) * 4.0, 0.0, 0.0)
+}
+
+impl Hangar {
+    // `ship` y `mission` son la elección actual, que queda marcada al entrar;
+    // `ship_count` las naves del registro
+    pub fn new(station: &str, ship: usize, ship_count: usize, mission: Mission, missions: Vec<Mission>) -> Self {
+        let piece = |half: Vec3, position: Vec3| {
+            let (vertices, indices) = box_mesh(half);
+            Piece { vertices, indices, position }
+        };
+        Hangar {
+            station: station.to_string(),
+            mission: missions.iter().position(|m| *m == mission).unwrap_or(0),
+            missions,
+            ship: ship.min(ship_count - 1),
+            ship_count,
+            camera_yaw: 0.0,
+            time: 0.0,
+            pieces: vec![
+                piece(Vec3::new(9.0, 0.1, 6.0), Vec3::new(0.0, -0.7, 0.0)),   // piso
+                piece(Vec3::new(9.0, 4.0, 0.1), Vec3::new(0.0, 3.2, -6.0)),   // pared del fondo
+                piece(Vec3::new(0.1, 4.0, 6.0), Vec3::new(-9.0, 3.2, 0.0)),   // pared izquierda
+                piece(Vec3::new(0.1, 4.0, 6.0), Vec3::new(9.0, 3.2, 0.0)),    // pared derecha
+                piece(Vec3::new(9.0, 0.1, 6.0), Vec3::new(0.0, 7.3, 0.0)),    // techo
+            ],
+            pad: box_mesh(Vec3::new(1.4, 0.1, 1.4)),
+        }
+    }
+
+    // Flechas izquierda/derecha eligen nave, arriba/abajo misión, A/D giran
+    // la cámara y Enter despega con lo elegido
+    pub fn update(&mut self, window: &Window, dt: f32) -> Option<Departure> {
+        self.time += dt;
+        if window.is_key_pressed(Key::Left, KeyRepeat::No) {
+            self.ship = (self.ship + self.ship_count - 1) % self.ship_count;
+        }
+        if window.is_key_pressed(Key::Right, KeyRepeat::No) {
+            self.ship = (self.ship + 1) % self.ship_count;
+        }
+        if window.is_key_pressed(Key::Up, KeyRepeat::No) {
+            self.mission = (self.mission + self.missions.len() - 1) % self.missions.len();
+        }
+        if window.is_key_pressed(Key::Down, KeyRepeat::No) {
+            self.mission = (self.mission + 1) % self.missions.len();
+        }
+        let turn = window.is_key_down(Key::D) as i32 - window.is_key_down(Key::A) as i32;
+        self.camera_yaw = (self.camera_yaw + turn as f32 * 0.8).clamp(-CAMERA_YAW_LIMIT, CAMERA_YAW_LIMIT);
+
+        if window.is_key_pressed(Key::Enter, KeyRepeat::No) {
+            return Some(Departure { ship: self.ship, mission: self.missions[self.mission] });
+        }
+        None
+    }
+
+    pub fn render<'a>(&self, framebuffer: &mut Framebuffer, ships: &'a [Ship]) {
+        let yaw = self.camera_yaw.to_radians();
+        let camera_position = Vec3::new(yaw.sin() * 11.0, 3.5, yaw.cos() * 11.0);
+        let view_matrix = look_at(&camera_position, &Vec3::new(0.0, 0.5, 0.0), &Vec3::new(0.0, 1.0, 0.0));
+        let viewport_matrix = create_viewport_matrix(framebuffer.width as f32, framebuffer.height as f32);
+        let projection_matrix = perspective(framebuffer.width as f32 / framebuffer.height as f32, 50.0f32.to_radians(), 0.1, 60.0);
+        let frustum = Frustum::from_matrix(&(projection_matrix * view_matrix));
+        // Luz cálida colgada del techo, con más luz ambiental que en el espacio
+        let light = Light {
+            position: Vec3::new(0.0, 6.5, 3.0),
+            color: Vec3::new(1.0, 0.85, 0.65),
+            ambient: Vec3::repeat(0.25),
+            occluder: None,
+            radius: 0.0,
+            eclipsers: &[],
+            rings: None,
+        };
+        let uniforms = |model_matrix: Mat4, shader: &'a dyn PlanetShader, texture: Option<&'a Texture>, material: Material| Uniforms {
+            model_matrix,
+            view_matrix,
+            projection_matrix,
+            viewport_matrix,
+            frustum,
+            shader_time: self.time,
+            shader,
+            seed: 0,
+            alpha: 1.0,
+            texture,
+            surface: &BARE_SURFACE,
+            light,
+            material,
+            camera_position,
+            debug_view: DebugView::Shaded,
+            screen_lighting: false,
+            detail: 0.0,
+        };
+        let metal = Material::new(false, 0.3, 24.0);
+
+        for piece in &self.pieces {
+            let model_matrix = create_model_matrix(piece.position, 1.0, Vec3::zeros());
+            let radius = crate::culling::bounding_radius(&piece.vertices);
+            render_model(framebuffer, &uniforms(model_matrix, &SpaceshipShader, None, metal), &piece.vertices, &piece.indices, radius);
+        }
+
+        let pad_radius = crate::culling::bounding_radius(&self.pad.0);
+        for (index, ship) in ships.iter().enumerate() {
+            let position = pad_position(index, ships.len());
+            let selected = index == self.ship;
+            // La plataforma elegida brilla y su nave gira despacio
+            let (pad_shader, pad_material): (&dyn PlanetShader, _) = if selected {
+                (&CheckpointShader, Material::new(true, 0.0, 32.0))
+            } else {
+                (&SpaceshipShader, metal)
+            };
+            let pad_matrix = create_model_matrix(position - Vec3::new(0.0, 0.5, 0.0), 1.0, Vec3::zeros());
+            render_model(framebuffer, &uniforms(pad_matrix, pad_shader, None, pad_material), &self.pad.0, &self.pad.1, pad_radius);
+
+            let spin = if selected { self.time * 1.5 } else { 0.0 };
+            let ship_matrix = create_model_matrix(position + Vec3::new(0.0, 0.6, 0.0), ship.config.scale, Vec3::new(0.0, std::f32::consts::FRAC_PI_2 + spin, 0.0));
+            for (part, texture) in &ship.parts {
+                let (shader, texture) = ship.look(texture);
+                let ship_uniforms = uniforms(ship_matrix, shader, texture, Material::new(false, 0.5, 32.0).with_reflection(SHIP_REFLECTIVITY, part.roughness));
+                render_model(framebuffer, &ship_uniforms, &part.vertices, &part.indices, ship.radius);
+            }
+        }
+
+        self.render_menu(framebuffer, ships);
+    }
+
+    fn render_menu(&self, framebuffer: &mut Framebuffer, ships: &[Ship]) {
+        let mut y = 10;
+        let mut line = |framebuffer: &mut Framebuffer, text: &str, color: u32| {
+            framebuffer.draw_text(10, y, text, color);
+            y += text::LINE_HEIGHT;
+        };
+        line(framebuffer, &format!("HANGAR - {}", self.station), 0xFFD080);
+        line(framebuffer, "", 0);
+        line(framebuffer, "Nave (izq/der):", 0xE0E0E0);
+        for (index, ship) in ships.iter().enumerate() {
+            let (marker, color) = if index == self.ship { ('>', 0x80FF80) } else { (' ', 0xA0A0A0) };
+            line(framebuffer, &format!("{} {} - {}", marker, ship.config.name, ship.config.description), color);
+        }
+        line(framebuffer, "", 0);
+        line(framebuffer, "Misión (arriba/abajo):", 0xE0E0E0);
+        for (index, mission) in self.missions.iter().enumerate() {
+            let (marker, color) = if index == self.mission { ('>', 0x80FF80) } else { (' ', 0xA0A0A0) };
+            line(framebuffer, &format!("{} {}", marker, mission.name()), color);
+        }
+        framebuffer.draw_text(10, framebuffer.height - 30, "Enter: despegar   A/D: girar la cámara", 0xE0E0E0);
+    }
+}
@@ -0,0 +1,177 @@
+// Integridad del casco: los choques que los escudos ya no aguantan dañan la
+// nave según la velocidad del golpe. La integridad se muestra con una barra
+// arriba al centro del HUD, cada golpe tiñe la pantalla de rojo y con el casco
+// dañado la nave echa humo, y chispas cuando está por romperse. Al llegar a
+// cero la nave queda destruida y reaparece en un punto de partida seguro con
+// el casco nuevo; el hangar de una estación también lo repara.
+
+use nalgebra_glm::{Mat4, Vec3};
+
+use crate::framebuffer::{pack_color, Framebuffer};
+use crate::particles::{ParticleConfig, ParticleSystem};
+use crate::procgen::Rng;
+use crate::text;
+
+// Velocidad de choque que se lleva el casco completo, y la que no lo daña
+const HULL_IMPACT_SPEED: f32 = 25.0;
+const MIN_DAMAGE_SPEED: f32 = 2.0;
+// Por debajo de esta integridad sale humo, y por debajo de la otra, chispas
+const SMOKE_INTEGRITY: f32 = 0.5;
+const SPARK_INTEGRITY: f32 = 0.25;
+// Tinte rojo: cuánto suma cada punto de daño, el máximo y cuánto se apaga
+// por unidad de tiempo
+const FLASH_PER_DAMAGE: f32 = 3.0;
+const MAX_FLASH: f32 = 0.6;
+const FLASH_DECAY: f32 = 1.5;
+// Chispas de una explosión al destruirse la nave
+const BURST_SPARKS: usize = 60;
+const SPARK_SPEED: f32 = 4.0;
+
+// Barra del HUD: ancho y alto en píxeles, distancia al borde de arriba y
+// separación con el texto a sus lados
+const BAR_WIDTH: usize = 160;
+const BAR_HEIGHT: usize = 8;
+const MARGIN: usize = 10;
+const GAP: usize = 8;
+const LABEL: &str = "CASCO";
+
+pub struct Hull {
+    // 1 con el casco nuevo, 0 destruido
+    integrity: f32,
+    flash: f32,
+    smoke: ParticleSystem,
+    sparks: ParticleSystem,
+    rng: Rng,
+}
+
+impl Default for Hull {
+    fn default() -> Self {
+        let smoke = ParticleConfig {
+            max_particles: 150,
+            emit_per_frame: 1,
+            lifetime: 1.6,
+            size: 0.18,
+            start_color: [0.45, 0.42, 0.4],
+            end_color: [0.08, 0.08, 0.08],
+        };
+        let sparks = ParticleConfig {
+            max_particles: 200,
+            emit_per_frame: 1,
+            lifetime: 0.4,
+            size: 0.05,
+            start_color: [1.0, 0.95, 0.6],
+            end_color: [1.0, 0.35, 0.05],
+        };
+        Hull {
+            integrity: 1.0,
+            flash: 0.0,
+            smoke: ParticleSystem::new(smoke),
+            sparks: ParticleSystem::new(sparks),
+            rng: Rng(0x5EED_CA5C),
+        }
+    }
+}
+
+impl Hull {
+    // Choque a `impact_speed` sin escudos. Devuelve si destruyó la nave.
+    pub fn damage(&mut self, impact_speed: f32) -> bool {
+        let damage = ((impact_speed - MIN_DAMAGE_SPEED) / HULL_IMPACT_SPEED).max(0.0);
+        if damage == 0.0 {
+            return false;
+        }
+        self.integrity = (self.integrity - damage).max(0.0);
+        self.flash = (self.flash + damage * FLASH_PER_DAMAGE).min(MAX_FLASH);
+        self.integrity <= 0.0
+    }
+
+    pub fn integrity(&self) -> f32 {
+        self.integrity
+    }
+
+    // Casco nuevo: al reaparecer y al salir del hangar
+    pub fn repair(&mut self) {
+        self.integrity = 1.0;
+    }
+
+    // Chispas en todas direcciones donde explotó la nave
+    pub fn burst(&mut self, position: Vec3) {
+        for _ in 0..BURST_SPARKS {
+            let direction = self.random_direction();
+            self.sparks.emit(position, direction * SPARK_SPEED * self.rng.range(0.3, 1.0));
+        }
+        self.flash = MAX_FLASH;
+    }
+
+    fn random_direction(&mut self) -> Vec3 {
+        let direction = Vec3::new(self.rng.range(-1.0, 1.0), self.rng.range(-1.0, 1.0), self.rng.range(-1.0, 1.0));
+        direction.try_normalize(1e-6).unwrap_or_else(Vec3::y)
+    }
+
+    // Humo y chispas del casco dañado, que salen de la nave en `position` y
+    // quedan atrás mientras avanza a `velocity`; el tinte se apaga
+    pub fn update(&mut self, dt: f32, position: Vec3, velocity: Vec3) {
+        self.flash = (self.flash - FLASH_DECAY * dt).max(0.0);
+        if self.integrity < SMOKE_INTEGRITY {
+            let drift = Vec3::new(self.rng.range(-0.2, 0.2), self.rng.range(0.2, 0.5), self.rng.range(-0.2, 0.2));
+            self.smoke.emit(position, velocity * 0.3 + drift);
+        }
+        // Cuanto peor está el casco, más seguido saltan
+        if self.integrity < SPARK_INTEGRITY && self.rng.next_f32() > self.integrity / SPARK_INTEGRITY {
+            let direction = self.random_direction();
+            self.sparks.emit(position, velocity + direction * SPARK_SPEED * 0.5);
+        }
+        self.smoke.update(dt);
+        self.sparks.update(dt);
+    }
+
+    pub fn render_particles(&self, framebuffer: &mut Framebuffer, view_matrix: &Mat4, projection_matrix: &Mat4) {
+        self.smoke.render(framebuffer, view_matrix, projection_matrix);
+        self.sparks.render(framebuffer, view_matrix, projection_matrix);
+    }
+
+    // Tinte rojo de la pantalla después de un golpe, debajo del resto del HUD
+    pub fn render_flash(&self, framebuffer: &mut Framebuffer) {
+        if self.flash <= 0.0 {
+            return;
+        }
+        let red = pack_color(Vec3::new(0.8, 0.0, 0.0));
+        for y in 0..framebuffer.height {
+            for x in 0..framebuffer.width {
+                framebuffer.overlay_blend(x, y, red, self.flash);
+            }
+        }
+    }
+
+    // Barra de integridad arriba al centro, entre su nombre y el porcentaje,
+    // de verde a rojo según el daño
+    pub fn render(&self, framebuffer: &mut Framebuffer, text_color: u32) {
+        let label_width = LABEL.len() * text::CHAR_ADVANCE;
+        let total = label_width + GAP + BAR_WIDTH + GAP + 4 * text::CHAR_ADVANCE;
+        if framebuffer.width < total + MARGIN * 2 {
+            return;
+        }
+        let left = (framebuffer.width - total) / 2;
+        let bar_left = left + label_width + GAP;
+        // Centrada en la altura de las letras
+        let bar_top = MARGIN + (text::LINE_HEIGHT - BAR_HEIGHT) / 2 - 1;
+        let color = if self.integrity > SMOKE_INTEGRITY {
+            Vec3::new(0.3, 0.9, 0.4)
+        } else if self.integrity > SPARK_INTEGRITY {
+            Vec3::new(1.0, 0.8, 0.2)
+        } else {
+            Vec3::new(1.0, 0.25, 0.2)
+        };
+        let filled = (self.integrity * BAR_WIDTH as f32).round() as usize;
+        for y in bar_top..bar_top + BAR_HEIGHT {
+            for x in 0..BAR_WIDTH {
+                if x < filled {
+                    framebuffer.overlay_pixel(bar_left + x, y, pack_color(color));
+                } else {
+                    framebuffer.overlay_blend(bar_left + x, y, pack_color(color * 0.25), 0.6);
+                }
+            }
+        }
+        framebuffer.draw_text(left, MARGIN, LABEL, text_color);
+        framebuffer.draw_text(bar_left + BAR_WIDTH + GAP, MARGIN, &format!("{:.0}%", self.integrity * 100.0), text_color);
+    }
+}
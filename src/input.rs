@@ -0,0 +1,110 @@
+// Entrada por gamepad, junto al teclado y el mouse. El stick izquierdo
+// mueve la nave, el derecho gira la cámara, los gatillos suben y bajan, la
+// cruceta hace zoom y el botón derecho (B en Xbox, círculo en PlayStation) frena. Usa gilrs y solo existe con la feature `gamepad`; sin
+// ella `poll` devuelve siempre un cuadro sin entrada.
+
+// Fracción del recorrido del stick que se ignora para evitar derivas
+#[cfg(feature = "gamepad")]
+const STICK_DEADZONE: f32 = 0.15;
+#[cfg(feature = "gamepad")]
+const TRIGGER_DEADZONE: f32 = 0.05;
+
+// Ejes ya filtrados, en -1..1 (gatillos y zoom en 0..1 / -1..1)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GamepadFrame {
+    pub forward: f32,
+    pub right: f32,
+    pub up: f32,
+    pub look_x: f32,
+    // Positivo = mirar hacia arriba
+    pub look_y: f32,
+    pub zoom: f32,
+    pub brake: bool,
+}
+
+impl GamepadFrame {
+    pub fn is_idle(&self) -> bool {
+        [self.forward, self.right, self.up, self.look_x, self.look_y, self.zoom]
+            .iter()
+            .all(|value| *value == 0.0)
+            && !self.brake
+    }
+}
+
+// Zona muerta radial: se descarta el centro del stick y el resto se
+// reescala para que el movimiento empiece suave justo después del borde
+#[cfg(feature = "gamepad")]
+fn radial_deadzone(x: f32, y: f32, deadzone: f32) -> (f32, f32) {
+    let magnitude = x.hypot(y);
+    if magnitude <= deadzone {
+        return (0.0, 0.0);
+    }
+    let scaled = ((magnitude - deadzone) / (1.0 - deadzone)).min(1.0);
+    (x / magnitude * scaled, y / magnitude * scaled)
+}
+
+#[cfg(feature = "gamepad")]
+fn axis_deadzone(value: f32, deadzone: f32) -> f32 {
+    radial_deadzone(value, 0.0, deadzone).0
+}
+
+pub struct GamepadInput {
+    #[cfg(feature = "gamepad")]
+    gilrs: Option<gilrs::Gilrs>,
+}
+
+impl GamepadInput {
+    #[cfg(feature = "gamepad")]
+    pub fn new() -> Self {
+        match gilrs::Gilrs::new() {
+            Ok(gilrs) => GamepadInput { gilrs: Some(gilrs) },
+            Err(e) => {
+                eprintln!("No se pudo iniciar el soporte de gamepad: {} (solo teclado y mouse)", e);
+                GamepadInput { gilrs: None }
+            }
+        }
+    }
+
+    #[cfg(not(feature = "gamepad"))]
+    pub fn new() -> Self {
+        GamepadInput {}
+    }
+
+    // Lee el estado del primer gamepad conectado
+    #[cfg(feature = "gamepad")]
+    pub fn poll(&mut self) -> GamepadFrame {
+        use gilrs::{Axis, Button};
+
+        let Some(gilrs) = &mut self.gilrs else {
+            return GamepadFrame::default();
+        };
+        // Procesar los eventos pendientes actualiza el estado de cada gamepad
+        while gilrs.next_event().is_some() {}
+        let Some((_, gamepad)) = gilrs.gamepads().next() else {
+            return GamepadFrame::default();
+        };
+
+        let trigger = |button: Button| {
+            let value = gamepad.button_data(button).map_or(0.0, |data| data.value());
+            axis_deadzone(value, TRIGGER_DEADZONE)
+        };
+        let (right, forward) = radial_deadzone(gamepad.value(Axis::LeftStickX), gamepad.value(Axis::LeftStickY), STICK_DEADZONE);
+        let (look_x, look_y) = radial_deadzone(gamepad.value(Axis::RightStickX), gamepad.value(Axis::RightStickY), STICK_DEADZONE);
+        let zoom = gamepad.is_pressed(Button::DPadUp) as i32 - gamepad.is_pressed(Button::DPadDown) as i32;
+
+        GamepadFrame {
+            forward,
+            right,
+            up: trigger(Button::RightTrigger2) - trigger(Button::LeftTrigger2),
+            look_x,
+            look_y,
+            zoom: zoom as f32,
+            brake: gamepad.is_pressed(Button::East),
+        }
+    }
+
+    #[cfg(not(feature = "gamepad"))]
+    pub fn poll(&mut self) -> GamepadFrame {
+        GamepadFrame::default()
+    }
+}
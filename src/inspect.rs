@@ -0,0 +1,197 @@
+// Modo de inspección (cuadro congelado): la simulación se detiene pero se
+// sigue dibujando, así se puede recorrer el sistema con la cámara libre,
+// elegir un cuerpo con el mouse y leer sus datos en un panel. En vuelo, al
+// acercarse la nave a un cuerpo aparece un panel más corto con sus datos
+// principales, que se desvanece al alejarse.
+
+use nalgebra_glm::{inverse, Mat4, Vec3, Vec4};
+
+use crate::framebuffer::{pack_color, unpack_color, Framebuffer};
+use crate::scene::{Collider, Scene};
+use crate::text::{CHAR_ADVANCE, LINE_HEIGHT};
+
+const MARGIN: usize = 10;
+const PADDING: usize = 8;
+const BACKGROUND_DIM: f32 = 0.3;
+const BORDER_COLOR: u32 = 0x606060;
+const TITLE_COLOR: u32 = 0xFFD080;
+const TEXT_COLOR: u32 = 0xE0E0E0;
+// Distancia a la superficie desde la que aparece el panel de acercamiento; los
+// cuerpos grandes lo muestran desde más lejos, a `APPROACH_SCALE` radios
+const APPROACH_DISTANCE: f32 = 20.0;
+const APPROACH_SCALE: f32 = 2.0;
+// Fracción exterior del alcance en la que el panel se desvanece
+const APPROACH_FADE: f32 = 0.25;
+
+// Cuerpo bajo el píxel (x, y): el rayo desde la cámara se obtiene
+// deshaciendo la proyección en los planos near y far, y gana el cuerpo que
+// el rayo toca primero
+pub fn pick_body(
+    colliders: &[Collider],
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    view_matrix: &Mat4,
+    projection_matrix: &Mat4,
+) -> Option<usize> {
+    let inverse_view_projection = inverse(&(projection_matrix * view_matrix));
+    let ndc_x = x / width * 2.0 - 1.0;
+    let ndc_y = 1.0 - y / height * 2.0;
+    let unproject = |ndc_z: f32| {
+        let world = inverse_view_projection * Vec4::new(ndc_x, ndc_y, ndc_z, 1.0);
+        world.xyz() / world.w
+    };
+    let (near, far) = (unproject(-1.0), unproject(1.0));
+    colliders
+        .iter()
+        .enumerate()
+        .filter_map(|(index, collider)| collider.segment_hit(near, far).map(|t| (index, t)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(index, _)| index)
+}
+
+fn info_lines(scene: &Scene, index: usize, camera_position: Vec3) -> Vec<String> {
+    let body = &scene.bodies[index];
+    let mut lines = vec![
+        format!("Shader: {}", body.shader),
+        format!("Radio: {:.2}  Masa: {:.1}", body.scale, body.mass),
+    ];
+    if body.orbit_radius > 0.0 {
+        if let Some(parent) = body.parent_index {
+            lines.push(format!("Orbita alrededor de {}", scene.bodies[parent].name));
+        }
+        let direction = if body.orbit_period < 0.0 { ", retrógrada" } else { "" };
+        lines.push(format!(
+            "Órbita: a {:.1}  e {:.2}  período {:.1}{}",
+            body.orbit_radius,
+            body.eccentricity,
+            body.orbit_period.abs(),
+            direction
+        ));
+        lines.push(format!("Inclinación: {:.1} grados", body.inclination));
+    } else {
+        lines.push("Sin órbita (fijo en el centro)".to_string());
+    }
+    if body.tidally_locked {
+        lines.push("Rotación sincronizada".to_string());
+    } else if body.day_length == 0.0 {
+        lines.push("Sin rotación".to_string());
+    } else {
+        let direction = if body.retrograde { ", retrógrado" } else { "" };
+        lines.push(format!("Día: {:.1}{}", body.day_length, direction));
+    }
+    lines.push(format!("Eje: {:.1} grados  Achatamiento: {:.2}", body.axial_tilt, body.oblateness));
+    let mut features = Vec::new();
+    if body.atmosphere.is_some() {
+        features.push("atmósfera");
+    }
+    if body.rings.is_some() {
+        features.push("anillos");
+    }
+    if body.craters > 0 {
+        features.push("cráteres");
+    }
+    if !features.is_empty() {
+        lines.push(format!("Con {}", features.join(", ")));
+    }
+    let distance = (scene.states[index].position - camera_position).magnitude();
+    lines.push(format!("Distancia: {:.1}", distance));
+    lines
+}
+
+// Tipo de cuerpo para el panel de acercamiento, según su shader
+fn body_kind(scene: &Scene, index: usize) -> String {
+    let body = &scene.bodies[index];
+    let kind = match body.shader.as_str() {
+        "star" => "Estrella",
+        "rocky" => "Planeta rocoso",
+        "gas_giant" => "Gigante gaseoso",
+        "ice" => "Planeta helado",
+        "desert" => "Planeta desértico",
+        "volcanic" => "Planeta volcánico",
+        "ocean" => "Planeta oceánico",
+        "ringed" => "Planeta con anillos",
+        "moon" => "Luna",
+        "comet" => "Cometa",
+        _ if body.emissive => "Estrella",
+        _ => "Planeta",
+    };
+    match body.parent_index {
+        Some(parent) if body.shader != "moon" => format!("{} (luna de {})", kind, scene.bodies[parent].name),
+        Some(parent) => format!("{} de {}", kind, scene.bodies[parent].name),
+        None => kind.to_string(),
+    }
+}
+
+fn approach_lines(scene: &Scene, index: usize, distance: f32) -> Vec<String> {
+    let body = &scene.bodies[index];
+    let mut lines = vec![body_kind(scene, index), format!("Radio: {:.2}", body.scale)];
+    if body.orbit_radius > 0.0 {
+        lines.push(format!("Órbita: radio {:.1}  período {:.1}", body.orbit_radius, body.orbit_period.abs()));
+    }
+    lines.push(format!("Distancia: {:.1}", distance));
+    lines
+}
+
+// Recuadro con el fondo oscurecido, un título y sus líneas; `fade` lo mezcla
+// con la imagen y el texto solo se dibuja a partir de la mitad
+fn draw_panel(framebuffer: &mut Framebuffer, left: usize, top: usize, title: &str, lines: &[String], fade: f32) {
+    let (width, height) = panel_size(title, lines);
+    for y in top..top + height {
+        for x in left..left + width {
+            let pixel = framebuffer.index(x, y);
+            let background = unpack_color(framebuffer.buffer[pixel]);
+            let border = x == left || y == top || x == left + width - 1 || y == top + height - 1;
+            let panel = if border { unpack_color(BORDER_COLOR) } else { background * BACKGROUND_DIM };
+            framebuffer.buffer[pixel] = pack_color(background.lerp(&panel, fade));
+        }
+    }
+    if fade < 0.5 {
+        return;
+    }
+
+    let x = left + PADDING;
+    let mut y = top + PADDING;
+    framebuffer.draw_text(x, y, title, TITLE_COLOR);
+    for line in lines {
+        y += LINE_HEIGHT;
+        framebuffer.draw_text(x, y, line, TEXT_COLOR);
+    }
+}
+
+fn panel_size(title: &str, lines: &[String]) -> (usize, usize) {
+    let columns = lines.iter().map(|line| line.chars().count()).chain([title.chars().count()]).max().unwrap_or(0);
+    (columns * CHAR_ADVANCE + PADDING * 2, (lines.len() + 1) * LINE_HEIGHT + PADDING * 2)
+}
+
+// Panel con los datos del cuerpo elegido en la esquina inferior derecha
+pub fn render_info_panel(framebuffer: &mut Framebuffer, scene: &Scene, index: usize, camera_position: Vec3) {
+    let title = &scene.bodies[index].name;
+    let lines = info_lines(scene, index, camera_position);
+    let (width, height) = panel_size(title, &lines);
+    if framebuffer.width < width + MARGIN || framebuffer.height < height + MARGIN {
+        return;
+    }
+    draw_panel(framebuffer, framebuffer.width - width - MARGIN, framebuffer.height - height - MARGIN, title, &lines, 1.0);
+}
+
+// Panel de acercamiento al cuerpo más cercano a la nave, en el borde derecho
+// a media altura
+pub fn render_approach_panel(framebuffer: &mut Framebuffer, scene: &Scene, ship_position: Vec3) {
+    let Some((index, distance)) = scene.nearest_body(ship_position) else {
+        return;
+    };
+    let range = APPROACH_DISTANCE.max(scene.bodies[index].scale * APPROACH_SCALE);
+    let fade = ((range - distance) / (range * APPROACH_FADE)).clamp(0.0, 1.0);
+    if fade <= 0.0 {
+        return;
+    }
+    let title = &scene.bodies[index].name;
+    let lines = approach_lines(scene, index, distance);
+    let (width, height) = panel_size(title, &lines);
+    if framebuffer.width < width + MARGIN || framebuffer.height < height + MARGIN * 2 {
+        return;
+    }
+    draw_panel(framebuffer, framebuffer.width - width - MARGIN, (framebuffer.height - height) / 2, title, &lines, fade);
+}
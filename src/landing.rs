@@ -0,0 +1,151 @@
+// Aterrizaje: al bajar hasta casi el margen de colisión de un planeta la
+// nave pasa a seguir su superficie. La altura se mide sobre la esfera del
+// cuerpo y se mantiene mientras W/S y Q/E la llevan por la superficie (que
+// sigue en órbita con la nave encima), Espacio/Shift suben o bajan y L
+// despega. La cámara se acerca y se alinea con la vertical del lugar, y el
+// shader del planeta suma octavas de detalle fino para verlo de cerca.
+
+use nalgebra_glm::{look_at, Mat4, Vec3};
+
+use crate::scene::{Collider, Scene};
+
+// Altura sobre el margen de colisión a la que empieza el aterrizaje
+const LANDING_RANGE: f32 = 0.5;
+// Altura de vuelo sobre la superficie, la mínima a la que se puede bajar y
+// la que se gana por encima de la de entrada antes de soltar el planeta
+const HOVER_ALTITUDE: f32 = 0.6;
+const MIN_ALTITUDE: f32 = 0.25;
+const TAKEOFF_CLEARANCE: f32 = 1.0;
+// Cambio de altura con Espacio/Shift, en unidades por cuadro
+const CLIMB_RATE: f32 = 0.05;
+// Fracción del camino hacia la altura pedida y hacia la cámara de superficie
+// que se recorre por cuadro
+const ALTITUDE_EASE: f32 = 0.08;
+const CAMERA_EASE: f32 = 0.05;
+// Cámara de superficie: detrás de la nave y sobre ella, más cerca que la normal
+const CAMERA_BEHIND: f32 = 4.0;
+const CAMERA_ABOVE: f32 = 1.5;
+
+pub struct Landing {
+    pub body: usize,
+    // Dirección desde el centro del cuerpo hasta la nave
+    direction: Vec3,
+    // Rumbo de la nave en el plano tangente; se transporta con ella al
+    // moverse, así no se da vuelta al pasar por los costados del planeta
+    forward: Vec3,
+    // Altura sobre la esfera del cuerpo (`collision_radius`)
+    altitude: f32,
+    target_altitude: f32,
+    // Altura a partir de la cual la nave deja el planeta
+    exit_altitude: f32,
+    // Avance 0..1 de la cámara y del detalle de superficie
+    blend: f32,
+    // Desplazamiento respecto al cuerpo en el último cuadro
+    motion: Vec3,
+}
+
+impl Landing {
+    // Empieza a aterrizar en el cuerpo no emisivo cuya superficie tiene la
+    // nave a menos de `LANDING_RANGE` por encima del margen de colisión.
+    // `forward` y `right` son el rumbo de la nave en el mundo.
+    pub fn check(scene: &Scene, colliders: &[Collider], ship_position: Vec3, forward: Vec3, right: Vec3) -> Option<Self> {
+        let (body, collider) = colliders
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !scene.bodies[*index].emissive)
+            .find(|(_, collider)| sphere_altitude(collider, ship_position) < collider.margin + LANDING_RANGE)?;
+        let direction = (ship_position - collider.center).try_normalize(1e-6).unwrap_or_else(|| Vec3::new(0.0, 1.0, 0.0));
+        // El rumbo proyectado en el plano tangente o, si apunta hacia el
+        // planeta o lejos de él, la perpendicular a su derecha
+        let projected = forward - direction * forward.dot(&direction);
+        let forward = if projected.magnitude() > 0.1 { projected.normalize() } else { direction.cross(&right).normalize() };
+        Some(Landing {
+            body,
+            direction,
+            forward,
+            altitude: sphere_altitude(collider, ship_position),
+            target_altitude: HOVER_ALTITUDE,
+            exit_altitude: collider.margin + LANDING_RANGE + TAKEOFF_CLEARANCE,
+            blend: 0.0,
+            motion: Vec3::zeros(),
+        })
+    }
+
+    // Mueve la nave sobre la superficie: `turn` gira el rumbo (radianes,
+    // positivo = derecha) y `forward`, `right` y `up` son los controles en
+    // -1..1. Devuelve la nueva posición de la nave.
+    pub fn update(&mut self, colliders: &[Collider], turn: f32, forward: f32, right: f32, up: f32, speed: f32) -> Vec3 {
+        let side = self.forward.cross(&self.direction);
+        self.forward = self.forward * turn.cos() + side * turn.sin();
+        let side = self.forward.cross(&self.direction);
+
+        // El desplazamiento sobre la superficie gira la dirección de la nave
+        let collider = &colliders[self.body];
+        let before = self.direction * (collider.radius + self.altitude);
+        self.direction = (self.direction + (self.forward * forward + side * right) * speed / before.magnitude()).normalize();
+        self.forward = (self.forward - self.direction * self.forward.dot(&self.direction)).normalize();
+
+        self.target_altitude = (self.target_altitude + up * CLIMB_RATE).max(MIN_ALTITUDE);
+        self.altitude += (self.target_altitude - self.altitude) * ALTITUDE_EASE;
+        // Al despegar la cámara vuelve a la de seguimiento a medida que sube,
+        // así ya está ahí cuando la nave deja el planeta
+        self.blend = if self.taking_off() {
+            let remaining = (self.exit_altitude - self.altitude) / (self.exit_altitude - MIN_ALTITUDE);
+            self.blend.min(remaining.clamp(0.0, 1.0))
+        } else {
+            self.blend + (1.0 - self.blend) * CAMERA_EASE
+        };
+        let offset = self.direction * (collider.radius + self.altitude);
+        self.motion = offset - before;
+        collider.center + offset
+    }
+
+    // Desplazamiento de la nave respecto al cuerpo en el último cuadro: sin
+    // la órbita del planeta, que la nave acompaña
+    pub fn motion(&self) -> Vec3 {
+        self.motion
+    }
+
+    // Rumbo en el plano XZ que corresponde al de la superficie, para el
+    // modelo de la nave; None si apunta casi en vertical
+    pub fn heading(&self) -> Option<f32> {
+        (self.forward.x.hypot(self.forward.z) > 0.1).then(|| self.forward.z.atan2(self.forward.x))
+    }
+
+    // L: subir hasta dejar el planeta
+    pub fn take_off(&mut self) {
+        self.target_altitude = self.exit_altitude + TAKEOFF_CLEARANCE;
+    }
+
+    fn taking_off(&self) -> bool {
+        self.target_altitude > self.exit_altitude
+    }
+
+    // La nave ya subió lo suficiente para volar libre
+    pub fn is_finished(&self) -> bool {
+        self.altitude >= self.exit_altitude
+    }
+
+    pub fn altitude(&self) -> f32 {
+        self.altitude
+    }
+
+    // Intensidad del detalle extra del shader del planeta (0..1)
+    pub fn detail(&self) -> f32 {
+        self.blend
+    }
+
+    // Cámara de superficie mezclada con la de seguimiento (`follow_eye`) según
+    // el avance del aterrizaje. Devuelve la matriz de vista y la posición.
+    pub fn camera(&self, ship_position: Vec3, follow_eye: Vec3) -> (Mat4, Vec3) {
+        let surface_eye = ship_position - self.forward * CAMERA_BEHIND + self.direction * CAMERA_ABOVE;
+        let eye = follow_eye.lerp(&surface_eye, self.blend);
+        let up = Vec3::new(0.0, 1.0, 0.0).lerp(&self.direction, self.blend).try_normalize(1e-6).unwrap_or(self.direction);
+        (look_at(&eye, &ship_position, &up), eye)
+    }
+}
+
+// Altura de `point` sobre la esfera de colisión del cuerpo
+fn sphere_altitude(collider: &Collider, point: Vec3) -> f32 {
+    (point - collider.center).magnitude() - collider.radius
+}
@@ -0,0 +1,142 @@
+// Destello de lente del sol: se proyecta el sol a pantalla, se mira en el
+// z-buffer cuánto de su disco queda a la vista y con eso se suman un
+// resplandor radial con dos rayos cruzados alrededor del sol y una fila de
+// reflejos (ghosts) sobre el eje que va del sol al centro de la pantalla. La
+// intensidad baja al acercarse el sol al borde y al taparlo un planeta o la
+// nave, y se suaviza entre cuadros para que el destello no parpadee.
+
+use nalgebra_glm::{Mat4, Vec3, Vec4};
+
+use crate::framebuffer::{pack_color, unpack_color, Framebuffer};
+use crate::{project_to_screen, projected_radius};
+
+const SUN_COLOR: Vec3 = Vec3::new(1.0, 0.85, 0.6);
+// Radio del resplandor como fracción del lado menor de la pantalla
+const GLARE_SIZE: f32 = 0.4;
+const GLARE_STRENGTH: f32 = 0.35;
+// Grosor en píxeles de los rayos cruzados
+const SPIKE_WIDTH: f32 = 1.5;
+// Muestras por lado de la grilla sobre el disco del sol
+const SAMPLES: usize = 5;
+// Fracción del borde de la pantalla en la que el destello se apaga
+const EDGE_FADE: f32 = 0.15;
+// Fracción de la diferencia con la visibilidad medida que se recupera por cuadro
+const SMOOTHING: f32 = 0.3;
+
+// Reflejos sobre el eje sol-centro: posición (0 en el sol, 1 en el centro,
+// 2 en el punto opuesto), radio como fracción del lado menor, color e intensidad
+const GHOSTS: [(f32, f32, Vec3, f32); 5] = [
+    (0.45, 0.03, Vec3::new(1.0, 0.8, 0.4), 0.12),
+    (0.8, 0.06, Vec3::new(0.5, 1.0, 0.6), 0.06),
+    (1.25, 0.02, Vec3::new(0.6, 0.7, 1.0), 0.15),
+    (1.6, 0.09, Vec3::new(0.4, 0.6, 1.0), 0.05),
+    (2.1, 0.05, Vec3::new(1.0, 0.5, 0.7), 0.08),
+];
+
+pub struct LensFlare {
+    pub intensity: f32,
+    // Fracción del sol a la vista, suavizada
+    visibility: f32,
+}
+
+impl LensFlare {
+    pub fn new(intensity: f32) -> Self {
+        LensFlare { intensity, visibility: 0.0 }
+    }
+
+    // Fracción de una grilla sobre el disco del sol (centro en pantalla y
+    // radio en píxeles) que no tapa nada más cercano que el frente del sol
+    fn measure(framebuffer: &Framebuffer, x: f32, y: f32, radius_px: f32, front_depth: f32) -> f32 {
+        let mut visible = 0;
+        let mut total = 0;
+        for row in 0..SAMPLES {
+            for column in 0..SAMPLES {
+                let u = column as f32 / (SAMPLES - 1) as f32 * 2.0 - 1.0;
+                let v = row as f32 / (SAMPLES - 1) as f32 * 2.0 - 1.0;
+                if u * u + v * v > 1.0 {
+                    continue;
+                }
+                total += 1;
+                // Las muestras fuera de la pantalla cuentan como tapadas
+                let (sample_x, sample_y) = (x + u * radius_px * 0.7, y + v * radius_px * 0.7);
+                if sample_x < 0.0 || sample_y < 0.0 {
+                    continue;
+                }
+                let (sample_x, sample_y) = (sample_x as usize, sample_y as usize);
+                if sample_x >= framebuffer.width || sample_y >= framebuffer.height {
+                    continue;
+                }
+                if framebuffer.zbuffer[framebuffer.index(sample_x, sample_y)] >= front_depth {
+                    visible += 1;
+                }
+            }
+        }
+        visible as f32 / total as f32
+    }
+
+    pub fn apply(&mut self, framebuffer: &mut Framebuffer, sun: Vec3, radius: f32, view_matrix: &Mat4, projection_matrix: &Mat4, viewport_matrix: &Mat4) {
+        // El sol detrás de la cámara no deja destello
+        let Some((x, y, view_depth)) = project_to_screen(sun, view_matrix, projection_matrix, viewport_matrix) else {
+            self.visibility = 0.0;
+            return;
+        };
+        // Profundidad del punto del sol más cercano a la cámara
+        let front = view_matrix * Vec4::new(sun.x, sun.y, sun.z, 1.0) + Vec4::new(0.0, 0.0, radius.min(view_depth * 0.5), 0.0);
+        let front_clip = projection_matrix * front;
+        let front_depth = front_clip.z / front_clip.w;
+        let radius_px = projected_radius(radius, view_depth, projection_matrix, viewport_matrix).max(1.0);
+
+        let (center_x, center_y) = (framebuffer.width as f32 * 0.5, framebuffer.height as f32 * 0.5);
+        let edge = ((x - center_x).abs() / center_x).max((y - center_y).abs() / center_y);
+        let target = Self::measure(framebuffer, x, y, radius_px, front_depth) * ((1.0 - edge) / EDGE_FADE).clamp(0.0, 1.0);
+        self.visibility += (target - self.visibility) * SMOOTHING;
+        let strength = self.visibility * self.intensity;
+        if strength < 0.01 {
+            return;
+        }
+
+        let size = framebuffer.width.min(framebuffer.height) as f32;
+        let glare_radius = size * GLARE_SIZE + radius_px;
+        add_light(framebuffer, x, y, glare_radius, |dx, dy, distance| {
+            let falloff = (1.0 - distance / glare_radius).max(0.0);
+            let spikes = (-dy * dy / (2.0 * SPIKE_WIDTH * SPIKE_WIDTH)).exp() * (1.0 - dx.abs() / glare_radius).max(0.0)
+                + (-dx * dx / (2.0 * SPIKE_WIDTH * SPIKE_WIDTH)).exp() * (1.0 - dy.abs() / glare_radius).max(0.0);
+            SUN_COLOR * ((falloff * falloff * falloff + spikes * 0.5) * GLARE_STRENGTH * strength)
+        });
+
+        for (position, ghost_size, color, ghost_strength) in GHOSTS {
+            let ghost_x = x + (center_x - x) * position;
+            let ghost_y = y + (center_y - y) * position;
+            let ghost_radius = size * ghost_size;
+            add_light(framebuffer, ghost_x, ghost_y, ghost_radius, |_, _, distance| {
+                // Más claro hacia el borde, con el borde suavizado un par de píxeles
+                let d = distance / ghost_radius;
+                let rim = ((ghost_radius - distance) / 2.0).clamp(0.0, 1.0);
+                color * ((0.4 + 0.6 * d * d) * rim * ghost_strength * strength)
+            });
+        }
+    }
+}
+
+// Suma la luz de `light(dx, dy, distancia)` en el disco de radio `radius`
+// alrededor de (x, y)
+fn add_light(framebuffer: &mut Framebuffer, x: f32, y: f32, radius: f32, light: impl Fn(f32, f32, f32) -> Vec3) {
+    let min_x = (x - radius).floor().max(0.0) as usize;
+    let min_y = (y - radius).floor().max(0.0) as usize;
+    let max_x = (x + radius).ceil().min(framebuffer.width as f32);
+    let max_y = (y + radius).ceil().min(framebuffer.height as f32);
+    if max_x <= 0.0 || max_y <= 0.0 {
+        return;
+    }
+    for py in min_y..max_y as usize {
+        for px in min_x..max_x as usize {
+            let (dx, dy) = (px as f32 + 0.5 - x, py as f32 + 0.5 - y);
+            let distance = dx.hypot(dy);
+            if distance >= radius {
+                continue;
+            }
+            let index = framebuffer.index(px, py);
+            framebuffer.buffer[index] = pack_color(unpack_color(framebuffer.buffer[index]) + light(dx, dy, distance));
+        }
+    }
+}
@@ -0,0 +1,27 @@
+// Biblioteca del rasterizador: el framebuffer, los shaders y el dibujo de
+// modelos, sin ventana ni entrada. La usan el juego (src/main.rs) y el banco
+// (benches/rasterizador.rs).
+
+pub mod bench;
+pub mod binning;
+pub mod clipping;
+pub mod color;
+pub mod culling;
+pub mod daily;
+pub mod debug_view;
+pub mod fragment;
+pub mod framebuffer;
+pub mod lighting;
+pub mod obj;
+pub mod pixel_format;
+pub mod render;
+pub mod shade_batch;
+pub mod shaders;
+pub mod skybox;
+pub mod text;
+pub mod texture;
+pub mod triangle;
+pub mod vertex;
+pub mod vertex_stage;
+
+pub use render::Uniforms;
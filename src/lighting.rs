@@ -0,0 +1,383 @@
+use nalgebra_glm::Vec3;
+use serde::{Deserialize, Serialize};
+
+use crate::shaders::shade_ring;
+use crate::skybox::environment;
+
+// Luz puntual (la estrella central) más un término ambiental
+#[derive(Debug, Clone, Copy)]
+pub struct Light<'a> {
+    pub position: Vec3,
+    pub color: Vec3,
+    // Luz ambiental ya multiplicada por su intensidad
+    pub ambient: Vec3,
+    // Objeto pequeño que proyecta sombra sobre los cuerpos (la nave)
+    pub occluder: Option<Occluder>,
+    // Radio de la estrella: tamaño de su disco visto desde cada punto en
+    // los eclipses (0 = fuente puntual, sombras sin penumbra)
+    pub radius: f32,
+    // Cuerpos que pueden tapar la estrella a lo que se está dibujando
+    pub eclipsers: &'a [Occluder],
+    // Anillos del cuerpo que se está dibujando, que le dejan su sombra a franjas
+    pub rings: Option<RingShadow<'a>>,
+}
+
+// Esfera envolvente de un objeto que tapa la luz
+#[derive(Debug, Clone, Copy)]
+pub struct Occluder {
+    pub center: Vec3,
+    pub radius: f32,
+}
+
+// Anillos vistos desde la luz: la corona en unidades del mundo y lo que usa
+// el shader de anillos para saber la densidad de cada banda (ver `rings`)
+#[derive(Debug, Clone, Copy)]
+pub struct RingShadow<'a> {
+    pub center: Vec3,
+    pub normal: Vec3,
+    pub inner_radius: f32,
+    pub outer_radius: f32,
+    pub opacity: f32,
+    pub seed: u32,
+    pub palette: &'a [Vec3],
+}
+
+// Luz ambiental y color del fondo de la escena (`[lighting]`): un sistema
+// puede sentirse más frío o más cálido con un ambiente teñido y un fondo que
+// no sea negro puro
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct LightingConfig {
+    // Color e intensidad de la luz que llega a los lados de noche
+    pub ambient_color: [f32; 3],
+    pub ambient_intensity: f32,
+    // Color del espacio detrás de las estrellas
+    pub background: [f32; 3],
+}
+
+impl Default for LightingConfig {
+    fn default() -> Self {
+        LightingConfig {
+            ambient_color: [1.0, 1.0, 1.0],
+            ambient_intensity: 0.08,
+            background: [0.0, 0.0, 0.0],
+        }
+    }
+}
+
+impl LightingConfig {
+    pub fn ambient(&self) -> Vec3 {
+        Vec3::from(self.ambient_color) * self.ambient_intensity
+    }
+
+    pub fn background(&self) -> Vec3 {
+        Vec3::from(self.background)
+    }
+}
+
+// Radio angular aparente del sol: ensancha la penumbra con la distancia
+const SUN_ANGULAR_RADIUS: f32 = 0.02;
+// La sombra se diluye del todo a esta distancia del oclusor, en radios suyos
+const SHADOW_REACH: f32 = 30.0;
+// Fracción de la luz directa que tapa la sombra en su centro
+const SHADOW_STRENGTH: f32 = 0.75;
+// Radio angular mínimo de la estrella en los eclipses, para que una fuente
+// puntual no deje el borde de la sombra dentado
+const MIN_SUN_ANGLE: f32 = 0.002;
+// Cuánto pasa la luz al lado de noche por unidad de densidad de la atmósfera
+const TERMINATOR_WRAP: f32 = 0.25;
+// Color de la luz que cruza mucho aire, cerca del terminador
+const TWILIGHT_TINT: Vec3 = Vec3::new(1.0, 0.55, 0.3);
+
+// Propiedades de superficie usadas por la etapa de iluminación
+#[derive(Debug, Clone, Copy)]
+pub struct Material {
+    // Las superficies emisivas (el sol) no reciben iluminación
+    pub emissive: bool,
+    // Intensidad del brillo especular Blinn-Phong (0 = sin especular)
+    pub specular: f32,
+    pub shininess: f32,
+    // Halo de atmósfera en el borde del cuerpo
+    pub atmosphere: Option<Atmosphere>,
+    // Reflejo del cielo de frente (0 = sin reflejos); de canto crece por Fresnel
+    pub reflectivity: f32,
+    // Rugosidad 0..1: cuánto se difumina el reflejo del entorno
+    pub roughness: f32,
+}
+
+// Brillo de la atmósfera vista de canto: un término de Fresnel que crece
+// donde la normal se aleja de la dirección de la cámara
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+pub struct Atmosphere {
+    pub color: [f32; 3],
+    #[serde(default = "default_intensity")]
+    pub intensity: f32,
+    // Exponente del Fresnel: más alto = halo más fino y pegado al borde
+    #[serde(default = "default_falloff")]
+    pub falloff: f32,
+    // Densidad del aire: ablanda el terminador y lo tiñe de tonos cálidos
+    // (0 = terminador duro, como en un cuerpo sin atmósfera)
+    #[serde(default = "default_density")]
+    pub density: f32,
+}
+
+fn default_intensity() -> f32 {
+    1.0
+}
+
+fn default_falloff() -> f32 {
+    3.0
+}
+
+fn default_density() -> f32 {
+    1.0
+}
+
+impl<'a> Light<'a> {
+    pub fn new(position: Vec3) -> Self {
+        Light {
+            position,
+            color: Vec3::new(1.0, 0.97, 0.9),
+            ambient: LightingConfig::default().ambient(),
+            occluder: None,
+            radius: 0.0,
+            eclipsers: &[],
+            rings: None,
+        }
+    }
+
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    // La misma luz con los cuerpos que pueden eclipsarla (ver
+    // `Scene::shadow_casters`)
+    pub fn with_eclipsers<'b>(self, eclipsers: &'b [Occluder]) -> Light<'b>
+    where
+        'a: 'b,
+    {
+        Light { eclipsers, ..self }
+    }
+
+    // La misma luz con la sombra de los anillos del cuerpo
+    pub fn with_rings<'b>(self, rings: Option<RingShadow<'b>>) -> Light<'b>
+    where
+        'a: 'b,
+    {
+        Light { rings, ..self }
+    }
+
+    pub fn with_ambient(mut self, ambient: Vec3) -> Self {
+        self.ambient = ambient;
+        self
+    }
+
+    pub fn with_occluder(mut self, occluder: Option<Occluder>) -> Self {
+        self.occluder = occluder;
+        self
+    }
+
+    // Fracción de luz directa que llega a `point` (1 = sin sombra): la
+    // sombra de la nave por los eclipses de los cuerpos y por la de los anillos
+    fn visibility(&self, point: Vec3) -> f32 {
+        self.occluder_visibility(point) * self.eclipse_visibility(point) * self.ring_visibility(point)
+    }
+
+    // Radio angular de la estrella vista a `light_distance`
+    fn sun_angle(&self, light_distance: f32) -> f32 {
+        (self.radius / light_distance).min(1.0).asin().max(MIN_SUN_ANGLE)
+    }
+
+    // El rayo hacia la luz se interseca con la esfera del oclusor y el borde
+    // se suaviza con una penumbra que crece con la distancia
+    fn occluder_visibility(&self, point: Vec3) -> f32 {
+        let Some(occluder) = self.occluder else {
+            return 1.0;
+        };
+        let to_light = self.position - point;
+        let light_distance = to_light.magnitude();
+        let offset = occluder.center - point;
+        // Los puntos del propio oclusor no se sombrean a sí mismos
+        if light_distance < 1e-6 || offset.magnitude() <= occluder.radius {
+            return 1.0;
+        }
+        let direction = to_light / light_distance;
+        let along = offset.dot(&direction);
+        if along <= 0.0 || along >= light_distance {
+            return 1.0;
+        }
+
+        let miss = (offset - direction * along).magnitude();
+        let penumbra = occluder.radius * 0.5 + along * SUN_ANGULAR_RADIUS;
+        let t = ((miss - (occluder.radius - penumbra)) / (2.0 * penumbra)).clamp(0.0, 1.0);
+        let coverage = 1.0 - t * t * (3.0 - 2.0 * t);
+        let fade = (1.0 - along / (occluder.radius * SHADOW_REACH)).clamp(0.0, 1.0);
+        1.0 - coverage * fade * SHADOW_STRENGTH
+    }
+
+    // Eclipses: desde `point`, la estrella y cada cuerpo son discos en el
+    // cielo (radios angulares) y la luz que llega es la parte del disco de la
+    // estrella que no tapan. Da umbra, penumbra y, si el cuerpo se ve más
+    // chico que la estrella, eclipses anulares.
+    fn eclipse_visibility(&self, point: Vec3) -> f32 {
+        let to_light = self.position - point;
+        let light_distance = to_light.magnitude();
+        if self.eclipsers.is_empty() || light_distance <= self.radius {
+            return 1.0;
+        }
+        let sun_angle = self.sun_angle(light_distance);
+        let sun_area = std::f32::consts::PI * sun_angle * sun_angle;
+        let mut visible = 1.0;
+        for body in self.eclipsers {
+            let offset = body.center - point;
+            let distance = offset.magnitude();
+            // Los puntos del propio cuerpo y los cuerpos detrás de la estrella no tapan
+            if distance <= body.radius || distance >= light_distance {
+                continue;
+            }
+            let body_angle = (body.radius / distance).asin();
+            let separation = (offset.dot(&to_light) / (distance * light_distance)).clamp(-1.0, 1.0).acos();
+            visible *= 1.0 - (disc_overlap(sun_angle, body_angle, separation) / sun_area).min(1.0);
+        }
+        visible
+    }
+
+    // Sombra de los anillos sobre su cuerpo: el rayo hacia la estrella cruza
+    // el plano de los anillos y pierde la densidad de la banda que atraviesa,
+    // así la sombra sale con las mismas franjas y huecos que los anillos. La
+    // penumbra promedia las bandas que cubre el disco de la estrella visto
+    // desde el punto.
+    fn ring_visibility(&self, point: Vec3) -> f32 {
+        let Some(rings) = self.rings else {
+            return 1.0;
+        };
+        let to_light = self.position - point;
+        let light_distance = to_light.magnitude();
+        let direction = to_light / light_distance.max(1e-6);
+        let facing = direction.dot(&rings.normal);
+        if facing.abs() < 1e-4 {
+            return 1.0;
+        }
+        // El plano tiene que quedar entre el punto y la estrella
+        let along = (rings.center - point).dot(&rings.normal) / facing;
+        if along <= 0.0 || along >= light_distance {
+            return 1.0;
+        }
+        let width = rings.outer_radius - rings.inner_radius;
+        let radius = (point + direction * along - rings.center).magnitude();
+        let radial = (radius - rings.inner_radius) / width;
+        // Ancho de la penumbra sobre el plano, en la escala de `radial`
+        let blur = (along * self.sun_angle(light_distance) / (width * facing.abs())).min(0.5);
+        if radial < -blur || radial > 1.0 + blur {
+            return 1.0;
+        }
+        let density: f32 = [-blur, 0.0, blur]
+            .iter()
+            .map(|offset| radial + offset)
+            .filter(|sample| (0.0..=1.0).contains(sample))
+            .map(|sample| shade_ring(sample, rings.seed, rings.palette).1)
+            .sum();
+        1.0 - (density / 3.0 * rings.opacity).min(1.0)
+    }
+}
+
+// Área común de dos discos de radios `a` y `b` con centros a `separation`
+fn disc_overlap(a: f32, b: f32, separation: f32) -> f32 {
+    if separation >= a + b {
+        return 0.0;
+    }
+    if separation <= (a - b).abs() {
+        let smaller = a.min(b);
+        return std::f32::consts::PI * smaller * smaller;
+    }
+    let d = separation;
+    let alpha = ((d * d + a * a - b * b) / (2.0 * d * a)).clamp(-1.0, 1.0).acos();
+    let beta = ((d * d + b * b - a * a) / (2.0 * d * b)).clamp(-1.0, 1.0).acos();
+    let kite = ((-d + a + b) * (d + a - b) * (d - a + b) * (d + a + b)).max(0.0).sqrt();
+    a * a * alpha + b * b * beta - 0.5 * kite
+}
+
+impl Material {
+    pub fn new(emissive: bool, specular: f32, shininess: f32) -> Self {
+        Material { emissive, specular, shininess, atmosphere: None, reflectivity: 0.0, roughness: 1.0 }
+    }
+
+    pub fn with_reflection(mut self, reflectivity: f32, roughness: f32) -> Self {
+        self.reflectivity = reflectivity;
+        self.roughness = roughness;
+        self
+    }
+
+    pub fn with_atmosphere(mut self, atmosphere: Option<Atmosphere>) -> Self {
+        self.atmosphere = atmosphere;
+        self
+    }
+}
+
+// Difuso y color de la luz según el ángulo con el sol. Sin atmósfera es
+// Lambert puro y el terminador queda duro; con aire la luz se dispersa un poco
+// hacia el lado de noche y, cerca del terminador, llega rojiza por cruzar
+// más atmósfera.
+fn scattered_light(n_dot_l: f32, color: Vec3, atmosphere: Option<&Atmosphere>) -> (f32, Vec3) {
+    let density = match atmosphere {
+        Some(atmosphere) if atmosphere.density > 0.0 => atmosphere.density,
+        _ => return (n_dot_l.max(0.0), color),
+    };
+    let wrap = density * TERMINATOR_WRAP;
+    let diffuse = ((n_dot_l + wrap) / (1.0 + wrap)).max(0.0);
+    let twilight = (1.0 - (n_dot_l / (wrap + 0.15)).abs()).clamp(0.0, 1.0) * density.min(1.0) * 0.8;
+    (diffuse, color.component_mul(&Vec3::repeat(1.0).lerp(&TWILIGHT_TINT, twilight)))
+}
+
+// Difuso Lambert + especular Blinn-Phong opcional sobre el color del shader,
+// más el reflejo del entorno y el halo de atmósfera si el material los
+// tiene. La sombra del oclusor de la luz atenúa el difuso y el especular.
+pub fn apply_lighting(
+    base: Vec3,
+    normal: Vec3,
+    world_position: Vec3,
+    camera_position: Vec3,
+    light: &Light,
+    material: &Material,
+) -> Vec3 {
+    if material.emissive || normal.magnitude_squared() < 1e-12 {
+        return base;
+    }
+
+    let n = normal.normalize();
+    let to_light = (light.position - world_position).normalize();
+    let shadow = light.visibility(world_position);
+    let (diffuse, light_color) = scattered_light(n.dot(&to_light), light.color, material.atmosphere.as_ref());
+    let diffuse = diffuse * shadow;
+
+    let mut color = base.component_mul(&light_color) * diffuse + base.component_mul(&light.ambient);
+
+    let to_camera = (camera_position - world_position).normalize();
+    if material.specular > 0.0 && diffuse > 0.0 {
+        let half_vector = (to_light + to_camera).normalize();
+        let specular = n.dot(&half_vector).max(0.0).powf(material.shininess) * material.specular * shadow;
+        color += light.color * specular;
+    }
+
+    // Reflejo metálico del cielo procedural a lo largo de la vista reflejada,
+    // teñido por el color de la superficie (Fresnel de Schlick)
+    if material.reflectivity > 0.0 {
+        let n_dot_v = n.dot(&to_camera).max(0.0);
+        let reflected = n * (2.0 * n_dot_v) - to_camera;
+        let sky = environment(reflected, to_light, light.color * shadow, material.roughness);
+        let fresnel = (material.reflectivity + (1.0 - material.reflectivity) * (1.0 - n_dot_v).powi(5))
+            * (1.0 - material.roughness * 0.5);
+        let tint = base.lerp(&Vec3::repeat(1.0), 0.5);
+        color = color * (1.0 - fresnel) + sky.component_mul(&tint) * fresnel;
+    }
+
+    if let Some(atmosphere) = &material.atmosphere {
+        let fresnel = (1.0 - n.dot(&to_camera).max(0.0)).powf(atmosphere.falloff);
+        // El halo brilla sobre todo del lado iluminado y se apaga de noche
+        let daylight = 0.15 + 0.85 * (n.dot(&to_light) * 0.5 + 0.5);
+        color += Vec3::from(atmosphere.color).component_mul(&light.color) * (fresnel * atmosphere.intensity * daylight);
+    }
+
+    color
+}
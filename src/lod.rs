@@ -0,0 +1,90 @@
+// Sistema de nivel de detalle (LOD) para los cuerpos celestes.
+// Los cuerpos lejanos se dibujan como impostores (un disco en pantalla
+// sombreado como esfera) y al acercarse se hace un fundido hacia la malla.
+// Cada cuerpo puede fijar sus propios umbrales con `lod = { impostor_radius,
+// mesh_radius }`, por ejemplo los que sugiere `--render-stats`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::scene::Body;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LodLevel {
+    Mesh,
+    Impostor,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(default)]
+pub struct LodThresholds {
+    // Radio proyectado (píxeles) por debajo del cual se usa el impostor
+    pub impostor_radius: f32,
+    // Radio proyectado (píxeles) por encima del cual se vuelve a la malla
+    pub mesh_radius: f32,
+}
+
+impl Default for LodThresholds {
+    fn default() -> Self {
+        LodThresholds { impostor_radius: 8.0, mesh_radius: 12.0 }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct LodState {
+    level: LodLevel,
+    // Peso de la malla en el fundido: 0 = solo impostor, 1 = solo malla
+    mesh_weight: f32,
+    thresholds: LodThresholds,
+}
+
+pub struct LodSystem {
+    states: Vec<LodState>,
+    // Duración del fundido en unidades de tiempo de simulación
+    pub fade_duration: f32,
+}
+
+// Pesos de dibujo para un cuerpo en el cuadro actual
+#[derive(Debug, Clone, Copy)]
+pub struct LodBlend {
+    pub mesh_alpha: f32,
+    pub draw_impostor: bool,
+}
+
+impl LodSystem {
+    pub fn new(bodies: &[Body]) -> Self {
+        LodSystem {
+            states: bodies
+                .iter()
+                .map(|body| LodState { level: LodLevel::Mesh, mesh_weight: 1.0, thresholds: body.lod.unwrap_or_default() })
+                .collect(),
+            fade_duration: 0.3,
+        }
+    }
+
+    // Actualiza el nivel del cuerpo `index` según su radio en pantalla y
+    // devuelve cómo debe dibujarse en este cuadro
+    pub fn update(&mut self, index: usize, projected_radius: f32, dt: f32) -> LodBlend {
+        let state = &mut self.states[index];
+        let thresholds = state.thresholds;
+
+        // Histéresis para no alternar de nivel en el umbral
+        state.level = match state.level {
+            LodLevel::Mesh if projected_radius < thresholds.impostor_radius => LodLevel::Impostor,
+            LodLevel::Impostor if projected_radius > thresholds.mesh_radius => LodLevel::Mesh,
+            level => level,
+        };
+
+        let target = if state.level == LodLevel::Mesh { 1.0 } else { 0.0 };
+        let step = if self.fade_duration > 0.0 { dt / self.fade_duration } else { 1.0 };
+        if state.mesh_weight < target {
+            state.mesh_weight = (state.mesh_weight + step).min(target);
+        } else {
+            state.mesh_weight = (state.mesh_weight - step).max(target);
+        }
+
+        LodBlend {
+            mesh_alpha: state.mesh_weight,
+            draw_impostor: state.mesh_weight < 1.0,
+        }
+    }
+}
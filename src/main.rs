@@ -1,180 +1,329 @@
 use nalgebra_glm::{Vec3, Vec4, Mat4, look_at, perspective};
-use minifb::{Key, Window, WindowOptions, MouseMode};
+use minifb::{Key, KeyRepeat, Window, WindowOptions, MouseMode};
 use std::f32::consts::PI;
+use std::time::Instant;
+use std::path::{Path, PathBuf};
 
-mod framebuffer;
-mod triangle;
-mod vertex;
-mod obj;
-mod color;
-mod fragment;
-mod shaders;
+use proyecto_nave::{bench, culling, daily, debug_view, framebuffer, lighting, obj, pixel_format, shaders, skybox, text, texture, vertex, vertex_stage};
+use proyecto_nave::render::{
+    create_model_matrix, create_scaled_model_matrix, create_viewport_matrix, project_to_screen, projected_radius,
+    render_impostor, render_model, render_model_cached, Uniforms,
+};
 
-use framebuffer::Framebuffer;
+mod scene;
+mod lod;
+mod render_stats;
+mod validation;
+mod particles;
+mod camera;
+mod scene_diff;
+mod flight_path;
+mod race;
+mod course;
+mod audio;
+mod ambient;
+mod cues;
+mod warp;
+mod post;
+mod lens_flare;
+mod input;
+mod ship_rig;
+mod animation;
+mod hangar;
+mod rings;
+mod minimap;
+mod cockpit;
+mod procgen;
+mod orbit_assist;
+mod weapons;
+mod inspect;
+mod spawn;
+mod comet;
+mod shader_registry;
+mod custom_shaders;
+mod screen_lighting;
+mod tour;
+mod antialias;
+mod still;
+mod scene_menu;
+mod landing;
+mod docking;
+mod galaxy;
+mod hull;
+mod mouse_look;
+mod scanner;
+mod anomaly;
+mod wormhole;
+mod flight;
+mod diagnose;
+mod pipeline;
+mod time_control;
+mod transmissions;
+mod power;
+mod ships;
+
+use framebuffer::{pack_color, Framebuffer};
 use vertex::Vertex;
 use obj::Obj;
-use triangle::triangle;
-use shaders::{vertex_shader, shade_star, shade_rocky, shade_gas_giant, shade_spaceship, 
-              shade_ice_planet, shade_desert_planet, shade_volcanic_planet,
-              shade_ocean_planet, shade_purple_planet, shade_ringed_planet};
-
-const WIDTH: usize = 800;
-const HEIGHT: usize = 600;
-
-pub struct Uniforms {
-    model_matrix: Mat4,
-    view_matrix: Mat4,
-    projection_matrix: Mat4,
-    viewport_matrix: Mat4,
-    time: f32,
-    shader_type: u32,
-}
-
-struct Camera {
-    yaw: f32,
-    pitch: f32,
-    distance: f32, // Distancia desde la nave
-    min_distance: f32,
-    max_distance: f32,
-}
-
-impl Camera {
-    fn new() -> Self {
-        Self {
-            yaw: 62.0, // Cámara directamente detrás de la nave
-            pitch: 10.0, // Ángulo de elevación suave
-            distance: 12.0, // Distancia por defecto (tercera persona) - más lejos
-            min_distance: 1.5, // Zoom mínimo para ver la nave completa
-            max_distance: 20.0, // Máximo zoom out aumentado
-        }
-    }
+use shaders::{BARE_SURFACE, CheckpointShader, DerelictShader, RingShader, SpaceshipShader};
+use lighting::{Light, Material, Occluder};
+use particles::{ParticleConfig, ParticleSystem};
+use scene::{Collider, Scene};
+use lod::LodSystem;
+use render_stats::RenderStats;
+use camera::{Camera, CameraMode, FreeCamera, cockpit_view};
+use flight_path::FlightPath;
+use race::Race;
+use course::{Course, torus_mesh};
+use culling::{bounding_radius, Frustum};
+use audio::{AudioOutput, Emitter, Listener, Tone, Waveform, spatialize};
+use cues::{CueTones, NavigationCues};
+use ambient::Soundscape;
+use warp::{Warp, render_streaks};
+use post::{Bloom, HeatDistortion, apply_exposure};
+use lens_flare::LensFlare;
+use skybox::{Cubemap, Skybox};
+use input::GamepadInput;
+use ship_rig::{ShipRig, ShipState};
+use animation::AnimatedModel;
+use hangar::{Hangar, Mission};
+use minimap::render_minimap;
+use cockpit::{CockpitView, render_cockpit_hud, HOLO_TEXT_COLOR};
+use orbit_assist::{OrbitAssist, OrbitReadout};
+use weapons::Weapons;
+use inspect::{pick_body, render_approach_panel, render_info_panel};
+use debug_view::DebugView;
+use comet::CometTails;
+use screen_lighting::{Quality, SCREEN_LIGHTING_RADIUS};
+use tour::{Tour, TourRecorder};
+use antialias::{Antialias, Resolver};
+use landing::Landing;
+use docking::{Docking, DOCK_SPEED};
+use vertex_stage::VertexCache;
+use scanner::Scanner;
+use anomaly::{AnomalyKind, Investigation, render_wormhole};
+use wormhole::{Transit, render_swirl};
+use flight::FlightConfig;
+use pipeline::{Pass, PostEffect, RenderPipeline};
+use time_control::TimeControl;
+use transmissions::{Channel, Transmissions};
+use power::{PowerSystem, Subsystem};
+use ships::Ship;
+use galaxy::Galaxy;
+use hull::Hull;
+use mouse_look::MouseLook;
 
-    fn get_view_matrix(&self, target: &Vec3, ship_yaw: f32) -> Mat4 {
-        let combined_yaw = (self.yaw + ship_yaw).to_radians();
-        let pitch_rad = self.pitch.to_radians();
-        
-        // Calcular posición de la cámara alrededor de la nave
-        let camera_pos = Vec3::new(
-            target.x + self.distance * combined_yaw.cos() * pitch_rad.cos(),
-            target.y + self.distance * pitch_rad.sin(),
-            target.z + self.distance * combined_yaw.sin() * pitch_rad.cos(),
-        );
-        
-        look_at(&camera_pos, target, &Vec3::new(0.0, 1.0, 0.0))
-    }
+// Resolución por defecto; `--resolution <ancho>x<alto>` la cambia y la
+// ventana se puede redimensionar
+const DEFAULT_WIDTH: usize = 800;
+const DEFAULT_HEIGHT: usize = 600;
+const TIME_STEP: f32 = 0.01;
+const DEFAULT_SCENE: &str = "scenes/sistema_solar.toml";
+// Rumbo inicial de la nave: mirando hacia -Z
+const INITIAL_HEADING: f32 = -PI / 2.0;
+// Velocidad de giro con A/D en radianes por cuadro
+const TURN_SPEED: f32 = 0.03;
+// Fracción del camino a la superficie que recorre por cuadro una nave que
+// quedó dentro de un cuerpo
+const PUSH_OUT_RATE: f32 = 0.15;
+// Más hondo que esta fracción del radio ya no se la empuja: reaparece
+const STUCK_DEPTH: f32 = 0.5;
+// Reflejo del cielo en el casco metálico de la nave, visto de frente
+const SHIP_REFLECTIVITY: f32 = 0.35;
+// Columna de aire caliente detrás de los motores: largo y radio en la salida
+// y en la punta, en unidades del mundo, y calor máximo (con el impulso)
+const EXHAUST_PLUME_LENGTH: f32 = 1.2;
+const EXHAUST_PLUME_RADIUS: [f32; 2] = [0.25, 0.5];
+const MAX_ENGINE_HEAT: f32 = 1.5;
+const FOV_DEGREES: f32 = 55.0;
+// Identificador del sonido continuo del motor de la nave fantasma
+const GHOST_ENGINE_SOUND: u32 = 1;
+// Teclas de salto: la tecla N lleva al cuerpo N de la escena
+const WARP_KEYS: [Key; 9] = [
+    Key::Key1, Key::Key2, Key::Key3, Key::Key4, Key::Key5,
+    Key::Key6, Key::Key7, Key::Key8, Key::Key9,
+];
 
-    fn update_rotation(&mut self, delta_x: f32) {
-        self.yaw += delta_x * 0.3;
-        // Solo rotación horizontal, pitch se mantiene fijo
-    }
+// Pantalla que ocupa el ciclo principal: el vuelo, el hangar de la estación
+// donde está acoplada la nave o el mapa galáctico (H)
+enum Screen {
+    Flight,
+    Hangar(Hangar),
+    Galaxy,
+}
 
-    fn zoom(&mut self, delta: f32) {
-        self.distance -= delta * 0.5;
-        self.distance = self.distance.clamp(self.min_distance, self.max_distance);
-    }
+// Anillo translúcido del pase transparente: los de un cuerpo de la escena o
+// los del circuito, por su índice
+enum TranslucentRing {
+    Planet(usize),
+    Checkpoint(usize),
 }
 
 struct Spaceship {
     position: Vec3,
     rotation: Vec3,
-    speed: f32,
+    // Velocidad en unidades por unidad de tiempo: los controles la empujan y
+    // la nave sigue con ella (ver `flight`)
+    velocity: Vec3,
+    flight: FlightConfig,
+    // Factor del impulso sobre el empuje y la velocidad máxima (1 sin impulso)
+    boost: f32,
     tilt_x: f32, // Inclinación lateral (roll)
     tilt_z: f32, // Inclinación frontal (pitch)
     target_tilt_x: f32,
     target_tilt_z: f32,
+    heading: f32, // Rumbo en radianes; el frente de la nave es (cos, 0, sin)
     camera_yaw: f32, // Ángulo de la cámara que sigue a la nave
     target_camera_yaw: f32,
+    // Empuje hacia adelante de los controles en este cuadro, por el impulso;
+    // calienta el aire detrás de los motores
+    throttle: f32,
+    engine_trail: ParticleSystem,
 }
 
 impl Spaceship {
-    fn new(position: Vec3) -> Self {
+    fn new(position: Vec3, trail_config: ParticleConfig, flight: FlightConfig) -> Self {
         Self {
             position,
             rotation: Vec3::new(0.0, 90.0, 0.0),
-            speed: 0.15,
+            velocity: Vec3::zeros(),
+            flight,
+            boost: 1.0,
             tilt_x: 0.0,
             tilt_z: 0.0,
             target_tilt_x: 0.0,
             target_tilt_z: 0.0,
+            heading: INITIAL_HEADING,
             camera_yaw: 0.0,
             target_camera_yaw: 0.0,
+            throttle: 0.0,
+            engine_trail: ParticleSystem::new(trail_config),
         }
     }
 
-    fn check_collision(&self, celestial_bodies: &[(Vec3, f32)]) -> bool {
-        // Verificar colisión con cada cuerpo celeste
-        for (body_pos, body_radius) in celestial_bodies {
-            let distance = (self.position - body_pos).magnitude();
-            // Radio de colisión = radio del planeta + margen de seguridad
-            if distance < body_radius + 2.0 {
-                return true;
-            }
-        }
-        false
+    // Primer cuerpo que deja a `point` a menos del margen de seguridad de su superficie
+    fn blocking_body(point: Vec3, celestial_bodies: &[Collider]) -> Option<&Collider> {
+        celestial_bodies
+            .iter()
+            .find(|collider| collider.clearance(point) < collider.margin)
     }
 
-    fn move_forward(&mut self, celestial_bodies: &[(Vec3, f32)]) {
-        let new_pos = Vec3::new(self.position.x, self.position.y, self.position.z - self.speed);
-        let old_pos = self.position;
-        self.position = new_pos;
-        if self.check_collision(celestial_bodies) {
-            self.position = old_pos; // Revertir movimiento si hay colisión
-        } else {
-            self.target_tilt_z = -0.15;
+    // Si la nave quedó dentro de un cuerpo (un salto de posición, la escena
+    // recargada con un cuerpo encima) la empuja de a poco hacia afuera por la
+    // normal. Devuelve el índice del cuerpo cuando por ahí no hay salida: está
+    // demasiado hondo o la superficie cae dentro de otro cuerpo.
+    fn push_out(&mut self, celestial_bodies: &[Collider]) -> Option<usize> {
+        let (index, collider) = celestial_bodies
+            .iter()
+            .enumerate()
+            .find(|(_, collider)| collider.clearance(self.position) < 0.0)?;
+        if collider.clearance(self.position) < -collider.radius * STUCK_DEPTH {
+            return Some(index);
+        }
+        let exit = collider.surface_point(self.position, collider.margin + 1e-3);
+        let blocked = celestial_bodies
+            .iter()
+            .enumerate()
+            .any(|(other, collider)| other != index && collider.clearance(exit) < collider.margin);
+        if blocked {
+            return Some(index);
         }
+        self.position += (exit - self.position) * PUSH_OUT_RATE;
+        None
     }
 
-    fn move_backward(&mut self, celestial_bodies: &[(Vec3, f32)]) {
-        let new_pos = Vec3::new(self.position.x, self.position.y, self.position.z + self.speed);
-        let old_pos = self.position;
-        self.position = new_pos;
-        if self.check_collision(celestial_bodies) {
-            self.position = old_pos;
-        } else {
-            self.target_tilt_z = 0.1;
+    // Dirección hacia la que apunta la nave en el plano XZ
+    fn forward(&self) -> Vec3 {
+        Vec3::new(self.heading.cos(), 0.0, self.heading.sin())
+    }
+
+    fn right(&self) -> Vec3 {
+        Vec3::new(-self.heading.sin(), 0.0, self.heading.cos())
+    }
+
+    // Desplaza la nave; si choca con un cuerpo celeste se quita la parte del
+    // movimiento que entra en él (proyección sobre el plano tangente) y se
+    // empuja la nave a la superficie, así se desliza en lugar de quedar pegada.
+    // La velocidad pierde también la componente hacia el cuerpo; devuelve la
+    // mayor velocidad de choque que se perdió así.
+    fn try_move(&mut self, delta: Vec3, celestial_bodies: &[Collider]) -> f32 {
+        let mut delta = delta;
+        let mut impact: f32 = 0.0;
+        // Varias pasadas por si al deslizarse sobre un cuerpo se toca otro
+        for _ in 0..4 {
+            let target = self.position + delta;
+            let Some(collider) = Self::blocking_body(target, celestial_bodies) else {
+                self.position = target;
+                return impact;
+            };
+            let normal = collider.normal(target);
+            let into = delta.dot(&normal);
+            if into < 0.0 {
+                delta -= normal * into;
+            }
+            let into = self.velocity.dot(&normal);
+            if into < 0.0 {
+                self.velocity -= normal * into;
+                impact = impact.max(-into);
+            }
+            // Resolver la penetración que queda por la curvatura de la superficie
+            let slid = self.position + delta;
+            delta = collider.surface_point(slid, collider.margin + 1e-3) - self.position;
         }
+        // Sin solución estable (p. ej. entre dos cuerpos): la nave se detiene
+        impact = impact.max(self.velocity.magnitude());
+        self.velocity = Vec3::zeros();
+        impact
     }
 
-    fn move_left(&mut self, celestial_bodies: &[(Vec3, f32)]) {
-        let new_pos = Vec3::new(self.position.x - self.speed, self.position.y, self.position.z);
-        let old_pos = self.position;
-        self.position = new_pos;
-        if self.check_collision(celestial_bodies) {
-            self.position = old_pos;
-        } else {
-            self.target_tilt_x = -0.2;
-            self.target_camera_yaw = -15.0;
+    // Empuje de los controles, cada eje en -1..1 en el marco de la nave
+    // (teclado: -1, 0 o 1; gamepad: analógico)
+    fn fly(&mut self, forward: f32, right: f32, up: f32, dt: f32) {
+        let thrust = self.forward() * forward + self.right() * right + Vec3::new(0.0, up, 0.0);
+        self.velocity += thrust * self.flight.thrust * self.boost * dt;
+        self.throttle = self.throttle.max(forward.max(0.0) * self.boost);
+        // Se inclina hacia donde empuja: de nariz al acelerar, hacia atrás al frenar
+        if forward > 0.0 {
+            self.target_tilt_z = -0.15 * forward;
+        } else if forward < 0.0 {
+            self.target_tilt_z = -0.1 * forward;
+        }
+        if right != 0.0 {
+            self.target_tilt_x = 0.2 * right;
         }
     }
 
-    fn move_right(&mut self, celestial_bodies: &[(Vec3, f32)]) {
-        let new_pos = Vec3::new(self.position.x + self.speed, self.position.y, self.position.z);
-        let old_pos = self.position;
-        self.position = new_pos;
-        if self.check_collision(celestial_bodies) {
-            self.position = old_pos;
+    fn brake(&mut self, dt: f32) {
+        self.velocity = self.flight.braked(self.velocity, dt);
+    }
+
+    // Avanza la nave con su velocidad, con arrastre y límite de velocidad.
+    // Devuelve la velocidad del choque, si lo hubo.
+    fn drift(&mut self, dt: f32, celestial_bodies: &[Collider]) -> f32 {
+        self.velocity = self.flight.coast(self.velocity, dt, self.boost);
+        if self.velocity.magnitude() > 0.0 {
+            self.try_move(self.velocity * dt, celestial_bodies)
         } else {
-            self.target_tilt_x = 0.2;
-            self.target_camera_yaw = 15.0;
+            0.0
         }
     }
 
-    fn move_up(&mut self, celestial_bodies: &[(Vec3, f32)]) {
-        let new_pos = Vec3::new(self.position.x, self.position.y + self.speed, self.position.z);
-        let old_pos = self.position;
-        self.position = new_pos;
-        if self.check_collision(celestial_bodies) {
-            self.position = old_pos;
-        }
+    // Detiene la nave: saltos, aterrizajes y reapariciones no conservan la velocidad
+    fn stop(&mut self) {
+        self.velocity = Vec3::zeros();
     }
 
-    fn move_down(&mut self, celestial_bodies: &[(Vec3, f32)]) {
-        let new_pos = Vec3::new(self.position.x, self.position.y - self.speed, self.position.z);
-        let old_pos = self.position;
-        self.position = new_pos;
-        if self.check_collision(celestial_bodies) {
-            self.position = old_pos;
-        }
+    // Gira la nave sobre su eje vertical (radianes, positivo = derecha)
+    fn turn(&mut self, angle: f32) {
+        self.heading += angle;
+        self.target_tilt_x = 0.2 * angle.signum();
+        self.target_camera_yaw = 15.0 * angle.signum();
+    }
+
+    // Yaw que recibe la cámara de seguimiento: la cámara queda detrás de la
+    // nave según su rumbo, más el balanceo suavizado de los giros
+    fn follow_camera_yaw(&self) -> f32 {
+        self.camera_yaw + (self.heading - INITIAL_HEADING).to_degrees()
     }
 
     fn update_animation(&mut self) {
@@ -192,503 +341,1796 @@ impl Spaceship {
         self.target_camera_yaw *= 0.9;
     }
 
+    // Empuje de los controles en el cuadro; empieza en cero para el siguiente
+    fn take_throttle(&mut self) -> f32 {
+        std::mem::take(&mut self.throttle)
+    }
+
+    // Salida de los motores: `anchor` es [atrás, arriba] del centro de la nave
+    fn engine_position(&self, anchor: [f32; 2]) -> Vec3 {
+        self.position - self.forward() * anchor[0] + Vec3::new(0.0, anchor[1], 0.0)
+    }
+
+    // Punto detrás de los motores según el movimiento del cuadro; None si la nave no se movió
+    fn exhaust_origin(&self, previous_position: Vec3) -> Option<Vec3> {
+        let movement = self.position - previous_position;
+        if movement.magnitude() > 1e-4 {
+            Some(self.position - movement.normalize() * 0.6)
+        } else {
+            None
+        }
+    }
+
+    // Emite la estela de los motores en sentido opuesto al movimiento del cuadro
+    fn update_engine_trail(&mut self, previous_position: Vec3, dt: f32) {
+        if let Some(exhaust_origin) = self.exhaust_origin(previous_position) {
+            let movement = self.position - previous_position;
+            self.engine_trail.emit(exhaust_origin, -movement / dt * 0.3);
+        }
+        self.engine_trail.update(dt);
+    }
+
     fn get_animated_rotation(&self) -> Vec3 {
         Vec3::new(
             self.rotation.x + self.tilt_z,
-            self.rotation.y,
+            self.rotation.y - (self.heading - INITIAL_HEADING),
             self.rotation.z + self.tilt_x,
         )
     }
 }
 
-fn create_model_matrix(translation: Vec3, scale: f32, rotation: Vec3) -> Mat4 {
-    let (sin_x, cos_x) = rotation.x.sin_cos();
-    let (sin_y, cos_y) = rotation.y.sin_cos();
-    let (sin_z, cos_z) = rotation.z.sin_cos();
-
-    let rotation_matrix_x = Mat4::new(
-        1.0,  0.0,    0.0,   0.0,
-        0.0,  cos_x, -sin_x, 0.0,
-        0.0,  sin_x,  cos_x, 0.0,
-        0.0,  0.0,    0.0,   1.0,
-    );
-
-    let rotation_matrix_y = Mat4::new(
-        cos_y,  0.0,  sin_y, 0.0,
-        0.0,    1.0,  0.0,   0.0,
-        -sin_y, 0.0,  cos_y, 0.0,
-        0.0,    0.0,  0.0,   1.0,
-    );
-
-    let rotation_matrix_z = Mat4::new(
-        cos_z, -sin_z, 0.0, 0.0,
-        sin_z,  cos_z, 0.0, 0.0,
-        0.0,    0.0,  1.0, 0.0,
-        0.0,    0.0,  0.0, 1.0,
-    );
-
-    let rotation_matrix = rotation_matrix_z * rotation_matrix_y * rotation_matrix_x;
-
-    let scale_matrix = Mat4::new(
-        scale, 0.0,   0.0,   0.0,
-        0.0,   scale, 0.0,   0.0,
-        0.0,   0.0,   scale, 0.0,
-        0.0,   0.0,   0.0,   1.0,
-    );
-
-    let translation_matrix = Mat4::new(
-        1.0, 0.0, 0.0, translation.x,
-        0.0, 1.0, 0.0, translation.y,
-        0.0, 0.0, 1.0, translation.z,
-        0.0, 0.0, 0.0, 1.0,
-    );
-
-    translation_matrix * rotation_matrix * scale_matrix
+fn render_orbit(framebuffer: &mut Framebuffer, center: Vec3, body: &scene::Body, view_matrix: &Mat4, projection_matrix: &Mat4, viewport_matrix: &Mat4) {
+    let segments = 100;
+    // Gris oscuro para las órbitas; las de los cometas en un azul apagado
+    framebuffer.set_current_color(if body.tail.is_some() { 0x2E4660 } else { 0x444444 });
+
+    let to_screen = |angle: f32| {
+        // Punto de la elipse real (anomalía excéntrica), ya inclinada y orientada
+        let point = center + body.orbit_point(angle);
+        let clip = projection_matrix * view_matrix * Vec4::new(point.x, point.y, point.z, 1.0);
+        if clip.w == 0.0 {
+            return None;
+        }
+        let ndc = clip / clip.w;
+        // Solo se dibujan los tramos dentro del frustum
+        if ndc.z <= 0.0 || ndc.z >= 1.0 {
+            return None;
+        }
+        let screen = viewport_matrix * Vec4::new(ndc.x, ndc.y, ndc.z, 1.0);
+        Some(Vec3::new(screen.x, screen.y, ndc.z))
+    };
+
+    let mut previous = to_screen(0.0);
+    for i in 1..=segments {
+        let current = to_screen((i as f32 / segments as f32) * 2.0 * std::f32::consts::PI);
+        if let (Some(from), Some(to)) = (previous, current) {
+            framebuffer.draw_line(from, to);
+        }
+        previous = current;
+    }
 }
 
-fn create_viewport_matrix(width: f32, height: f32) -> Mat4 {
-    Mat4::new(
-        width / 2.0, 0.0, 0.0, width / 2.0,
-        0.0, -height / 2.0, 0.0, height / 2.0,
-        0.0, 0.0, 1.0, 0.0,
-        0.0, 0.0, 0.0, 1.0,
-    )
+// Velocidad, posición, FPS y planeta más cercano en la esquina superior izquierda
+fn render_hud(framebuffer: &mut Framebuffer, scene: &Scene, spaceship: &Spaceship, speed: f32, fps: f32, extra: &[String], color: u32) {
+    let position = spaceship.position;
+    let mut lines = vec![
+        format!("Velocidad: {:.1}", speed),
+        format!("Posición: {:.1} {:.1} {:.1}", position.x, position.y, position.z),
+        format!("FPS: {:.0}", fps),
+    ];
+    if let Some((index, distance)) = scene.nearest_body(position) {
+        lines.push(format!("Cerca de: {} ({:.1})", scene.bodies[index].name, distance));
+    }
+    lines.extend_from_slice(extra);
+
+    for (i, line) in lines.iter().enumerate() {
+        framebuffer.draw_text(10, 10 + i * text::LINE_HEIGHT, line, color);
+    }
+}
+
+// Valor que sigue a `flag` en la línea de comandos (p. ej. `--scene archivo`)
+fn arg_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
+}
+
+// Ruta de la escena: `--scene <archivo>` o el sistema solar por defecto
+fn scene_path_from_args(args: &[String]) -> String {
+    arg_value(args, "--scene").unwrap_or(DEFAULT_SCENE).to_string()
 }
 
-fn render_model(framebuffer: &mut Framebuffer, uniforms: &Uniforms, vertices: &[Vertex], indices: &[u32]) {
-    let mut transformed_vertices = Vec::with_capacity(vertices.len());
-    for vertex in vertices {
-        transformed_vertices.push(vertex_shader(vertex, uniforms));
+// Coloca la nave detrás del primer anillo, apuntando hacia él
+fn place_at_course_start(spaceship: &mut Spaceship, course: &Course) {
+    if let Some(first) = course.rings.first() {
+        spaceship.position = first.center - first.normal * 8.0;
+        spaceship.heading = first.normal.z.atan2(first.normal.x);
+        spaceship.stop();
     }
+}
 
-    // Process triangles with early culling
-    for i in (0..indices.len()).step_by(3) {
-        let v1 = &transformed_vertices[indices[i] as usize];
-        let v2 = &transformed_vertices[indices[i+1] as usize];
-        let v3 = &transformed_vertices[indices[i+2] as usize];
+// Objetos animados de la escena (glTF con fotogramas clave u OBJ), con su
+// índice en `scene.props`
+fn load_props(scene: &Scene) -> Vec<(usize, AnimatedModel)> {
+    scene.props
+        .iter()
+        .enumerate()
+        .filter_map(|(index, prop)| match AnimatedModel::load(&prop.model) {
+            Ok(model) => Some((index, model)),
+            Err(e) => {
+                eprintln!("No se pudo cargar '{}' ({}): {}", prop.name, prop.model, e);
+                None
+            }
+        })
+        .collect()
+}
 
-        // Early clip space culling - skip triangles completely outside view
-        let clip_coords = [v1.transformed_position, v2.transformed_position, v3.transformed_position];
-        if clip_coords.iter().all(|v| v.x.abs() > v.w.abs() * 1.5 || v.y.abs() > v.w.abs() * 1.5 || v.z < -v.w || v.z > v.w) {
-            continue;
-        }
+// Malla de los anillos de un cuerpo: vértices, índices y radio
+type RingMesh = (Vec<Vertex>, Vec<u32>, f32);
 
-        let fragments = triangle(v1, v2, v3, uniforms);
-        for fragment in fragments {
-            let x = fragment.position.x as usize;
-            let y = fragment.position.y as usize;
-
-            if x < WIDTH && y < HEIGHT {
-                let color_vec = match uniforms.shader_type {
-                    0 => shade_star(fragment.vertex_position, uniforms.time),
-                    1 => shade_rocky(fragment.vertex_position, uniforms.time),
-                    2 => shade_gas_giant(fragment.vertex_position, uniforms.time),
-                    3 => shade_spaceship(fragment.vertex_position, uniforms.time),
-                    4 => shade_ice_planet(fragment.vertex_position, uniforms.time),
-                    5 => shade_desert_planet(fragment.vertex_position, uniforms.time),
-                    6 => shade_volcanic_planet(fragment.vertex_position, uniforms.time),
-                    7 => shade_ocean_planet(fragment.vertex_position, uniforms.time),
-                    8 => shade_purple_planet(fragment.vertex_position, uniforms.time),
-                    9 => shade_ringed_planet(fragment.vertex_position, uniforms.time),
-                    _ => Vec3::new(0.5, 0.5, 0.5), // Gris por defecto
-                };
+// Una malla de anillos por cada cuerpo que los declara
+fn ring_meshes(scene: &Scene) -> Vec<Option<RingMesh>> {
+    scene.bodies.iter()
+        .map(|body| body.rings.as_ref().map(|rings| {
+            let (vertices, indices) = rings.mesh();
+            let radius = bounding_radius(&vertices);
+            (vertices, indices, radius)
+        }))
+        .collect()
+}
 
-                let r = (color_vec.x * 255.0).clamp(0.0, 255.0) as u32;
-                let g = (color_vec.y * 255.0).clamp(0.0, 255.0) as u32;
-                let b = (color_vec.z * 255.0).clamp(0.0, 255.0) as u32;
-                let color = (r << 16) | (g << 8) | b;
-                
-                framebuffer.set_current_color(color);
-                framebuffer.point(x, y, fragment.depth);
+// Escribe el informe de --render-stats y muestra su resumen
+fn save_render_stats(path: &str, stats: &RenderStats) {
+    match stats.save(path) {
+        Ok(()) => {
+            println!("Informe de dibujo guardado en {}", path);
+            for line in stats.summary() {
+                println!("  {}", line);
             }
         }
+        Err(e) => eprintln!("No se pudo guardar el informe de dibujo {}: {}", path, e),
     }
 }
 
-fn render_orbit(framebuffer: &mut Framebuffer, radius: f32, inclination: f32, view_matrix: &Mat4, projection_matrix: &Mat4, viewport_matrix: &Mat4) {
-    let segments = 100;
-    let orbit_color = 0x444444; // Gris oscuro para las órbitas
-    framebuffer.set_current_color(orbit_color);
-    
-    for i in 0..segments {
-        let angle1 = (i as f32 / segments as f32) * 2.0 * std::f32::consts::PI;
-        let angle2 = ((i + 1) as f32 / segments as f32) * 2.0 * std::f32::consts::PI;
-        
-        // Puntos en el espacio 3D con inclinación orbital
-        let p1 = Vec3::new(
-            angle1.cos() * radius,
-            angle1.sin() * radius * inclination.sin(),
-            angle1.sin() * radius * inclination.cos()
-        );
-        let p2 = Vec3::new(
-            angle2.cos() * radius,
-            angle2.sin() * radius * inclination.sin(),
-            angle2.sin() * radius * inclination.cos()
-        );
-        
-        // Transformar a espacio de pantalla
-        let p1_4d = Vec4::new(p1.x, p1.y, p1.z, 1.0);
-        let p2_4d = Vec4::new(p2.x, p2.y, p2.z, 1.0);
-        
-        let p1_transformed = projection_matrix * view_matrix * p1_4d;
-        let p2_transformed = projection_matrix * view_matrix * p2_4d;
-        
-        // Perspective divide
-        if p1_transformed.w != 0.0 && p2_transformed.w != 0.0 {
-            let p1_ndc = p1_transformed / p1_transformed.w;
-            let p2_ndc = p2_transformed / p2_transformed.w;
-            
-            // Solo dibujar si están dentro del frustum
-            if p1_ndc.z > 0.0 && p1_ndc.z < 1.0 && p2_ndc.z > 0.0 && p2_ndc.z < 1.0 {
-                let p1_screen = viewport_matrix * Vec4::new(p1_ndc.x, p1_ndc.y, p1_ndc.z, 1.0);
-                let p2_screen = viewport_matrix * Vec4::new(p2_ndc.x, p2_ndc.y, p2_ndc.z, 1.0);
-                
-                let x1 = p1_screen.x as i32;
-                let y1 = p1_screen.y as i32;
-                let x2 = p2_screen.x as i32;
-                let y2 = p2_screen.y as i32;
-                
-                // Dibujar línea más gruesa (3 píxeles de grosor)
-                for offset_x in -1..=1 {
-                    for offset_y in -1..=1 {
-                        let px1 = (x1 + offset_x) as usize;
-                        let py1 = (y1 + offset_y) as usize;
-                        let px2 = (x2 + offset_x) as usize;
-                        let py2 = (y2 + offset_y) as usize;
-                        
-                        if px1 < framebuffer.width && py1 < framebuffer.height {
-                            framebuffer.point(px1, py1, p1_ndc.z);
-                        }
-                        if px2 < framebuffer.width && py2 < framebuffer.height {
-                            framebuffer.point(px2, py2, p2_ndc.z);
-                        }
-                    }
-                }
+// `--resolution 1280x720`; sin la opción se usa la resolución por defecto
+fn resolution_from_args(args: &[String]) -> Result<(usize, usize), String> {
+    let Some(value) = arg_value(args, "--resolution") else {
+        return Ok((DEFAULT_WIDTH, DEFAULT_HEIGHT));
+    };
+    let parsed = value
+        .split_once('x')
+        .and_then(|(width, height)| Some((width.parse::<usize>().ok()?, height.parse::<usize>().ok()?)));
+    match parsed {
+        Some((width, height)) if width >= 160 && height >= 120 => Ok((width, height)),
+        _ => Err(format!("resolución inválida '{}' (se espera <ancho>x<alto>, mínimo 160x120)", value)),
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    // Los shaders registrados tienen que estar antes de cargar cualquier escena
+    if shader_registry::install(custom_shaders::registry()).is_err() {
+        eprintln!("El registro de shaders ya estaba fijado");
+    }
+    if let Some(code) = scene_diff::run_cli(&args) {
+        std::process::exit(code);
+    }
+    // --diagnose [carpeta] dibuja las escenas de prueba sin ventana y sale
+    if args.iter().any(|arg| arg == "--diagnose") {
+        let directory = arg_value(&args, "--diagnose").filter(|value| !value.starts_with("--")).unwrap_or(diagnose::DEFAULT_DIRECTORY);
+        std::process::exit(diagnose::run(Path::new(directory)));
+    }
+    // --bench mide las etapas del rasterizador sobre escenas fijas y sale
+    // (`cargo bench --features bench` lo corre con criterion)
+    if args.iter().any(|arg| arg == "--bench") {
+        std::process::exit(bench::run());
+    }
+
+    let (width, height) = match resolution_from_args(&args) {
+        Ok(resolution) => resolution,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // --menu muestra primero las escenas de la carpeta de --scene para elegir una
+    let scene_path = scene_path_from_args(&args);
+    let scene_path = if args.iter().any(|arg| arg == "--menu") {
+        let directory = Path::new(&scene_path).parent().unwrap_or(Path::new("."));
+        match scene_menu::choose(directory, width, height) {
+            Some(path) => path.to_string_lossy().into_owned(),
+            None => return,
+        }
+    } else {
+        scene_path
+    };
+
+    // --generate <semilla> arma un sistema procedural completo en lugar de
+    // cargar la escena
+    let generated = arg_value(&args, "--generate").map(|value| match value.parse::<u64>() {
+        Ok(seed) => seed,
+        Err(_) => {
+            eprintln!("Semilla inválida para --generate: '{}' (se espera un entero sin signo)", value);
+            std::process::exit(1);
+        }
+    });
+    let loaded = match generated {
+        Some(seed) => procgen::generate(seed),
+        None => Scene::load(&scene_path),
+    };
+    let mut scene = match loaded {
+        Ok(scene) => scene,
+        Err(e) => {
+            match generated {
+                Some(seed) => eprintln!("No se pudo generar el sistema {}: {}", seed, e),
+                None => eprintln!("No se pudo cargar la escena {}: {}", scene_path, e),
             }
+            std::process::exit(1);
         }
+    };
+    if generated.is_none() {
+        scene_menu::remember(Path::new(&scene_path));
+    }
+    if let Some(seed) = generated {
+        let planets = scene.bodies.iter().filter(|body| body.orbit_radius > 0.0 && body.parent.is_none()).count();
+        println!("Sistema generado con la semilla {}: {} ({} planetas)", seed, scene.bodies[0].name, planets);
     }
-}
 
-fn render_starfield(framebuffer: &mut Framebuffer, time: f32) {
-    use std::f32::consts::PI;
-    let width = framebuffer.width;
-    let height = framebuffer.height;
-    
-    // Estrellas fijas
-    for i in 0..800 {
-        let seed = i as f32 * 12.9898;
-        let x = ((seed.sin() * 43758.5453).fract() * width as f32) as usize;
-        let y = (((seed * 1.234).cos() * 43758.5453).fract() * height as f32) as usize;
-        
-        if x < width && y < height {
-            let brightness = ((seed * 2.345).sin() * 0.5 + 0.5) * 255.0;
-            let b = brightness as u32;
-            let color = (b << 16) | (b << 8) | b;
-            framebuffer.set_current_color(color);
-            framebuffer.point(x, y, 0.0);
+    // --daily usa la semilla de hoy; --seed <n> reproduce una semilla compartida.
+    // Sin ninguna de las dos vale la `seed` de la escena, si la tiene.
+    let mut system_seed = if args.iter().any(|arg| arg == "--daily") {
+        Some(daily::today_seed())
+    } else {
+        arg_value(&args, "--seed").and_then(|value| value.parse::<u32>().ok()).or(scene.seed)
+    };
+    if let Some(seed) = system_seed {
+        scene.reseed(seed);
+    }
+    // Al volver al sistema de partida desde el mapa galáctico se le aplica
+    // otra vez la misma semilla
+    let home_seed = system_seed;
+
+    // --galaxy <semilla> elige los sistemas vecinos del mapa galáctico (H)
+    let galaxy_seed = arg_value(&args, "--galaxy").map_or(galaxy::DEFAULT_SEED, |value| match value.parse::<u64>() {
+        Ok(seed) => seed,
+        Err(_) => {
+            eprintln!("Semilla inválida para --galaxy: '{}' (se espera un entero sin signo)", value);
+            std::process::exit(1);
+        }
+    });
+    let home_origin = match generated {
+        Some(seed) => galaxy::Origin::Generated(seed),
+        None => galaxy::Origin::File(PathBuf::from(&scene_path)),
+    };
+    let mut galaxy = Galaxy::new(galaxy_seed, &scene, home_origin);
+
+    // --record <archivo> graba la trayectoria de la nave al salir;
+    // --ghost <archivo> la reproduce como una estela fantasma
+    let record_path = arg_value(&args, "--record");
+    let mut recorded_path = FlightPath::default();
+    let ghost_path = arg_value(&args, "--ghost").and_then(|path| match FlightPath::load(path) {
+        Ok(flight) => Some(flight),
+        Err(e) => {
+            eprintln!("No se pudo cargar la trayectoria {}: {}", path, e);
+            None
+        }
+    });
+
+    // --race activa el circuito `[race]` de la escena
+    let mut race = if args.iter().any(|arg| arg == "--race") {
+        match &scene.race {
+            Some(course) => Some(Race::new(course)),
+            None => {
+                eprintln!("La escena {} no define un circuito [race]", scene_path);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        None
+    };
+    let ghost_race_time = match (&scene.race, &ghost_path) {
+        (Some(course), Some(ghost)) if race.is_some() => Race::replay(course, ghost),
+        _ => None,
+    };
+
+    // --quality low ilumina los cuerpos lejanos con un pase en pantalla más barato
+    let quality = match arg_value(&args, "--quality").map(|value| (value, Quality::parse(value))) {
+        None => Quality::default(),
+        Some((_, Some(quality))) => quality,
+        Some((value, None)) => {
+            eprintln!("Calidad inválida para --quality: '{}' (se espera low o high)", value);
+            std::process::exit(1);
+        }
+    };
+
+    // --aa fxaa|ssaa2|ssaa4 suaviza los bordes de los planetas y las órbitas
+    let antialias = match arg_value(&args, "--aa").map(|value| (value, Antialias::parse(value))) {
+        None => Antialias::default(),
+        Some((_, Some(antialias))) => antialias,
+        Some((value, None)) => {
+            eprintln!("Antialiasing inválido para --aa: '{}' (se espera off, fxaa, ssaa2 o ssaa4)", value);
+            std::process::exit(1);
+        }
+    };
+
+    // --passes y --post eligen y ordenan los pases de dibujo y los efectos de
+    // posproceso (ver `pipeline`)
+    let mut pipeline = RenderPipeline::default();
+    if let Some(value) = arg_value(&args, "--passes") {
+        if let Err(e) = pipeline.set_passes(value) {
+            eprintln!("Pases inválidos para --passes: {}", e);
+            std::process::exit(1);
+        }
+    }
+    if let Some(value) = arg_value(&args, "--post") {
+        if let Err(e) = pipeline.set_post_chain(value) {
+            eprintln!("Efectos inválidos para --post: {}", e);
+            std::process::exit(1);
         }
     }
-}
 
-fn main() {
+    // --tour <archivo> carga un recorrido cinemático que se reproduce con T
+    let mut tour = arg_value(&args, "--tour").map(|path| match Tour::load(path).and_then(|tour| tour.check(&scene).map(|_| tour)) {
+        Ok(tour) => tour,
+        Err(e) => {
+            eprintln!("No se pudo cargar el recorrido {}: {}", path, e);
+            std::process::exit(1);
+        }
+    });
+    // --record-tour <archivo> graba un recorrido con K desde la cámara actual;
+    // lo grabado reemplaza al de --tour para reproducirlo con T
+    let mut tour_recorder = arg_value(&args, "--record-tour").map(|path| match TourRecorder::new(path) {
+        Ok(recorder) => recorder,
+        Err(e) => {
+            eprintln!("No se pudo abrir el recorrido {}: {}", path, e);
+            std::process::exit(1);
+        }
+    });
+
+    // --spawn <nombre> elige el punto de partida de la nave (`[[spawn]]` en la escena)
+    let spawn = match spawn::choose(&scene, arg_value(&args, "--spawn")) {
+        Ok(spawn) => spawn,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
     let mut window = Window::new(
-        "Proyecto 3 - Space Travel (WASD: mover nave, Click derecho: rotar cámara, Scroll: zoom)",
-        WIDTH,
-        HEIGHT,
-        WindowOptions::default(),
+        "Proyecto 3 - Space Travel (W/S: avanzar, A/D: girar, Click derecho: girar nave, Scroll: zoom)",
+        width,
+        height,
+        WindowOptions { resize: true, ..WindowOptions::default() },
     )
     .unwrap();
 
-    let mut framebuffer = Framebuffer::new(WIDTH, HEIGHT);
+    // La escena se dibuja en `framebuffer`, con supersampling a mayor tamaño
+    // que la ventana; el HUD se dibuja en `display`, ya a su tamaño
+    let aa_scale = antialias.scale();
+    let mut framebuffer = Framebuffer::new(width * aa_scale, height * aa_scale);
+    let mut display = Framebuffer::new(width, height);
+    let mut resolver = Resolver::new(antialias);
     
+    // --planet-model cambia el modelo de los planetas (OBJ o glTF, según la extensión)
+    let load_model = |flag: &str, default: &str| {
+        let path = arg_value(&args, flag).unwrap_or(default);
+        Obj::load(path).unwrap_or_else(|e| {
+            eprintln!("No se pudo cargar el modelo {}: {}", path, e);
+            std::process::exit(1);
+        })
+    };
+
     // Load planet model for celestial bodies
-    let planet_obj = Obj::load("assets/planeta.obj").expect("No se pudo cargar planeta.obj");
+    let planet_obj = load_model("--planet-model", "assets/planeta.obj");
     let (planet_vertices, planet_indices) = planet_obj.get_vertex_and_index_arrays();
+    let planet_radius = bounding_radius(&planet_vertices);
 
-    // Load spaceship model
-    let nave_obj = Obj::load("assets/CazaTie.obj").expect("No se pudo cargar CazaTie.obj");
-    let (nave_vertices, nave_indices) = nave_obj.get_vertex_and_index_arrays();
+    // Naves del registro (--ships), cada una con su modelo, escala y cámara;
+    // --ship-model cambia el modelo de la primera, la de partida
+    let ships_path = arg_value(&args, "--ships").unwrap_or(ships::DEFAULT_REGISTRY);
+    let mut ship_configs = ships::load_registry(ships_path).unwrap_or_else(|e| {
+        eprintln!("No se pudo cargar el registro de naves {}: {}", ships_path, e);
+        std::process::exit(1);
+    });
+    if let Some(path) = arg_value(&args, "--ship-model") {
+        ship_configs[0].model = path.to_string();
+    }
+    let ships: Vec<Ship> = ship_configs
+        .into_iter()
+        .map(|config| {
+            Ship::load(config).unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            })
+        })
+        .collect();
+    let mut ship_rig = ShipRig::new(ships[0].parts.iter().map(|(part, _)| part));
+
+    let mut props = load_props(&scene);
 
-    let projection_matrix = perspective(WIDTH as f32 / HEIGHT as f32, 55.0 * PI / 180.0, 0.1, 150.0);
-    let viewport_matrix = create_viewport_matrix(WIDTH as f32, HEIGHT as f32);
+    let mut viewport_matrix = create_viewport_matrix(framebuffer.width as f32, framebuffer.height as f32);
+    let mut display_viewport = create_viewport_matrix(width as f32, height as f32);
 
+    let mut lod = LodSystem::new(&scene.bodies);
+    // Vértices transformados de cada cuerpo, para reutilizarlos entre cuadros
+    let mut vertex_caches: Vec<VertexCache> = scene.bodies.iter().map(|_| VertexCache::default()).collect();
+    // Cuerpos que eclipsan la estrella al que se está dibujando y a la nave
+    let mut eclipsers = Vec::new();
+    let mut ship_eclipsers = Vec::new();
+    // --render-stats <archivo> mide el dibujo de cada cuerpo y al salir
+    // escribe un informe con umbrales de nivel de detalle sugeridos
+    let render_stats_path = arg_value(&args, "--render-stats");
+    let mut render_stats = render_stats_path.map(|_| RenderStats::new(&scene));
     let mut camera = Camera::new();
-    let mut spaceship = Spaceship::new(Vec3::new(35.0, 15.0, 40.0));
+    camera.set_view(spawn.camera_pitch, spawn.camera_distance);
+    let mut camera_mode = CameraMode::Follow;
+    // Vista desde la cabina con el HUD holográfico (V), dentro del modo que sigue a la nave
+    let mut cockpit = false;
+    let mut free_camera = FreeCamera::looking_at(Vec3::new(0.0, 20.0, 80.0), Vec3::new(0.0, 0.0, 0.0));
+    let mut spaceship = Spaceship::new(spawn.position, scene.engine_trail.clone(), scene.flight.scaled(ships[0].config.speed, ships[0].config.thrust));
+    if let Some(heading) = spawn.heading {
+        spaceship.heading = heading;
+    }
+
+    // --course genera un circuito de anillos sobre los planetas; la nave
+    // empieza detrás del primer anillo, apuntando hacia él
+    let mut course = if args.iter().any(|arg| arg == "--course") {
+        match Course::generate(&scene, &scene.course.clone().unwrap_or_default()) {
+            Some(course) => Some(course),
+            None => {
+                eprintln!("La escena {} no tiene suficientes planetas en órbita para un circuito", scene_path);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        None
+    };
+    if let Some(course) = &course {
+        place_at_course_start(&mut spaceship, course);
+    }
+    // --investigate empieza la misión de investigar las anomalías de la escena
+    let mut investigation = if args.iter().any(|arg| arg == "--investigate") {
+        if scene.anomalies.is_empty() {
+            eprintln!("La escena {} no tiene anomalías que investigar", scene_path);
+            std::process::exit(1);
+        }
+        Some(Investigation::new(&scene))
+    } else {
+        None
+    };
+    // Nave y misión elegidas; se cambian en el hangar de una estación acoplable
+    let mut ship_choice = 0;
+    let mut mission = if race.is_some() {
+        Mission::Race
+    } else if course.is_some() {
+        Mission::Course
+    } else if investigation.is_some() {
+        Mission::Investigate
+    } else {
+        Mission::Free
+    };
+    let mut screen = Screen::Flight;
+    let mut planet_rings = ring_meshes(&scene);
+    let (ring_vertices, ring_indices) = torus_mesh(0.12, 32, 8);
+
+    // Señales sonoras: Tab elige el cuerpo objetivo de los pitidos de proximidad
+    let audio = AudioOutput::new();
+    let mut cues = NavigationCues::new();
+    let mut soundscape = Soundscape::new(scene.ambient.clone());
+    let mut target: Option<usize> = None;
+    let mut warp: Option<Warp> = None;
+    // Travesía de un agujero de gusano en curso
+    let mut transit: Option<Transit> = None;
+    // Aterrizaje en curso: la nave sigue la superficie de un planeta (L despega)
+    let mut landing: Option<Landing> = None;
+    let mut docking: Option<Docking> = None;
+    let mut previous_camera_position: Option<Vec3> = None;
+    // B activa/desactiva el bloom del sol y la lava
+    let mut bloom = Bloom::new(0.9, 0.8, 6);
+    let mut minimap_visible = true;
+    // G recorre los modos de depuración del rasterizado
+    let mut debug_view = DebugView::default();
+    // Lecturas de periapsis/apoapsis y ayuda para circularizar (O)
+    let mut orbit_assist = OrbitAssist::default();
+    let mut orbit_readouts = false;
+    let mut orbit: Option<OrbitReadout> = None;
+    // Láser de la nave (X)
+    let mut weapons = Weapons::default();
+    // Energía compartida por el impulso, los escudos y el láser (flechas: reparto)
+    let mut power = PowerSystem::default();
+    // Integridad del casco: baja con los choques que los escudos no aguantan
+    let mut hull = Hull::default();
+    let mut scanner = Scanner::default();
+    let mut transmissions = Transmissions::default();
+    let mut comet_tails = CometTails::default();
+    // Cuadro congelado (I): nada se mueve, pero la cámara libre y el clic
+    // para inspeccionar cuerpos siguen funcionando
+    let mut frozen = false;
+    // Tiempo transcurrido del recorrido mientras se reproduce
+    let mut tour_elapsed: Option<f32> = None;
+    let mut inspected: Option<usize> = None;
+    let mut left_was_down = false;
+    // `--skybox <carpeta>` carga un cubemap (px, nx, py, ny, pz, nz) bajo las estrellas
+    let cubemap = arg_value(&args, "--skybox").and_then(|directory| match Cubemap::load(directory) {
+        Ok(cubemap) => Some(cubemap),
+        Err(e) => {
+            eprintln!("No se pudo cargar el skybox: {} (solo estrellas)", e);
+            None
+        }
+    });
+    let mut skybox = Skybox::new(&scene.starfield, cubemap);
+    // Aire caliente detrás de los motores: sube al acelerar y se disipa al frenar
+    let mut heat_distortion = HeatDistortion::new(3.0);
+    let mut lens_flare = LensFlare::new(1.0);
+    let mut engine_heat = 0.0f32;
+    let ring_radius = bounding_radius(&ring_vertices);
+    // `time` anima los shaders y los efectos; `sim_time` mueve las órbitas
+    // y se controla con 0, +/- e I
     let mut time = 0.0;
+    let mut sim_time = 0.0;
+    let mut time_control = TimeControl::default();
     let mut last_mouse_pos: Option<(f32, f32)> = None;
+    // --mouse-look empieza con la mirada continua (J); --mouse-sensitivity
+    // <grados por píxel> e --invert-y la ajustan, también al arrastrar
+    let mouse_sensitivity = arg_value(&args, "--mouse-sensitivity").map_or(mouse_look::DEFAULT_SENSITIVITY, |value| match value.parse::<f32>() {
+        Ok(sensitivity) if sensitivity > 0.0 => sensitivity,
+        _ => {
+            eprintln!("Sensibilidad inválida para --mouse-sensitivity: '{}' (se espera un número positivo)", value);
+            std::process::exit(1);
+        }
+    });
+    let mut mouse_look = MouseLook::new(mouse_sensitivity, args.iter().any(|arg| arg == "--invert-y"));
+    if args.iter().any(|arg| arg == "--mouse-look") {
+        mouse_look.toggle(&mut window);
+    }
+    let mut gamepad = GamepadInput::new();
+    let mut last_frame = Instant::now();
+    let mut fps = 0.0;
 
+    println!("Escena: {} ({} cuerpos)", scene_path, scene.bodies.len());
+    if let Some(seed) = system_seed {
+        println!("Semilla del sistema: {}", seed);
+    }
+    for body in &scene.bodies {
+        let spin = if body.tidally_locked {
+            "rotación sincronizada".to_string()
+        } else if body.day_length == 0.0 {
+            "sin rotación".to_string()
+        } else if body.retrograde {
+            format!("día de {:.1}, retrógrado", body.day_length)
+        } else {
+            format!("día de {:.1}", body.day_length)
+        };
+        println!("  {} ({})", body.name, spin);
+    }
+    let custom_shaders: Vec<&str> = shader_registry::registry().names().collect();
+    if !custom_shaders.is_empty() {
+        println!("Shaders registrados: {}", custom_shaders.join(", "));
+    }
+    if !scene.spawns.is_empty() {
+        let names: Vec<&str> = scene.spawns.iter().map(|preset| preset.name.as_str()).collect();
+        println!("Puntos de partida (--spawn): {}", names.join(", "));
+    }
     println!("Controles:");
-    println!("  WASD: Mover nave");
+    println!("  W/S: Avanzar/retroceder, A/D: Girar, Q/E: Desplazamiento lateral");
+    println!("  Z: Frenar (la nave conserva su velocidad sin los motores)");
+    println!("  P: Pausar/reanudar las órbitas, +/-: Acelerar/frenar el tiempo (hasta x100), R: Invertirlo");
+    println!("  Ctrl: Impulso (gasta energía)");
+    println!("  Flechas: Repartir la energía (arriba: motores, izquierda: escudos, derecha: armas, abajo: parejo)");
     println!("  Scroll: Zoom in/out (primera/tercera persona)");
+    println!("  1-9: Saltar al cuerpo correspondiente");
+    println!("  B: Activar/desactivar bloom");
+    println!("  G: Modos de depuración (profundidad, alambre, normales, sobredibujado)");
+    println!("  X: Disparar el láser");
+    println!("  Y: Pulso del escáner (marca estaciones y cuerpos cercanos)");
+    if tour.is_some() {
+        println!("  T: Reproducir/detener el recorrido (--tour)");
+    }
+    if tour_recorder.is_some() {
+        println!("  K: Agregar un punto clave al recorrido grabado (--record-tour)");
+    }
+    println!("  I: Congelar el cuadro (cámara libre y clic para inspeccionar cuerpos)");
+    println!("  Tab: Elegir objetivo de los pitidos de proximidad");
+    println!("  F: Cámara libre (WASD, Espacio/C, mouse derecho; Shift rápido, Ctrl lento)");
+    println!("  L: Despegar (la nave aterriza al bajar cerca de un planeta)");
+    println!("  Enter: Entrar al hangar con la nave acoplada (en el hangar: flechas eligen nave y misión)");
+    println!("  U: Desacoplarse (la nave se acopla al entrar despacio en la zona de una estación)");
+    println!("  J: Mirada continua con el mouse, sin botón (--mouse-sensitivity, --invert-y)");
+    println!("  H: Mapa galáctico (flechas eligen un sistema, Enter viaja, H vuelve)");
     println!("  ESC: Salir");
 
     while window.is_open() && !window.is_key_down(Key::Escape) {
+        // Al redimensionar la ventana se rehace el framebuffer a su nuevo tamaño
+        let (window_width, window_height) = window.get_size();
+        if window_width > 0 && window_height > 0 && (window_width, window_height) != (display.width, display.height) {
+            framebuffer = Framebuffer::new(window_width * aa_scale, window_height * aa_scale);
+            display = Framebuffer::new(window_width, window_height);
+            viewport_matrix = create_viewport_matrix(framebuffer.width as f32, framebuffer.height as f32);
+            display_viewport = create_viewport_matrix(window_width as f32, window_height as f32);
+        }
+        // El fondo del espacio sale de la escena (`[lighting]`)
+        framebuffer.set_background_color(pack_color(scene.lighting.background()));
         framebuffer.clear();
-        
-        // Renderizar fondo estrellado
-        render_starfield(&mut framebuffer, time);
-        
-        time += 0.01;
-
-        // Calculate all celestial body positions for collision detection
-        let rocky_angle = time * 0.3;
-        let rocky_orbit_radius = 45.0;
-        let rocky_inclination = 5.0_f32.to_radians(); // 5 grados de inclinación
-        let rocky_pos = Vec3::new(
-            rocky_angle.cos() * rocky_orbit_radius,
-            (rocky_angle.sin() * rocky_orbit_radius * rocky_inclination.sin()),
-            rocky_angle.sin() * rocky_orbit_radius * rocky_inclination.cos(),
-        );
-        
-        let gas_angle = time * 0.15;
-        let gas_orbit_radius = 60.0;
-        let gas_inclination = (-8.0_f32).to_radians(); // -8 grados (inclinación opuesta)
-        let gas_pos = Vec3::new(
-            -gas_angle.cos() * gas_orbit_radius,
-            (gas_angle.sin() * gas_orbit_radius * gas_inclination.sin()),
-            gas_angle.sin() * gas_orbit_radius * gas_inclination.cos(),
-        );
-        
-        let ice_angle = time * 0.25;
-        let ice_orbit_radius = 53.0;
-        let ice_inclination = 12.0_f32.to_radians(); // 12 grados
-        let ice_pos = Vec3::new(
-            (ice_angle + PI * 0.5).cos() * ice_orbit_radius,
-            ((ice_angle + PI * 0.5).sin() * ice_orbit_radius * ice_inclination.sin()),
-            (ice_angle + PI * 0.5).sin() * ice_orbit_radius * ice_inclination.cos(),
-        );
-        
-        let desert_angle = time * 0.35;
-        let desert_orbit_radius = 38.0;
-        let desert_inclination = (-6.0_f32).to_radians(); // -6 grados
-        let desert_pos = Vec3::new(
-            (desert_angle + PI).cos() * desert_orbit_radius,
-            ((desert_angle + PI).sin() * desert_orbit_radius * desert_inclination.sin()),
-            (desert_angle + PI).sin() * desert_orbit_radius * desert_inclination.cos(),
-        );
-        
-        let volcanic_angle = time * 0.4;
-        let volcanic_orbit_radius = 72.0;
-        let volcanic_inclination = 15.0_f32.to_radians(); // 15 grados
-        let volcanic_pos = Vec3::new(
-            (volcanic_angle + PI * 1.5).cos() * volcanic_orbit_radius,
-            ((volcanic_angle + PI * 1.5).sin() * volcanic_orbit_radius * volcanic_inclination.sin()),
-            (volcanic_angle + PI * 1.5).sin() * volcanic_orbit_radius * volcanic_inclination.cos(),
-        );
-        
-        let ocean_angle = time * 0.28;
-        let ocean_orbit_radius = 49.0;
-        let ocean_inclination = (-10.0_f32).to_radians(); // -10 grados
-        let ocean_pos = Vec3::new(
-            (ocean_angle + PI * 0.25).cos() * ocean_orbit_radius,
-            ((ocean_angle + PI * 0.25).sin() * ocean_orbit_radius * ocean_inclination.sin()),
-            (ocean_angle + PI * 0.25).sin() * ocean_orbit_radius * ocean_inclination.cos(),
-        );
-        
-        let purple_angle = time * 0.2;
-        let purple_orbit_radius = 57.0;
-        let purple_inclination = 18.0_f32.to_radians(); // 18 grados
-        let purple_pos = Vec3::new(
-            (purple_angle + PI * 0.75).cos() * purple_orbit_radius,
-            ((purple_angle + PI * 0.75).sin() * purple_orbit_radius * purple_inclination.sin()),
-            (purple_angle + PI * 0.75).sin() * purple_orbit_radius * purple_inclination.cos(),
-        );
-        
-        let ringed_angle = time * 0.18;
-        let ringed_orbit_radius = 67.0;
-        let ringed_inclination = (-14.0_f32).to_radians(); // -14 grados
-        let ringed_pos = Vec3::new(
-            (ringed_angle + PI * 1.25).cos() * ringed_orbit_radius,
-            ((ringed_angle + PI * 1.25).sin() * ringed_orbit_radius * ringed_inclination.sin()),
-            (ringed_angle + PI * 1.25).sin() * ringed_orbit_radius * ringed_inclination.cos(),
-        );
-        
-        // Lista de todos los cuerpos celestes (posición, radio)
-        let celestial_bodies = vec![
-            (Vec3::new(0.0, 0.0, 0.0), 8.0),  // Sol
-            (rocky_pos, 0.8),
-            (gas_pos, 1.2),
-            (ice_pos, 0.7),
-            (desert_pos, 3.0),
-            (volcanic_pos, 4.5),
-            (ocean_pos, 3.8),
-            (purple_pos, 4.2),
-            (ringed_pos, 5.0),
+
+        // Acoplado: solo se dibuja el hangar y el sistema queda en pausa
+        if let Screen::Hangar(interior) = &mut screen {
+            let departure = interior.update(&window, TIME_STEP);
+            display.clear();
+            interior.render(&mut display, &ships);
+            if let Some(departure) = departure {
+                ship_choice = departure.ship;
+                mission = departure.mission;
+                let ship = &ships[ship_choice].config;
+                spaceship.flight = scene.flight.scaled(ship.speed, ship.thrust);
+                ship_rig = ShipRig::new(ships[ship_choice].parts.iter().map(|(part, _)| part));
+                spaceship.stop();
+                hull.repair();
+                // Despega desde el collar de la estación
+                if let Some(docked) = &mut docking {
+                    docked.undock();
+                }
+                race = None;
+                course = None;
+                investigation = None;
+                match mission {
+                    Mission::Free => {}
+                    Mission::Race => race = scene.race.as_ref().map(Race::new),
+                    Mission::Course => {
+                        course = Course::generate(&scene, &scene.course.clone().unwrap_or_default());
+                        if let Some(course) = &course {
+                            place_at_course_start(&mut spaceship, course);
+                            docking = None;
+                        }
+                    }
+                    Mission::Investigate => investigation = Some(Investigation::new(&scene)),
+                }
+                println!("Despegue: {}, {}", ship.name, mission.name());
+                transmissions.send(Channel::Control, "Control", format!("Despegue autorizado, {}. Misión: {}.", ship.name, mission.name()));
+                screen = Screen::Flight;
+            }
+            let (width, height) = (display.width, display.height);
+            window.update_with_buffer(display.present(), width, height).unwrap();
+            continue;
+        }
+
+        // Mapa galáctico: el sistema queda en pausa, como en el hangar; al
+        // viajar se arma el sistema elegido y la nave aparece en su borde
+        if let Screen::Galaxy = screen {
+            let destination = galaxy.update(&window, TIME_STEP);
+            display.clear();
+            galaxy.render(&mut display);
+            if window.is_key_pressed(Key::H, KeyRepeat::No) {
+                screen = Screen::Flight;
+            }
+            if let Some(index) = destination {
+                match galaxy.load(index) {
+                    Ok(mut next) => {
+                        // El sistema de partida vuelve con su semilla de siempre
+                        system_seed = if index == 0 { home_seed } else { next.seed };
+                        if let Some(seed) = system_seed {
+                            next.reseed(seed);
+                        }
+                        sim_time = 0.0;
+                        next.update(sim_time);
+                        let (position, heading) = galaxy::arrival(&next, galaxy.heading_to(index));
+                        scene = next;
+                        props = load_props(&scene);
+                        planet_rings = ring_meshes(&scene);
+                        lod = LodSystem::new(&scene.bodies);
+                        vertex_caches = scene.bodies.iter().map(|_| VertexCache::default()).collect();
+                        soundscape = Soundscape::new(scene.ambient.clone());
+                        skybox.set_starfield(&scene.starfield);
+                        // Lo que apuntaba a cuerpos del sistema anterior se descarta
+                        cues = NavigationCues::new();
+                        scanner = Scanner::default();
+                        transmissions = Transmissions::default();
+                        comet_tails = CometTails::default();
+                        orbit_assist = OrbitAssist::default();
+                        weapons = Weapons::default();
+                        race = None;
+                        course = None;
+                        investigation = None;
+                        mission = Mission::Free;
+                        target = None;
+                        warp = None;
+                        transit = None;
+                        landing = None;
+                        docking = None;
+                        inspected = None;
+                        orbit = None;
+                        tour_elapsed = None;
+                        previous_camera_position = None;
+                        // El informe de --render-stats es del sistema de partida
+                        if let (Some(path), Some(stats)) = (render_stats_path, render_stats.take()) {
+                            save_render_stats(path, &stats);
+                        }
+                        let ship = &ships[ship_choice].config;
+                        spaceship.position = position;
+                        spaceship.heading = heading;
+                        spaceship.stop();
+                        spaceship.flight = scene.flight.scaled(ship.speed, ship.thrust);
+                        spaceship.engine_trail = ParticleSystem::new(scene.engine_trail.clone());
+                        if record_path.is_some() {
+                            recorded_path.record_jump(time, position);
+                        }
+                        let name = &galaxy.systems[index].name;
+                        println!("Salto a {} ({} cuerpos)", name, scene.bodies.len());
+                        transmissions.send(Channel::Control, "Control", format!("Bienvenida al sistema {}.", name));
+                        galaxy.current = index;
+                        screen = Screen::Flight;
+                    }
+                    Err(e) => eprintln!("No se pudo cargar el sistema {}: {}", galaxy.systems[index].name, e),
+                }
+            }
+            let (width, height) = (display.width, display.height);
+            window.update_with_buffer(display.present(), width, height).unwrap();
+            continue;
+        }
+
+        if window.is_key_pressed(Key::I, KeyRepeat::No) {
+            frozen = !frozen;
+            inspected = None;
+        }
+        if window.is_key_pressed(Key::T, KeyRepeat::No) {
+            if let Some(tour) = &tour {
+                // Los cuerpos del recorrido pueden no estar en el sistema al que se viajó
+                if let Err(e) = tour.check(&scene) {
+                    println!("El recorrido {} no sirve en este sistema: {}", tour.name, e);
+                } else {
+                    tour_elapsed = match tour_elapsed {
+                        Some(_) => None,
+                        None => Some(0.0),
+                    };
+                    println!("Recorrido {}: {}", tour.name, if tour_elapsed.is_some() { "iniciado" } else { "detenido" });
+                }
+            }
+        }
+        // P pausa las órbitas, +/- cambian su velocidad y R las invierte
+        let time_keys = [
+            window.is_key_pressed(Key::P, KeyRepeat::No),
+            window.is_key_pressed(Key::Equal, KeyRepeat::No) || window.is_key_pressed(Key::NumPadPlus, KeyRepeat::No),
+            window.is_key_pressed(Key::Minus, KeyRepeat::No) || window.is_key_pressed(Key::NumPadMinus, KeyRepeat::No),
+            window.is_key_pressed(Key::R, KeyRepeat::No),
         ];
+        if time_keys[0] { time_control.toggle_pause(); }
+        if time_keys[1] { time_control.faster(); }
+        if time_keys[2] { time_control.slower(); }
+        if time_keys[3] { time_control.toggle_reverse(); }
+        if time_keys.contains(&true) {
+            println!("Tiempo de la simulación: {}", time_control.label());
+        }
+
+        // El recorrido puede acelerar o frenar el tiempo de la simulación
+        let tour_playing = tour.as_ref().zip(tour_elapsed);
+        let time_scale = tour_playing.map_or(1.0, |(tour, elapsed)| tour.time_scale(elapsed));
+        if !frozen {
+            time += TIME_STEP;
+            sim_time += TIME_STEP * time_scale * time_control.factor();
+        }
+
+        // Lista de todos los cuerpos celestes (posición, radio) para colisiones
+        scene.update(sim_time);
+        let prop_poses: Vec<Vec<Mat4>> = props
+            .iter()
+            .map(|(index, model)| {
+                let (prop, state) = (&scene.props[*index], &scene.prop_states[*index]);
+                let placement = create_model_matrix(state.position, prop.scale, state.rotation);
+                model.pose(time * prop.speed).into_iter().map(|pose| placement * pose).collect()
+            })
+            .collect();
+        // N pasa a la siguiente nave del registro sin entrar al hangar
+        if window.is_key_pressed(Key::N, KeyRepeat::No) && ships.len() > 1 && !frozen {
+            ship_choice = (ship_choice + 1) % ships.len();
+            let ship = &ships[ship_choice].config;
+            spaceship.flight = scene.flight.scaled(ship.speed, ship.thrust);
+            ship_rig = ShipRig::new(ships[ship_choice].parts.iter().map(|(part, _)| part));
+            println!("Nave: {} ({})", ship.name, ship.description);
+        }
+        // Los cuerpos con un margen menor que el radio de la nave lo agrandan
+        let mut celestial_bodies = scene.collision_bodies();
+        let ship_radius = ships[ship_choice].collision_radius();
+        for collider in &mut celestial_bodies {
+            collider.margin = collider.margin.max(ship_radius);
+        }
+        let tour_frame = tour_playing.map(|(tour, elapsed)| tour.sample(&scene, elapsed));
+        if let (Some(tour), Some(elapsed)) = (&tour, tour_elapsed.as_mut()) {
+            if !frozen {
+                *elapsed += TIME_STEP;
+            }
+            if tour.finished(*elapsed) {
+                tour_elapsed = None;
+                println!("Recorrido {}: terminado", tour.name);
+            }
+        }
+
+        // F alterna entre la cámara que sigue a la nave y la cámara libre
+        if window.is_key_pressed(Key::F, KeyRepeat::No) {
+            camera_mode = match camera_mode {
+                CameraMode::Follow => {
+                    let position = camera.get_position(&spaceship.position, spaceship.follow_camera_yaw());
+                    free_camera = FreeCamera::looking_at(position, spaceship.position);
+                    CameraMode::Free
+                }
+                CameraMode::Free => CameraMode::Follow,
+            };
+        }
+
+        if window.is_key_pressed(Key::V, KeyRepeat::No) {
+            cockpit = !cockpit;
+        }
+        let in_cockpit = cockpit && camera_mode == CameraMode::Follow && tour_frame.is_none() && docking.is_none();
+
+        let mut previous_ship_position = spaceship.position;
 
-        // Spaceship movement controls with collision detection
-        if window.is_key_down(Key::W) { spaceship.move_forward(&celestial_bodies); }
-        if window.is_key_down(Key::S) { spaceship.move_backward(&celestial_bodies); }
-        if window.is_key_down(Key::A) { spaceship.move_left(&celestial_bodies); }
-        if window.is_key_down(Key::D) { spaceship.move_right(&celestial_bodies); }
-        if window.is_key_down(Key::Space) { spaceship.move_up(&celestial_bodies); }
-        if window.is_key_down(Key::LeftShift) || window.is_key_down(Key::RightShift) { spaceship.move_down(&celestial_bodies); }
+        // Teclas 1-9: salto hacia el cuerpo correspondiente de la escena
+        if warp.is_none() && transit.is_none() && docking.is_none() && !frozen && camera_mode == CameraMode::Follow {
+            let pressed = WARP_KEYS.iter().position(|key| window.is_key_pressed(*key, KeyRepeat::No));
+            if let Some(index) = pressed.filter(|index| *index < scene.bodies.len()) {
+                let jump = Warp::new(spaceship.position, index);
+                let direction = jump.destination(&scene) - spaceship.position;
+                spaceship.heading = direction.z.atan2(direction.x);
+                spaceship.stop();
+                warp = Some(jump);
+                landing = None;
+            }
+        }
+        if let Some(active) = warp.as_mut().filter(|_| !frozen) {
+            spaceship.position = active.update(TIME_STEP, &scene);
+            if active.is_finished() {
+                // Al llegar la nave queda mirando al cuerpo
+                let to_body = scene.states[active.target()].position - spaceship.position;
+                spaceship.heading = to_body.z.atan2(to_body.x);
+                warp = None;
+            }
+        }
+
+        // Agujeros de gusano: al entrar en la garganta de uno con par la nave
+        // sale por el otro
+        if let Some(active) = transit.as_mut().filter(|_| !frozen) {
+            let (position, jumped) = active.update(TIME_STEP, &scene);
+            spaceship.position = position;
+            if jumped {
+                println!("Travesía hasta {}", scene.anomalies[active.exit()].name);
+                audio.play(Tone::new(90.0, 0.6, 0.3, Waveform::Sine).with_tremolo(8.0));
+                if record_path.is_some() {
+                    recorded_path.record_jump(time, position);
+                }
+            }
+            // Sin estela ni velocidad entre una boca y la otra
+            previous_ship_position = spaceship.position;
+            if active.is_finished() {
+                transit = None;
+            }
+        } else if warp.is_none() && !frozen && landing.is_none() && docking.is_none() && camera_mode == CameraMode::Follow {
+            transit = Transit::check(&scene, spaceship.position, spaceship.forward());
+            // Sale por la otra boca con la misma rapidez, en la dirección del rumbo
+            if transit.is_some() {
+                spaceship.velocity = spaceship.forward() * spaceship.velocity.magnitude();
+            }
+        }
+
+        // Una nave atrapada dentro de un cuerpo sale por la normal, o reaparece
+        // en el punto de partida libre más cercano si no puede
+        if warp.is_none() && !frozen && landing.is_none() {
+            if let Some(index) = spaceship.push_out(&celestial_bodies) {
+                spaceship.stop();
+                let name = &scene.bodies[index].name;
+                match spawn::nearest_safe(&scene, spaceship.position, &celestial_bodies) {
+                    Some((preset, safe)) => {
+                        println!("La nave quedó atrapada dentro de {}: reaparece en '{}'", name, preset);
+                        spaceship.position = safe.position;
+                        if let Some(heading) = safe.heading {
+                            spaceship.heading = heading;
+                        }
+                    }
+                    None => {
+                        println!("La nave quedó atrapada dentro de {}: sale a su superficie", name);
+                        let collider = &celestial_bodies[index];
+                        spaceship.position = collider.surface_point(spaceship.position, collider.margin + 1e-3);
+                    }
+                }
+                // Sin estela entre el punto donde quedó atrapada y el nuevo
+                previous_ship_position = spaceship.position;
+            }
+        }
 
-        // Actualizar animación de la nave
-        spaceship.update_animation();
+        // Controles de avance, lateral y altura sobre la superficie, con la nave aterrizada
+        let heading_before = spaceship.heading;
+        let mut surface_input = Vec3::zeros();
+        let axis = |positive: bool, negative: bool| -> f32 { (positive as i32 - negative as i32) as f32 };
+        let shift = window.is_key_down(Key::LeftShift) || window.is_key_down(Key::RightShift);
+        spaceship.boost = 1.0;
+        match camera_mode {
+            // Durante el salto, acoplada o con el cuadro congelado la nave no responde a los controles
+            CameraMode::Follow if warp.is_some() || transit.is_some() || docking.is_some() || frozen => {}
+            CameraMode::Follow if landing.is_some() => {
+                surface_input += Vec3::new(
+                    axis(window.is_key_down(Key::W), window.is_key_down(Key::S)),
+                    axis(window.is_key_down(Key::E), window.is_key_down(Key::Q)),
+                    axis(window.is_key_down(Key::Space), shift),
+                );
+                if window.is_key_down(Key::A) { spaceship.turn(-TURN_SPEED); }
+                if window.is_key_down(Key::D) { spaceship.turn(TURN_SPEED); }
+            }
+            CameraMode::Follow => {
+                // Spaceship movement controls with collision detection
+                // Los controles empujan la nave; Z frena
+                spaceship.fly(
+                    axis(window.is_key_down(Key::W), window.is_key_down(Key::S)),
+                    axis(window.is_key_down(Key::E), window.is_key_down(Key::Q)),
+                    axis(window.is_key_down(Key::Space), shift),
+                    TIME_STEP,
+                );
+                if window.is_key_down(Key::Z) { spaceship.brake(TIME_STEP); }
+                // Ctrl: impulso mientras quede energía
+                if window.is_key_down(Key::LeftCtrl) || window.is_key_down(Key::RightCtrl) {
+                    spaceship.boost = power.boost(TIME_STEP);
+                }
+                if window.is_key_down(Key::A) { spaceship.turn(-TURN_SPEED); }
+                if window.is_key_down(Key::D) { spaceship.turn(TURN_SPEED); }
+            }
+            CameraMode::Free => {
+                // Shift acelera, Ctrl permite movimientos finos
+                let multiplier = if window.is_key_down(Key::LeftShift) {
+                    4.0
+                } else if window.is_key_down(Key::LeftCtrl) {
+                    0.25
+                } else {
+                    1.0
+                };
+                let key_axis = |positive: Key, negative: Key| axis(window.is_key_down(positive), window.is_key_down(negative));
+                free_camera.translate(
+                    key_axis(Key::W, Key::S),
+                    key_axis(Key::D, Key::A),
+                    key_axis(Key::Space, Key::C),
+                    multiplier,
+                );
+            }
+        }
 
-        // Mouse camera rotation with right click (horizontal only)
-        if let Some((mouse_x, mouse_y)) = window.get_mouse_pos(minifb::MouseMode::Discard) {
+        // La nave sigue con su velocidad, también sin controles o con la cámara libre
+        if landing.is_none() && warp.is_none() && transit.is_none() && !frozen {
+            // Los escudos se llevan los choques; sin ellos la nave se queda
+            // detenida contra el cuerpo y el golpe daña el casco
+            let impact = spaceship.drift(TIME_STEP, &celestial_bodies);
+            if impact > 1.0 {
+                if power.absorb(impact) {
+                    audio.play(Tone::new(300.0, 0.2, 0.2, Waveform::Sine).with_tremolo(20.0));
+                } else if hull.damage(impact) {
+                    // Casco destruido: la nave reaparece en el punto de partida
+                    // libre más cercano o, si no hay ninguno, en el borde del
+                    // sistema actual, como al llegar a él por el mapa
+                    hull.burst(spaceship.position);
+                    audio.play(Tone::new(60.0, 0.9, 0.4, Waveform::Noise));
+                    spaceship.stop();
+                    match spawn::nearest_safe(&scene, spaceship.position, &celestial_bodies) {
+                        Some((name, safe)) => {
+                            println!("Nave destruida: reaparece en '{}'", name);
+                            transmissions.send(Channel::Control, "Control", format!("Perdimos la nave. Una de reemplazo sale desde '{}'.", name));
+                            spaceship.position = safe.position;
+                            if let Some(heading) = safe.heading {
+                                spaceship.heading = heading;
+                            }
+                        }
+                        None => {
+                            println!("Nave destruida: reaparece en el borde del sistema");
+                            transmissions.send(Channel::Control, "Control", "Perdimos la nave. Una de reemplazo sale desde el borde del sistema.");
+                            let away = scene.star_position() - spaceship.position;
+                            let heading = Vec3::new(away.x, 0.0, away.z).try_normalize(1e-6).unwrap_or_else(|| Vec3::new(1.0, 0.0, 0.0));
+                            let (position, yaw) = galaxy::arrival(&scene, heading);
+                            spaceship.position = position;
+                            spaceship.heading = yaw;
+                        }
+                    }
+                    previous_ship_position = spaceship.position;
+                    hull.repair();
+                } else {
+                    spaceship.stop();
+                    println!("Choque sin escudos: casco al {:.0}%", hull.integrity() * 100.0);
+                    audio.play(Tone::new(90.0, 0.4, 0.3, Waveform::Noise));
+                }
+            }
+        }
+
+        if !frozen {
+            power.update(TIME_STEP);
+            if window.is_key_pressed(Key::Up, KeyRepeat::No) { power.bias(Subsystem::Engines); }
+            if window.is_key_pressed(Key::Left, KeyRepeat::No) { power.bias(Subsystem::Shields); }
+            if window.is_key_pressed(Key::Right, KeyRepeat::No) { power.bias(Subsystem::Weapons); }
+            if window.is_key_pressed(Key::Down, KeyRepeat::No) { power.balance(); }
+
+            // Actualizar animación de la nave
+            spaceship.update_animation();
+            spaceship.update_engine_trail(previous_ship_position, TIME_STEP);
+            hull.update(TIME_STEP, spaceship.position, spaceship.velocity);
+            if record_path.is_some() {
+                recorded_path.record(time, spaceship.position);
+            }
+            if let Some(race) = &mut race {
+                race.update(time, spaceship.position);
+            }
+            if let Some(course) = &mut course {
+                course.update(time, previous_ship_position, spaceship.position);
+            }
+
+            // X dispara el láser desde la nariz de la nave en la dirección del rumbo
+            if camera_mode == CameraMode::Follow && warp.is_none() && docking.is_none() && window.is_key_down(Key::X) && power.can_fire() {
+                let muzzle = spaceship.position + spaceship.forward() * 0.6;
+                if weapons.fire(muzzle, spaceship.forward(), power.fire_rate()) {
+                    power.spend_shot();
+                    audio.play(Tone::new(1400.0, 0.06, 0.12, Waveform::Square));
+                }
+            }
+            // Y lanza un pulso del escáner; cada punto de interés que alcanza suena
+            if warp.is_none() && window.is_key_pressed(Key::Y, KeyRepeat::No) && scanner.pulse(spaceship.position) {
+                audio.play(Tone::new(520.0, 0.35, 0.15, Waveform::Sine).with_tremolo(12.0));
+            }
+            if scanner.update(TIME_STEP, &scene) > 0 {
+                audio.play(Tone::new(1760.0, 0.05, 0.2, Waveform::Sine));
+            }
+            if let Some(investigation) = &mut investigation {
+                if let Some(index) = investigation.update(&scene, scanner.contacts(), spaceship.position) {
+                    let anomaly = &scene.anomalies[index];
+                    println!("Anomalía investigada: {} ({})", anomaly.name, anomaly.kind.label());
+                    audio.play(Tone::new(660.0, 0.4, 0.2, Waveform::Sine).with_tremolo(6.0));
+                    transmissions.send(Channel::Control, "Control", format!("Recibimos sus datos de {}. Buen trabajo.", anomaly.name));
+                    if investigation.is_finished() {
+                        println!("Misión cumplida: todas las anomalías investigadas");
+                        transmissions.send(Channel::Control, "Control", "Misión cumplida: todas las anomalías investigadas. Regrese cuando quiera.");
+                    }
+                }
+            }
+            comet_tails.update(&scene, TIME_STEP);
+            transmissions.watch(&scene, spaceship.position);
+            if let Some(tone) = transmissions.update(TIME_STEP) {
+                audio.play(tone);
+            }
+            for hit in weapons.update(TIME_STEP, &celestial_bodies) {
+                audio.play(Tone::new(900.0, 0.15, 0.2, Waveform::Noise));
+                println!("Impacto en {}", scene.bodies[hit].name);
+            }
+        }
+
+        // Enter con la nave acoplada entra al hangar
+        let dock = docking
+            .as_ref()
+            .filter(|docked| docked.is_docked() && camera_mode == CameraMode::Follow && !frozen)
+            .map(|docked| &scene.props[docked.station]);
+        if let Some(station) = dock {
+            if window.is_key_pressed(Key::Enter, KeyRepeat::No) {
+                println!("Hangar de {}", station.name);
+                mouse_look.forget();
+                screen = Screen::Hangar(Hangar::new(&station.name, ship_choice, ships.len(), mission, hangar::available_missions(&scene)));
+            }
+        }
+
+        // H abre el mapa galáctico, con la nave volando por su cuenta
+        let cruising = camera_mode == CameraMode::Follow && !frozen && tour_elapsed.is_none();
+        if cruising && warp.is_none() && transit.is_none() && landing.is_none() && docking.is_none() && window.is_key_pressed(Key::H, KeyRepeat::No) {
+            galaxy.open();
+            mouse_look.forget();
+            screen = Screen::Galaxy;
+        }
+
+        if window.is_key_pressed(Key::Tab, KeyRepeat::No) {
+            target = match target {
+                None if !scene.bodies.is_empty() => Some(0),
+                Some(index) if index + 1 < scene.bodies.len() => Some(index + 1),
+                _ => None,
+            };
+        }
+        let cue_tones = if frozen {
+            CueTones::default()
+        } else {
+            cues.update(TIME_STEP, &scene, spaceship.position, target)
+        };
+
+        // Arrastrar con el botón derecho gira la nave en el modo de seguimiento
+        // (la cámara se queda detrás) y mira alrededor en el modo libre; el
+        // movimiento vertical sube o baja la cámara. Con la mirada continua (J)
+        // no hace falta el botón.
+        if window.is_key_pressed(Key::J, KeyRepeat::No) {
+            mouse_look.toggle(&mut window);
+            println!("Mirada con el mouse: {}", if mouse_look.enabled { "activada" } else { "desactivada" });
+        }
+        let mut look = mouse_look.update(&window);
+        if look.is_some() {
+            last_mouse_pos = None;
+        } else if let Some((mouse_x, mouse_y)) = window.get_mouse_pos(MouseMode::Discard) {
             if window.get_mouse_down(minifb::MouseButton::Right) {
-                if let Some((last_x, _last_y)) = last_mouse_pos {
-                    let delta_x = mouse_x - last_x;
-                    camera.update_rotation(delta_x);
+                if let Some((last_x, last_y)) = last_mouse_pos {
+                    look = Some(mouse_look.angles(mouse_x - last_x, mouse_y - last_y));
                 }
                 last_mouse_pos = Some((mouse_x, mouse_y));
             } else {
                 last_mouse_pos = None;
             }
         }
+        if let Some((yaw, pitch)) = look {
+            match camera_mode {
+                CameraMode::Follow if frozen || docking.is_some() => {}
+                CameraMode::Follow => {
+                    spaceship.turn(yaw.to_radians());
+                    camera.tilt(-pitch);
+                }
+                CameraMode::Free => free_camera.turn(yaw, pitch),
+            }
+        }
+
+        // Gamepad: stick izquierdo y gatillos mueven, stick derecho mira, cruceta zoom
+        let pad = gamepad.poll();
+        if !pad.is_idle() {
+            match camera_mode {
+                CameraMode::Follow if warp.is_some() || transit.is_some() || docking.is_some() || frozen => {}
+                CameraMode::Follow => {
+                    if landing.is_some() {
+                        surface_input += Vec3::new(pad.forward, pad.right, pad.up);
+                    } else {
+                        spaceship.fly(pad.forward, pad.right, pad.up, TIME_STEP);
+                        if pad.brake {
+                            spaceship.brake(TIME_STEP);
+                        }
+                    }
+                    if pad.look_x != 0.0 {
+                        spaceship.turn(pad.look_x * TURN_SPEED);
+                    }
+                    camera.tilt(-pad.look_y * 1.5);
+                    if cockpit {
+                        cockpit = pad.zoom >= 0.0;
+                    } else if camera.zoom(pad.zoom * 0.5) {
+                        cockpit = true;
+                    }
+                }
+                CameraMode::Free => {
+                    free_camera.translate(pad.forward, pad.right, pad.up, 1.0);
+                    free_camera.look(pad.look_x * 5.0, -pad.look_y * 5.0);
+                    free_camera.adjust_speed(pad.zoom * 0.5);
+                }
+            }
+        }
+
+        // Aterrizada, la nave acompaña al planeta y se mueve sobre su superficie
+        // con el rumbo que dejaron los giros de este cuadro; al bajar cerca de
+        // uno sin estar aterrizada, aterriza
+        if let Some(landed) = landing.as_mut().filter(|_| warp.is_none() && !frozen) {
+            if window.is_key_pressed(Key::L, KeyRepeat::No) {
+                landed.take_off();
+            }
+            let turn = spaceship.heading - heading_before;
+            let surface_speed = spaceship.flight.max_speed * TIME_STEP;
+            spaceship.position = landed.update(&celestial_bodies, turn, surface_input.x, surface_input.y, surface_input.z, surface_speed);
+            if let Some(heading) = landed.heading() {
+                spaceship.heading = heading;
+            }
+            if landed.is_finished() {
+                println!("Despegue de {}", scene.bodies[landed.body].name);
+                landing = None;
+            }
+        } else if landing.is_none() && docking.is_none() && warp.is_none() && transit.is_none() && !frozen && camera_mode == CameraMode::Follow && tour_frame.is_none() {
+            landing = Landing::check(&scene, &celestial_bodies, spaceship.position, spaceship.forward(), spaceship.right());
+            if let Some(landed) = &landing {
+                println!("Aterrizaje en {}", scene.bodies[landed.body].name);
+                transmissions.send(Channel::Control, "Control", format!("Confirmamos contacto con la superficie de {}.", scene.bodies[landed.body].name));
+                spaceship.stop();
+            }
+        }
+
+        // Acoplada, la nave sigue el collar de la estación en su órbita y su
+        // giro; al entrar despacio en la zona de acoplamiento de una, se acopla
+        if let Some(docked) = docking.as_mut().filter(|_| !frozen) {
+            if window.is_key_pressed(Key::U, KeyRepeat::No) && docked.is_docked() {
+                docked.undock();
+            }
+            let (position, heading) = docked.update(&scene, TIME_STEP);
+            spaceship.position = position;
+            spaceship.heading = heading;
+            if docked.is_finished(&scene) {
+                println!("Desacople de {}", scene.props[docked.station].name);
+                docking = None;
+            }
+        } else if docking.is_none() && landing.is_none() && warp.is_none() && transit.is_none() && !frozen && camera_mode == CameraMode::Follow && tour_frame.is_none() {
+            docking = Docking::check(&scene, spaceship.position, spaceship.velocity, spaceship.heading);
+            if let Some(docked) = &docking {
+                let station = &scene.props[docked.station].name;
+                println!("Acoplado a {}", station);
+                transmissions.send(Channel::Station, station, "Acoplamiento confirmado. Enter para pasar al hangar, U para soltarse.");
+                spaceship.stop();
+            }
+        }
 
-        // Scroll wheel zoom control
+        // Scroll wheel: zoom in follow mode, flight speed in free mode. Acercarse
+        // más allá del zoom mínimo entra a la cabina y alejarse sale de ella.
         if let Some(scroll) = window.get_scroll_wheel() {
-            camera.zoom(scroll.1);
-        }
-
-        let view_matrix = camera.get_view_matrix(&spaceship.position, spaceship.camera_yaw);
-
-        // Render orbital paths for all planets with their inclinations
-        render_orbit(&mut framebuffer, 45.0, 5.0_f32.to_radians(), &view_matrix, &projection_matrix, &viewport_matrix);  // Rocky
-        render_orbit(&mut framebuffer, 60.0, (-8.0_f32).to_radians(), &view_matrix, &projection_matrix, &viewport_matrix);  // Gas Giant
-        render_orbit(&mut framebuffer, 53.0, 12.0_f32.to_radians(), &view_matrix, &projection_matrix, &viewport_matrix);  // Ice
-        render_orbit(&mut framebuffer, 38.0, (-6.0_f32).to_radians(), &view_matrix, &projection_matrix, &viewport_matrix);  // Desert
-        render_orbit(&mut framebuffer, 72.0, 15.0_f32.to_radians(), &view_matrix, &projection_matrix, &viewport_matrix);  // Volcanic
-        render_orbit(&mut framebuffer, 49.0, (-10.0_f32).to_radians(), &view_matrix, &projection_matrix, &viewport_matrix);  // Ocean
-        render_orbit(&mut framebuffer, 57.0, 18.0_f32.to_radians(), &view_matrix, &projection_matrix, &viewport_matrix);  // Purple
-        render_orbit(&mut framebuffer, 67.0, (-14.0_f32).to_radians(), &view_matrix, &projection_matrix, &viewport_matrix);  // Ringed
-
-        // Render Sun (center, no rotation, much bigger size)
-        let sun_rotation = Vec3::new(0.0, 0.0, 0.0); // No rotation
-        let sun_model = create_model_matrix(Vec3::new(0.0, 0.0, 0.0), 8.0, sun_rotation);
-        let sun_uniforms = Uniforms {
-            model_matrix: sun_model,
-            view_matrix,
-            projection_matrix,
-            viewport_matrix,
-            time,
-            shader_type: 0, // Star shader
-        };
-        render_model(&mut framebuffer, &sun_uniforms, &planet_vertices, &planet_indices);
-
-        // Render Rocky Planet (orbiting)
-        let rocky_rotation = Vec3::new(0.0, time * 0.5, 0.0);
-        let rocky_model = create_model_matrix(rocky_pos, 0.8, rocky_rotation);
-        let rocky_uniforms = Uniforms {
-            model_matrix: rocky_model,
-            view_matrix,
-            projection_matrix,
-            viewport_matrix,
-            time,
-            shader_type: 1, // Rocky shader
-        };
-        render_model(&mut framebuffer, &rocky_uniforms, &planet_vertices, &planet_indices);
-
-        // Render Gas Giant (orbiting in opposite direction)
-        let gas_rotation = Vec3::new(0.0, time * 0.3, 0.0);
-        let gas_model = create_model_matrix(gas_pos, 1.2, gas_rotation);
-        let gas_uniforms = Uniforms {
-            model_matrix: gas_model,
-            view_matrix,
-            projection_matrix,
-            viewport_matrix,
-            time,
-            shader_type: 2, // Gas giant shader
-        };
-        render_model(&mut framebuffer, &gas_uniforms, &planet_vertices, &planet_indices);
-
-        // Render Ice Planet (orbiting)
-        let ice_rotation = Vec3::new(0.0, time * 0.4, 0.0);
-        let ice_model = create_model_matrix(ice_pos, 0.7, ice_rotation);
-        let ice_uniforms = Uniforms {
-            model_matrix: ice_model,
-            view_matrix,
-            projection_matrix,
-            viewport_matrix,
-            time,
-            shader_type: 4, // Ice planet shader
-        };
-        render_model(&mut framebuffer, &ice_uniforms, &planet_vertices, &planet_indices);
-
-        // Render Desert Planet (orbiting)
-        let desert_rotation = Vec3::new(0.0, time * 0.6, 0.0);
-        let desert_model = create_model_matrix(desert_pos, 3.0, desert_rotation);
-        let desert_uniforms = Uniforms {
-            model_matrix: desert_model,
-            view_matrix,
-            projection_matrix,
-            viewport_matrix,
-            time,
-            shader_type: 5, // Desert planet shader
-        };
-        render_model(&mut framebuffer, &desert_uniforms, &planet_vertices, &planet_indices);
-
-        // Render Volcanic Planet (orbiting)
-        let volcanic_rotation = Vec3::new(0.0, time * 0.7, 0.0);
-        let volcanic_model = create_model_matrix(volcanic_pos, 4.5, volcanic_rotation);
-        let volcanic_uniforms = Uniforms {
-            model_matrix: volcanic_model,
-            view_matrix,
-            projection_matrix,
-            viewport_matrix,
-            time,
-            shader_type: 6, // Volcanic planet shader
-        };
-        render_model(&mut framebuffer, &volcanic_uniforms, &planet_vertices, &planet_indices);
-
-        // Render Ocean Planet (orbiting)
-        let ocean_rotation = Vec3::new(0.0, time * 0.45, 0.0);
-        let ocean_model = create_model_matrix(ocean_pos, 3.8, ocean_rotation);
-        let ocean_uniforms = Uniforms {
-            model_matrix: ocean_model,
-            view_matrix,
-            projection_matrix,
-            viewport_matrix,
-            time,
-            shader_type: 7, // Ocean planet shader
+            match camera_mode {
+                CameraMode::Follow if cockpit => cockpit = scroll.1 >= 0.0,
+                CameraMode::Follow => cockpit = camera.zoom(scroll.1),
+                CameraMode::Free => free_camera.adjust_speed(scroll.1),
+            }
+        }
+
+        // El campo de visión se abre durante el salto; el recorrido fija el suyo
+        let fov = match &tour_frame {
+            Some(frame) => frame.fov,
+            None => FOV_DEGREES + warp.as_ref().map_or(0.0, Warp::fov_boost),
         };
-        render_model(&mut framebuffer, &ocean_uniforms, &planet_vertices, &planet_indices);
-
-        // Render Purple Alien Planet (orbiting)
-        let purple_rotation = Vec3::new(0.0, time * 0.55, 0.0);
-        let purple_model = create_model_matrix(purple_pos, 4.2, purple_rotation);
-        let purple_uniforms = Uniforms {
-            model_matrix: purple_model,
-            view_matrix,
-            projection_matrix,
-            viewport_matrix,
-            time,
-            shader_type: 8, // Purple planet shader
+        let projection_matrix = perspective(framebuffer.width as f32 / framebuffer.height as f32, fov * PI / 180.0, 0.1, 150.0);
+
+        let (view_matrix, camera_position) = match (&tour_frame, camera_mode) {
+            (Some(frame), _) => (look_at(&frame.position, &frame.look_at, &Vec3::new(0.0, 1.0, 0.0)), frame.position),
+            (None, CameraMode::Follow) if in_cockpit => {
+                cockpit_view(spaceship.position, spaceship.forward(), spaceship.tilt_x, spaceship.tilt_z, ships[ship_choice].config.camera_anchor)
+            }
+            (None, CameraMode::Follow) => match (&landing, &docking) {
+                (Some(landed), _) => landed.camera(spaceship.position, camera.get_position(&spaceship.position, spaceship.follow_camera_yaw())),
+                (None, Some(docked)) => docked.camera(&scene, spaceship.position, camera.get_position(&spaceship.position, spaceship.follow_camera_yaw())),
+                (None, None) => (
+                    camera.get_view_matrix(&spaceship.position, spaceship.follow_camera_yaw()),
+                    camera.get_position(&spaceship.position, spaceship.follow_camera_yaw()),
+                ),
+            },
+            (None, CameraMode::Free) => (free_camera.get_view_matrix(), free_camera.position),
         };
-        render_model(&mut framebuffer, &purple_uniforms, &planet_vertices, &planet_indices);
-
-        // Render Ringed Turquoise Planet (orbiting)
-        let ringed_rotation = Vec3::new(0.0, time * 0.35, 0.0);
-        let ringed_model = create_model_matrix(ringed_pos, 5.0, ringed_rotation);
-        let ringed_uniforms = Uniforms {
-            model_matrix: ringed_model,
-            view_matrix,
-            projection_matrix,
-            viewport_matrix,
-            time,
-            shader_type: 9, // Ringed planet shader
+        let frustum = Frustum::from_matrix(&(projection_matrix * view_matrix));
+
+        // K agrega un punto clave con la cámara de este cuadro al recorrido grabado
+        if let Some(recorder) = tour_recorder.as_mut().filter(|_| tour_elapsed.is_none()) {
+            if window.is_key_pressed(Key::K, KeyRepeat::No) {
+                let forward = -Vec3::new(view_matrix[(2, 0)], view_matrix[(2, 1)], view_matrix[(2, 2)]);
+                match recorder.add(&scene, time, camera_position, forward, fov) {
+                    Ok(body) => {
+                        let count = recorder.tour.keyframes.len();
+                        let anchor = body.map_or_else(String::new, |name| format!(", junto a {}", name));
+                        println!("Punto clave {} del recorrido grabado{}", count, anchor);
+                        if count >= 2 {
+                            println!("Recorrido guardado en {} (T para reproducirlo)", recorder.path().display());
+                            tour = Some(recorder.tour.clone());
+                        }
+                    }
+                    Err(e) => eprintln!("No se pudo grabar el punto clave: {}", e),
+                }
+            }
+        }
+
+        // Clic izquierdo con el cuadro congelado: inspeccionar el cuerpo bajo el cursor
+        let left_down = window.get_mouse_down(minifb::MouseButton::Left);
+        if frozen && left_down && !left_was_down {
+            if let Some((mouse_x, mouse_y)) = window.get_mouse_pos(MouseMode::Discard) {
+                let (width, height) = (display.width as f32, display.height as f32);
+                inspected = pick_body(&celestial_bodies, mouse_x, mouse_y, width, height, &view_matrix, &projection_matrix);
+            }
+        }
+        left_was_down = left_down;
+
+        if window.is_key_pressed(Key::G, KeyRepeat::No) {
+            debug_view = debug_view.next();
+        }
+
+        // Audio posicional: la cámara escucha, con su velocidad para el Doppler
+        let listener = Listener {
+            position: camera_position,
+            right: Vec3::new(view_matrix[(0, 0)], view_matrix[(0, 1)], view_matrix[(0, 2)]),
+            velocity: previous_camera_position.map_or(Vec3::zeros(), |previous| (camera_position - previous) / TIME_STEP),
         };
-        render_model(&mut framebuffer, &ringed_uniforms, &planet_vertices, &planet_indices);
+        previous_camera_position = Some(camera_position);
+        if let (Some(beep), Some(index)) = (cue_tones.beep, target) {
+            let source = Emitter { position: scene.states[index].position, velocity: Vec3::zeros() };
+            audio.play(beep.spatialized(&spatialize(&listener, &source)));
+        }
+        if let Some(warning) = cue_tones.warning {
+            audio.play(warning);
+        }
+        // El motor de la nave fantasma zumba con Doppler al pasar cerca
+        if let Some(ghost) = &ghost_path {
+            let looping = race.is_none();
+            let current = ghost.replay_position(time, looping);
+            let velocity = ghost.replay_velocity(time, TIME_STEP, looping);
+            match (current, velocity) {
+                (Some(position), Some(velocity)) => {
+                    let source = Emitter { position, velocity };
+                    let hum = Tone::new(110.0, 0.0, 0.15, Waveform::Square);
+                    audio.set_loop(GHOST_ENGINE_SOUND, hum.spatialized(&spatialize(&listener, &source)));
+                }
+                _ => audio.stop_loop(GHOST_ENGINE_SOUND),
+            }
+        }
+        soundscape.update(TIME_STEP, &scene, spaceship.position, &audio);
+
+        // La luz sale del primer cuerpo emisivo (la estrella central)
+        let light_position = scene.star_position();
+        let ship = &ships[ship_choice];
+        // La nave proyecta su sombra sobre los cuerpos que tiene detrás
+        let light = Light::new(light_position)
+            .with_radius(scene.star().map_or(0.0, |(_, radius)| radius))
+            .with_ambient(scene.lighting.ambient())
+            .with_occluder(Some(Occluder {
+                center: spaceship.position,
+                radius: ship.radius * ship.config.scale,
+            }));
+        // La nave también puede quedar a la sombra de un cuerpo
+        scene.shadow_casters(spaceship.position, ship.radius * ship.config.scale, &mut ship_eclipsers);
+        let ship_light = light.with_eclipsers(&ship_eclipsers);
 
-        // Render Spaceship (TIE Fighter) - Controlled by player with animation
+        // Estado de la nave que usan los pases: la holgura se mide hasta el
+        // margen de colisión de la nave
+        let surface_distance = celestial_bodies
+            .iter()
+            .map(|collider| collider.clearance(spaceship.position) - collider.margin)
+            .fold(f32::INFINITY, f32::min);
+        let ship_velocity = (spaceship.position - previous_ship_position) / TIME_STEP;
+        if !frozen {
+            // Aterrizada cuenta el movimiento sobre el planeta, no su órbita
+            let rig_velocity = landing.as_ref().map_or(ship_velocity, |landed| landed.motion() / TIME_STEP);
+            ship_rig.update(ShipState::from_motion(rig_velocity, spaceship.forward(), surface_distance), TIME_STEP);
+        }
         let animated_rotation = spaceship.get_animated_rotation();
-        let nave_model = create_model_matrix(spaceship.position, 0.3, animated_rotation);
-        let nave_uniforms = Uniforms {
-            model_matrix: nave_model,
-            view_matrix,
-            projection_matrix,
-            viewport_matrix,
-            time,
-            shader_type: 3, // Spaceship shader
+
+        // Calor de los motores para la distorsión: sube al empujar hacia
+        // adelante (más con el impulso) y se disipa al soltar
+        let throttle = spaceship.take_throttle();
+        let thrust = if warp.is_none() { throttle.min(MAX_ENGINE_HEAT) } else { 0.0 };
+        engine_heat += (thrust - engine_heat) * 0.15;
+
+        if window.is_key_pressed(Key::B, KeyRepeat::No) {
+            pipeline.toggle_post(PostEffect::Bloom);
+        }
+        if window.is_key_pressed(Key::M, KeyRepeat::No) {
+            minimap_visible = !minimap_visible;
+        }
+        if window.is_key_pressed(Key::O, KeyRepeat::No) {
+            orbit_readouts = !orbit_readouts;
+        }
+        // Congelado se conservan las últimas lecturas: sin movimiento no hay velocidad
+        if !frozen {
+            orbit = orbit_assist.update(&scene, spaceship.position, TIME_STEP);
+        }
+
+        // FPS suavizado para que el número no parpadee
+        let frame_seconds = last_frame.elapsed().as_secs_f32();
+        last_frame = Instant::now();
+        if frame_seconds > 0.0 {
+            fps = if fps == 0.0 { 1.0 / frame_seconds } else { fps * 0.9 + 0.1 / frame_seconds };
+        }
+        let ship_speed = (spaceship.position - previous_ship_position).magnitude() / TIME_STEP;
+
+        // Las piezas animadas se posan con `rig`; sin él se dibuja la pose neutra
+        let draw_ship = |framebuffer: &mut Framebuffer, model_matrix: Mat4, alpha: f32, rig: Option<&ShipRig>| {
+            for (index, (part, texture)) in ship.parts.iter().enumerate() {
+                let part_matrix = rig.map_or_else(Mat4::identity, |rig| rig.part_matrix(index));
+                // Textura del material con el shader texturizado (gris sin ella) o el shader de la nave
+                let (shader, texture) = ship.look(texture);
+                let nave_uniforms = Uniforms {
+                    model_matrix: model_matrix * part_matrix,
+                    view_matrix,
+                    projection_matrix,
+                    viewport_matrix,
+                    frustum,
+                    shader_time: time,
+                    shader,
+                    seed: 0,
+                    alpha,
+                    texture,
+                    surface: &BARE_SURFACE,
+                    light: ship_light,
+                    material: Material::new(false, 0.5, 32.0).with_reflection(SHIP_REFLECTIVITY, part.roughness),
+                    camera_position,
+                    debug_view,
+                    screen_lighting: false,
+                    detail: 0.0,
+                };
+                render_model(framebuffer, &nave_uniforms, &part.vertices, &part.indices, ship.radius);
+            }
         };
-        render_model(&mut framebuffer, &nave_uniforms, &nave_vertices, &nave_indices);
 
-        window
-            .update_with_buffer(&framebuffer.buffer, WIDTH, HEIGHT)
-            .unwrap();
+        for pass in pipeline.passes() {
+            match pass {
+                Pass::Background => {
+                    // Fondo estrellado fijo a las direcciones del mundo; los modos de
+                    // depuración lo omiten para que solo se vea la geometría
+                    if debug_view == DebugView::Shaded {
+                        skybox.render(&mut framebuffer, &view_matrix, &projection_matrix, time);
+                    }
+
+                    // Render orbital paths for all planets and moons with their inclinations
+                    for (index, body) in scene.bodies.iter().enumerate().filter(|(_, b)| b.orbit_radius > 0.0) {
+                        render_orbit(&mut framebuffer, scene.orbit_center(index), body, &view_matrix, &projection_matrix, &viewport_matrix);
+                    }
+                }
+                Pass::Opaque => {
+                    // Render celestial bodies described by the scene, switching to
+                    // impostors when they are small on screen. Van de adelante
+                    // hacia atrás para que el early-Z (ver `shade_fragments`)
+                    // descarte lo que tapan los cuerpos cercanos.
+                    let surface_distance = |index: usize| (scene.states[index].position - camera_position).magnitude() - scene.bodies[index].scale;
+                    let mut draw_order: Vec<usize> = (0..scene.bodies.len()).collect();
+                    draw_order.sort_by(|&a, &b| surface_distance(a).total_cmp(&surface_distance(b)));
+                    for index in draw_order {
+                        let (body, state) = (&scene.bodies[index], &scene.states[index]);
+                        let position = state.position;
+                        let model_matrix = create_scaled_model_matrix(position, body.scale_vector(), state.rotation);
+                        let view_depth = -(view_matrix * Vec4::new(position.x, position.y, position.z, 1.0)).z;
+                        let radius_px = projected_radius(body.scale, view_depth, &projection_matrix, &viewport_matrix);
+                        let blend = lod.update(index, radius_px, TIME_STEP);
+                        // Eclipses: los cuerpos entre este y la estrella
+                        scene.shadow_casters(position, body.scale, &mut eclipsers);
+                        // Sus anillos le dejan una sombra a franjas
+                        let ring_shadow = body.rings.as_ref().map(|rings| rings.shadow(position, body.scale, state.rotation, body.shader_seed(), &body.surface.ring_palette));
+
+                        let mut uniforms = Uniforms {
+                            model_matrix,
+                            view_matrix,
+                            projection_matrix,
+                            viewport_matrix,
+                            frustum,
+                            shader_time: time,
+                            shader: body.fragment_shader(),
+                            seed: body.shader_seed(),
+                            alpha: 1.0,
+                            texture: None,
+                            surface: &body.surface,
+                            light: light.with_eclipsers(&eclipsers).with_rings(ring_shadow),
+                            material: body.material(),
+                            camera_position,
+                            debug_view,
+                            screen_lighting: quality == Quality::Low && !body.emissive && radius_px < SCREEN_LIGHTING_RADIUS,
+                            detail: landing.as_ref().filter(|landed| landed.body == index).map_or(0.0, Landing::detail),
+                        };
+                        let impostor_time = blend.draw_impostor.then(|| {
+                            let started = Instant::now();
+                            render_impostor(&mut framebuffer, &uniforms, position, body.scale);
+                            started.elapsed()
+                        });
+                        let mesh_time = (blend.mesh_alpha > 0.0).then(|| {
+                            let started = Instant::now();
+                            uniforms.alpha = blend.mesh_alpha;
+                            render_model_cached(&mut framebuffer, &uniforms, &planet_vertices, &planet_indices, planet_radius, &mut vertex_caches[index]);
+                            started.elapsed()
+                        });
+                        if let Some(stats) = render_stats.as_mut().filter(|_| view_depth > 0.0) {
+                            stats.record(index, (position - camera_position).magnitude(), radius_px, mesh_time, impostor_time);
+                        }
+                    }
+
+                    // Calidad baja: los cuerpos lejanos se iluminan ahora, de una vez, con
+                    // normales sacadas del z-buffer
+                    if quality == Quality::Low {
+                        screen_lighting::apply(&mut framebuffer, &view_matrix, &projection_matrix, &light);
+                    }
+
+                    // Objetos animados: cada malla con la matriz de su nodo en este cuadro
+                    for ((_, model), poses) in props.iter().zip(&prop_poses) {
+                        for (mesh, model_matrix) in model.meshes.iter().zip(poses) {
+                            let prop_uniforms = Uniforms {
+                                model_matrix: *model_matrix,
+                                view_matrix,
+                                projection_matrix,
+                                viewport_matrix,
+                                frustum,
+                                shader_time: time,
+                                shader: &SpaceshipShader,
+                                seed: 0,
+                                alpha: 1.0,
+                                texture: None,
+                                surface: &BARE_SURFACE,
+                                light,
+                                material: Material::new(false, 0.5, 32.0),
+                                camera_position,
+                                debug_view,
+                                screen_lighting: false,
+                                detail: 0.0,
+                            };
+                            render_model(&mut framebuffer, &prop_uniforms, &mesh.vertices, &mesh.indices, mesh.radius);
+                        }
+                    }
+
+                    // Naves abandonadas: la malla de la primera nave del registro con el casco quemado
+                    for (anomaly, state) in scene.anomalies.iter().zip(&scene.anomaly_states) {
+                        if anomaly.kind != AnomalyKind::Derelict {
+                            continue;
+                        }
+                        for (part, _) in &ships[0].parts {
+                            let derelict_uniforms = Uniforms {
+                                model_matrix: create_model_matrix(state.position, anomaly.size, state.rotation),
+                                view_matrix,
+                                projection_matrix,
+                                viewport_matrix,
+                                frustum,
+                                shader_time: time,
+                                shader: &DerelictShader,
+                                seed: 0,
+                                alpha: 1.0,
+                                texture: None,
+                                surface: &BARE_SURFACE,
+                                light,
+                                material: Material::new(false, 0.2, 16.0),
+                                camera_position,
+                                debug_view,
+                                screen_lighting: false,
+                                detail: 0.0,
+                            };
+                            render_model(&mut framebuffer, &derelict_uniforms, &part.vertices, &part.indices, ships[0].radius);
+                        }
+                    }
+
+                    // Render Spaceship (TIE Fighter) - Controlled by player with animation.
+                    // Desde la cabina la nave no se ve
+                    if !in_cockpit {
+                        draw_ship(&mut framebuffer, create_model_matrix(spaceship.position, ship.config.scale, animated_rotation), 1.0, Some(&ship_rig));
+                    }
+                }
+                Pass::Transparent => {
+                    // Los anillos planetarios y los del circuito no escriben la
+                    // profundidad: se dibujan del más lejano al más cercano para
+                    // que cada uno quede sobre los que tiene detrás
+                    let view_depth = |point: Vec3| -(view_matrix * Vec4::new(point.x, point.y, point.z, 1.0)).z;
+                    let mut translucent = Vec::new();
+                    for (index, (body, state)) in scene.bodies.iter().zip(&scene.states).enumerate() {
+                        let (Some(rings), Some(_)) = (&body.rings, &planet_rings[index]) else {
+                            continue;
+                        };
+                        let depth = view_depth(state.position);
+                        let radius_px = projected_radius(body.scale, depth, &projection_matrix, &viewport_matrix);
+                        if radius_px * rings.outer_radius >= 2.0 {
+                            translucent.push((depth, TranslucentRing::Planet(index)));
+                        }
+                    }
+                    if let Some(course) = &course {
+                        for (index, ring) in course.rings.iter().enumerate() {
+                            if course.ring_alpha(index) > 0.0 {
+                                translucent.push((view_depth(ring.center), TranslucentRing::Checkpoint(index)));
+                            }
+                        }
+                    }
+                    translucent.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+
+                    for (_, ring) in &translucent {
+                        match *ring {
+                            // Anillos planetarios, sobre los cuerpos ya dibujados. Siguen el
+                            // ecuador: su inclinación propia y luego la del eje del cuerpo.
+                            // Se iluminan completos aunque el cuerpo use la luz en pantalla,
+                            // que ya se aplicó.
+                            TranslucentRing::Planet(index) => {
+                                let (body, state) = (&scene.bodies[index], &scene.states[index]);
+                                let (Some(rings), Some((ring_vertices, ring_indices, ring_radius))) = (&body.rings, &planet_rings[index]) else {
+                                    continue;
+                                };
+                                let position = state.position;
+                                // El planeta proyecta su sombra sobre sus anillos
+                                scene.shadow_casters(position, body.scale * rings.outer_radius, &mut eclipsers);
+                                eclipsers.push(Occluder { center: position, radius: body.scale });
+                                let ring_uniforms = Uniforms {
+                                    model_matrix: create_model_matrix(position, body.scale, rings.rotation(state.rotation)),
+                                    view_matrix,
+                                    projection_matrix,
+                                    viewport_matrix,
+                                    frustum,
+                                    shader_time: time,
+                                    shader: &RingShader,
+                                    seed: body.shader_seed(),
+                                    alpha: rings.opacity,
+                                    texture: None,
+                                    surface: &body.surface,
+                                    light: light.with_eclipsers(&eclipsers),
+                                    material: Material::new(false, 0.0, 1.0),
+                                    camera_position,
+                                    debug_view,
+                                    screen_lighting: false,
+                                    detail: 0.0,
+                                };
+                                render_model(&mut framebuffer, &ring_uniforms, ring_vertices, ring_indices, *ring_radius);
+                            }
+                            // Anillos del circuito: emisivos, el siguiente sólido y el resto translúcidos
+                            TranslucentRing::Checkpoint(index) => {
+                                let Some(course) = &course else {
+                                    continue;
+                                };
+                                let (ring, alpha) = (&course.rings[index], course.ring_alpha(index));
+                                let ring_uniforms = Uniforms {
+                                    model_matrix: ring.model_matrix(),
+                                    view_matrix,
+                                    projection_matrix,
+                                    viewport_matrix,
+                                    frustum,
+                                    shader_time: time,
+                                    shader: &CheckpointShader,
+                                    seed: 0,
+                                    alpha,
+                                    texture: None,
+                                    surface: &BARE_SURFACE,
+                                    light,
+                                    material: Material::new(true, 0.0, 32.0),
+                                    camera_position,
+                                    debug_view,
+                                    screen_lighting: false,
+                                    detail: 0.0,
+                                };
+                                render_model(&mut framebuffer, &ring_uniforms, &ring_vertices, &ring_indices, ring_radius);
+                            }
+                        }
+                    }
+
+                    // Agujeros de gusano: un disco que mira a la cámara
+                    for (anomaly, state) in scene.anomalies.iter().zip(&scene.anomaly_states) {
+                        if anomaly.kind == AnomalyKind::Wormhole {
+                            render_wormhole(&mut framebuffer, state.position, anomaly.size, time, &view_matrix, &projection_matrix, &viewport_matrix);
+                        }
+                    }
+
+                    // Nave fantasma: en carrera arranca junto con el jugador y no se repite
+                    if let Some(ghost) = &ghost_path {
+                        ghost.render_ghost(&mut framebuffer, &view_matrix, &projection_matrix);
+                        if let Some(position) = ghost.replay_position(time, race.is_none()) {
+                            draw_ship(&mut framebuffer, create_model_matrix(position, ship.config.scale, spaceship.rotation), 0.45, None);
+                        }
+                    }
+                    if let Some(race) = &race {
+                        race.render(&mut framebuffer, &view_matrix, &projection_matrix);
+                    }
+                    scanner.render_pulse(&mut framebuffer, &view_matrix, &projection_matrix, &viewport_matrix);
+                }
+                Pass::Particles => {
+                    comet_tails.render(&mut framebuffer, &view_matrix, &projection_matrix, &viewport_matrix);
+                    spaceship.engine_trail.render(&mut framebuffer, &view_matrix, &projection_matrix);
+                    hull.render_particles(&mut framebuffer, &view_matrix, &projection_matrix);
+                    weapons.render(&mut framebuffer, &view_matrix, &projection_matrix, &viewport_matrix);
+                }
+                Pass::Post => {
+                    // El destello sale de la primera estrella
+                    let sun = scene.bodies.iter().zip(&scene.states).find(|(body, _)| body.emissive);
+                    for effect in pipeline.post_chain() {
+                        match effect {
+                            // Las vistas de depuración reemplazan la imagen y no llevan
+                            // los efectos de la imagen sombreada
+                            PostEffect::DebugView => debug_view.resolve(&mut framebuffer, &projection_matrix),
+                            // Distorsión por calor en la zona de pantalla detrás de los motores
+                            PostEffect::HeatDistortion => {
+                                heat_distortion.clear(framebuffer.width, framebuffer.height);
+                                // Una columna de aire que sale de los motores hacia atrás y se ensancha
+                                let engine = spaceship.engine_position(ship.config.engine_anchor);
+                                let tail = engine - spaceship.forward() * EXHAUST_PLUME_LENGTH;
+                                let project = |point: Vec3, radius: f32| {
+                                    project_to_screen(point, &view_matrix, &projection_matrix, &viewport_matrix)
+                                        .map(|(x, y, depth)| (x, y, projected_radius(radius, depth, &projection_matrix, &viewport_matrix).min(120.0)))
+                                };
+                                if engine_heat > 0.01 {
+                                    if let Some(start) = project(engine, EXHAUST_PLUME_RADIUS[0]) {
+                                        let end = project(tail, EXHAUST_PLUME_RADIUS[1]).unwrap_or(start);
+                                        heat_distortion.add_plume(&framebuffer, start, end, engine_heat);
+                                    }
+                                }
+                                if debug_view == DebugView::Shaded {
+                                    heat_distortion.apply(&mut framebuffer, time);
+                                }
+                            }
+                            PostEffect::Exposure => {
+                                if let Some(frame) = tour_frame.as_ref().filter(|_| debug_view == DebugView::Shaded) {
+                                    apply_exposure(&mut framebuffer, frame.exposure);
+                                }
+                            }
+                            PostEffect::Bloom => {
+                                if debug_view == DebugView::Shaded {
+                                    bloom.apply(&mut framebuffer);
+                                }
+                            }
+                            PostEffect::LensFlare => {
+                                if let Some((body, state)) = sun.filter(|_| debug_view == DebugView::Shaded) {
+                                    lens_flare.apply(&mut framebuffer, state.position, body.scale, &view_matrix, &projection_matrix, &viewport_matrix);
+                                }
+                            }
+                            PostEffect::WarpStreaks => {
+                                if let Some(active) = &warp {
+                                    render_streaks(&mut framebuffer, active.intensity(), time);
+                                }
+                            }
+                            PostEffect::WormholeSwirl => {
+                                if let Some(active) = &transit {
+                                    render_swirl(&mut framebuffer, active.intensity(), time);
+                                }
+                            }
+                        }
+                    }
+                }
+                Pass::Resolve => resolver.resolve(&framebuffer, &mut display),
+                Pass::Hud => {
+                    hull.render_flash(&mut display);
+                    scanner.render_contacts(&mut display, &view_matrix, &projection_matrix, &display_viewport, spaceship.position);
+                    if minimap_visible {
+                        render_minimap(&mut display, &scene, scanner.contacts(), spaceship.position, spaceship.forward());
+                    }
+                    if let Some(index) = inspected {
+                        render_info_panel(&mut display, &scene, index, camera_position);
+                    } else if landing.is_none() {
+                        render_approach_panel(&mut display, &scene, spaceship.position);
+                    }
+
+                    let mut hud_extra = Vec::new();
+                    if let (Some(tour), Some(elapsed)) = (&tour, tour_elapsed) {
+                        hud_extra.push(format!("Recorrido {}: {:.1} / {:.1} (T)", tour.name, elapsed, tour.duration()));
+                    }
+                    if let Some(recorder) = tour_recorder.as_ref().filter(|_| tour_elapsed.is_none()) {
+                        hud_extra.push(format!("Grabando recorrido: {} puntos (K)", recorder.tour.keyframes.len()));
+                    }
+                    if let Some(seed) = system_seed {
+                        hud_extra.push(format!("Semilla: {}", seed));
+                    }
+                    if let Some(index) = target {
+                        let distance = (scene.states[index].position - spaceship.position).magnitude();
+                        hud_extra.push(format!("Objetivo: {} ({:.1})", scene.bodies[index].name, distance));
+                    }
+                    if let Some(race) = &race {
+                        hud_extra.extend(race.hud_lines(time, ghost_race_time));
+                    }
+                    if let Some(course) = &course {
+                        hud_extra.extend(course.hud_lines(time));
+                    }
+                    if let Some(investigation) = &investigation {
+                        hud_extra.extend(investigation.hud_lines(&scene, spaceship.position));
+                    }
+                    if !time_control.is_normal() {
+                        hud_extra.push(format!("Tiempo: {} (P, +/-, R)", time_control.label()));
+                    }
+                    if frozen {
+                        hud_extra.push("CUADRO CONGELADO - clic: inspeccionar un cuerpo, I: continuar".to_string());
+                    }
+                    if debug_view != DebugView::Shaded {
+                        hud_extra.push(format!("Depuración: {} (G)", debug_view.label()));
+                    }
+                    if let Some(station) = dock {
+                        hud_extra.push(format!("Acoplada a {} (Enter: hangar, U: desacoplar)", station.name));
+                    } else if let Some((index, distance)) = Docking::approach(&scene, spaceship.position).filter(|_| docking.is_none()) {
+                        hud_extra.push(format!(
+                            "Zona de acoplamiento de {}: a {:.1}, entrar a menos de {:.0} (ahora {:.1})",
+                            scene.props[index].name,
+                            distance,
+                            DOCK_SPEED,
+                            spaceship.velocity.magnitude()
+                        ));
+                    }
+                    if let Some(landed) = &landing {
+                        hud_extra.push(format!("Sobre {}: altura {:.1} (L: despegar)", scene.bodies[landed.body].name, landed.altitude()));
+                    }
+                    if let Some(orbit) = orbit.as_ref().filter(|_| orbit_readouts) {
+                        hud_extra.extend(orbit.hud_lines(&scene));
+                    }
+                    let hud_color = if in_cockpit {
+                        let view = CockpitView {
+                            view_matrix: &view_matrix,
+                            projection_matrix: &projection_matrix,
+                            viewport_matrix: &display_viewport,
+                            camera_position,
+                            time,
+                        };
+                        render_cockpit_hud(&mut display, &view, &scene, spaceship.forward(), ship_speed, target);
+                        HOLO_TEXT_COLOR
+                    } else {
+                        0xE0E0E0
+                    };
+                    render_hud(&mut display, &scene, &spaceship, ship_speed, fps, &hud_extra, hud_color);
+                    power.render(&mut display, hud_color);
+                    hull.render(&mut display, hud_color);
+                    if inspected.is_none() {
+                        transmissions.render(&mut display, hud_color);
+                    }
+                    if cues.warning {
+                        display.draw_text((display.width / 2).saturating_sub(110), 40, "ALERTA DE COLISIÓN", 0xFF4040);
+                    }
+                }
+            }
+        }
+
+        let (width, height) = (display.width, display.height);
+        window.update_with_buffer(display.present(), width, height).unwrap();
     }
-}
\ No newline at end of file
+
+    if let (Some(path), Some(stats)) = (render_stats_path, &render_stats) {
+        save_render_stats(path, stats);
+    }
+
+    if let Some(path) = record_path {
+        match recorded_path.save(path) {
+            Ok(()) => println!("Trayectoria guardada en {} ({} puntos)", path, recorded_path.points.len()),
+            Err(e) => eprintln!("No se pudo guardar la trayectoria {}: {}", path, e),
+        }
+    }
+}
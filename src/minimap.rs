@@ -0,0 +1,170 @@
+// Minimapa: vista cenital del sistema en una esquina de la pantalla con el
+// sol, los planetas, sus órbitas, los contactos del escáner y la nave
+// apuntando hacia donde vuela. Se dibuja sobre el cuadro ya renderizado,
+// como el HUD.
+
+use nalgebra_glm::{Vec2, Vec3};
+
+use crate::framebuffer::{pack_color, unpack_color, Framebuffer};
+use crate::scanner::Contact;
+use crate::scene::Scene;
+
+// Lado del minimapa como fracción del lado menor de la ventana
+const SIZE_FRACTION: f32 = 0.28;
+const MARGIN: usize = 10;
+const ORBIT_SEGMENTS: usize = 64;
+const BACKGROUND_DIM: f32 = 0.35;
+const BORDER_COLOR: u32 = 0x606060;
+const ORBIT_COLOR: u32 = 0x3A3A3A;
+const STAR_COLOR: u32 = 0xFFD040;
+const PLANET_COLOR: u32 = 0x80B0FF;
+const SHIP_COLOR: u32 = 0x80FF80;
+// Radio en píxeles del aro que marca un contacto del escáner
+const CONTACT_RADIUS: f32 = 4.5;
+
+// Transformación del plano XZ del mundo al recuadro del minimapa
+struct MapArea {
+    left: f32,
+    top: f32,
+    size: f32,
+    center: Vec2,
+    scale: f32,
+}
+
+impl MapArea {
+    fn to_map(&self, point: Vec3) -> Vec2 {
+        self.center + Vec2::new(point.x, point.z) * self.scale
+    }
+
+    // Lleva un punto fuera del mapa a su borde, para no perder la nave
+    fn clamp(&self, point: Vec2) -> Vec2 {
+        let inset = 4.0;
+        Vec2::new(
+            point.x.clamp(self.left + inset, self.left + self.size - inset),
+            point.y.clamp(self.top + inset, self.top + self.size - inset),
+        )
+    }
+
+    fn contains(&self, x: f32, y: f32) -> bool {
+        x >= self.left && x < self.left + self.size && y >= self.top && y < self.top + self.size
+    }
+}
+
+pub fn render_minimap(framebuffer: &mut Framebuffer, scene: &Scene, contacts: &[Contact], ship_position: Vec3, ship_forward: Vec3) {
+    let size = (framebuffer.width.min(framebuffer.height) as f32 * SIZE_FRACTION) as usize;
+    if size < 32 || framebuffer.width < size + MARGIN {
+        return;
+    }
+    let left = framebuffer.width - size - MARGIN;
+    let top = MARGIN;
+
+    // La escala se ajusta a la órbita más lejana de los cuerpos sin padre
+    let extent = scene.bodies.iter()
+        .zip(&scene.states)
+        .filter(|(body, _)| body.parent_index.is_none())
+        .map(|(body, state)| {
+            let horizontal = Vec2::new(state.position.x, state.position.z).magnitude();
+            (body.orbit_radius * (1.0 + body.eccentricity)).max(horizontal)
+        })
+        .fold(1.0, f32::max);
+    let area = MapArea {
+        left: left as f32,
+        top: top as f32,
+        size: size as f32,
+        center: Vec2::new((left + size / 2) as f32, (top + size / 2) as f32),
+        scale: (size as f32 * 0.5 - 6.0) / extent,
+    };
+
+    // Fondo oscurecido y borde
+    for y in top..top + size {
+        for x in left..left + size {
+            let index = framebuffer.index(x, y);
+            framebuffer.buffer[index] = pack_color(unpack_color(framebuffer.buffer[index]) * BACKGROUND_DIM);
+        }
+    }
+    let (right, bottom) = ((left + size - 1) as f32, (top + size - 1) as f32);
+    let corners = [
+        Vec2::new(left as f32, top as f32),
+        Vec2::new(right, top as f32),
+        Vec2::new(right, bottom),
+        Vec2::new(left as f32, bottom),
+    ];
+    for i in 0..4 {
+        draw_map_line(framebuffer, &area, corners[i], corners[(i + 1) % 4], BORDER_COLOR);
+    }
+
+    // Órbitas de los planetas (las de las lunas quedan demasiado pequeñas)
+    for (index, body) in scene.bodies.iter().enumerate() {
+        if body.parent_index.is_some() || body.orbit_radius <= 0.0 {
+            continue;
+        }
+        let center = scene.orbit_center(index);
+        let mut previous = area.to_map(center + body.orbit_point(0.0));
+        for i in 1..=ORBIT_SEGMENTS {
+            let angle = i as f32 / ORBIT_SEGMENTS as f32 * std::f32::consts::TAU;
+            let current = area.to_map(center + body.orbit_point(angle));
+            draw_map_line(framebuffer, &area, previous, current, ORBIT_COLOR);
+            previous = current;
+        }
+    }
+
+    for (body, state) in scene.bodies.iter().zip(&scene.states) {
+        if body.parent_index.is_some() {
+            continue;
+        }
+        let (radius, color) = if body.emissive { (3.5, STAR_COLOR) } else { (2.0, PLANET_COLOR) };
+        fill_circle(framebuffer, &area, area.to_map(state.position), radius, color);
+    }
+
+    // Contactos del escáner: un aro del color de su tipo que se apaga con la marca
+    for contact in contacts {
+        let center = area.to_map(contact.position);
+        let color = pack_color(unpack_color(contact.kind.color()) * contact.fade());
+        let point = |i: usize| {
+            let angle = i as f32 / 12.0 * std::f32::consts::TAU;
+            center + Vec2::new(angle.cos(), angle.sin()) * CONTACT_RADIUS
+        };
+        for i in 0..12 {
+            draw_map_line(framebuffer, &area, point(i), point(i + 1), color);
+        }
+    }
+
+    // La nave es una flecha en la dirección de vuelo
+    let heading = Vec2::new(ship_forward.x, ship_forward.z).try_normalize(1e-6).unwrap_or_else(|| Vec2::new(1.0, 0.0));
+    let side = Vec2::new(-heading.y, heading.x);
+    let ship = area.clamp(area.to_map(ship_position));
+    let tip = ship + heading * 6.0;
+    let left_wing = ship - heading * 4.0 + side * 4.0;
+    let right_wing = ship - heading * 4.0 - side * 4.0;
+    draw_map_line(framebuffer, &area, tip, left_wing, SHIP_COLOR);
+    draw_map_line(framebuffer, &area, left_wing, ship, SHIP_COLOR);
+    draw_map_line(framebuffer, &area, ship, right_wing, SHIP_COLOR);
+    draw_map_line(framebuffer, &area, right_wing, tip, SHIP_COLOR);
+}
+
+// Línea DDA sin profundidad, recortada al recuadro del minimapa
+fn draw_map_line(framebuffer: &mut Framebuffer, area: &MapArea, from: Vec2, to: Vec2, color: u32) {
+    let steps = (to - from).abs().max().ceil().min(area.size * 2.0) as usize;
+    for step in 0..=steps {
+        let t = if steps == 0 { 0.0 } else { step as f32 / steps as f32 };
+        let point = from.lerp(&to, t);
+        if area.contains(point.x, point.y) {
+            framebuffer.overlay_pixel(point.x as usize, point.y as usize, color);
+        }
+    }
+}
+
+fn fill_circle(framebuffer: &mut Framebuffer, area: &MapArea, center: Vec2, radius: f32, color: u32) {
+    let reach = radius.ceil() as i32;
+    for dy in -reach..=reach {
+        for dx in -reach..=reach {
+            if (dx * dx + dy * dy) as f32 > radius * radius {
+                continue;
+            }
+            let (x, y) = (center.x + dx as f32, center.y + dy as f32);
+            if area.contains(x, y) {
+                framebuffer.overlay_pixel(x as usize, y as usize, color);
+            }
+        }
+    }
+}
@@ -0,0 +1,82 @@
+// Mirada continua con el mouse (J o `--mouse-look`): sin mantener ningún
+// botón, el movimiento del mouse gira la nave (o la cámara libre) y sube o
+// baja la cámara. minifb no puede mover el cursor, así que el bloqueo del
+// puntero se emula: el cursor se oculta, se suman las diferencias de posición
+// de un cuadro al siguiente y, cuando llega a la franja del borde de la
+// ventana y ya no puede seguir, el giro continúa hacia ese lado mientras se
+// lo empuje ahí, así la vista da vueltas sin límite. `--mouse-sensitivity`
+// cambia los grados por píxel (también del arrastre con el botón derecho) e
+// `--invert-y` invierte el eje vertical.
+
+use minifb::{MouseMode, Window};
+
+// Grados por píxel de movimiento, los mismos del arrastre de siempre
+pub const DEFAULT_SENSITIVITY: f32 = 0.3;
+// Ancho de la franja del borde como fracción del lado de la ventana
+const EDGE_BAND: f32 = 0.06;
+// Píxeles por cuadro que se suman con el cursor al fondo de la franja
+const EDGE_SPEED: f32 = 10.0;
+
+pub struct MouseLook {
+    pub enabled: bool,
+    sensitivity: f32,
+    invert_y: bool,
+    // Posición del cursor en el cuadro anterior
+    last: Option<(f32, f32)>,
+}
+
+// Cuánto se metió `position` en la franja del borde de un lado de `size`:
+// de -1 (al fondo del borde izquierdo o de arriba) a 1, y 0 fuera de ella
+fn edge_push(position: f32, size: f32) -> f32 {
+    let band = (size * EDGE_BAND).max(1.0);
+    if position < band {
+        -((band - position) / band).min(1.0)
+    } else if position > size - band {
+        ((position - (size - band)) / band).min(1.0)
+    } else {
+        0.0
+    }
+}
+
+impl MouseLook {
+    pub fn new(sensitivity: f32, invert_y: bool) -> Self {
+        MouseLook { enabled: false, sensitivity, invert_y, last: None }
+    }
+
+    // Activa o desactiva el modo; el cursor se oculta mientras está activo
+    pub fn toggle(&mut self, window: &mut Window) {
+        self.enabled = !self.enabled;
+        self.last = None;
+        window.set_cursor_visibility(!self.enabled);
+    }
+
+    // Olvida la posición anterior, para que el cursor movido en el hangar o
+    // en el mapa no haga saltar la vista al volver
+    pub fn forget(&mut self) {
+        self.last = None;
+    }
+
+    // Giro y elevación en grados para un movimiento del mouse en píxeles,
+    // con la sensibilidad y el eje vertical elegidos. Positivo = mirar hacia
+    // arriba.
+    pub fn angles(&self, delta_x: f32, delta_y: f32) -> (f32, f32) {
+        let vertical = if self.invert_y { delta_y } else { -delta_y };
+        (delta_x * self.sensitivity, vertical * self.sensitivity)
+    }
+
+    // Giro y elevación de este cuadro, o `None` con el modo apagado
+    pub fn update(&mut self, window: &Window) -> Option<(f32, f32)> {
+        if !self.enabled {
+            return None;
+        }
+        let Some((x, y)) = window.get_mouse_pos(MouseMode::Pass) else {
+            return Some((0.0, 0.0));
+        };
+        let (width, height) = window.get_size();
+        let (mut delta_x, mut delta_y) = self.last.map_or((0.0, 0.0), |(last_x, last_y)| (x - last_x, y - last_y));
+        self.last = Some((x, y));
+        delta_x += edge_push(x, width as f32) * EDGE_SPEED;
+        delta_y += edge_push(y, height as f32) * EDGE_SPEED;
+        Some(self.angles(delta_x, delta_y))
+    }
+}
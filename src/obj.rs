@@ -1,29 +1,167 @@
-use tobj;
-use nalgebra_glm::{Vec2, Vec3};
+// Carga de modelos: Wavefront OBJ (con su MTL) o glTF (.gltf/.glb), según
+// la extensión del archivo. Los dos dan las mismas mallas con normales, UVs,
+// textura difusa y rugosidad, así la nave o los planetas pueden venir de
+// cualquiera de los dos formatos.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use nalgebra_glm::{Mat3, Mat4, Vec2, Vec3, Vec4};
 use crate::vertex::Vertex;
 
+// Rugosidad de los materiales que no indican brillo (`Ns`)
+const DEFAULT_ROUGHNESS: f32 = 0.5;
+
 pub struct Obj {
     meshes: Vec<Mesh>,
 }
 
+#[derive(Debug)]
+pub enum ModelError {
+    Obj(tobj::LoadError),
+    Gltf(gltf::Error),
+}
+
+impl fmt::Display for ModelError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ModelError::Obj(e) => write!(f, "OBJ inválido: {}", e),
+            ModelError::Gltf(e) => write!(f, "glTF inválido: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ModelError {}
+
+pub fn is_gltf_path(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("gltf") || ext.eq_ignore_ascii_case("glb"))
+}
+
 struct Mesh {
+    name: String,
     vertices: Vec<Vec3>,
     normals: Vec<Vec3>,
     texcoords: Vec<Vec2>,
     indices: Vec<u32>,
+    diffuse_texture: Option<PathBuf>,
+    roughness: f32,
+}
+
+// Parte de un modelo con su propia textura difusa (map_Kd del MTL)
+pub struct SubMesh {
+    pub name: String,
+    pub vertices: Vec<Vertex>,
+    pub indices: Vec<u32>,
+    pub diffuse_texture: Option<PathBuf>,
+    // Rugosidad 0..1 del material, para los reflejos del entorno
+    pub roughness: f32,
+}
+
+impl Mesh {
+    fn vertex(&self, i: usize) -> Vertex {
+        let position = self.vertices[i];
+        let normal = self.normals.get(i)
+            .cloned()
+            .unwrap_or(Vec3::new(0.0, 1.0, 0.0));
+        let tex_coords = self.texcoords.get(i)
+            .cloned()
+            .unwrap_or(Vec2::new(0.0, 0.0));
+
+        Vertex::new(position, normal, tex_coords)
+    }
+}
+
+// Agrega las mallas del nodo y de sus hijos, en el orden del archivo. Los
+// ejes se giran igual que en el OBJ, así un modelo exportado en los dos
+// formatos queda con la misma orientación; las UV de glTF ya tienen el origen
+// arriba.
+fn gltf_node_meshes(node: &gltf::Node, parent: &Mat4, buffers: &[gltf::buffer::Data], directory: &Path, meshes: &mut Vec<Mesh>) {
+    let transform = parent * Mat4::from(node.transform().matrix());
+    if let Some(mesh) = node.mesh() {
+        let normal_matrix = Mat3::from_fn(|row, column| transform[(row, column)])
+            .try_inverse()
+            .map_or_else(Mat3::identity, |inverse| inverse.transpose());
+
+        for primitive in mesh.primitives().filter(|p| p.mode() == gltf::mesh::Mode::Triangles) {
+            let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+            let Some(positions) = reader.read_positions() else { continue };
+            let vertices: Vec<Vec3> = positions
+                .map(|p| {
+                    let world = transform * Vec4::new(p[0], p[1], p[2], 1.0);
+                    Vec3::new(world.x, -world.y, -world.z)
+                })
+                .collect();
+            let normals = reader
+                .read_normals()
+                .map(|normals| {
+                    normals
+                        .map(|n| {
+                            let world = (normal_matrix * Vec3::from(n)).normalize();
+                            Vec3::new(world.x, -world.y, -world.z)
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            let texcoords = reader.read_tex_coords(0).map(|t| t.into_f32().map(Vec2::from).collect()).unwrap_or_default();
+            let indices = match reader.read_indices() {
+                Some(indices) => indices.into_u32().collect(),
+                None => (0..vertices.len() as u32).collect(),
+            };
+
+            let pbr = primitive.material().pbr_metallic_roughness();
+            // Solo las imágenes en archivos aparte: `Texture` se carga desde una ruta
+            let diffuse_texture = pbr.base_color_texture().and_then(|info| match info.texture().source().source() {
+                gltf::image::Source::Uri { uri, .. } if !uri.starts_with("data:") => Some(directory.join(uri)),
+                _ => None,
+            });
+            meshes.push(Mesh {
+                name: mesh.name().or(node.name()).unwrap_or("").to_string(),
+                vertices,
+                normals,
+                texcoords,
+                indices,
+                diffuse_texture,
+                roughness: pbr.roughness_factor(),
+            });
+        }
+    }
+    for child in node.children() {
+        gltf_node_meshes(&child, &transform, buffers, directory, meshes);
+    }
 }
 
 impl Obj {
-    pub fn load(filename: &str) -> Result<Self, tobj::LoadError> {
-        let (models, _) = tobj::load_obj(filename, &tobj::LoadOptions {
+    // OBJ o glTF según la extensión
+    pub fn load(filename: &str) -> Result<Self, ModelError> {
+        if is_gltf_path(Path::new(filename)) {
+            Self::load_gltf(filename).map_err(ModelError::Gltf)
+        } else {
+            Self::load_obj(filename).map_err(ModelError::Obj)
+        }
+    }
+
+    fn load_obj(filename: &str) -> Result<Self, tobj::LoadError> {
+        let (models, materials) = tobj::load_obj(filename, &tobj::LoadOptions {
             single_index: true,
             triangulate: true,
             ..Default::default()
         })?;
 
+        // Las texturas del MTL son relativas a la carpeta del OBJ
+        let directory = Path::new(filename).parent().unwrap_or(Path::new("")).to_path_buf();
+        let materials = materials.unwrap_or_default();
+
         let meshes = models.into_iter().map(|model| {
             let mesh = model.mesh;
+            let material = mesh.material_id.and_then(|id| materials.get(id));
+            let diffuse_texture = material
+                .and_then(|material| material.diffuse_texture.as_ref())
+                .map(|texture| directory.join(texture));
+            // Blender exporta `Ns = (1 - rugosidad)² · 1000`
+            let roughness = material
+                .and_then(|material| material.shininess)
+                .map_or(DEFAULT_ROUGHNESS, |shininess| 1.0 - (shininess / 1000.0).clamp(0.0, 1.0).sqrt());
             Mesh {
+                name: model.name,
                 vertices: mesh.positions.chunks(3)
                     .map(|v| Vec3::new(v[0], -v[1], -v[2]))
                     .collect(),
@@ -34,12 +172,29 @@ impl Obj {
                     .map(|t| Vec2::new(t[0], 1.0 - t[1]))
                     .collect(),
                 indices: mesh.indices,
+                diffuse_texture,
+                roughness,
             }
         }).collect();
 
         Ok(Obj { meshes })
     }
 
+    // Cada primitiva de triángulos de la escena del archivo es una parte,
+    // con las transformaciones de sus nodos ya aplicadas
+    fn load_gltf(filename: &str) -> Result<Self, gltf::Error> {
+        let (document, buffers, _) = gltf::import(filename)?;
+        let directory = Path::new(filename).parent().unwrap_or(Path::new(""));
+        let mut meshes = Vec::new();
+        if let Some(scene) = document.default_scene().or_else(|| document.scenes().next()) {
+            for node in scene.nodes() {
+                gltf_node_meshes(&node, &Mat4::identity(), &buffers, directory, &mut meshes);
+            }
+        }
+        Ok(Obj { meshes })
+    }
+
+    #[allow(dead_code)]
     pub fn get_vertex_array(&self) -> Vec<Vertex> {
         let mut vertices = Vec::new();
 
@@ -70,15 +225,7 @@ impl Obj {
         for mesh in &self.meshes {
             // Agregar todos los vértices únicos de este mesh
             for i in 0..mesh.vertices.len() {
-                let position = mesh.vertices[i];
-                let normal = mesh.normals.get(i)
-                    .cloned()
-                    .unwrap_or(Vec3::new(0.0, 1.0, 0.0));
-                let tex_coords = mesh.texcoords.get(i)
-                    .cloned()
-                    .unwrap_or(Vec2::new(0.0, 0.0));
-
-                all_vertices.push(Vertex::new(position, normal, tex_coords));
+                all_vertices.push(mesh.vertex(i));
             }
 
             // Agregar los índices ajustados por el offset
@@ -92,7 +239,19 @@ impl Obj {
         (all_vertices, all_indices)
     }
 
+    // Vértices e índices separados por mesh, para dibujar cada parte con su material
+    pub fn get_submeshes(&self) -> Vec<SubMesh> {
+        self.meshes.iter().map(|mesh| SubMesh {
+            name: mesh.name.clone(),
+            vertices: (0..mesh.vertices.len()).map(|i| mesh.vertex(i)).collect(),
+            indices: mesh.indices.clone(),
+            diffuse_texture: mesh.diffuse_texture.clone(),
+            roughness: mesh.roughness,
+        }).collect()
+    }
+
     // Método para obtener información del modelo
+    #[allow(dead_code)]
     pub fn get_model_info(&self) -> String {
         let total_vertices: usize = self.meshes.iter().map(|m| m.vertices.len()).sum();
         let total_indices: usize = self.meshes.iter().map(|m| m.indices.len()).sum();
@@ -105,4 +264,4 @@ impl Obj {
             total_triangles
         )
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,123 @@
+// Asistente de inserción orbital: con la velocidad actual de la nave respecto
+// al cuerpo que más la atrae, calcula la órbita kepleriana que seguiría si
+// apagara los motores (periapsis y apoapsis sobre la superficie) y cuánto
+// cambio de velocidad hace falta para circularizarla a la altura actual.
+
+use nalgebra_glm::Vec3;
+
+use crate::scene::Scene;
+
+// Constante gravitatoria de la simulación: con ella la masa del sol de la
+// escena por defecto da velocidades circulares parecidas a las de la nave
+const GRAVITATIONAL_CONSTANT: f32 = 8.0;
+// Por debajo de este cambio de velocidad la órbita ya se considera circular
+const CIRCULAR_TOLERANCE: f32 = 0.2;
+
+pub struct OrbitReadout {
+    pub body: usize,
+    // Alturas sobre la superficie; `apoapsis` es None en una trayectoria de escape
+    pub periapsis: f32,
+    pub apoapsis: Option<f32>,
+    pub eccentricity: f32,
+    // Cambio de velocidad para circularizar, separado en la dirección de
+    // avance (positivo = acelerar) y la radial (positivo = alejarse)
+    pub prograde_burn: f32,
+    pub radial_burn: f32,
+}
+
+impl OrbitReadout {
+    pub fn hud_lines(&self, scene: &Scene) -> Vec<String> {
+        let name = &scene.bodies[self.body].name;
+        let apoapsis = match self.apoapsis {
+            Some(apoapsis) => format!("{:.1}", apoapsis),
+            None => "escape".to_string(),
+        };
+        let mut lines = vec![format!("Órbita de {}: Pe {:.1}  Ap {}  e {:.2}", name, self.periapsis, apoapsis, self.eccentricity)];
+        if self.periapsis < 0.0 {
+            lines.push("Trayectoria de impacto".to_string());
+        }
+        let burn = (self.prograde_burn * self.prograde_burn + self.radial_burn * self.radial_burn).sqrt();
+        if burn < CIRCULAR_TOLERANCE {
+            lines.push("Órbita circular".to_string());
+        } else {
+            let along = if self.prograde_burn >= 0.0 { "acelerar" } else { "frenar" };
+            let radial = if self.radial_burn >= 0.0 { "alejarse" } else { "acercarse" };
+            lines.push(format!(
+                "Circularizar: Δv {:.1} ({} {:.1}, {} {:.1})",
+                burn,
+                along,
+                self.prograde_burn.abs(),
+                radial,
+                self.radial_burn.abs()
+            ));
+        }
+        lines
+    }
+}
+
+// Sigue al cuerpo dominante y a la posición relativa del cuadro anterior,
+// para derivar la velocidad relativa sin depender de cómo se mueva la nave
+#[derive(Default)]
+pub struct OrbitAssist {
+    previous: Option<(usize, Vec3)>,
+}
+
+impl OrbitAssist {
+    pub fn update(&mut self, scene: &Scene, ship_position: Vec3, dt: f32) -> Option<OrbitReadout> {
+        let body = dominant_body(scene, ship_position)?;
+        let relative = ship_position - scene.states[body].position;
+        let previous = self.previous.replace((body, relative));
+        // Al cambiar de cuerpo falta un cuadro para tener velocidad
+        let velocity = match previous {
+            Some((previous_body, previous_relative)) if previous_body == body && dt > 0.0 => (relative - previous_relative) / dt,
+            _ => return None,
+        };
+        let mu = GRAVITATIONAL_CONSTANT * scene.bodies[body].mass;
+        Some(readout(body, relative, velocity, mu, scene.bodies[body].scale))
+    }
+}
+
+// Cuerpo con masa cuya aceleración gravitatoria sobre la nave es mayor
+fn dominant_body(scene: &Scene, point: Vec3) -> Option<usize> {
+    scene.bodies
+        .iter()
+        .zip(&scene.states)
+        .enumerate()
+        .filter(|(_, (body, _))| body.mass > 0.0)
+        .map(|(index, (body, state))| (index, body.mass / (point - state.position).magnitude_squared().max(1e-6)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(index, _)| index)
+}
+
+// Elementos orbitales a partir del vector de estado (posición y velocidad
+// relativas) y el parámetro gravitatorio `mu`
+fn readout(body: usize, position: Vec3, velocity: Vec3, mu: f32, surface_radius: f32) -> OrbitReadout {
+    let r = position.magnitude().max(1e-6);
+    let radial_direction = position / r;
+    let h = position.cross(&velocity);
+    let eccentricity_vector = velocity.cross(&h) / mu - radial_direction;
+    let eccentricity = eccentricity_vector.magnitude();
+    let energy = velocity.magnitude_squared() * 0.5 - mu / r;
+    // Distancia del periapsis desde el semilatus rectum: sirve también para
+    // trayectorias abiertas (e >= 1)
+    let semi_latus_rectum = h.magnitude_squared() / mu;
+    let periapsis = semi_latus_rectum / (1.0 + eccentricity);
+    let apoapsis = (energy < 0.0 && eccentricity < 1.0).then(|| semi_latus_rectum / (1.0 - eccentricity));
+
+    // Órbita circular a esta altura: velocidad sqrt(mu / r) perpendicular al
+    // radio, en el sentido en que ya se gira (o hacia adelante si no se gira)
+    let tangential = velocity - radial_direction * velocity.dot(&radial_direction);
+    let tangent = tangential.try_normalize(1e-4).unwrap_or_else(|| {
+        radial_direction.cross(&Vec3::new(0.0, 1.0, 0.0)).try_normalize(1e-4).unwrap_or_else(|| Vec3::new(1.0, 0.0, 0.0))
+    });
+    let burn = tangent * (mu / r).sqrt() - velocity;
+
+    OrbitReadout {
+        body,
+        periapsis: periapsis - surface_radius,
+        apoapsis: apoapsis.map(|apoapsis| apoapsis - surface_radius),
+        eccentricity,
+        prograde_burn: burn.dot(&tangent),
+        radial_burn: burn.dot(&radial_direction),
+    }
+}
@@ -0,0 +1,130 @@
+// Sistema de partículas simple: sprites cuadrados con profundidad que se
+// desvanecen con el tiempo. Se usa para la estela de los motores de la nave.
+
+use std::collections::VecDeque;
+
+use nalgebra_glm::{Mat4, Vec3, Vec4};
+use serde::{Deserialize, Serialize};
+
+use crate::framebuffer::{pack_color, Framebuffer};
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ParticleConfig {
+    // Máximo de partículas vivas al mismo tiempo
+    pub max_particles: usize,
+    // Partículas emitidas por cuadro mientras la nave se mueve
+    pub emit_per_frame: usize,
+    // Vida de cada partícula en unidades de tiempo de simulación
+    pub lifetime: f32,
+    // Tamaño del sprite en unidades del mundo
+    pub size: f32,
+    pub start_color: [f32; 3],
+    pub end_color: [f32; 3],
+}
+
+impl Default for ParticleConfig {
+    fn default() -> Self {
+        ParticleConfig {
+            max_particles: 400,
+            emit_per_frame: 4,
+            lifetime: 0.6,
+            size: 0.12,
+            start_color: [0.6, 0.9, 1.0],
+            end_color: [0.1, 0.2, 0.8],
+        }
+    }
+}
+
+struct Particle {
+    position: Vec3,
+    velocity: Vec3,
+    age: f32,
+}
+
+pub struct ParticleSystem {
+    config: ParticleConfig,
+    // Las más viejas adelante, para descartarlas sin mover las demás
+    particles: VecDeque<Particle>,
+    rng_state: u32,
+}
+
+impl ParticleSystem {
+    pub fn new(config: ParticleConfig) -> Self {
+        ParticleSystem {
+            particles: VecDeque::with_capacity(config.max_particles),
+            config,
+            rng_state: 0x9E37_79B9,
+        }
+    }
+
+    // Número pseudoaleatorio en [-1, 1] (xorshift)
+    fn random(&mut self) -> f32 {
+        self.rng_state ^= self.rng_state << 13;
+        self.rng_state ^= self.rng_state >> 17;
+        self.rng_state ^= self.rng_state << 5;
+        (self.rng_state as f32 / u32::MAX as f32) * 2.0 - 1.0
+    }
+
+    // Emite partículas en `origin` con una velocidad base y algo de dispersión
+    pub fn emit(&mut self, origin: Vec3, velocity: Vec3) {
+        for _ in 0..self.config.emit_per_frame {
+            if self.particles.len() >= self.config.max_particles {
+                self.particles.pop_front();
+            }
+            let jitter = Vec3::new(self.random(), self.random(), self.random()) * 0.05;
+            self.particles.push_back(Particle {
+                position: origin + jitter,
+                velocity: velocity + jitter * 0.5,
+                age: 0.0,
+            });
+        }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        let lifetime = self.config.lifetime;
+        self.particles.retain_mut(|particle| {
+            particle.age += dt;
+            particle.position += particle.velocity * dt;
+            particle.age < lifetime
+        });
+    }
+
+    pub fn render(&self, framebuffer: &mut Framebuffer, view_matrix: &Mat4, projection_matrix: &Mat4) {
+        let width = framebuffer.width as f32;
+        let height = framebuffer.height as f32;
+        let start_color = Vec3::from(self.config.start_color);
+        let end_color = Vec3::from(self.config.end_color);
+
+        for particle in &self.particles {
+            let view = view_matrix * Vec4::new(particle.position.x, particle.position.y, particle.position.z, 1.0);
+            if -view.z <= 0.1 {
+                continue;
+            }
+            let clip = projection_matrix * view;
+            let ndc = clip.xyz() / clip.w;
+            if ndc.z < -1.0 || ndc.z > 1.0 {
+                continue;
+            }
+
+            let screen_x = (ndc.x * 0.5 + 0.5) * width;
+            let screen_y = (1.0 - (ndc.y * 0.5 + 0.5)) * height;
+            let half_size = (self.config.size * projection_matrix[(1, 1)] / -view.z * height * 0.5).clamp(0.5, 6.0);
+
+            let life = particle.age / self.config.lifetime;
+            let color = start_color.lerp(&end_color, life);
+            let packed = pack_color(color);
+            let alpha = (1.0 - life).clamp(0.0, 1.0);
+
+            let min_x = (screen_x - half_size).max(0.0) as usize;
+            let max_x = (screen_x + half_size).min(width - 1.0).max(0.0) as usize;
+            let min_y = (screen_y - half_size).max(0.0) as usize;
+            let max_y = (screen_y + half_size).min(height - 1.0).max(0.0) as usize;
+            for y in min_y..=max_y {
+                for x in min_x..=max_x {
+                    framebuffer.point_blend(x, y, ndc.z, packed, alpha);
+                }
+            }
+        }
+    }
+}
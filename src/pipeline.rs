@@ -0,0 +1,162 @@
+// Orden de dibujo de cada cuadro: una lista de pases (fondo, opacos,
+// translúcidos, partículas, cadena de posproceso, resolución a la ventana y
+// HUD) definida en un solo lugar, cada uno con su interruptor. El bucle
+// principal recorre la lista en orden y ejecuta los pases activos, así que
+// reordenar o saltar uno es cambiar la lista y no el código de dibujo.
+//
+// `--passes` y `--post` la reemplazan con nombres separados por comas; los
+// que no aparecen quedan desactivados. La resolución es obligatoria y separa
+// lo que se dibuja en la escena (antes) del HUD, que va a la ventana (después).
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pass {
+    // Estrellas del fondo y órbitas
+    Background,
+    // Cuerpos, estaciones, naves abandonadas y la nave
+    Opaque,
+    // Anillos planetarios y del circuito, agujeros de gusano, la nave
+    // fantasma y el pulso del escáner
+    Transparent,
+    // Colas de los cometas, estela del motor y disparos
+    Particles,
+    // La cadena de efectos de `PostEffect`, en su orden
+    Post,
+    // Antialiasing y copia a la resolución de la ventana
+    Resolve,
+    Hud,
+}
+
+impl Pass {
+    pub const ALL: [Pass; 7] =
+        [Pass::Background, Pass::Opaque, Pass::Transparent, Pass::Particles, Pass::Post, Pass::Resolve, Pass::Hud];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Pass::Background => "background",
+            Pass::Opaque => "opaque",
+            Pass::Transparent => "transparent",
+            Pass::Particles => "particles",
+            Pass::Post => "post",
+            Pass::Resolve => "resolve",
+            Pass::Hud => "hud",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PostEffect {
+    // Las vistas de depuración (G) reemplazan la imagen
+    DebugView,
+    HeatDistortion,
+    // Exposición del recorrido cinemático
+    Exposure,
+    Bloom,
+    // Destello de lente del sol
+    LensFlare,
+    WarpStreaks,
+    WormholeSwirl,
+}
+
+impl PostEffect {
+    pub const ALL: [PostEffect; 7] = [
+        PostEffect::DebugView,
+        PostEffect::HeatDistortion,
+        PostEffect::Exposure,
+        PostEffect::Bloom,
+        PostEffect::LensFlare,
+        PostEffect::WarpStreaks,
+        PostEffect::WormholeSwirl,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            PostEffect::DebugView => "debug",
+            PostEffect::HeatDistortion => "heat",
+            PostEffect::Exposure => "exposure",
+            PostEffect::Bloom => "bloom",
+            PostEffect::LensFlare => "flare",
+            PostEffect::WarpStreaks => "warp",
+            PostEffect::WormholeSwirl => "swirl",
+        }
+    }
+}
+
+pub struct RenderPipeline {
+    passes: Vec<(Pass, bool)>,
+    post_chain: Vec<(PostEffect, bool)>,
+}
+
+impl Default for RenderPipeline {
+    fn default() -> Self {
+        RenderPipeline {
+            passes: Pass::ALL.iter().map(|&pass| (pass, true)).collect(),
+            post_chain: PostEffect::ALL.iter().map(|&effect| (effect, true)).collect(),
+        }
+    }
+}
+
+// Nombres separados por comas, en el orden dado; rechaza los desconocidos y
+// los repetidos
+fn parse_list<T: Copy + PartialEq>(value: &str, all: &[T], name: fn(T) -> &'static str) -> Result<Vec<T>, String> {
+    let mut list = Vec::new();
+    for item in value.split(',').map(str::trim).filter(|item| !item.is_empty()) {
+        let Some(&stage) = all.iter().find(|&&stage| name(stage) == item) else {
+            let known: Vec<_> = all.iter().map(|&stage| name(stage)).collect();
+            return Err(format!("'{}' desconocido (se espera {})", item, known.join(", ")));
+        };
+        if list.contains(&stage) {
+            return Err(format!("'{}' repetido", item));
+        }
+        list.push(stage);
+    }
+    Ok(list)
+}
+
+// Los elegidos primero y activos, en su orden; el resto detrás, apagados
+fn reorder<T: Copy + PartialEq>(chosen: &[T], all: &[T]) -> Vec<(T, bool)> {
+    let rest = all.iter().filter(|stage| !chosen.contains(stage));
+    chosen.iter().map(|&stage| (stage, true)).chain(rest.map(|&stage| (stage, false))).collect()
+}
+
+impl RenderPipeline {
+    // --passes: los pases a ejecutar, en orden
+    pub fn set_passes(&mut self, value: &str) -> Result<(), String> {
+        let passes = parse_list(value, &Pass::ALL, Pass::name)?;
+        let Some(resolve) = passes.iter().position(|&pass| pass == Pass::Resolve) else {
+            return Err("falta 'resolve'".to_string());
+        };
+        // El HUD se dibuja sobre la imagen de la ventana y el resto sobre la escena
+        if let Some(pass) = passes[..resolve].iter().find(|&&pass| pass == Pass::Hud) {
+            return Err(format!("'{}' tiene que ir después de 'resolve'", pass.name()));
+        }
+        if let Some(pass) = passes[resolve + 1..].iter().find(|&&pass| pass != Pass::Hud) {
+            return Err(format!("'{}' tiene que ir antes de 'resolve'", pass.name()));
+        }
+        self.passes = reorder(&passes, &Pass::ALL);
+        Ok(())
+    }
+
+    // --post: los efectos de la cadena de posproceso, en orden
+    pub fn set_post_chain(&mut self, value: &str) -> Result<(), String> {
+        let effects = parse_list(value, &PostEffect::ALL, PostEffect::name)?;
+        self.post_chain = reorder(&effects, &PostEffect::ALL);
+        Ok(())
+    }
+
+    // Enciende o apaga un efecto sin moverlo de su lugar en la cadena
+    pub fn toggle_post(&mut self, effect: PostEffect) {
+        for (stage, enabled) in &mut self.post_chain {
+            if *stage == effect {
+                *enabled = !*enabled;
+            }
+        }
+    }
+
+    pub fn passes(&self) -> Vec<Pass> {
+        self.passes.iter().filter(|(_, enabled)| *enabled).map(|(pass, _)| *pass).collect()
+    }
+
+    pub fn post_chain(&self) -> Vec<PostEffect> {
+        self.post_chain.iter().filter(|(_, enabled)| *enabled).map(|(effect, _)| *effect).collect()
+    }
+}
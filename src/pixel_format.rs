@@ -0,0 +1,58 @@
+// Formatos de píxel empaquetado. Todo el dibujo trabaja en uno solo,
+// `Xrgb8888` (el que recibe minifb: 0x00RRGGBB en un u32), con
+// `pack_color`/`unpack_color`; la conversión al formato que espera cada
+// salida se hace una vez, al final, con `encode`: RGBA8 para las miniaturas
+// PNG y las imágenes del diagnóstico.
+
+use nalgebra_glm::Vec3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelFormat {
+    // 0x00RRGGBB: el formato de trabajo del framebuffer
+    Xrgb8888,
+    // Bytes R, G, B, A (alfa opaco)
+    Rgba8,
+}
+
+fn to_byte(channel: f32) -> u8 {
+    (channel * 255.0).clamp(0.0, 255.0) as u8
+}
+
+impl PixelFormat {
+    // Color 0..1 (se recorta) a un píxel en este formato, con los bytes en
+    // el orden en que se escriben (little-endian)
+    pub fn pack(self, color: Vec3) -> u32 {
+        let (r, g, b) = (to_byte(color.x), to_byte(color.y), to_byte(color.z));
+        match self {
+            PixelFormat::Xrgb8888 => ((r as u32) << 16) | ((g as u32) << 8) | b as u32,
+            PixelFormat::Rgba8 => u32::from_le_bytes([r, g, b, 0xFF]),
+        }
+    }
+
+    pub fn unpack(self, pixel: u32) -> Vec3 {
+        let (r, g, b) = match self {
+            PixelFormat::Xrgb8888 => ((pixel >> 16) & 0xFF, (pixel >> 8) & 0xFF, pixel & 0xFF),
+            PixelFormat::Rgba8 => {
+                let [r, g, b, _] = pixel.to_le_bytes();
+                (r as u32, g as u32, b as u32)
+            }
+        };
+        Vec3::new(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0)
+    }
+
+    // Imagen en el formato de trabajo a los bytes de este formato
+    pub fn encode(self, pixels: &[u32]) -> Vec<u8> {
+        pixels
+            .iter()
+            .flat_map(|&pixel| self.pack(PixelFormat::Xrgb8888.unpack(pixel)).to_le_bytes())
+            .collect()
+    }
+
+    // Bytes en este formato a una imagen en el formato de trabajo
+    pub fn decode(self, bytes: &[u8]) -> Vec<u32> {
+        bytes
+            .chunks_exact(4)
+            .map(|chunk| PixelFormat::Xrgb8888.pack(self.unpack(u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))))
+            .collect()
+    }
+}
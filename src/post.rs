@@ -0,0 +1,233 @@
+// Post-procesado sobre el framebuffer terminado. Bloom: se extraen las
+// zonas que superan el rango visible (bright-pass), se desenfocan con un
+// filtro gaussiano separable a media resolución y se suman a la imagen.
+// La distorsión por calor desplaza los píxeles marcados en una máscara.
+// La exposición escala el brillo de toda la imagen antes del bloom.
+
+use nalgebra_glm::Vec3;
+
+use crate::framebuffer::{pack_color, unpack_color, Framebuffer};
+
+pub struct Bloom {
+    // Luminancia a partir de la cual un píxel aporta brillo
+    pub threshold: f32,
+    pub intensity: f32,
+    weights: Vec<f32>,
+    bright: Vec<Vec3>,
+    scratch: Vec<Vec3>,
+}
+
+fn luminance(color: &Vec3) -> f32 {
+    color.x * 0.2126 + color.y * 0.7152 + color.z * 0.0722
+}
+
+impl Bloom {
+    pub fn new(threshold: f32, intensity: f32, radius: usize) -> Self {
+        // Núcleo gaussiano normalizado con sigma = radio / 2
+        let sigma = (radius as f32 * 0.5).max(0.5);
+        let mut weights: Vec<f32> = (0..=radius)
+            .map(|i| (-((i * i) as f32) / (2.0 * sigma * sigma)).exp())
+            .collect();
+        let total = weights[0] + 2.0 * weights[1..].iter().sum::<f32>();
+        weights.iter_mut().for_each(|w| *w /= total);
+
+        Bloom {
+            threshold,
+            intensity,
+            weights,
+            bright: Vec::new(),
+            scratch: Vec::new(),
+        }
+    }
+
+    pub fn apply(&mut self, framebuffer: &mut Framebuffer) {
+        let width = framebuffer.width / 2;
+        let height = framebuffer.height / 2;
+        if width == 0 || height == 0 {
+            return;
+        }
+        self.bright.resize(width * height, Vec3::zeros());
+        self.scratch.resize(width * height, Vec3::zeros());
+
+        // Bright-pass a media resolución (promedio de 2x2 píxeles)
+        let mut any_bright = false;
+        for y in 0..height {
+            for x in 0..width {
+                let mut sum = Vec3::zeros();
+                for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                    let color = framebuffer.hdr[framebuffer.index(x * 2 + dx, y * 2 + dy)];
+                    let excess = luminance(&color) - self.threshold;
+                    if excess > 0.0 {
+                        sum += color * (excess / luminance(&color));
+                    }
+                }
+                any_bright |= sum != Vec3::zeros();
+                self.bright[y * width + x] = sum * 0.25;
+            }
+        }
+        if !any_bright {
+            return;
+        }
+
+        // Desenfoque separable: horizontal a `scratch`, vertical de vuelta a `bright`
+        let radius = self.weights.len() as isize - 1;
+        for y in 0..height {
+            for x in 0..width {
+                let mut sum = self.bright[y * width + x] * self.weights[0];
+                for offset in 1..=radius {
+                    let weight = self.weights[offset as usize];
+                    let left = (x as isize - offset).max(0) as usize;
+                    let right = (x as isize + offset).min(width as isize - 1) as usize;
+                    sum += (self.bright[y * width + left] + self.bright[y * width + right]) * weight;
+                }
+                self.scratch[y * width + x] = sum;
+            }
+        }
+        for y in 0..height {
+            for x in 0..width {
+                let mut sum = self.scratch[y * width + x] * self.weights[0];
+                for offset in 1..=radius {
+                    let weight = self.weights[offset as usize];
+                    let up = (y as isize - offset).max(0) as usize;
+                    let down = (y as isize + offset).min(height as isize - 1) as usize;
+                    sum += (self.scratch[up * width + x] + self.scratch[down * width + x]) * weight;
+                }
+                self.bright[y * width + x] = sum;
+            }
+        }
+
+        // Composición aditiva sobre la imagen final
+        for y in 0..framebuffer.height {
+            let by = (y / 2).min(height - 1);
+            for x in 0..framebuffer.width {
+                let glow = self.bright[by * width + (x / 2).min(width - 1)];
+                if glow == Vec3::zeros() {
+                    continue;
+                }
+                let index = framebuffer.index(x, y);
+                let color = unpack_color(framebuffer.buffer[index]) + glow * self.intensity;
+                framebuffer.buffer[index] = pack_color(color);
+            }
+        }
+    }
+}
+
+// Exposición en pasos (+1 duplica el brillo). Los píxeles que conservan su
+// color HDR se escalan desde ese valor, así al bajar la exposición reaparece
+// el detalle de lo que estaba recortado, como el disco del sol.
+pub fn apply_exposure(framebuffer: &mut Framebuffer, stops: f32) {
+    if stops == 0.0 {
+        return;
+    }
+    let factor = 2f32.powf(stops);
+    for (pixel, hdr) in framebuffer.buffer.iter_mut().zip(framebuffer.hdr.iter_mut()) {
+        // Lo que se dibujó encima sin HDR ya no coincide con el valor guardado
+        if *hdr != Vec3::zeros() && pack_color(*hdr) == *pixel {
+            *hdr *= factor;
+            *pixel = pack_color(*hdr);
+        } else {
+            *pixel = pack_color(unpack_color(*pixel) * factor);
+        }
+    }
+}
+
+// Distorsión por calor: una máscara en pantalla indica cuánto se desplaza
+// cada píxel, y los píxeles marcados se leen con un desplazamiento ondulante
+// que simula la refracción del aire caliente.
+pub struct HeatDistortion {
+    // Desplazamiento máximo en píxeles
+    pub strength: f32,
+    mask: Vec<f32>,
+    source: Vec<u32>,
+    active: bool,
+}
+
+impl HeatDistortion {
+    pub fn new(strength: f32) -> Self {
+        HeatDistortion { strength, mask: Vec::new(), source: Vec::new(), active: false }
+    }
+
+    // Limpia la máscara al inicio del cuadro
+    pub fn clear(&mut self, width: usize, height: usize) {
+        if self.active || self.mask.len() != width * height {
+            self.mask.clear();
+            self.mask.resize(width * height, 0.0);
+        }
+        self.active = false;
+    }
+
+    // Marca una columna que va de `start` a `end`, cada uno (x, y, radio) en
+    // pantalla: el radio cambia a lo largo de la columna y el calor se
+    // atenúa hacia el borde y hacia `end`. `amount` es 1 a pleno empuje.
+    pub fn add_plume(&mut self, framebuffer: &Framebuffer, start: (f32, f32, f32), end: (f32, f32, f32), amount: f32) {
+        let ((x0, y0, r0), (x1, y1, r1)) = (start, end);
+        if r0.max(r1) < 1.0 || amount <= 0.0 || self.mask.len() != framebuffer.width * framebuffer.height {
+            return;
+        }
+        let reach = r0.max(r1);
+        let min_x = (x0.min(x1) - reach).max(0.0) as usize;
+        let max_x = ((x0.max(x1) + reach).max(0.0) as usize).min(framebuffer.width.saturating_sub(1));
+        let min_y = (y0.min(y1) - reach).max(0.0) as usize;
+        let max_y = ((y0.max(y1) + reach).max(0.0) as usize).min(framebuffer.height.saturating_sub(1));
+        let (dx, dy) = (x1 - x0, y1 - y0);
+        let length_squared = dx * dx + dy * dy;
+        for py in min_y..=max_y {
+            for px in min_x..=max_x {
+                // Punto más cercano del eje de la columna
+                let along = if length_squared > 0.0 {
+                    (((px as f32 - x0) * dx + (py as f32 - y0) * dy) / length_squared).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                let radius = r0 + (r1 - r0) * along;
+                let distance = (px as f32 - (x0 + dx * along)).hypot(py as f32 - (y0 + dy * along)) / radius.max(1.0);
+                if distance >= 1.0 {
+                    continue;
+                }
+                let falloff = (1.0 - distance) * (1.0 - distance) * (1.0 - along * 0.7) * amount;
+                let value = &mut self.mask[py * framebuffer.width + px];
+                *value = value.max(falloff);
+                self.active = true;
+            }
+        }
+    }
+
+    pub fn apply(&mut self, framebuffer: &mut Framebuffer, time: f32) {
+        if !self.active {
+            return;
+        }
+        self.source.clear();
+        self.source.extend_from_slice(&framebuffer.buffer);
+
+        for y in 0..framebuffer.height {
+            for x in 0..framebuffer.width {
+                let amount = self.mask[y * framebuffer.width + x];
+                if amount <= 0.0 {
+                    continue;
+                }
+                // Ondas que suben con el tiempo, como el aire sobre el asfalto
+                // caliente; el desplazamiento no es entero, así que se lee
+                // entre los cuatro píxeles vecinos para que ondule sin saltos
+                let offset_x = (y as f32 * 0.35 + time * 60.0).sin() * amount * self.strength;
+                let offset_y = (x as f32 * 0.27 - time * 45.0).cos() * amount * self.strength * 0.5;
+                let color = self.sample(framebuffer, x as f32 + offset_x, y as f32 + offset_y);
+                let index = framebuffer.index(x, y);
+                framebuffer.buffer[index] = pack_color(color);
+            }
+        }
+    }
+
+    // Color de la copia del cuadro en (x, y), interpolado entre los cuatro
+    // píxeles vecinos y limitado a la pantalla
+    fn sample(&self, framebuffer: &Framebuffer, x: f32, y: f32) -> Vec3 {
+        let x = x.clamp(0.0, (framebuffer.width - 1) as f32);
+        let y = y.clamp(0.0, (framebuffer.height - 1) as f32);
+        let (x0, y0) = (x.floor() as usize, y.floor() as usize);
+        let (x1, y1) = ((x0 + 1).min(framebuffer.width - 1), (y0 + 1).min(framebuffer.height - 1));
+        let (fx, fy) = (x - x0 as f32, y - y0 as f32);
+        let pixel = |px, py| unpack_color(self.source[framebuffer.index(px, py)]);
+        let top = pixel(x0, y0).lerp(&pixel(x1, y0), fx);
+        let bottom = pixel(x0, y1).lerp(&pixel(x1, y1), fx);
+        top.lerp(&bottom, fy)
+    }
+}
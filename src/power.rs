@@ -0,0 +1,172 @@
+// Energía de la nave: una sola reserva que se recarga sola y que comparten
+// los motores (impulso, Ctrl), los escudos y el láser. Con las flechas se
+// reparte la potencia entre los tres (arriba: motores, izquierda: escudos,
+// derecha: armas, abajo: reparto parejo); el reparto cambia cuánto empuja el
+// impulso, qué tan rápido se recargan los escudos y la cadencia del láser.
+// Los escudos absorben los choques contra los cuerpos; sin escudos la nave
+// queda detenida contra la superficie en lugar de deslizarse.
+
+use nalgebra_glm::Vec3;
+
+use crate::framebuffer::{pack_color, Framebuffer};
+use crate::text;
+
+// Recarga de la reserva por unidad de tiempo de simulación (la reserva va de 0 a 1)
+const ENERGY_REGEN: f32 = 0.25;
+// Gasto del impulso por unidad de tiempo y de cada disparo
+const BOOST_COST: f32 = 0.5;
+const SHOT_COST: f32 = 0.03;
+// Velocidad máxima y empuje extra del impulso con el reparto parejo
+const BOOST_STRENGTH: f32 = 0.6;
+// Recarga de los escudos por unidad de tiempo con el reparto parejo; cada
+// punto de escudo cuesta lo mismo de la reserva
+const SHIELD_RECHARGE: f32 = 0.3;
+// Velocidad de choque que se lleva el escudo completo
+const SHIELD_IMPACT_SPEED: f32 = 30.0;
+// Fracción del total que se mueve con cada pulsación de una flecha
+const BIAS_STEP: f32 = 1.0 / 6.0;
+
+// Barras del HUD: ancho, alto y separación en píxeles
+const BAR_WIDTH: usize = 10;
+const BAR_HEIGHT: usize = 60;
+const BAR_GAP: usize = 8;
+const MARGIN: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Subsystem {
+    Engines,
+    Shields,
+    Weapons,
+}
+
+impl Subsystem {
+    const ALL: [Subsystem; 3] = [Subsystem::Engines, Subsystem::Shields, Subsystem::Weapons];
+
+    fn label(self) -> &'static str {
+        match self {
+            Subsystem::Engines => "MOT",
+            Subsystem::Shields => "DEF",
+            Subsystem::Weapons => "ARM",
+        }
+    }
+
+    fn color(self) -> Vec3 {
+        match self {
+            Subsystem::Engines => Vec3::new(1.0, 0.6, 0.2),
+            Subsystem::Shields => Vec3::new(0.3, 0.7, 1.0),
+            Subsystem::Weapons => Vec3::new(1.0, 0.3, 0.3),
+        }
+    }
+}
+
+pub struct PowerSystem {
+    energy: f32,
+    // Fracción de la potencia de cada subsistema, en el orden de
+    // `Subsystem::ALL`; suman 1
+    distribution: [f32; 3],
+    shield: f32,
+}
+
+impl Default for PowerSystem {
+    fn default() -> Self {
+        PowerSystem { energy: 1.0, distribution: [1.0 / 3.0; 3], shield: 1.0 }
+    }
+}
+
+impl PowerSystem {
+    // Potencia relativa del subsistema: 1 con el reparto parejo, hasta 3
+    // con toda la potencia y 0 sin ninguna
+    fn factor(&self, subsystem: Subsystem) -> f32 {
+        self.distribution[subsystem as usize] * 3.0
+    }
+
+    // Pasa potencia de los otros dos al subsistema, a partes iguales
+    // mientras tengan
+    pub fn bias(&mut self, subsystem: Subsystem) {
+        let target = subsystem as usize;
+        let mut moved = 0.0;
+        for (index, share) in self.distribution.iter_mut().enumerate() {
+            if index != target {
+                let taken = share.min(BIAS_STEP * 0.5);
+                *share -= taken;
+                moved += taken;
+            }
+        }
+        self.distribution[target] += moved;
+    }
+
+    pub fn balance(&mut self) {
+        self.distribution = [1.0 / 3.0; 3];
+    }
+
+    // Recarga la reserva y, con lo que hay en ella, los escudos
+    pub fn update(&mut self, dt: f32) {
+        self.energy = (self.energy + ENERGY_REGEN * dt).min(1.0);
+        let recharge = (SHIELD_RECHARGE * self.factor(Subsystem::Shields) * dt).min(1.0 - self.shield).min(self.energy);
+        self.shield += recharge;
+        self.energy -= recharge;
+    }
+
+    // Impulso durante `dt`: gasta energía y devuelve el factor sobre la
+    // velocidad máxima y el empuje (1 sin energía)
+    pub fn boost(&mut self, dt: f32) -> f32 {
+        if self.energy <= 0.0 {
+            return 1.0;
+        }
+        self.energy = (self.energy - BOOST_COST * dt).max(0.0);
+        1.0 + BOOST_STRENGTH * self.factor(Subsystem::Engines)
+    }
+
+    // Cadencia relativa del láser: el doble con toda la potencia, la mitad sin ninguna
+    pub fn fire_rate(&self) -> f32 {
+        0.5 + self.factor(Subsystem::Weapons) * 0.5
+    }
+
+    pub fn can_fire(&self) -> bool {
+        self.energy >= SHOT_COST
+    }
+
+    pub fn spend_shot(&mut self) {
+        self.energy = (self.energy - SHOT_COST).max(0.0);
+    }
+
+    // Choque a `impact_speed`: el escudo se lleva el golpe si le queda algo.
+    // Devuelve si lo aguantó.
+    pub fn absorb(&mut self, impact_speed: f32) -> bool {
+        if self.shield <= 0.0 {
+            return false;
+        }
+        self.shield = (self.shield - impact_speed / SHIELD_IMPACT_SPEED).max(0.0);
+        true
+    }
+
+    // Barras en la esquina inferior izquierda: el reparto de los tres
+    // subsistemas y, a su derecha, la reserva y el escudo
+    pub fn render(&self, framebuffer: &mut Framebuffer, text_color: u32) {
+        if framebuffer.height < BAR_HEIGHT + text::LINE_HEIGHT + MARGIN * 2 {
+            return;
+        }
+        let bottom = framebuffer.height - MARGIN - text::LINE_HEIGHT;
+        let bars = Subsystem::ALL
+            .iter()
+            .map(|&subsystem| (subsystem.label(), self.distribution[subsystem as usize], subsystem.color()))
+            .chain([("RES", self.energy, Vec3::new(0.9, 0.9, 0.4)), ("ESC", self.shield, Vec3::new(0.5, 0.9, 1.0))]);
+        for (index, (label, level, color)) in bars.enumerate() {
+            // Un hueco más entre el reparto y las reservas
+            let left = MARGIN + index * (text::CHAR_ADVANCE * 3 + BAR_GAP) + if index >= 3 { BAR_GAP * 2 } else { 0 };
+            let filled = (level.clamp(0.0, 1.0) * BAR_HEIGHT as f32).round() as usize;
+            for row in 0..BAR_HEIGHT {
+                let lit = row < filled;
+                for column in 0..BAR_WIDTH {
+                    let (x, y) = (left + text::CHAR_ADVANCE + column, bottom - 1 - row);
+                    if lit {
+                        framebuffer.overlay_pixel(x, y, pack_color(color));
+                    } else {
+                        framebuffer.overlay_blend(x, y, pack_color(color * 0.25), 0.6);
+                    }
+                }
+            }
+            framebuffer.draw_text(left, bottom + 2, label, text_color);
+        }
+    }
+}
@@ -0,0 +1,323 @@
+// Generador procedural de sistemas solares: a partir de una semilla de 64
+// bits arma una escena completa (estrella, planetas con sus lunas, órbitas
+// espaciadas, tamaños, inclinaciones y parámetros de los shaders). La misma
+// semilla produce siempre el mismo sistema. De vez en cuando aparecen
+// anomalías: naves abandonadas en los huecos entre zonas planetarias y un
+// agujero de gusano más allá del último planeta, unido a otro en uno de los
+// huecos interiores.
+//
+// La escena generada pasa por el mismo camino que un archivo (`Scene::parse`),
+// así se valida y se prepara igual que las escenas escritas a mano.
+
+use crate::anomaly::{Anomaly, AnomalyKind};
+use crate::lighting::Atmosphere;
+use crate::rings::RingSystem;
+use crate::scene::{Body, Scene, SceneError};
+use crate::shaders::Climate;
+use crate::skybox::StarfieldConfig;
+
+const MIN_PLANETS: usize = 3;
+const MAX_PLANETS: usize = 6;
+const MAX_MOONS: usize = 2;
+// Margen de colisión de las lunas, más chico que el global para poder pasar entre ellas
+const MOON_COLLISION_MARGIN: f32 = 0.5;
+// Espacio libre alrededor de cada planeta y sus lunas, para poder volar entre órbitas
+const ZONE_MARGIN: f32 = 3.0;
+// Período orbital = KEPLER_FACTOR · radio^1.5 (tercera ley de Kepler)
+const KEPLER_FACTOR: f32 = 0.07;
+// Probabilidad de un agujero de gusano por sistema y de una nave abandonada
+// por cada hueco entre zonas
+const WORMHOLE_CHANCE: f32 = 0.35;
+const DERELICT_CHANCE: f32 = 0.3;
+
+// Shaders de los planetas interiores (cálidos, rocosos) y exteriores
+const INNER_SHADERS: [&str; 4] = ["rocky", "desert", "volcanic", "ocean"];
+const OUTER_SHADERS: [&str; 4] = ["gas_giant", "ice", "ringed", "purple"];
+
+const SYLLABLES: [&str; 16] = [
+    "ka", "ve", "lor", "mi", "tan", "sa", "rul", "xe", "no", "dra", "qui", "bel", "zor", "an", "te", "yun",
+];
+const ROMAN: [&str; MAX_MOONS] = ["I", "II"];
+
+// SplitMix64: rápido, sin dependencias y con buena dispersión de semillas
+// cercanas. El mapa galáctico lo usa también para repartir los sistemas.
+pub struct Rng(pub u64);
+
+impl Rng {
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Uniforme en [0, 1)
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    pub fn range(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.next_f32()
+    }
+
+    fn index(&mut self, len: usize) -> usize {
+        (self.next_u64() % len as u64) as usize
+    }
+
+    fn chance(&mut self, probability: f32) -> bool {
+        self.next_f32() < probability
+    }
+}
+
+fn star_name(rng: &mut Rng) -> String {
+    let count = 2 + rng.index(2);
+    let name: String = (0..count).map(|_| SYLLABLES[rng.index(SYLLABLES.len())]).collect();
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => name,
+    }
+}
+
+// Período con algo de variación; de vez en cuando, retrógrado
+fn orbit_period(rng: &mut Rng, radius: f32, factor: f32) -> f32 {
+    let period = factor * radius.powf(1.5) * rng.range(0.85, 1.15);
+    if rng.chance(0.1) { -period } else { period }
+}
+
+fn atmosphere(rng: &mut Rng, shader: &str) -> Option<Atmosphere> {
+    let color = match shader {
+        "rocky" | "ocean" => [0.35, 0.6, 1.0],
+        "ice" => [0.7, 0.9, 1.0],
+        "purple" => [0.8, 0.5, 1.0],
+        "ringed" => [1.0, 0.85, 0.6],
+        _ => return None,
+    };
+    rng.chance(0.7).then(|| Atmosphere {
+        color,
+        intensity: rng.range(0.5, 1.0),
+        falloff: rng.range(2.5, 4.0),
+        density: rng.range(0.3, 1.5),
+    })
+}
+
+fn rings(rng: &mut Rng, shader: &str) -> Option<RingSystem> {
+    let probability = match shader {
+        "ringed" => 1.0,
+        "gas_giant" => 0.4,
+        _ => 0.0,
+    };
+    if !rng.chance(probability) {
+        return None;
+    }
+    let inner_radius = rng.range(1.3, 1.6);
+    let base = [rng.range(0.5, 0.9), rng.range(0.45, 0.85), rng.range(0.4, 0.8)];
+    Some(RingSystem {
+        inner_radius,
+        outer_radius: inner_radius + rng.range(0.3, 0.9),
+        tilt: rng.range(-20.0, 20.0),
+        palette: vec![base, base.map(|c| c * 0.7), base.map(|c| (c * 1.15).min(1.0))],
+        opacity: rng.range(0.4, 0.9),
+        solid: false,
+    })
+}
+
+fn planet(rng: &mut Rng, name: String, shader: &str) -> Body {
+    let scale = match shader {
+        "gas_giant" | "ringed" => rng.range(1.5, 3.5),
+        "purple" | "ice" => rng.range(0.8, 2.0),
+        _ => rng.range(0.6, 1.4),
+    };
+    let craters = match shader {
+        "rocky" => rng.index(20) as u32,
+        "desert" => 10 + rng.index(50) as u32,
+        _ => 0,
+    };
+    let climate = (shader == "rocky").then(|| Climate {
+        equator_temperature: rng.range(15.0, 40.0),
+        pole_temperature: rng.range(-50.0, -10.0),
+        lapse_rate: rng.range(20.0, 45.0),
+        axial_tilt: rng.range(0.0, 45.0),
+    });
+    Body {
+        name,
+        shader: shader.to_string(),
+        seed: Some(rng.next_u64() as u32),
+        scale,
+        collision_radius: scale,
+        oblateness: if shader == "gas_giant" { rng.range(0.04, 0.15) } else { 0.0 },
+        mass: scale.powi(3),
+        day_length: rng.range(8.0, 25.0),
+        retrograde: rng.chance(0.15),
+        // Los gigantes suelen tener el eje más inclinado
+        axial_tilt: match shader {
+            "gas_giant" | "ringed" => rng.range(0.0, 35.0),
+            _ => rng.range(0.0, 25.0),
+        },
+        specular: if shader == "ocean" { 0.5 } else { rng.range(0.0, 0.2) },
+        shininess: 32.0,
+        atmosphere: atmosphere(rng, shader),
+        rings: rings(rng, shader),
+        craters,
+        climate,
+        ..Default::default()
+    }
+}
+
+// Punto a `distance` de la estrella en una dirección al azar, cerca del plano
+fn scatter(rng: &mut Rng, distance: f32) -> [f32; 3] {
+    let angle = rng.range(0.0, std::f32::consts::TAU);
+    [angle.cos() * distance, rng.range(-2.0, 2.0), angle.sin() * distance]
+}
+
+// Anomalías en los huecos libres `gaps` (distancias a la estrella) y más allá
+// de `occupied`. Se sortean después de los planetas para que una semilla siga
+// dando los mismos planetas que antes.
+fn anomalies(rng: &mut Rng, star: &str, gaps: &[(f32, f32)], occupied: f32) -> Vec<Anomaly> {
+    let mut anomalies = Vec::new();
+    for &(inner, outer) in gaps {
+        if !rng.chance(DERELICT_CHANCE) {
+            continue;
+        }
+        let width = outer - inner;
+        let position = scatter(rng, inner + width * 0.5);
+        anomalies.push(Anomaly {
+            name: format!("Pecio {}-{}", star, anomalies.len() + 1),
+            kind: AnomalyKind::Derelict,
+            position,
+            size: rng.range(0.2, 0.35),
+            drift: rng.range(0.5, 2.0).min(width * 0.25),
+            tumble: rng.range(0.05, 0.2),
+            exit: None,
+        });
+    }
+    if rng.chance(WORMHOLE_CHANCE) {
+        let size = rng.range(1.5, 3.0);
+        let distance = occupied + size + rng.range(5.0, 15.0);
+        let position = scatter(rng, distance);
+        let name = format!("Anomalía {}", star);
+        // La otra boca, en el medio de un hueco entre zonas
+        let (inner, outer) = gaps[rng.index(gaps.len())];
+        let exit_position = scatter(rng, (inner + outer) * 0.5);
+        let exit_name = format!("{} (salida)", name);
+        anomalies.push(Anomaly {
+            name,
+            kind: AnomalyKind::Wormhole,
+            position,
+            size,
+            drift: 0.0,
+            tumble: 0.0,
+            exit: Some(exit_name.clone()),
+        });
+        anomalies.push(Anomaly {
+            name: exit_name,
+            kind: AnomalyKind::Wormhole,
+            position: exit_position,
+            size,
+            drift: 0.0,
+            tumble: 0.0,
+            exit: None,
+        });
+    }
+    anomalies
+}
+
+pub fn generate(seed: u64) -> Result<Scene, SceneError> {
+    let mut rng = Rng(seed);
+    let star = star_name(&mut rng);
+    let star_scale = rng.range(5.0, 9.0);
+    let mut bodies = vec![Body {
+        name: star.clone(),
+        shader: "star".to_string(),
+        seed: Some(rng.next_u64() as u32),
+        scale: star_scale,
+        collision_radius: star_scale,
+        mass: 1000.0,
+        emissive: true,
+        shininess: 32.0,
+        ..Default::default()
+    }];
+
+    let planet_count = MIN_PLANETS + rng.index(MAX_PLANETS - MIN_PLANETS + 1);
+    // Borde exterior ocupado hasta ahora: la estrella y luego cada zona planetaria
+    let mut occupied = star_scale + ZONE_MARGIN * 2.0;
+    // Huecos entre una zona y la siguiente, donde no pasa ninguna órbita
+    let mut gaps = Vec::with_capacity(planet_count);
+    for index in 0..planet_count {
+        // Los planetas se nombran como los exoplanetas: estrella + b, c, d...
+        let name = format!("{} {}", star, (b'b' + index as u8) as char);
+        let outer = index * 2 >= planet_count;
+        let shaders = if outer { &OUTER_SHADERS } else { &INNER_SHADERS };
+        let shader = shaders[rng.index(shaders.len())];
+        let mut body = planet(&mut rng, name, shader);
+
+        // Lunas a pocos radios del planeta, separadas entre sí
+        let moon_count = if body.scale > 1.0 { rng.index(MAX_MOONS + 1) } else { rng.index(2) };
+        let mut moons = Vec::with_capacity(moon_count);
+        let ring_edge = body.rings.as_ref().map_or(0.0, |rings| rings.outer_radius + 0.3);
+        let mut moon_orbit = body.scale * ring_edge.max(2.0);
+        for moon_name in ROMAN.iter().take(moon_count) {
+            let moon_scale = rng.range(0.15, 0.35);
+            moon_orbit += rng.range(0.6, 1.4) + moon_scale;
+            moons.push(Body {
+                name: format!("{} {}", body.name, moon_name),
+                parent: Some(body.name.clone()),
+                shader: "moon".to_string(),
+                seed: Some(rng.next_u64() as u32),
+                scale: moon_scale,
+                collision_radius: moon_scale,
+                collision_margin: Some(MOON_COLLISION_MARGIN),
+                orbit_radius: moon_orbit,
+                orbit_period: orbit_period(&mut rng, moon_orbit, 0.6),
+                inclination: rng.range(-15.0, 15.0),
+                phase: rng.range(0.0, 360.0),
+                tidally_locked: true,
+                shininess: 32.0,
+                ..Default::default()
+            });
+            moon_orbit += moon_scale;
+        }
+
+        // Zona del planeta: su radio más las lunas, con margen para pasar.
+        // La órbita se aleja lo suficiente de la zona anterior incluso en el
+        // periapsis, y la excentricidad se acota para respetarlo.
+        let zone = moon_orbit.max(body.scale) + ZONE_MARGIN;
+        let gap = rng.range(0.3, 0.8) * zone;
+        body.eccentricity = rng.range(0.0, 0.08);
+        body.orbit_radius = (occupied + zone + gap) / (1.0 - body.eccentricity);
+        gaps.push((occupied, occupied + gap));
+        body.argument_of_periapsis = rng.range(0.0, 360.0);
+        body.orbit_period = orbit_period(&mut rng, body.orbit_radius, KEPLER_FACTOR);
+        body.inclination = rng.range(-15.0, 15.0);
+        body.phase = rng.range(0.0, 360.0);
+        occupied = body.orbit_radius * (1.0 + body.eccentricity) + zone;
+
+        bodies.push(body);
+        bodies.extend(moons);
+    }
+    let anomalies = anomalies(&mut rng, &star, &gaps, occupied);
+    // Cada sistema ve su propio cielo
+    let starfield = StarfieldConfig { seed: rng.next_u64() as u32, ..Default::default() };
+
+    let scene = Scene {
+        barycenter_wobble: false,
+        seed: None,
+        engine_trail: Default::default(),
+        flight: Default::default(),
+        lighting: Default::default(),
+        starfield,
+        ambient: Default::default(),
+        race: None,
+        course: None,
+        bodies,
+        props: Vec::new(),
+        spawns: Vec::new(),
+        anomalies,
+        states: Vec::new(),
+        anomaly_states: Vec::new(),
+        prop_states: Vec::new(),
+    };
+    let source = toml::to_string(&scene).expect("una escena generada siempre se puede serializar");
+    Scene::parse(&source, false)
+}
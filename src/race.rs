@@ -0,0 +1,111 @@
+// Modo carrera: un circuito de puntos de control definido en la escena
+// (`[race]`) que el jugador recorre en orden contra el reloj, opcionalmente
+// contra el fantasma de un vuelo grabado.
+
+use nalgebra_glm::{Mat4, Vec3};
+use serde::{Deserialize, Serialize};
+
+use crate::flight_path::{draw_marker, FlightPath};
+use crate::framebuffer::Framebuffer;
+
+const NEXT_WAYPOINT_COLOR: u32 = 0x40FF80;
+const WAYPOINT_COLOR: u32 = 0x208040;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RaceCourse {
+    // Puntos de control en orden; el primero es la salida y el último la meta
+    pub waypoints: Vec<[f32; 3]>,
+    // Distancia a la que se considera que la nave pasó por un punto
+    #[serde(default = "default_radius")]
+    pub radius: f32,
+}
+
+fn default_radius() -> f32 {
+    3.0
+}
+
+pub struct Race {
+    waypoints: Vec<Vec3>,
+    radius: f32,
+    next: usize,
+    start_time: Option<f32>,
+    // Tiempo acumulado al pasar por cada punto después de la salida
+    splits: Vec<f32>,
+}
+
+impl Race {
+    pub fn new(course: &RaceCourse) -> Self {
+        Race {
+            waypoints: course.waypoints.iter().map(|p| Vec3::from(*p)).collect(),
+            radius: course.radius,
+            next: 0,
+            start_time: None,
+            splits: Vec::new(),
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.waypoints.len()
+    }
+
+    pub fn finish_time(&self) -> Option<f32> {
+        if self.is_finished() { self.splits.last().copied() } else { None }
+    }
+
+    // El cronómetro arranca al cruzar la salida y se detiene en la meta
+    pub fn update(&mut self, time: f32, position: Vec3) {
+        let Some(target) = self.waypoints.get(self.next) else {
+            return;
+        };
+        if (target - position).magnitude() > self.radius {
+            return;
+        }
+
+        match self.start_time {
+            None => self.start_time = Some(time),
+            Some(start) => self.splits.push(time - start),
+        }
+        self.next += 1;
+    }
+
+    // Tiempo que hizo el vuelo grabado en este mismo circuito, si lo completó
+    pub fn replay(course: &RaceCourse, flight: &FlightPath) -> Option<f32> {
+        let mut race = Race::new(course);
+        for point in &flight.points {
+            race.update(point.time, Vec3::from(point.position));
+        }
+        race.finish_time()
+    }
+
+    pub fn hud_lines(&self, time: f32, ghost_time: Option<f32>) -> Vec<String> {
+        let mut lines = Vec::new();
+        match (self.start_time, self.finish_time()) {
+            (None, _) => lines.push("Carrera: cruza la salida".to_string()),
+            (Some(_), Some(total)) => lines.push(format!("Meta: {:.2}", total)),
+            (Some(start), None) => lines.push(format!(
+                "Carrera: {:.2}  punto {}/{}",
+                time - start,
+                self.next,
+                self.waypoints.len() - 1
+            )),
+        }
+        if let Some(split) = self.splits.last() {
+            lines.push(format!("Último parcial: {:.2}", split));
+        }
+        if let Some(ghost) = ghost_time {
+            lines.push(format!("Fantasma: {:.2}", ghost));
+        }
+        lines
+    }
+
+    pub fn render(&self, framebuffer: &mut Framebuffer, view_matrix: &Mat4, projection_matrix: &Mat4) {
+        for (index, waypoint) in self.waypoints.iter().enumerate().skip(self.next) {
+            let (color, half_size) = if index == self.next {
+                (NEXT_WAYPOINT_COLOR, 5)
+            } else {
+                (WAYPOINT_COLOR, 3)
+            };
+            draw_marker(framebuffer, *waypoint, half_size, color, 0.7, view_matrix, projection_matrix);
+        }
+    }
+}
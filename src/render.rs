@@ -0,0 +1,445 @@
+// Dibujo de modelos en software: las constantes de cada cuerpo
+// (`Uniforms`), las matrices de modelo y de viewport, el recorte y el
+// rasterizado por bloques de los triángulos, el sombreado de los fragmentos
+// y los impostores de las esferas lejanas. Vive en la biblioteca para que el
+// banco (`benches/rasterizador.rs`) lo use sin compilar la ventana ni el
+// resto del juego.
+
+use nalgebra_glm::{Mat3, Mat4, Vec2, Vec3, Vec4};
+
+use crate::binning::TileBins;
+use crate::clipping::{clip_triangle, is_inside, FAR_PLANE, NEAR_PLANE};
+use crate::culling::{world_sphere, Frustum};
+use crate::debug_view::{draw_wireframe, normal_color, DebugView};
+use crate::fragment::Fragment;
+use crate::framebuffer::Framebuffer;
+use crate::lighting::{apply_lighting, Light, Material};
+use crate::shade_batch::shade_batch;
+use crate::shaders::{apply_surface_detail, seeded_point, FragmentInput, PlanetShader, Surface};
+use crate::texture::Texture;
+use crate::triangle::{setup_triangle, triangle, ScreenTriangle};
+use crate::vertex::Vertex;
+use crate::vertex_stage::{transform_vertices, VertexCache};
+
+pub struct Uniforms<'a> {
+    pub model_matrix: Mat4,
+    pub view_matrix: Mat4,
+    pub projection_matrix: Mat4,
+    pub viewport_matrix: Mat4,
+    // Frustum de `projection_matrix * view_matrix`, armado una vez por cuadro
+    // para descartar los modelos que quedan fuera
+    pub frustum: Frustum,
+    // Tiempo de la animación de los shaders (nubes, lava, destellos): corre
+    // siempre al mismo ritmo, aparte del de las órbitas, que se puede
+    // acelerar, pausar o invertir (ver `time_control`)
+    pub shader_time: f32,
+    pub shader: &'a dyn PlanetShader,
+    pub seed: u32,
+    pub alpha: f32,
+    pub texture: Option<&'a Texture>,
+    // Cráteres y clima del cuerpo que se está dibujando
+    pub surface: &'a Surface,
+    pub light: Light<'a>,
+    pub material: Material,
+    pub camera_position: Vec3,
+    // Modo de depuración del rasterizado (G); `Shaded` es la imagen normal
+    pub debug_view: DebugView,
+    // Dejar los fragmentos opacos sin iluminar para el pase en pantalla
+    // (calidad baja, cuerpos lejanos)
+    pub screen_lighting: bool,
+    // Intensidad del detalle fino de superficie (el planeta donde aterrizó la nave)
+    pub detail: f32,
+}
+
+pub fn create_model_matrix(translation: Vec3, scale: f32, rotation: Vec3) -> Mat4 {
+    create_scaled_model_matrix(translation, Vec3::new(scale, scale, scale), rotation)
+}
+
+// Igual que `create_model_matrix` con una escala distinta por eje
+pub fn create_scaled_model_matrix(translation: Vec3, scale: Vec3, rotation: Vec3) -> Mat4 {
+    let (sin_x, cos_x) = rotation.x.sin_cos();
+    let (sin_y, cos_y) = rotation.y.sin_cos();
+    let (sin_z, cos_z) = rotation.z.sin_cos();
+
+    let rotation_matrix_x = Mat4::new(
+        1.0,  0.0,    0.0,   0.0,
+        0.0,  cos_x, -sin_x, 0.0,
+        0.0,  sin_x,  cos_x, 0.0,
+        0.0,  0.0,    0.0,   1.0,
+    );
+
+    let rotation_matrix_y = Mat4::new(
+        cos_y,  0.0,  sin_y, 0.0,
+        0.0,    1.0,  0.0,   0.0,
+        -sin_y, 0.0,  cos_y, 0.0,
+        0.0,    0.0,  0.0,   1.0,
+    );
+
+    let rotation_matrix_z = Mat4::new(
+        cos_z, -sin_z, 0.0, 0.0,
+        sin_z,  cos_z, 0.0, 0.0,
+        0.0,    0.0,  1.0, 0.0,
+        0.0,    0.0,  0.0, 1.0,
+    );
+
+    let rotation_matrix = rotation_matrix_z * rotation_matrix_y * rotation_matrix_x;
+
+    let scale_matrix = Mat4::new(
+        scale.x, 0.0,     0.0,     0.0,
+        0.0,     scale.y, 0.0,     0.0,
+        0.0,     0.0,     scale.z, 0.0,
+        0.0,     0.0,     0.0,     1.0,
+    );
+
+    let translation_matrix = Mat4::new(
+        1.0, 0.0, 0.0, translation.x,
+        0.0, 1.0, 0.0, translation.y,
+        0.0, 0.0, 1.0, translation.z,
+        0.0, 0.0, 0.0, 1.0,
+    );
+
+    translation_matrix * rotation_matrix * scale_matrix
+}
+
+pub fn create_viewport_matrix(width: f32, height: f32) -> Mat4 {
+    Mat4::new(
+        width / 2.0, 0.0, 0.0, width / 2.0,
+        0.0, -height / 2.0, 0.0, height / 2.0,
+        0.0, 0.0, 1.0, 0.0,
+        0.0, 0.0, 0.0, 1.0,
+    )
+}
+
+// Radio en píxeles de una esfera de radio `radius` vista a `view_depth` unidades
+pub fn projected_radius(radius: f32, view_depth: f32, projection_matrix: &Mat4, viewport_matrix: &Mat4) -> f32 {
+    if view_depth <= 0.0 {
+        return 0.0;
+    }
+    // viewport_matrix[(1, 1)] es -alto / 2
+    radius * projection_matrix[(1, 1)] / view_depth * -viewport_matrix[(1, 1)]
+}
+
+// Inclinación mínima con la que se calcula el tamaño de un píxel sobre una
+// superficie: en el borde de un planeta el píxel se estira sin límite y
+// apagaría todo el ruido justo en la silueta
+const MIN_FACING: f32 = 0.2;
+
+// Tamaño de un píxel sobre la esfera unitaria del modelo en un punto de su
+// superficie (`model_scale` es la escala del modelo); los shaders apagan las
+// octavas de ruido más finas que eso
+fn surface_footprint(uniforms: &Uniforms, world_position: Vec3, normal: Vec3, model_scale: f32) -> f32 {
+    let view_depth = -(uniforms.view_matrix * Vec4::new(world_position.x, world_position.y, world_position.z, 1.0)).z;
+    let radius_px = projected_radius(model_scale, view_depth, &uniforms.projection_matrix, &uniforms.viewport_matrix);
+    if radius_px <= 0.0 {
+        return 0.0;
+    }
+    let to_camera = (uniforms.camera_position - world_position).try_normalize(1e-6).unwrap_or(normal);
+    1.0 / (radius_px * normal.dot(&to_camera).abs().max(MIN_FACING))
+}
+
+// Posición en pantalla (x, y) y profundidad en vista de un punto del mundo;
+// None si queda detrás de la cámara
+pub fn project_to_screen(point: Vec3, view_matrix: &Mat4, projection_matrix: &Mat4, viewport_matrix: &Mat4) -> Option<(f32, f32, f32)> {
+    let view = view_matrix * Vec4::new(point.x, point.y, point.z, 1.0);
+    let view_depth = -view.z;
+    if view_depth <= 0.1 {
+        return None;
+    }
+    let clip = projection_matrix * view;
+    let screen = viewport_matrix * Vec4::new(clip.x / clip.w, clip.y / clip.w, 0.0, 1.0);
+    Some((screen.x, screen.y, view_depth))
+}
+
+// Dibuja una esfera como impostor: un disco en pantalla donde cada píxel
+// reconstruye la normal de la esfera y se sombrea con el shader del cuerpo
+pub fn render_impostor(framebuffer: &mut Framebuffer, uniforms: &Uniforms, center: Vec3, radius: f32) {
+    let center_view = uniforms.view_matrix * Vec4::new(center.x, center.y, center.z, 1.0);
+    let view_depth = -center_view.z;
+    if view_depth <= 0.1 {
+        return;
+    }
+
+    let clip = uniforms.projection_matrix * center_view;
+    let screen = uniforms.viewport_matrix * Vec4::new(clip.x / clip.w, clip.y / clip.w, 0.0, 1.0);
+    let (screen_x, screen_y) = (screen.x, screen.y);
+    let radius_px = projected_radius(radius, view_depth, &uniforms.projection_matrix, &uniforms.viewport_matrix);
+    if radius_px < 0.5 {
+        return;
+    }
+
+    // Transformación inversa de vista+modelo para llevar la normal a espacio de objeto
+    let model_view = uniforms.view_matrix * uniforms.model_matrix;
+    let model_view_3 = Mat3::from_columns(&[
+        model_view.column(0).xyz(),
+        model_view.column(1).xyz(),
+        model_view.column(2).xyz(),
+    ]);
+    let inverse_model_view = model_view_3.try_inverse().unwrap_or_else(Mat3::identity);
+    // La vista es una transformación rígida: su inversa es la transpuesta
+    let view_3 = Mat3::from_columns(&[
+        uniforms.view_matrix.column(0).xyz(),
+        uniforms.view_matrix.column(1).xyz(),
+        uniforms.view_matrix.column(2).xyz(),
+    ]);
+    let inverse_view = view_3.transpose();
+
+    let min_x = (screen_x - radius_px).floor().max(0.0) as usize;
+    let max_x = (screen_x + radius_px).ceil().min(framebuffer.width as f32 - 1.0).max(0.0) as usize;
+    let min_y = (screen_y - radius_px).floor().max(0.0) as usize;
+    let max_y = (screen_y + radius_px).ceil().min(framebuffer.height as f32 - 1.0).max(0.0) as usize;
+
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            let dx = (x as f32 + 0.5 - screen_x) / radius_px;
+            let dy = -(y as f32 + 0.5 - screen_y) / radius_px;
+            let dist_sq = dx * dx + dy * dy;
+            if dist_sq > 1.0 {
+                continue;
+            }
+
+            let normal_view = Vec3::new(dx, dy, (1.0 - dist_sq).sqrt());
+            let object_point = (inverse_model_view * normal_view).normalize();
+
+            // Profundidad ligeramente desplazada hacia atrás para que la malla
+            // gane el test de profundidad durante el fundido
+            let surface = center_view.xyz() + normal_view * radius * 0.9;
+            let surface_clip = uniforms.projection_matrix * Vec4::new(surface.x, surface.y, surface.z, 1.0);
+            let depth = surface_clip.z / surface_clip.w;
+
+            let normal_world = inverse_view * normal_view;
+            match uniforms.debug_view {
+                DebugView::Overdraw => {
+                    framebuffer.count_fragment(x, y);
+                    continue;
+                }
+                DebugView::Normals => {
+                    framebuffer.point_hdr(x, y, depth, normal_color(normal_world), 1.0);
+                    continue;
+                }
+                _ => {}
+            }
+            // Tapado por algo ya dibujado: no vale la pena sombrearlo
+            if !framebuffer.passes_depth(x, y, depth) {
+                continue;
+            }
+            // El píxel cubre más superficie hacia el borde del disco
+            let footprint = 1.0 / (radius_px * normal_view.z.max(MIN_FACING));
+            let fragment = FragmentInput { point: seeded_point(object_point, uniforms.seed), tex_coords: Vec2::zeros(), footprint };
+            let mut base = uniforms.shader.shade(&fragment, uniforms);
+            if uniforms.detail > 0.0 {
+                base = apply_surface_detail(base, object_point, uniforms.detail, footprint);
+            }
+            if uniforms.screen_lighting {
+                framebuffer.point_unlit(x, y, depth, base);
+                continue;
+            }
+            let color_vec = apply_lighting(
+                base,
+                normal_world,
+                center + normal_world * radius,
+                uniforms.camera_position,
+                &uniforms.light,
+                &uniforms.material,
+            );
+            framebuffer.point_hdr(x, y, depth, color_vec, 1.0);
+        }
+    }
+}
+
+pub fn render_model(framebuffer: &mut Framebuffer, uniforms: &Uniforms, vertices: &[Vertex], indices: &[u32], local_radius: f32) {
+    // Skip the whole model when its bounding sphere is outside the view frustum
+    if !model_in_view(uniforms, local_radius) {
+        return;
+    }
+    let mut transformed_vertices = Vec::new();
+    transform_vertices(vertices, uniforms, &mut transformed_vertices);
+    rasterize_model(framebuffer, uniforms, &mut transformed_vertices, indices);
+}
+
+// Como `render_model`, pero guarda los vértices transformados en `cache` y
+// los reutiliza mientras las matrices del modelo no cambien
+pub fn render_model_cached(framebuffer: &mut Framebuffer, uniforms: &Uniforms, vertices: &[Vertex], indices: &[u32], local_radius: f32, cache: &mut VertexCache) {
+    if !model_in_view(uniforms, local_radius) {
+        return;
+    }
+    rasterize_model(framebuffer, uniforms, cache.transform(vertices, uniforms), indices);
+}
+
+fn model_in_view(uniforms: &Uniforms, local_radius: f32) -> bool {
+    let (center, radius) = world_sphere(&uniforms.model_matrix, local_radius);
+    uniforms.frustum.contains_sphere(center, radius)
+}
+
+// Recorta, arma y sombrea los triángulos de un modelo ya transformado; los
+// triángulos recortados agregan sus vértices al final de `transformed_vertices`
+fn rasterize_model(framebuffer: &mut Framebuffer, uniforms: &Uniforms, transformed_vertices: &mut Vec<Vertex>, indices: &[u32]) {
+
+    let clip_planes = [NEAR_PLANE, FAR_PLANE];
+    let mut scratch = RasterScratch::default();
+    // Triángulos visibles como índices en `transformed_vertices` (los que
+    // salen del recorte agregan sus vértices al final), con su proyección
+    let mut visible: Vec<([usize; 3], ScreenTriangle)> = Vec::new();
+    let mut bins = TileBins::default();
+
+    // Process triangles with early culling
+    for i in (0..indices.len()).step_by(3) {
+        let v1 = &transformed_vertices[indices[i] as usize];
+        let v2 = &transformed_vertices[indices[i+1] as usize];
+        let v3 = &transformed_vertices[indices[i+2] as usize];
+
+        // Early clip space culling - skip triangles whose vertices are all
+        // outside the same frustum plane
+        let clip_coords = [v1.transformed_position, v2.transformed_position, v3.transformed_position];
+        if clip_coords.iter().all(|v| v.x > v.w * 1.5)
+            || clip_coords.iter().all(|v| v.x < -v.w * 1.5)
+            || clip_coords.iter().all(|v| v.y > v.w * 1.5)
+            || clip_coords.iter().all(|v| v.y < -v.w * 1.5)
+            || clip_coords.iter().all(|v| v.z < -v.w)
+            || clip_coords.iter().all(|v| v.z > v.w)
+        {
+            continue;
+        }
+
+        // Triangles crossing the near/far planes are clipped before the perspective divide
+        if is_inside(v1, &clip_planes) && is_inside(v2, &clip_planes) && is_inside(v3, &clip_planes) {
+            if uniforms.debug_view == DebugView::Wireframe {
+                draw_wireframe(framebuffer, &uniforms.viewport_matrix, [v1, v2, v3]);
+            } else if let Some(setup) = setup_triangle(v1, v2, v3, uniforms) {
+                let triangle_indices = [indices[i] as usize, indices[i + 1] as usize, indices[i + 2] as usize];
+                visible.push((triangle_indices, setup));
+            }
+        } else {
+            for [a, b, c] in clip_triangle(v1, v2, v3, &clip_planes) {
+                if uniforms.debug_view == DebugView::Wireframe {
+                    draw_wireframe(framebuffer, &uniforms.viewport_matrix, [&a, &b, &c]);
+                } else if let Some(setup) = setup_triangle(&a, &b, &c, uniforms) {
+                    let first = transformed_vertices.len();
+                    transformed_vertices.extend([a, b, c]);
+                    visible.push(([first, first + 1, first + 2], setup));
+                }
+            }
+        }
+    }
+
+    // Los modelos translúcidos no escriben profundidad (ver
+    // `Framebuffer::point_blend`): sus triángulos se mezclan del más lejano al
+    // más cercano para que cada uno quede sobre los que tiene detrás
+    if uniforms.alpha < 1.0 || uniforms.shader.translucent() {
+        visible.sort_by(|(_, a), (_, b)| b.view_depth().total_cmp(&a.view_depth()));
+    }
+
+    // Rasterizado por bloques del framebuffer (ver `binning`)
+    for (triangle, (_, setup)) in visible.iter().enumerate() {
+        bins.insert(framebuffer, triangle, setup.bounds);
+    }
+    for (tile, triangles) in bins.by_tile() {
+        let rect = framebuffer.tile_bounds(tile);
+        for triangle in triangles {
+            let (vertex_indices, setup) = &visible[triangle];
+            let vertices = vertex_indices.map(|index| &transformed_vertices[index]);
+            shade_triangle(framebuffer, uniforms, &mut scratch, vertices, setup, rect);
+        }
+    }
+}
+
+// Memoria de trabajo del rasterizado: se crea una vez por modelo y cada
+// triángulo la vacía y la vuelve a llenar
+#[derive(Default)]
+pub struct RasterScratch {
+    pub fragments: Vec<Fragment>,
+    points: Vec<Vec3>,
+    footprints: Vec<f32>,
+    colors: Vec<Vec3>,
+}
+
+// Sombrea la parte del triángulo que cae en el bloque `rect`
+fn shade_triangle(
+    framebuffer: &mut Framebuffer,
+    uniforms: &Uniforms,
+    scratch: &mut RasterScratch,
+    vertices: [&Vertex; 3],
+    setup: &ScreenTriangle,
+    rect: (usize, usize, usize, usize),
+) {
+    triangle(vertices, setup, rect, &mut scratch.fragments);
+    shade_fragments(framebuffer, uniforms, scratch);
+}
+
+// Sombrea e ilumina los fragmentos de `scratch.fragments` y los escribe en
+// el framebuffer
+pub fn shade_fragments(framebuffer: &mut Framebuffer, uniforms: &Uniforms, scratch: &mut RasterScratch) {
+    // En los modos de depuración por fragmento no se sombrea nada
+    match uniforms.debug_view {
+        DebugView::Overdraw => {
+            for fragment in &scratch.fragments {
+                framebuffer.count_fragment(fragment.position.x as usize, fragment.position.y as usize);
+            }
+            return;
+        }
+        DebugView::Normals => {
+            for fragment in &scratch.fragments {
+                let (x, y) = (fragment.position.x as usize, fragment.position.y as usize);
+                framebuffer.point_hdr(x, y, fragment.depth, normal_color(fragment.normal), 1.0);
+            }
+            return;
+        }
+        _ => {}
+    }
+    // Early-Z: los fragmentos que ya tapó lo dibujado antes se descartan sin
+    // sombrear, así el fbm de un planeta oculto detrás de otro no se calcula.
+    // Los de un mismo triángulo no se tapan entre sí, así que el resultado es
+    // el mismo que probar la profundidad al escribir cada uno.
+    scratch.fragments.retain(|fragment| framebuffer.passes_depth(fragment.position.x as usize, fragment.position.y as usize, fragment.depth));
+    let fragments = &scratch.fragments;
+    let model_scale = uniforms.model_matrix.column(0).xyz().magnitude();
+    let footprint = |fragment: &Fragment| surface_footprint(uniforms, fragment.world_position, fragment.normal, model_scale);
+    // Los shaders con camino por lotes evalúan su ruido para grupos de fragmentos
+    let batch = uniforms.shader.batch();
+    if let Some(batch) = batch {
+        scratch.points.clear();
+        scratch.points.extend(fragments.iter().map(|fragment| seeded_point(fragment.vertex_position, uniforms.seed)));
+        scratch.footprints.clear();
+        scratch.footprints.extend(fragments.iter().map(footprint));
+        scratch.colors.clear();
+        shade_batch(batch, &scratch.points, &scratch.footprints, uniforms.shader_time, &mut scratch.colors);
+    }
+    for (index, fragment) in fragments.iter().enumerate() {
+        let x = fragment.position.x as usize;
+        let y = fragment.position.y as usize;
+
+        if x < framebuffer.width && y < framebuffer.height {
+            let (color_vec, density) = if batch.is_some() {
+                (scratch.colors[index], 1.0)
+            } else {
+                let input = FragmentInput {
+                    point: seeded_point(fragment.vertex_position, uniforms.seed),
+                    tex_coords: fragment.tex_coords,
+                    footprint: footprint(fragment),
+                };
+                uniforms.shader.shade_with_density(&input, uniforms)
+            };
+            let alpha = uniforms.alpha * density;
+            let color_vec = if uniforms.detail > 0.0 {
+                apply_surface_detail(color_vec, fragment.vertex_position, uniforms.detail, footprint(fragment))
+            } else {
+                color_vec
+            };
+            if alpha <= 0.0 {
+                continue;
+            }
+            if uniforms.screen_lighting && alpha >= 1.0 {
+                framebuffer.point_unlit(x, y, fragment.depth, color_vec);
+                continue;
+            }
+            let color_vec = apply_lighting(
+                color_vec,
+                fragment.normal,
+                fragment.world_position,
+                uniforms.camera_position,
+                &uniforms.light,
+                &uniforms.material,
+            );
+            framebuffer.point_hdr(x, y, fragment.depth, color_vec, alpha);
+        }
+    }
+}
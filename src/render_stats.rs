@@ -0,0 +1,275 @@
+// Estadísticas de dibujo por cuerpo (`--render-stats <archivo>`): durante el
+// vuelo se mide cuánto tarda cada cuerpo en rasterizarse como malla y como
+// impostor, agrupado por su radio en pantalla, y al salir se escribe un
+// informe con los cuerpos más caros, a qué distancias lo fueron y unos
+// umbrales de nivel de detalle sugeridos para guardar en la escena.
+//
+// La sugerencia ajusta dos rectas sobre el área del disco en pantalla
+// (radio²): la malla cuesta un fijo (los vértices) más una parte por píxel, y
+// el impostor solo por píxel. El radio en que los dos cuestan lo mismo es el
+// umbral: por debajo conviene el impostor. Un cuerpo con pocas muestras de
+// una de las dos formas usa el ajuste de todos los cuerpos juntos.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::lod::LodThresholds;
+use crate::scene::Scene;
+
+// Grupos por radio en pantalla: [0, 2), [2, 4), [4, 8)... y 256 o más
+const BUCKETS: usize = 9;
+// Muestras mínimas para confiar en el ajuste de un cuerpo
+const MIN_SAMPLES: u32 = 30;
+// Límites de los umbrales sugeridos, en píxeles
+const MIN_THRESHOLD: f32 = 4.0;
+const MAX_THRESHOLD: f32 = 64.0;
+// El umbral de vuelta a la malla guarda la proporción de los de siempre
+const HYSTERESIS: f32 = 1.5;
+// Cuerpos que se listan en la consola al salir
+const SUMMARY_SIZE: usize = 3;
+
+// Sumas para ajustar por mínimos cuadrados tiempo = a + b * radio²
+#[derive(Debug, Clone, Copy, Default)]
+struct Fit {
+    samples: u32,
+    sum_x: f64,
+    sum_y: f64,
+    sum_xy: f64,
+    sum_xx: f64,
+}
+
+impl Fit {
+    fn add(&mut self, radius: f32, seconds: f64) {
+        let x = (radius as f64).powi(2);
+        self.samples += 1;
+        self.sum_x += x;
+        self.sum_y += seconds;
+        self.sum_xy += x * seconds;
+        self.sum_xx += x * x;
+    }
+
+    fn merge(&mut self, other: &Fit) {
+        self.samples += other.samples;
+        self.sum_x += other.sum_x;
+        self.sum_y += other.sum_y;
+        self.sum_xy += other.sum_xy;
+        self.sum_xx += other.sum_xx;
+    }
+
+    // (a, b) de la recta; con todas las muestras del mismo tamaño, el promedio y 0
+    fn line(&self) -> (f64, f64) {
+        let n = self.samples as f64;
+        let denominator = n * self.sum_xx - self.sum_x * self.sum_x;
+        if denominator.abs() < 1e-9 {
+            return (self.sum_y / n, 0.0);
+        }
+        let slope = (n * self.sum_xy - self.sum_x * self.sum_y) / denominator;
+        ((self.sum_y - slope * self.sum_x) / n, slope)
+    }
+
+    // Pendiente de la recta que pasa por el origen
+    fn slope_through_origin(&self) -> f64 {
+        if self.sum_xx > 0.0 { self.sum_xy / self.sum_xx } else { 0.0 }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    frames: u32,
+    mesh_seconds: f64,
+    mesh_frames: u32,
+    impostor_seconds: f64,
+    impostor_frames: u32,
+    min_distance: f32,
+    max_distance: f32,
+}
+
+impl Default for Bucket {
+    fn default() -> Self {
+        Bucket {
+            frames: 0,
+            mesh_seconds: 0.0,
+            mesh_frames: 0,
+            impostor_seconds: 0.0,
+            impostor_frames: 0,
+            min_distance: f32::INFINITY,
+            max_distance: 0.0,
+        }
+    }
+}
+
+struct BodyStats {
+    name: String,
+    current: LodThresholds,
+    buckets: [Bucket; BUCKETS],
+    mesh: Fit,
+    impostor: Fit,
+}
+
+impl BodyStats {
+    fn seconds(&self) -> f64 {
+        self.buckets.iter().map(|bucket| bucket.mesh_seconds + bucket.impostor_seconds).sum()
+    }
+
+    fn frames(&self) -> u32 {
+        self.buckets.iter().map(|bucket| bucket.frames).sum()
+    }
+}
+
+fn bucket_of(radius: f32) -> usize {
+    (radius.max(1.0).log2().floor() as usize).min(BUCKETS - 1)
+}
+
+fn bucket_label(index: usize) -> String {
+    let low = if index == 0 { 0 } else { 1 << index };
+    if index == BUCKETS - 1 {
+        format!("{}+", low)
+    } else {
+        format!("{}-{}", low, 1 << (index + 1))
+    }
+}
+
+fn ms(seconds: f64) -> f64 {
+    seconds * 1000.0
+}
+
+pub struct RenderStats {
+    bodies: Vec<BodyStats>,
+}
+
+impl RenderStats {
+    pub fn new(scene: &Scene) -> Self {
+        RenderStats {
+            bodies: scene
+                .bodies
+                .iter()
+                .map(|body| BodyStats {
+                    name: body.name.clone(),
+                    current: body.lod.unwrap_or_default(),
+                    buckets: [Bucket::default(); BUCKETS],
+                    mesh: Fit::default(),
+                    impostor: Fit::default(),
+                })
+                .collect(),
+        }
+    }
+
+    // Un cuadro del cuerpo `index`: su distancia a la cámara, su radio en
+    // pantalla y lo que tardaron la malla y el impostor, si se dibujaron
+    pub fn record(&mut self, index: usize, distance: f32, radius: f32, mesh: Option<Duration>, impostor: Option<Duration>) {
+        let body = &mut self.bodies[index];
+        let bucket = &mut body.buckets[bucket_of(radius)];
+        bucket.frames += 1;
+        bucket.min_distance = bucket.min_distance.min(distance);
+        bucket.max_distance = bucket.max_distance.max(distance);
+        if let Some(time) = mesh {
+            bucket.mesh_seconds += time.as_secs_f64();
+            bucket.mesh_frames += 1;
+            body.mesh.add(radius, time.as_secs_f64());
+        }
+        if let Some(time) = impostor {
+            bucket.impostor_seconds += time.as_secs_f64();
+            bucket.impostor_frames += 1;
+            body.impostor.add(radius, time.as_secs_f64());
+        }
+    }
+
+    // Umbrales sugeridos para cada cuerpo, o None si nunca se dibujó
+    fn suggestions(&self) -> Vec<Option<LodThresholds>> {
+        let mut pooled_mesh = Fit::default();
+        let mut pooled_impostor = Fit::default();
+        for body in &self.bodies {
+            pooled_mesh.merge(&body.mesh);
+            pooled_impostor.merge(&body.impostor);
+        }
+        let pick = |own: &Fit, pooled: &Fit| if own.samples >= MIN_SAMPLES { *own } else { *pooled };
+
+        self.bodies
+            .iter()
+            .map(|body| {
+                let mesh = pick(&body.mesh, &pooled_mesh);
+                let impostor = pick(&body.impostor, &pooled_impostor);
+                if body.frames() == 0 || mesh.samples == 0 || impostor.samples == 0 {
+                    return None;
+                }
+                let (fixed, mesh_per_pixel) = mesh.line();
+                let impostor_per_pixel = impostor.slope_through_origin();
+                // Si el impostor cuesta menos por píxel que la malla, siempre conviene
+                let radius = if impostor_per_pixel <= mesh_per_pixel {
+                    MAX_THRESHOLD
+                } else {
+                    ((fixed.max(0.0) / (impostor_per_pixel - mesh_per_pixel)).sqrt() as f32).clamp(MIN_THRESHOLD, MAX_THRESHOLD)
+                };
+                let impostor_radius = (radius * 2.0).round() / 2.0;
+                Some(LodThresholds { impostor_radius, mesh_radius: (impostor_radius * HYSTERESIS * 2.0).round() / 2.0 })
+            })
+            .collect()
+    }
+
+    pub fn report(&self) -> String {
+        let total: f64 = self.bodies.iter().map(BodyStats::seconds).sum();
+        let suggestions = self.suggestions();
+        let mut order: Vec<usize> = (0..self.bodies.len()).collect();
+        order.sort_by(|&a, &b| self.bodies[b].seconds().total_cmp(&self.bodies[a].seconds()));
+
+        let mut report = String::new();
+        let _ = writeln!(report, "Estadísticas de dibujo de los cuerpos: {:.1} ms en total", ms(total));
+        let _ = writeln!(report, "Cada cuerpo, del más caro al más barato; los umbrales sugeridos se copian en su [[body]].");
+        for index in order {
+            let body = &self.bodies[index];
+            let frames = body.frames();
+            if frames == 0 {
+                continue;
+            }
+            let share = if total > 0.0 { body.seconds() / total * 100.0 } else { 0.0 };
+            let _ = writeln!(report);
+            let _ = writeln!(report, "{}: {:.1} ms ({:.0}%), {:.3} ms por cuadro en {} cuadros", body.name, ms(body.seconds()), share, ms(body.seconds()) / frames as f64, frames);
+            let _ = writeln!(report, "  {:<10} {:<15} {:>8} {:>10} {:>12}", "radio px", "distancia", "cuadros", "malla ms", "impostor ms");
+            for (bucket_index, bucket) in body.buckets.iter().enumerate().filter(|(_, bucket)| bucket.frames > 0) {
+                let average = |seconds: f64, count: u32| if count > 0 { format!("{:.3}", ms(seconds) / count as f64) } else { "-".to_string() };
+                let _ = writeln!(
+                    report,
+                    "  {:<10} {:<15} {:>8} {:>10} {:>12}",
+                    bucket_label(bucket_index),
+                    format!("{:.0}-{:.0}", bucket.min_distance, bucket.max_distance),
+                    bucket.frames,
+                    average(bucket.mesh_seconds, bucket.mesh_frames),
+                    average(bucket.impostor_seconds, bucket.impostor_frames),
+                );
+            }
+            let current = body.current;
+            match suggestions[index] {
+                Some(suggested) => {
+                    let _ = writeln!(
+                        report,
+                        "  Sugerencia: lod = {{ impostor_radius = {:.1}, mesh_radius = {:.1} }}  (ahora {:.1} y {:.1})",
+                        suggested.impostor_radius, suggested.mesh_radius, current.impostor_radius, current.mesh_radius
+                    );
+                }
+                None => {
+                    let _ = writeln!(report, "  Sin datos suficientes para sugerir umbrales");
+                }
+            }
+        }
+        report
+    }
+
+    // Los cuerpos más caros, para la consola
+    pub fn summary(&self) -> Vec<String> {
+        let total: f64 = self.bodies.iter().map(BodyStats::seconds).sum();
+        let mut bodies: Vec<&BodyStats> = self.bodies.iter().filter(|body| body.frames() > 0).collect();
+        bodies.sort_by(|a, b| b.seconds().total_cmp(&a.seconds()));
+        bodies
+            .iter()
+            .take(SUMMARY_SIZE)
+            .map(|body| format!("{}: {:.1} ms ({:.0}%)", body.name, ms(body.seconds()), if total > 0.0 { body.seconds() / total * 100.0 } else { 0.0 }))
+            .collect()
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.report())
+    }
+}
@@ -0,0 +1,175 @@
+// Sistemas de anillos planetarios: cualquier cuerpo puede declarar uno en la
+// escena (`rings = { ... }`) con sus radios, inclinación y paleta. Todos se
+// dibujan con la misma malla plana y el shader de anillos, que saca de la
+// paleta las bandas de color y deja huecos translúcidos entre ellas.
+
+use std::f32::consts::TAU;
+
+use nalgebra_glm::{Vec2, Vec3};
+use serde::{Deserialize, Serialize};
+
+use crate::lighting::RingShadow;
+use crate::vertex::Vertex;
+
+// Segmentos alrededor del anillo; con menos se nota el borde poligonal de cerca
+const SEGMENTS: u32 = 96;
+// Medio espesor del disco de colisión de los anillos sólidos, en radios del cuerpo
+const HALF_THICKNESS: f32 = 0.02;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RingSystem {
+    // Radios interior y exterior en radios del cuerpo
+    pub inner_radius: f32,
+    pub outer_radius: f32,
+    // Inclinación del plano de los anillos respecto al ecuador, en grados
+    pub tilt: f32,
+    // Colores de las bandas de adentro hacia afuera
+    pub palette: Vec<[f32; 3]>,
+    // Opacidad de las bandas más densas (0 = invisibles, 1 = opacas)
+    pub opacity: f32,
+    // Anillos sólidos: la nave choca con ellos en lugar de atravesarlos
+    pub solid: bool,
+}
+
+// Volumen de colisión de unos anillos sólidos: una corona plana con un poco
+// de espesor, centrada en el cuerpo. Las medidas ya están en unidades del mundo.
+#[derive(Debug, Clone, Copy)]
+pub struct RingDisc {
+    pub normal: Vec3,
+    pub inner_radius: f32,
+    pub outer_radius: f32,
+    pub half_thickness: f32,
+}
+
+impl Default for RingSystem {
+    fn default() -> Self {
+        RingSystem {
+            inner_radius: 1.4,
+            outer_radius: 2.3,
+            tilt: 0.0,
+            palette: vec![[0.85, 0.78, 0.62], [0.65, 0.55, 0.42], [0.9, 0.86, 0.78]],
+            opacity: 0.8,
+            solid: false,
+        }
+    }
+}
+
+impl RingSystem {
+    pub fn palette(&self) -> Vec<Vec3> {
+        self.palette.iter().map(|[r, g, b]| Vec3::new(*r, *g, *b)).collect()
+    }
+
+    // Rotación del modelo de los anillos de un cuerpo girado `body_rotation`
+    pub fn rotation(&self, body_rotation: Vec3) -> Vec3 {
+        Vec3::new(self.tilt.to_radians(), 0.0, body_rotation.z)
+    }
+
+    // Disco de colisión para un cuerpo de radio `scale` con esa rotación
+    pub fn disc(&self, scale: f32, body_rotation: Vec3) -> RingDisc {
+        // +Y girado sobre X y luego sobre Z, como la matriz de modelo
+        let rotation = self.rotation(body_rotation);
+        let (sin_x, cos_x) = rotation.x.sin_cos();
+        let (sin_z, cos_z) = rotation.z.sin_cos();
+        RingDisc {
+            normal: Vec3::new(-cos_x * sin_z, cos_x * cos_z, sin_x),
+            inner_radius: self.inner_radius * scale,
+            outer_radius: self.outer_radius * scale,
+            half_thickness: HALF_THICKNESS * scale,
+        }
+    }
+
+    // Sombra de estos anillos sobre su cuerpo, centrado en `center`, con las
+    // bandas del shader de anillos (`seed` y `palette` como en su dibujo)
+    pub fn shadow<'a>(&self, center: Vec3, scale: f32, body_rotation: Vec3, seed: u32, palette: &'a [Vec3]) -> RingShadow<'a> {
+        let disc = self.disc(scale, body_rotation);
+        RingShadow {
+            center,
+            normal: disc.normal,
+            inner_radius: disc.inner_radius,
+            outer_radius: disc.outer_radius,
+            opacity: self.opacity,
+            seed,
+            palette,
+        }
+    }
+
+    // Corona plana en el plano XZ del cuerpo (antes de escalar). Cada cara
+    // va dos veces con el orden invertido para verse desde arriba y desde
+    // abajo a pesar del descarte de caras traseras. `tex_coords.x` es la
+    // posición radial: 0 en el borde interior y 1 en el exterior.
+    pub fn mesh(&self) -> (Vec<Vertex>, Vec<u32>) {
+        let mut vertices = Vec::with_capacity(SEGMENTS as usize * 4);
+        let mut indices = Vec::with_capacity(SEGMENTS as usize * 12);
+        for (side, normal) in [(0, Vec3::new(0.0, 1.0, 0.0)), (1, Vec3::new(0.0, -1.0, 0.0))] {
+            let base = vertices.len() as u32;
+            for segment in 0..SEGMENTS {
+                let angle = segment as f32 / SEGMENTS as f32 * TAU;
+                let direction = Vec3::new(angle.cos(), 0.0, angle.sin());
+                let around = segment as f32 / SEGMENTS as f32;
+                vertices.push(Vertex::new(direction * self.inner_radius, normal, Vec2::new(0.0, around)));
+                vertices.push(Vertex::new(direction * self.outer_radius, normal, Vec2::new(1.0, around)));
+            }
+            for segment in 0..SEGMENTS {
+                let inner = base + segment * 2;
+                let next = base + (segment + 1) % SEGMENTS * 2;
+                let quad = if side == 0 {
+                    [inner, next, inner + 1, next, next + 1, inner + 1]
+                } else {
+                    [inner, inner + 1, next, next, inner + 1, next + 1]
+                };
+                indices.extend_from_slice(&quad);
+            }
+        }
+        (vertices, indices)
+    }
+}
+
+impl RingDisc {
+    // Distancia de la corona a `offset` (desde el centro del cuerpo) fuera
+    // del plano y en el plano; cada una es negativa si está dentro en ese sentido
+    fn distances(&self, offset: Vec3) -> (f32, f32, Vec3) {
+        let height = offset.dot(&self.normal);
+        let planar = offset - self.normal * height;
+        let radius = planar.magnitude();
+        let radial = (self.inner_radius - radius).max(radius - self.outer_radius);
+        (height.abs() - self.half_thickness, radial, planar)
+    }
+
+    // Distancia con signo desde `offset` hasta la superficie del disco
+    pub fn clearance(&self, offset: Vec3) -> f32 {
+        let (vertical, radial, _) = self.distances(offset);
+        let outside = (vertical.max(0.0).powi(2) + radial.max(0.0).powi(2)).sqrt();
+        outside + vertical.max(radial).min(0.0)
+    }
+
+    // Normal hacia afuera del disco en el punto de la superficie más cercano a `offset`
+    pub fn normal(&self, offset: Vec3) -> Vec3 {
+        let (vertical, radial, planar) = self.distances(offset);
+        let up = if offset.dot(&self.normal) < 0.0 { -self.normal } else { self.normal };
+        // Hacia afuera del borde exterior o hacia el cuerpo desde el interior
+        let radius = planar.magnitude();
+        let direction = planar.try_normalize(1e-6).unwrap_or_else(|| Vec3::new(1.0, 0.0, 0.0));
+        let side = if self.inner_radius - radius > radius - self.outer_radius { -direction } else { direction };
+        let normal = if vertical > 0.0 && radial > 0.0 {
+            up * vertical + side * radial
+        } else if vertical >= radial {
+            up
+        } else {
+            side
+        };
+        normal.try_normalize(1e-6).unwrap_or(up)
+    }
+
+    // Fracción 0..1 del segmento `from`-`to` (desde el centro del cuerpo) en
+    // la que cruza el plano de los anillos dentro de la corona, o None
+    pub fn segment_hit(&self, from: Vec3, to: Vec3) -> Option<f32> {
+        let (height_from, height_to) = (from.dot(&self.normal), to.dot(&self.normal));
+        if (height_from > 0.0) == (height_to > 0.0) {
+            return None;
+        }
+        let t = height_from / (height_from - height_to);
+        let radius = (from + (to - from) * t).magnitude();
+        (self.inner_radius..=self.outer_radius).contains(&radius).then_some(t)
+    }
+}
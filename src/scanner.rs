@@ -0,0 +1,221 @@
+// Escáner (Y): lanza desde la nave un pulso que se expande como un anillo
+// sobre el plano horizontal. Cada punto de interés que el frente del pulso
+// alcanza (estaciones, planetas, lunas, cometas y anomalías) queda marcado
+// en pantalla y en el minimapa con su nombre y distancia; las marcas se
+// desvanecen a los pocos segundos. La estrella no se marca: se ve desde
+// cualquier parte.
+
+use nalgebra_glm::{Mat4, Vec3, Vec4};
+
+use crate::framebuffer::{pack_color, unpack_color, Framebuffer};
+use crate::project_to_screen;
+use crate::scene::Scene;
+
+// Alcance del pulso y velocidad del frente (unidades por unidad de tiempo)
+const SCAN_RANGE: f32 = 90.0;
+const PULSE_SPEED: f32 = 60.0;
+// Duración de una marca desde que el pulso la encuentra
+const CONTACT_DURATION: f32 = 3.0;
+// Fracción final en la que la marca se desvanece
+const FADE_FRACTION: f32 = 0.4;
+const RING_SEGMENTS: usize = 96;
+// Cian muy por encima de 1.0 para que el bloom haga brillar el anillo
+const RING_COLOR: Vec3 = Vec3::new(0.6, 2.4, 2.8);
+// Lado del rombo que marca un contacto, en píxeles
+const MARKER_SIZE: f32 = 6.0;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ContactKind {
+    Station,
+    Planet,
+    Moon,
+    Comet,
+    Anomaly,
+}
+
+impl ContactKind {
+    pub fn color(self) -> u32 {
+        match self {
+            ContactKind::Station => 0xFFC040,
+            ContactKind::Planet => 0x80B0FF,
+            ContactKind::Moon => 0xC0C0C0,
+            ContactKind::Comet => 0x80E0FF,
+            ContactKind::Anomaly => 0xE060FF,
+        }
+    }
+}
+
+// Un punto de interés encontrado por el pulso, en la posición que tenía
+// cuando el pulso lo alcanzó
+pub struct Contact {
+    pub name: String,
+    pub kind: ContactKind,
+    pub position: Vec3,
+    age: f32,
+}
+
+impl Contact {
+    // Opacidad 0..1: entera hasta que empieza a desvanecerse
+    pub fn fade(&self) -> f32 {
+        ((CONTACT_DURATION - self.age) / (CONTACT_DURATION * FADE_FRACTION)).clamp(0.0, 1.0)
+    }
+}
+
+struct Pulse {
+    origin: Vec3,
+    radius: f32,
+}
+
+#[derive(Default)]
+pub struct Scanner {
+    pulse: Option<Pulse>,
+    contacts: Vec<Contact>,
+}
+
+// Puntos de interés de la escena en este instante
+fn points_of_interest(scene: &Scene) -> impl Iterator<Item = (&str, ContactKind, Vec3)> {
+    let props = scene
+        .props
+        .iter()
+        .zip(&scene.prop_states)
+        .map(|(prop, state)| (prop.name.as_str(), ContactKind::Station, state.position));
+    let bodies = scene.bodies.iter().zip(&scene.states).filter(|(body, _)| !body.emissive).map(|(body, state)| {
+        let kind = if body.tail.is_some() {
+            ContactKind::Comet
+        } else if body.parent_index.is_some() {
+            ContactKind::Moon
+        } else {
+            ContactKind::Planet
+        };
+        (body.name.as_str(), kind, state.position)
+    });
+    let anomalies = scene
+        .anomalies
+        .iter()
+        .zip(&scene.anomaly_states)
+        .map(|(anomaly, state)| (anomaly.name.as_str(), ContactKind::Anomaly, state.position));
+    props.chain(bodies).chain(anomalies)
+}
+
+impl Scanner {
+    // Lanza un pulso desde `origin`; no hace nada si el anterior sigue
+    // expandiéndose. Devuelve si salió el pulso (para el sonido).
+    pub fn pulse(&mut self, origin: Vec3) -> bool {
+        if self.pulse.is_some() {
+            return false;
+        }
+        self.pulse = Some(Pulse { origin, radius: 0.0 });
+        true
+    }
+
+    // Avanza el pulso y las marcas; devuelve cuántos puntos de interés
+    // encontró el pulso en este cuadro
+    pub fn update(&mut self, dt: f32, scene: &Scene) -> usize {
+        self.contacts.retain_mut(|contact| {
+            contact.age += dt;
+            contact.age < CONTACT_DURATION
+        });
+        let Some(pulse) = &mut self.pulse else {
+            return 0;
+        };
+        let inner = pulse.radius;
+        pulse.radius += PULSE_SPEED * dt;
+        let (origin, outer) = (pulse.origin, pulse.radius);
+        if outer >= SCAN_RANGE {
+            self.pulse = None;
+        }
+
+        let mut found = 0;
+        for (name, kind, position) in points_of_interest(scene) {
+            let distance = (position - origin).magnitude();
+            if distance < inner || distance >= outer || distance >= SCAN_RANGE {
+                continue;
+            }
+            // Un cuerpo que se mueve puede volver a caer en el frente: se
+            // actualiza su marca en lugar de repetirla
+            self.contacts.retain(|contact| contact.name != name);
+            self.contacts.push(Contact { name: name.to_string(), kind, position, age: 0.0 });
+            found += 1;
+        }
+        found
+    }
+
+    pub fn contacts(&self) -> &[Contact] {
+        &self.contacts
+    }
+
+    // Anillo del pulso en el mundo, con profundidad: se apaga al llegar al alcance
+    pub fn render_pulse(&self, framebuffer: &mut Framebuffer, view_matrix: &Mat4, projection_matrix: &Mat4, viewport_matrix: &Mat4) {
+        let Some(pulse) = &self.pulse else {
+            return;
+        };
+        let alpha = 1.0 - pulse.radius / SCAN_RANGE;
+        // Posición en pantalla con la profundidad NDC del z-buffer
+        let to_screen = |angle: f32| {
+            let point = pulse.origin + Vec3::new(angle.cos(), 0.0, angle.sin()) * pulse.radius;
+            let clip = projection_matrix * view_matrix * Vec4::new(point.x, point.y, point.z, 1.0);
+            if clip.w <= 0.1 {
+                return None;
+            }
+            let ndc = clip / clip.w;
+            let screen = viewport_matrix * Vec4::new(ndc.x, ndc.y, ndc.z, 1.0);
+            Some(Vec3::new(screen.x, screen.y, ndc.z))
+        };
+        let mut previous = to_screen(0.0);
+        for i in 1..=RING_SEGMENTS {
+            let current = to_screen(i as f32 / RING_SEGMENTS as f32 * std::f32::consts::TAU);
+            if let (Some(from), Some(to)) = (previous, current) {
+                let steps = (to.x - from.x).abs().max((to.y - from.y).abs()).ceil().min(2048.0) as usize;
+                for step in 0..=steps {
+                    let t = if steps == 0 { 0.0 } else { step as f32 / steps as f32 };
+                    let point = from.lerp(&to, t);
+                    if point.x >= 0.0 && point.y >= 0.0 && point.z > -1.0 && point.z < 1.0 {
+                        framebuffer.point_hdr(point.x as usize, point.y as usize, point.z, RING_COLOR, alpha);
+                    }
+                }
+            }
+            previous = current;
+        }
+    }
+
+    // Marcas de los contactos sobre la imagen final: un rombo con el nombre y
+    // la distancia a la nave, del color de su tipo
+    pub fn render_contacts(
+        &self,
+        framebuffer: &mut Framebuffer,
+        view_matrix: &Mat4,
+        projection_matrix: &Mat4,
+        viewport_matrix: &Mat4,
+        ship_position: Vec3,
+    ) {
+        for contact in &self.contacts {
+            let Some((x, y, _)) = project_to_screen(contact.position, view_matrix, projection_matrix, viewport_matrix) else {
+                continue;
+            };
+            let fade = contact.fade();
+            let color = contact.kind.color();
+            // Los cuatro lados del rombo, a la vez
+            for step in 0..MARKER_SIZE as usize {
+                let along = step as f32;
+                let edges = [
+                    (along, along - MARKER_SIZE),
+                    (MARKER_SIZE - along, along),
+                    (-along, MARKER_SIZE - along),
+                    (along - MARKER_SIZE, -along),
+                ];
+                for (dx, dy) in edges {
+                    let (px, py) = (x + dx, y + dy);
+                    if px >= 0.0 && py >= 0.0 {
+                        framebuffer.overlay_blend(px as usize, py as usize, color, fade);
+                    }
+                }
+            }
+            if x >= 0.0 && y >= 0.0 {
+                let distance = (contact.position - ship_position).magnitude();
+                let label = format!("{} {:.0}", contact.name, distance);
+                let text_color = pack_color(unpack_color(color) * fade);
+                framebuffer.draw_text(x as usize + MARKER_SIZE as usize + 4, (y as usize).saturating_sub(4), &label, text_color);
+            }
+        }
+    }
+}
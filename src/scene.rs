@@ -0,0 +1,653 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use nalgebra_glm::{self as glm, Vec3};
+use serde::{Deserialize, Serialize};
+
+use crate::ambient::AmbientConfig;
+use crate::anomaly::Anomaly;
+use crate::comet::CometTail;
+use crate::lod::LodThresholds;
+use crate::animation::PropConfig;
+use crate::course::CourseConfig;
+use crate::daily::mix_seed;
+use crate::flight::FlightConfig;
+use crate::lighting::LightingConfig;
+use crate::lighting::{Atmosphere, Material, Occluder};
+use crate::particles::ParticleConfig;
+use crate::race::RaceCourse;
+use crate::rings::{RingDisc, RingSystem};
+use crate::shader_registry::{registry, ShaderParam};
+use crate::shaders::{
+    crater_field, Climate, CometShader, DesertShader, GasGiantShader, IceShader, MoonShader, OceanShader, PlanetShader,
+    PurpleShader, RingedShader, RockyShader, SpaceshipShader, StarShader, Surface, VolcanicShader,
+};
+use crate::skybox::StarfieldConfig;
+use crate::spawn::SpawnPreset;
+use crate::validation::{validate_scene, ValidationIssue};
+
+// Descripción de un sistema solar cargada desde un archivo TOML o JSON
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Scene {
+    // Hace oscilar las estrellas alrededor del baricentro según las masas
+    #[serde(default)]
+    pub barycenter_wobble: bool,
+    // Semilla del sistema: la escena se carga como con `--seed`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u32>,
+    // Estela de los motores de la nave
+    #[serde(default)]
+    pub engine_trail: ParticleConfig,
+    // Velocidad máxima, empuje, arrastre y freno de la nave
+    #[serde(default)]
+    pub flight: FlightConfig,
+    // Luz ambiental y color del fondo del espacio
+    #[serde(default)]
+    pub lighting: LightingConfig,
+    // Catálogo de estrellas del fondo
+    #[serde(default)]
+    pub starfield: StarfieldConfig,
+    // Sonidos ambientales al acercarse a los planetas
+    #[serde(default)]
+    pub ambient: AmbientConfig,
+    // Circuito de puntos de control para el modo carrera
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub race: Option<RaceCourse>,
+    // Circuito de anillos generado sobre los planetas (modo --course)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub course: Option<CourseConfig>,
+    #[serde(rename = "body")]
+    pub bodies: Vec<Body>,
+    // Objetos con animación por fotogramas clave (estaciones, compuertas)
+    #[serde(default, rename = "prop", skip_serializing_if = "Vec::is_empty")]
+    pub props: Vec<PropConfig>,
+    // Puntos de partida con nombre para la nave (`--spawn <nombre>`)
+    #[serde(default, rename = "spawn", skip_serializing_if = "Vec::is_empty")]
+    pub spawns: Vec<SpawnPreset>,
+    // Encuentros especiales: agujeros de gusano y naves abandonadas
+    #[serde(default, rename = "anomaly", skip_serializing_if = "Vec::is_empty")]
+    pub anomalies: Vec<Anomaly>,
+    // Estado animado de cada cuerpo, recalculado en `update`
+    #[serde(skip)]
+    pub states: Vec<BodyState>,
+    // Posición y rotación de cada anomalía, también recalculadas en `update`
+    #[serde(skip)]
+    pub anomaly_states: Vec<BodyState>,
+    // Lo mismo para los objetos (`props`), que pueden orbitar un cuerpo
+    #[serde(skip)]
+    pub prop_states: Vec<BodyState>,
+}
+
+// Distancia mínima entre la nave y la superficie de un cuerpo sin
+// `collision_margin` propio
+pub const DEFAULT_COLLISION_MARGIN: f32 = 2.0;
+
+// Volumen de colisión de un cuerpo: un elipsoide achatado en su eje Y local
+// (una esfera si `polar_scale` es 1) y, si tiene anillos sólidos, su disco
+#[derive(Debug, Clone, Copy)]
+pub struct Collider {
+    pub center: Vec3,
+    pub radius: f32,
+    pub polar_scale: f32,
+    // Rotación del cuerpo, la misma de su matriz de modelo
+    pub rotation: Vec3,
+    // Distancia mínima a la que la nave puede acercarse a la superficie
+    pub margin: f32,
+    pub rings: Option<RingDisc>,
+}
+
+impl Collider {
+    // Desplazamiento desde el centro en los ejes del cuerpo con Y estirado:
+    // el elipsoide se vuelve una esfera
+    fn stretched(&self, point: Vec3) -> Vec3 {
+        let offset = self.local_offset(point - self.center);
+        Vec3::new(offset.x, offset.y / self.polar_scale, offset.z)
+    }
+
+    // Inversa de la rotación de la matriz de modelo (X, después Y y por último Z)
+    fn local_offset(&self, offset: Vec3) -> Vec3 {
+        let offset = glm::rotate_z_vec3(&offset, -self.rotation.z);
+        let offset = glm::rotate_y_vec3(&offset, -self.rotation.y);
+        glm::rotate_x_vec3(&offset, -self.rotation.x)
+    }
+
+    fn world_offset(&self, local: Vec3) -> Vec3 {
+        let local = glm::rotate_x_vec3(&local, self.rotation.x);
+        let local = glm::rotate_y_vec3(&local, self.rotation.y);
+        glm::rotate_z_vec3(&local, self.rotation.z)
+    }
+
+    fn ellipsoid_clearance(&self, point: Vec3) -> f32 {
+        self.stretched(point).magnitude() - self.radius
+    }
+
+    // Disco de los anillos si `point` está más cerca de él que del elipsoide
+    fn nearest_rings(&self, point: Vec3) -> Option<&RingDisc> {
+        let body = self.ellipsoid_clearance(point);
+        self.rings.as_ref().filter(|rings| rings.clearance(point - self.center) < body)
+    }
+
+    // Distancia aproximada desde `point` hasta la superficie (negativa si está dentro)
+    pub fn clearance(&self, point: Vec3) -> f32 {
+        let body = self.ellipsoid_clearance(point);
+        self.rings.map_or(body, |rings| body.min(rings.clearance(point - self.center)))
+    }
+
+    // Normal hacia afuera de la superficie en la dirección de `point`
+    pub fn normal(&self, point: Vec3) -> Vec3 {
+        if let Some(rings) = self.nearest_rings(point) {
+            return rings.normal(point - self.center);
+        }
+        let stretched = self.stretched(point);
+        self.world_offset(Vec3::new(stretched.x, stretched.y / self.polar_scale, stretched.z))
+            .try_normalize(1e-6)
+            .unwrap_or_else(|| Vec3::new(0.0, 1.0, 0.0))
+    }
+
+    // Punto de la superficie agrandada en `margin` en la dirección de `point`
+    pub fn surface_point(&self, point: Vec3, margin: f32) -> Vec3 {
+        if let Some(rings) = self.nearest_rings(point) {
+            let offset = point - self.center;
+            return point + rings.normal(offset) * (margin - rings.clearance(offset));
+        }
+        let direction = self.stretched(point).try_normalize(1e-6).unwrap_or_else(|| Vec3::new(0.0, 1.0, 0.0));
+        let stretched = direction * (self.radius + margin);
+        self.center + self.world_offset(Vec3::new(stretched.x, stretched.y * self.polar_scale, stretched.z))
+    }
+
+    // Fracción 0..1 del segmento `from`-`to` en la que entra al cuerpo o a
+    // sus anillos, o None si no los toca
+    pub fn segment_hit(&self, from: Vec3, to: Vec3) -> Option<f32> {
+        let body = self.ellipsoid_hit(from, to);
+        let rings = self.rings.and_then(|rings| rings.segment_hit(from - self.center, to - self.center));
+        match (body, rings) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    // Entrada al elipsoide: se resuelve como rayo contra esfera en el espacio
+    // estirado, donde el segmento sigue siendo recto
+    fn ellipsoid_hit(&self, from: Vec3, to: Vec3) -> Option<f32> {
+        let origin = self.stretched(from);
+        let delta = self.stretched(to) - origin;
+        let a = delta.magnitude_squared();
+        let c = origin.magnitude_squared() - self.radius * self.radius;
+        if c <= 0.0 {
+            return Some(0.0);
+        }
+        if a < 1e-12 {
+            return None;
+        }
+        let b = origin.dot(&delta);
+        let discriminant = b * b - a * c;
+        if discriminant < 0.0 {
+            return None;
+        }
+        let t = (-b - discriminant.sqrt()) / a;
+        (0.0..=1.0).contains(&t).then_some(t)
+    }
+}
+
+// Posición y rotación de un cuerpo en el cuadro actual
+#[derive(Debug, Clone, Copy)]
+pub struct BodyState {
+    pub position: Vec3,
+    pub rotation: Vec3,
+}
+
+// Un cuerpo celeste: su órbita alrededor del origen (o de su cuerpo padre
+// si es una luna), tamaño y shader
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Body {
+    pub name: String,
+    pub shader: String,
+    // Nombre del cuerpo alrededor del cual orbita; debe estar definido antes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    // Semilla que varía el patrón procedural del shader
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u32>,
+    pub scale: f32,
+    pub collision_radius: f32,
+    // Distancia mínima entre la nave y la superficie; sin valor, la de siempre
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collision_margin: Option<f32>,
+    // Achatamiento de los polos: 0 = esfera, 0.1 = el radio polar es un 10% menor
+    #[serde(default)]
+    pub oblateness: f32,
+    // Semieje mayor de la órbita (el radio, si es circular)
+    #[serde(default, alias = "semi_major_axis")]
+    pub orbit_radius: f32,
+    // Período orbital en unidades de tiempo de simulación (negativo = retrógrado)
+    #[serde(default)]
+    pub orbit_period: f32,
+    // Excentricidad de la órbita: 0 = círculo, cerca de 1 = elipse muy alargada
+    #[serde(default)]
+    pub eccentricity: f32,
+    // Argumento del periapsis en grados: hacia dónde apunta el punto más
+    // cercano al centro dentro del plano de la órbita
+    #[serde(default)]
+    pub argument_of_periapsis: f32,
+    // Inclinación de la órbita en grados
+    #[serde(default)]
+    pub inclination: f32,
+    // Anomalía media inicial en grados (el ángulo inicial, en una órbita circular)
+    #[serde(default)]
+    pub phase: f32,
+    // Duración de un día (una vuelta sobre su eje) en unidades de tiempo; 0 = sin rotación
+    #[serde(default)]
+    pub day_length: f32,
+    // Rotación en sentido contrario al habitual
+    #[serde(default)]
+    pub retrograde: bool,
+    // Inclinación del eje de rotación en grados (más de 90 = rota "de espaldas")
+    #[serde(default)]
+    pub axial_tilt: f32,
+    // Rotación sincronizada con la órbita: siempre muestra la misma cara al centro
+    #[serde(default)]
+    pub tidally_locked: bool,
+    // Cuerpo que emite luz (no recibe iluminación)
+    #[serde(default)]
+    pub emissive: bool,
+    // Intensidad del brillo especular (0 = superficie mate)
+    #[serde(default)]
+    pub specular: f32,
+    #[serde(default = "default_shininess")]
+    pub shininess: f32,
+    // Halo de atmósfera en el borde (`atmosphere = { color = [r, g, b] }`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub atmosphere: Option<Atmosphere>,
+    // Masa relativa, usada para el bamboleo del baricentro
+    #[serde(default)]
+    pub mass: f32,
+    // Cantidad de cráteres sembrados en la superficie (shaders rocky y desert)
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub craters: u32,
+    // Temperaturas que definen los biomas del shader rocky
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub climate: Option<Climate>,
+    // Sistema de anillos (`rings = { inner_radius, outer_radius, tilt, palette }`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rings: Option<RingSystem>,
+    // Cola de cometa (`tail = { length, color, active_distance }`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tail: Option<CometTail>,
+    // Umbrales de nivel de detalle propios (`lod = { impostor_radius, mesh_radius }`);
+    // sin valor, los de siempre
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lod: Option<LodThresholds>,
+    // Parámetros de un shader registrado (`params = { nombre = valor }`);
+    // los que falten toman el valor por defecto del shader
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub params: BTreeMap<String, f32>,
+    // El shader de `shader`, resuelto al cargar
+    #[serde(skip)]
+    pub resolved_shader: Option<&'static dyn PlanetShader>,
+    // Índice del cuerpo padre en `Scene::bodies`, resuelto al cargar
+    #[serde(skip)]
+    pub parent_index: Option<usize>,
+    // Cráteres (generados a partir de `craters` y la semilla), clima y
+    // colores de los anillos
+    #[serde(skip)]
+    pub surface: Surface,
+}
+
+fn is_zero(value: &u32) -> bool {
+    *value == 0
+}
+
+fn default_shininess() -> f32 {
+    32.0
+}
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io(std::io::Error),
+    Toml(toml::de::Error),
+    Json(serde_json::Error),
+    Invalid(Vec<ValidationIssue>),
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SceneError::Io(e) => write!(f, "no se pudo leer el archivo: {}", e),
+            SceneError::Toml(e) => write!(f, "TOML inválido: {}", e),
+            SceneError::Json(e) => write!(f, "JSON inválido: {}", e),
+            SceneError::Invalid(issues) => {
+                write!(f, "{} problema(s) en la escena:", issues.len())?;
+                for issue in issues {
+                    write!(f, "\n  {}", issue)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+// Nombres de shader aceptados en los archivos de escena y su implementación
+const SHADER_NAMES: [(&str, &dyn PlanetShader); 12] = [
+    ("star", &StarShader),
+    ("rocky", &RockyShader),
+    ("gas_giant", &GasGiantShader),
+    ("spaceship", &SpaceshipShader),
+    ("ice", &IceShader),
+    ("desert", &DesertShader),
+    ("volcanic", &VolcanicShader),
+    ("ocean", &OceanShader),
+    ("purple", &PurpleShader),
+    ("ringed", &RingedShader),
+    ("moon", &MoonShader),
+    ("comet", &CometShader),
+];
+
+pub fn is_json_path(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+}
+
+pub fn builtin_shader(name: &str) -> Option<&'static dyn PlanetShader> {
+    SHADER_NAMES.iter().find(|(n, _)| *n == name).map(|(_, shader)| *shader)
+}
+
+// Los shaders de `shaders.rs` y luego los registrados al arrancar
+pub fn shader_from_name(name: &str) -> Option<&'static dyn PlanetShader> {
+    builtin_shader(name).or_else(|| registry().get(name).map(|shader| shader as &dyn PlanetShader))
+}
+
+// Nombres de todos los shaders de planeta: los de `shaders.rs` y los registrados
+pub fn shader_names() -> impl Iterator<Item = &'static str> {
+    SHADER_NAMES.iter().map(|(name, _)| *name).chain(registry().names())
+}
+
+// Parámetros que acepta el shader `name` (los de `shaders.rs` no tienen)
+pub fn shader_params(name: &str) -> &'static [ShaderParam] {
+    registry().get(name).map_or(&[], |shader| shader.params.as_slice())
+}
+
+impl Scene {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, SceneError> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path).map_err(SceneError::Io)?;
+        Scene::parse(&source, is_json_path(path))
+    }
+
+    pub fn parse(source: &str, is_json: bool) -> Result<Self, SceneError> {
+        let mut scene: Scene = if is_json {
+            serde_json::from_str(source).map_err(SceneError::Json)?
+        } else {
+            toml::from_str(source).map_err(SceneError::Toml)?
+        };
+
+        let issues = validate_scene(&scene, source, is_json);
+        if !issues.is_empty() {
+            return Err(SceneError::Invalid(issues));
+        }
+
+        let names: Vec<String> = scene.bodies.iter().map(|body| body.name.clone()).collect();
+        for prop in &mut scene.props {
+            prop.parent_index = prop.parent.as_ref().and_then(|parent| names.iter().position(|name| name == parent));
+        }
+        for body in &mut scene.bodies {
+            body.resolved_shader = shader_from_name(&body.shader);
+            body.parent_index = body.parent
+                .as_ref()
+                .and_then(|parent| names.iter().position(|name| name == parent));
+            body.surface = Surface {
+                craters: crater_field(body.shader_seed(), body.craters),
+                climate: body.climate.unwrap_or_default(),
+                ring_palette: body.rings.as_ref().map(RingSystem::palette).unwrap_or_default(),
+                params: shader_params(&body.shader)
+                    .iter()
+                    .map(|param| body.params.get(param.name).copied().unwrap_or(param.default))
+                    .collect(),
+            };
+        }
+
+        scene.update(0.0);
+        Ok(scene)
+    }
+
+    // Avanza la animación de todos los cuerpos, anomalías y objetos al instante
+    // `time`. Las lunas orbitan la posición ya animada de su padre, que
+    // siempre va antes.
+    pub fn update(&mut self, time: f32) {
+        self.states = self.bodies
+            .iter()
+            .map(|body| BodyState {
+                position: body.position(time),
+                rotation: body.rotation(time),
+            })
+            .collect();
+
+        if self.barycenter_wobble {
+            self.apply_barycenter_wobble();
+        }
+
+        for index in 0..self.bodies.len() {
+            if let Some(parent) = self.bodies[index].parent_index {
+                let parent_position = self.states[parent].position;
+                self.states[index].position += parent_position;
+            }
+        }
+        self.anomaly_states = self.anomalies.iter().map(|anomaly| anomaly.state(time)).collect();
+        self.prop_states = self.props
+            .iter()
+            .map(|prop| prop.state(time, prop.parent_index.map_or(Vec3::zeros(), |parent| self.states[parent].position)))
+            .collect();
+    }
+
+    // Centro de la órbita del cuerpo `index`: su padre o el origen
+    pub fn orbit_center(&self, index: usize) -> Vec3 {
+        self.bodies[index]
+            .parent_index
+            .map(|parent| self.states[parent].position)
+            .unwrap_or_else(|| Vec3::new(0.0, 0.0, 0.0))
+    }
+
+    // Desplaza los cuerpos centrales (sin órbita) para que el baricentro del
+    // sistema quede en el origen: p_central = -Σ(m_i · p_i) / m_central
+    fn apply_barycenter_wobble(&mut self) {
+        let orbiting_moment = self.bodies
+            .iter()
+            .zip(&self.states)
+            .filter(|(body, _)| body.orbit_radius > 0.0 && body.parent.is_none())
+            .fold(Vec3::new(0.0, 0.0, 0.0), |acc, (body, state)| acc + state.position * body.mass);
+
+        for (body, state) in self.bodies.iter().zip(self.states.iter_mut()) {
+            if body.orbit_radius == 0.0 && body.parent.is_none() && body.mass > 0.0 {
+                state.position -= orbiting_moment / body.mass;
+            }
+        }
+    }
+
+    // Genera una variante del sistema a partir de una semilla: cada cuerpo
+    // recibe su propio patrón procedural y los planetas una fase inicial nueva
+    pub fn reseed(&mut self, seed: u32) {
+        for (index, body) in self.bodies.iter_mut().enumerate() {
+            let index = index as u32;
+            body.seed = Some(mix_seed(seed, index));
+            if body.orbit_radius > 0.0 {
+                body.phase = (mix_seed(seed, index + 1000) % 360) as f32;
+            }
+            body.surface.craters = crater_field(body.shader_seed(), body.craters);
+        }
+        self.update(0.0);
+    }
+
+    // Volúmenes de colisión de todos los cuerpos en el cuadro actual
+    pub fn collision_bodies(&self) -> Vec<Collider> {
+        self.bodies
+            .iter()
+            .zip(&self.states)
+            .map(|(body, state)| Collider {
+                center: state.position,
+                radius: body.collision_radius,
+                polar_scale: body.polar_scale(),
+                rotation: state.rotation,
+                margin: body.collision_margin(),
+                rings: body.rings.as_ref().filter(|rings| rings.solid).map(|rings| rings.disc(body.scale, state.rotation)),
+            })
+            .collect()
+    }
+
+    // Posición y radio de la estrella: el primer cuerpo emisivo
+    pub fn star(&self) -> Option<(Vec3, f32)> {
+        self.bodies
+            .iter()
+            .zip(&self.states)
+            .find(|(body, _)| body.emissive)
+            .map(|(body, state)| (state.position, body.scale))
+    }
+
+    // Posición de la estrella, o el origen si no hay
+    pub fn star_position(&self) -> Vec3 {
+        self.star().map_or_else(Vec3::zeros, |(position, _)| position)
+    }
+
+    // Cuerpos que pueden eclipsar la estrella a una esfera en `center` de
+    // radio `radius`: los que se cruzan con el cilindro entre la esfera y la
+    // estrella, ensanchado por la penumbra. No cuentan los emisivos ni el
+    // cuerpo que contiene a `center` (el que se está dibujando).
+    pub fn shadow_casters(&self, center: Vec3, radius: f32, casters: &mut Vec<Occluder>) {
+        casters.clear();
+        let Some((star, star_radius)) = self.star() else {
+            return;
+        };
+        let to_star = star - center;
+        let distance = to_star.magnitude();
+        if distance < 1e-6 {
+            return;
+        }
+        let direction = to_star / distance;
+        for (body, state) in self.bodies.iter().zip(&self.states) {
+            let offset = state.position - center;
+            if body.emissive || offset.magnitude() <= body.scale {
+                continue;
+            }
+            let along = offset.dot(&direction);
+            if along <= 0.0 || along >= distance {
+                continue;
+            }
+            let miss = (offset - direction * along).magnitude();
+            let penumbra = along * (star_radius + body.scale) / (distance - along);
+            if miss < radius + body.scale + penumbra {
+                casters.push(Occluder { center: state.position, radius: body.scale });
+            }
+        }
+    }
+
+    // Índice del cuerpo cuya superficie está más cerca de `point` y la distancia a ella
+    pub fn nearest_body(&self, point: Vec3) -> Option<(usize, f32)> {
+        self.bodies
+            .iter()
+            .zip(&self.states)
+            .map(|(body, state)| ((state.position - point).magnitude() - body.scale).max(0.0))
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+    }
+}
+
+// Resuelve la ecuación de Kepler M = E - e·sin(E) por Newton-Raphson y
+// devuelve la anomalía excéntrica E
+fn eccentric_anomaly(mean_anomaly: f32, eccentricity: f32) -> f32 {
+    let mean_anomaly = mean_anomaly.rem_euclid(2.0 * std::f32::consts::PI);
+    // Con excentricidades altas Newton converge mejor empezando en π
+    let mut anomaly = if eccentricity > 0.8 { std::f32::consts::PI } else { mean_anomaly };
+    for _ in 0..10 {
+        let step = (anomaly - eccentricity * anomaly.sin() - mean_anomaly) / (1.0 - eccentricity * anomaly.cos());
+        anomaly -= step;
+        if step.abs() < 1e-6 {
+            break;
+        }
+    }
+    anomaly
+}
+
+impl Body {
+    // Anomalía media: avanza de manera uniforme con el tiempo
+    pub fn mean_anomaly(&self, time: f32) -> f32 {
+        let angular_speed = if self.orbit_period != 0.0 {
+            2.0 * std::f32::consts::PI / self.orbit_period
+        } else {
+            0.0
+        };
+        self.phase.to_radians() + time * angular_speed
+    }
+
+    // Punto de la elipse, relativo al centro de la órbita (en uno de sus
+    // focos), para la anomalía excéntrica `anomaly`
+    pub fn orbit_point(&self, anomaly: f32) -> Vec3 {
+        let a = self.orbit_radius;
+        let e = self.eccentricity;
+        let x = a * (anomaly.cos() - e);
+        let y = a * (1.0 - e * e).sqrt() * anomaly.sin();
+
+        // Girar dentro del plano para orientar el periapsis y luego inclinar el plano
+        let (sin_w, cos_w) = self.argument_of_periapsis.to_radians().sin_cos();
+        let (px, py) = (x * cos_w - y * sin_w, x * sin_w + y * cos_w);
+        let inclination = self.inclination.to_radians();
+        Vec3::new(px, py * inclination.sin(), py * inclination.cos())
+    }
+
+    pub fn position(&self, time: f32) -> Vec3 {
+        self.orbit_point(eccentric_anomaly(self.mean_anomaly(time), self.eccentricity))
+    }
+
+    // Distancia mínima al centro de la órbita
+    pub fn periapsis(&self) -> f32 {
+        self.orbit_radius * (1.0 - self.eccentricity)
+    }
+
+    // Velocidad angular de rotación propia con signo (rad por unidad de tiempo)
+    pub fn spin_speed(&self) -> f32 {
+        if self.day_length == 0.0 {
+            return 0.0;
+        }
+        let speed = 2.0 * std::f32::consts::PI / self.day_length;
+        if self.retrograde { -speed } else { speed }
+    }
+
+    // Distancia mínima entre la nave y la superficie del cuerpo
+    pub fn collision_margin(&self) -> f32 {
+        self.collision_margin.unwrap_or(DEFAULT_COLLISION_MARGIN)
+    }
+
+    // Radio polar relativo al ecuatorial
+    pub fn polar_scale(&self) -> f32 {
+        1.0 - self.oblateness
+    }
+
+    // Escala del modelo por eje: el eje Y (el de rotación) se achata
+    pub fn scale_vector(&self) -> Vec3 {
+        Vec3::new(self.scale, self.scale * self.polar_scale(), self.scale)
+    }
+
+    pub fn shader_seed(&self) -> u32 {
+        self.seed.unwrap_or_default()
+    }
+
+    // Shader con el que se dibuja; la validación asegura que exista
+    pub fn fragment_shader(&self) -> &'static dyn PlanetShader {
+        self.resolved_shader.unwrap_or(&SpaceshipShader)
+    }
+
+    pub fn material(&self) -> Material {
+        Material::new(self.emissive, self.specular, self.shininess).with_atmosphere(self.atmosphere)
+    }
+
+    // Giro sobre el eje Y local y luego la inclinación del eje sobre Z: la
+    // matriz de modelo aplica X, después Y y por último Z
+    pub fn rotation(&self, time: f32) -> Vec3 {
+        let tilt = self.axial_tilt.to_radians();
+        if self.tidally_locked && self.orbit_radius > 0.0 {
+            // Orientar el eje +X local hacia el centro de la órbita
+            let offset = self.position(time);
+            let yaw = offset.z.atan2(-offset.x);
+            return Vec3::new(0.0, yaw, tilt);
+        }
+        Vec3::new(0.0, time * self.spin_speed(), tilt)
+    }
+}
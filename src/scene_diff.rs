@@ -0,0 +1,347 @@
+// Comparación semántica y fusión de archivos de escena, para intercambiar
+// sistemas solares sin tener que comparar el texto línea por línea.
+//
+//   proyecto_nave diff base.toml otro.toml [--output parche.toml]
+//   proyecto_nave merge base.toml parche.toml [--output resultado.toml]
+//
+// Un parche es un archivo TOML (o JSON) con cuatro secciones opcionales:
+// `[settings]` (opciones globales de la escena), `[[remove]]` (cuerpos a
+// quitar por nombre), `[[change]]` (nombre + campos modificados) y `[[add]]`
+// (cuerpos completos nuevos).
+
+use std::fs;
+use std::path::Path;
+
+use toml::map::Map;
+use toml::Value;
+
+use crate::scene::{is_json_path, Scene};
+
+type Table = Map<String, Value>;
+
+// Cambios de un cuerpo presente en ambas escenas: (campo, antes, después)
+struct BodyChange {
+    name: String,
+    fields: Vec<(String, Option<Value>, Option<Value>)>,
+}
+
+struct SceneDiff {
+    settings: Vec<(String, Option<Value>, Option<Value>)>,
+    added: Vec<Table>,
+    removed: Vec<String>,
+    changed: Vec<BodyChange>,
+}
+
+impl SceneDiff {
+    fn is_empty(&self) -> bool {
+        self.settings.is_empty() && self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    fn print(&self) {
+        if self.is_empty() {
+            println!("Las escenas son equivalentes");
+            return;
+        }
+        for (key, old, new) in &self.settings {
+            println!("~ [{}] {} -> {}", key, describe(old), describe(new));
+        }
+        for name in &self.removed {
+            println!("- {}", name);
+        }
+        for body in &self.added {
+            let name = body.get("name").and_then(Value::as_str).unwrap_or("?");
+            println!("+ {}", name);
+        }
+        for change in &self.changed {
+            println!("~ {}", change.name);
+            for (field, old, new) in &change.fields {
+                println!("    {}: {} -> {}", field, describe(old), describe(new));
+            }
+        }
+    }
+
+    // Parche que, aplicado con `merge` sobre la escena base, produce la otra escena
+    fn to_patch(&self) -> Table {
+        let mut patch = Table::new();
+
+        // Un campo eliminado vuelve a su valor por defecto, así que no se
+        // puede expresar en el parche; solo se guardan valores nuevos
+        let settings: Table = self.settings
+            .iter()
+            .filter_map(|(key, _, new)| new.clone().map(|value| (key.clone(), value)))
+            .collect();
+        if !settings.is_empty() {
+            patch.insert("settings".into(), Value::Table(settings));
+        }
+
+        if !self.removed.is_empty() {
+            let removed = self.removed
+                .iter()
+                .map(|name| {
+                    let mut entry = Table::new();
+                    entry.insert("name".into(), Value::String(name.clone()));
+                    Value::Table(entry)
+                })
+                .collect();
+            patch.insert("remove".into(), Value::Array(removed));
+        }
+
+        if !self.changed.is_empty() {
+            let changed = self.changed
+                .iter()
+                .map(|change| {
+                    let mut entry = Table::new();
+                    entry.insert("name".into(), Value::String(change.name.clone()));
+                    for (field, _, new) in &change.fields {
+                        if let Some(value) = new {
+                            entry.insert(field.clone(), value.clone());
+                        }
+                    }
+                    Value::Table(entry)
+                })
+                .collect();
+            patch.insert("change".into(), Value::Array(changed));
+        }
+
+        if !self.added.is_empty() {
+            let added = self.added.iter().cloned().map(Value::Table).collect();
+            patch.insert("add".into(), Value::Array(added));
+        }
+
+        patch
+    }
+}
+
+fn describe(value: &Option<Value>) -> String {
+    match value {
+        Some(value) => value.to_string(),
+        None => "(sin valor)".to_string(),
+    }
+}
+
+// Los campos de la escena son f32: al pasarlos a f64 se recorta la
+// representación más corta para que 0.6 no aparezca como 0.6000000238418579
+fn shorten_floats(value: &mut Value) {
+    match value {
+        Value::Float(f) => *f = (*f as f32).to_string().parse().unwrap_or(*f),
+        Value::Array(items) => items.iter_mut().for_each(shorten_floats),
+        Value::Table(table) => table.iter_mut().for_each(|(_, value)| shorten_floats(value)),
+        _ => {}
+    }
+}
+
+// Convierte la escena a una tabla genérica para compararla campo por campo
+fn scene_to_table(scene: &Scene) -> Result<Table, String> {
+    match Value::try_from(scene) {
+        Ok(Value::Table(mut table)) => {
+            table.iter_mut().for_each(|(_, value)| shorten_floats(value));
+            Ok(table)
+        }
+        Ok(_) => Err("la escena no es una tabla".to_string()),
+        Err(e) => Err(format!("no se pudo convertir la escena: {}", e)),
+    }
+}
+
+fn split_bodies(mut table: Table) -> (Table, Vec<Table>) {
+    let bodies = match table.remove("body") {
+        Some(Value::Array(bodies)) => bodies
+            .into_iter()
+            .filter_map(|body| match body {
+                Value::Table(body) => Some(body),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    (table, bodies)
+}
+
+fn body_name(body: &Table) -> &str {
+    body.get("name").and_then(Value::as_str).unwrap_or_default()
+}
+
+fn diff_tables(old: &Table, new: &Table) -> Vec<(String, Option<Value>, Option<Value>)> {
+    let mut keys: Vec<&String> = old.keys().chain(new.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    keys.into_iter()
+        .filter_map(|key| {
+            let before = old.get(key);
+            let after = new.get(key);
+            (before != after).then(|| (key.clone(), before.cloned(), after.cloned()))
+        })
+        .collect()
+}
+
+fn diff_scenes(base: &Scene, other: &Scene) -> Result<SceneDiff, String> {
+    let (base_settings, base_bodies) = split_bodies(scene_to_table(base)?);
+    let (other_settings, other_bodies) = split_bodies(scene_to_table(other)?);
+
+    let removed = base_bodies
+        .iter()
+        .filter(|body| !other_bodies.iter().any(|b| body_name(b) == body_name(body)))
+        .map(|body| body_name(body).to_string())
+        .collect();
+
+    let mut added = Vec::new();
+    let mut changed = Vec::new();
+    for body in &other_bodies {
+        match base_bodies.iter().find(|b| body_name(b) == body_name(body)) {
+            Some(previous) => {
+                let fields = diff_tables(previous, body);
+                if !fields.is_empty() {
+                    changed.push(BodyChange { name: body_name(body).to_string(), fields });
+                }
+            }
+            None => added.push(body.clone()),
+        }
+    }
+
+    Ok(SceneDiff {
+        settings: diff_tables(&base_settings, &other_settings),
+        added,
+        removed,
+        changed,
+    })
+}
+
+fn entries<'a>(patch: &'a Table, key: &str) -> Result<Vec<&'a Table>, String> {
+    match patch.get(key) {
+        None => Ok(Vec::new()),
+        Some(Value::Array(items)) => items
+            .iter()
+            .map(|item| item.as_table().ok_or_else(|| format!("cada entrada de [[{}]] debe ser una tabla", key)))
+            .collect(),
+        Some(_) => Err(format!("`{}` debe ser una lista de tablas [[{}]]", key, key)),
+    }
+}
+
+fn entry_name<'a>(entry: &'a Table, key: &str) -> Result<&'a str, String> {
+    entry.get("name")
+        .and_then(Value::as_str)
+        .ok_or_else(|| format!("una entrada de [[{}]] no tiene `name`", key))
+}
+
+// Aplica el parche sobre la escena base y valida el resultado
+fn apply_patch(base: &Scene, patch: &Table) -> Result<Scene, String> {
+    let (mut settings, mut bodies) = split_bodies(scene_to_table(base)?);
+
+    if let Some(value) = patch.get("settings") {
+        let overrides = value.as_table().ok_or("`settings` debe ser una tabla")?;
+        for (key, value) in overrides {
+            settings.insert(key.clone(), value.clone());
+        }
+    }
+
+    for entry in entries(patch, "remove")? {
+        let name = entry_name(entry, "remove")?;
+        let count = bodies.len();
+        bodies.retain(|body| body_name(body) != name);
+        if bodies.len() == count {
+            return Err(format!("no se puede quitar '{}': no existe en la escena base", name));
+        }
+    }
+
+    for entry in entries(patch, "change")? {
+        let name = entry_name(entry, "change")?;
+        let body = bodies
+            .iter_mut()
+            .find(|body| body_name(body) == name)
+            .ok_or_else(|| format!("no se puede modificar '{}': no existe en la escena base", name))?;
+        for (key, value) in entry {
+            body.insert(key.clone(), value.clone());
+        }
+    }
+
+    for entry in entries(patch, "add")? {
+        let name = entry_name(entry, "add")?;
+        if bodies.iter().any(|body| body_name(body) == name) {
+            return Err(format!("no se puede agregar '{}': ya existe en la escena base", name));
+        }
+        bodies.push(entry.clone());
+    }
+
+    settings.insert("body".into(), Value::Array(bodies.into_iter().map(Value::Table).collect()));
+    let source = toml::to_string(&Value::Table(settings)).map_err(|e| e.to_string())?;
+    Scene::parse(&source, false).map_err(|e| format!("la escena resultante no es válida: {}", e))
+}
+
+fn load_scene(path: &str) -> Result<Scene, String> {
+    Scene::load(path).map_err(|e| format!("no se pudo cargar la escena {}: {}", path, e))
+}
+
+fn load_patch(path: &str) -> Result<Table, String> {
+    let source = fs::read_to_string(path).map_err(|e| format!("no se pudo leer el parche {}: {}", path, e))?;
+    let patch: Result<Table, String> = if is_json_path(Path::new(path)) {
+        serde_json::from_str(&source).map_err(|e| e.to_string())
+    } else {
+        toml::from_str(&source).map_err(|e| e.to_string())
+    };
+    patch.map_err(|e| format!("parche inválido {}: {}", path, e))
+}
+
+fn write_table(path: &str, table: &Table) -> Result<(), String> {
+    let contents = if is_json_path(Path::new(path)) {
+        serde_json::to_string_pretty(table).map_err(|e| e.to_string())?
+    } else {
+        toml::to_string(table).map_err(|e| e.to_string())?
+    };
+    fs::write(path, contents).map_err(|e| format!("no se pudo escribir {}: {}", path, e))
+}
+
+fn output_path(args: &[String]) -> Option<&str> {
+    args.iter()
+        .position(|arg| arg == "--output")
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
+}
+
+fn run_diff(args: &[String]) -> Result<(), String> {
+    let [base, other, ..] = args else {
+        return Err("uso: diff <base> <otra> [--output parche.toml]".to_string());
+    };
+    let diff = diff_scenes(&load_scene(base)?, &load_scene(other)?)?;
+    diff.print();
+
+    if let Some(path) = output_path(args) {
+        write_table(path, &diff.to_patch())?;
+        println!("Parche guardado en {}", path);
+    }
+    Ok(())
+}
+
+fn run_merge(args: &[String]) -> Result<(), String> {
+    let [base, patch, ..] = args else {
+        return Err("uso: merge <base> <parche> [--output resultado.toml]".to_string());
+    };
+    let merged = apply_patch(&load_scene(base)?, &load_patch(patch)?)?;
+    let table = scene_to_table(&merged)?;
+
+    match output_path(args) {
+        Some(path) => {
+            write_table(path, &table)?;
+            println!("Escena fusionada guardada en {} ({} cuerpos)", path, merged.bodies.len());
+        }
+        None => print!("{}", toml::to_string(&table).map_err(|e| e.to_string())?),
+    }
+    Ok(())
+}
+
+// Ejecuta el subcomando `diff` o `merge` si aparece como primer argumento.
+// Devuelve el código de salida, o `None` si hay que abrir la ventana normal.
+pub fn run_cli(args: &[String]) -> Option<i32> {
+    let result = match args.get(1).map(String::as_str) {
+        Some("diff") => run_diff(&args[2..]),
+        Some("merge") => run_merge(&args[2..]),
+        _ => return None,
+    };
+
+    Some(match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    })
+}
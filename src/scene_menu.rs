@@ -0,0 +1,267 @@
+// Menú de escenas (`--menu`): antes de abrir el sistema se muestran las
+// escenas de la carpeta de `--scene` con una miniatura y sus datos (nombre,
+// cuerpos y semilla), primero las que se abrieron hace poco. Las miniaturas
+// salen de `render_still` y se guardan como PNG en `.miniaturas/` dentro de
+// la carpeta; se vuelven a generar cuando la escena es más nueva que su PNG.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use minifb::{Key, KeyRepeat, Window, WindowOptions};
+
+use crate::framebuffer::Framebuffer;
+use crate::pixel_format::PixelFormat;
+use crate::scene::Scene;
+use crate::still::render_still;
+use crate::text;
+
+const THUMBNAIL_WIDTH: usize = 160;
+const THUMBNAIL_HEIGHT: usize = 120;
+const CACHE_DIRECTORY: &str = ".miniaturas";
+const RECENT_FILE: &str = "recientes.txt";
+const MAX_RECENT: usize = 5;
+// Tamaño de cada tarjeta del menú, con la separación
+const CARD_WIDTH: usize = 200;
+const CARD_HEIGHT: usize = THUMBNAIL_HEIGHT + 4 * text::LINE_HEIGHT;
+const MARGIN: usize = 20;
+const TITLE_HEIGHT: usize = 50;
+
+// Datos de una escena que se muestran en su tarjeta
+struct SceneInfo {
+    bodies: usize,
+    seed: Option<u32>,
+}
+
+struct SceneEntry {
+    path: PathBuf,
+    name: String,
+    info: Result<SceneInfo, String>,
+    recent: bool,
+    thumbnail: Vec<u32>,
+}
+
+fn cache_directory(directory: &Path) -> PathBuf {
+    directory.join(CACHE_DIRECTORY)
+}
+
+// Archivos de las escenas abiertas hace poco, del más reciente al más viejo
+fn recent_scenes(directory: &Path) -> Vec<String> {
+    fs::read_to_string(cache_directory(directory).join(RECENT_FILE))
+        .map(|source| source.lines().filter(|line| !line.is_empty()).map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+// Anota la escena como la más reciente de su carpeta. Si no se puede escribir
+// (una carpeta de solo lectura) el menú simplemente no la destaca.
+pub fn remember(scene_path: &Path) {
+    let (Some(directory), Some(file_name)) = (scene_path.parent(), scene_path.file_name()) else {
+        return;
+    };
+    let file_name = file_name.to_string_lossy().into_owned();
+    let mut recent = recent_scenes(directory);
+    recent.retain(|name| *name != file_name);
+    recent.insert(0, file_name);
+    recent.truncate(MAX_RECENT);
+    let cache = cache_directory(directory);
+    if fs::create_dir_all(&cache).is_ok() {
+        let _ = fs::write(cache.join(RECENT_FILE), recent.join("\n") + "\n");
+    }
+}
+
+// ¿Es `cached` al menos tan nuevo como `source`?
+fn is_fresh(cached: &Path, source: &Path) -> bool {
+    let modified = |path: &Path| fs::metadata(path).and_then(|metadata| metadata.modified()).ok();
+    matches!((modified(cached), modified(source)), (Some(cached), Some(source)) if cached >= source)
+}
+
+fn load_thumbnail(path: &Path) -> Option<Vec<u32>> {
+    let image = image::open(path).ok()?.to_rgba8();
+    if image.dimensions() != (THUMBNAIL_WIDTH as u32, THUMBNAIL_HEIGHT as u32) {
+        return None;
+    }
+    Some(PixelFormat::Rgba8.decode(image.as_raw()))
+}
+
+fn save_thumbnail(path: &Path, pixels: &[u32]) -> Result<(), String> {
+    let bytes = PixelFormat::Rgba8.encode(pixels);
+    let image = image::RgbaImage::from_raw(THUMBNAIL_WIDTH as u32, THUMBNAIL_HEIGHT as u32, bytes).ok_or("tamaño de miniatura inválido")?;
+    image.save(path).map_err(|e| e.to_string())
+}
+
+// Miniatura de la escena desde el caché, o dibujada y guardada si no hay una al día
+fn thumbnail(scene_path: &Path, scene: &Scene) -> Vec<u32> {
+    let Some(directory) = scene_path.parent() else {
+        return render_still(scene, THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT).present().to_vec();
+    };
+    let file_name = scene_path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+    let cached = cache_directory(directory).join(format!("{}.png", file_name));
+    if is_fresh(&cached, scene_path) {
+        if let Some(pixels) = load_thumbnail(&cached) {
+            return pixels;
+        }
+    }
+    let pixels = render_still(scene, THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT).present().to_vec();
+    let saved = fs::create_dir_all(cache_directory(directory))
+        .map_err(|e| e.to_string())
+        .and_then(|_| save_thumbnail(&cached, &pixels));
+    if let Err(e) = saved {
+        eprintln!("No se pudo guardar la miniatura {}: {}", cached.display(), e);
+    }
+    pixels
+}
+
+fn load_entry(path: PathBuf, recent: bool) -> SceneEntry {
+    let name = path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+    match Scene::load(&path) {
+        Ok(mut scene) => {
+            if let Some(seed) = scene.seed {
+                scene.reseed(seed);
+            }
+            let thumbnail = thumbnail(&path, &scene);
+            let info = Ok(SceneInfo { bodies: scene.bodies.len(), seed: scene.seed });
+            SceneEntry { path, name, info, recent, thumbnail }
+        }
+        Err(e) => SceneEntry {
+            path,
+            name,
+            info: Err(e.to_string()),
+            recent,
+            thumbnail: vec![0; THUMBNAIL_WIDTH * THUMBNAIL_HEIGHT],
+        },
+    }
+}
+
+pub struct SceneMenu {
+    entries: Vec<SceneEntry>,
+    selected: usize,
+}
+
+impl SceneMenu {
+    // Escenas TOML/JSON de `directory`: las recientes en orden y luego el
+    // resto por nombre
+    pub fn new(directory: &Path) -> Self {
+        let mut paths: Vec<PathBuf> = fs::read_dir(directory)
+            .map(|entries| entries.filter_map(|entry| entry.ok().map(|entry| entry.path())).collect())
+            .unwrap_or_default();
+        paths.retain(|path| path.is_file() && matches!(path.extension().and_then(|e| e.to_str()), Some("toml" | "json")));
+        paths.sort();
+
+        let recent = recent_scenes(directory);
+        let rank = |path: &PathBuf| {
+            let file_name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+            recent.iter().position(|name| *name == file_name)
+        };
+        paths.sort_by_key(|path| rank(path).unwrap_or(usize::MAX));
+        let entries = paths
+            .into_iter()
+            .map(|path| {
+                let recent = rank(&path).is_some();
+                load_entry(path, recent)
+            })
+            .collect();
+        SceneMenu { entries, selected: 0 }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn columns(width: usize) -> usize {
+        ((width.saturating_sub(MARGIN)) / CARD_WIDTH).max(1)
+    }
+
+    // Flechas eligen la escena y Enter la abre (las que tienen errores no)
+    pub fn update(&mut self, window: &Window, width: usize) -> Option<PathBuf> {
+        let columns = Self::columns(width);
+        let last = self.entries.len().saturating_sub(1);
+        if window.is_key_pressed(Key::Left, KeyRepeat::Yes) {
+            self.selected = self.selected.saturating_sub(1);
+        }
+        if window.is_key_pressed(Key::Right, KeyRepeat::Yes) {
+            self.selected = (self.selected + 1).min(last);
+        }
+        if window.is_key_pressed(Key::Up, KeyRepeat::Yes) {
+            self.selected = self.selected.saturating_sub(columns);
+        }
+        if window.is_key_pressed(Key::Down, KeyRepeat::Yes) {
+            self.selected = (self.selected + columns).min(last);
+        }
+        if window.is_key_pressed(Key::Enter, KeyRepeat::No) {
+            let entry = self.entries.get(self.selected)?;
+            match &entry.info {
+                Ok(_) => return Some(entry.path.clone()),
+                Err(e) => eprintln!("No se puede abrir {}: {}", entry.path.display(), e),
+            }
+        }
+        None
+    }
+
+    pub fn render(&self, framebuffer: &mut Framebuffer) {
+        framebuffer.draw_text(MARGIN, 16, "CARGAR ESCENA", 0xFFD080);
+        let columns = Self::columns(framebuffer.width);
+        let visible_rows = (framebuffer.height.saturating_sub(TITLE_HEIGHT + 30) / CARD_HEIGHT).max(1);
+        // Las filas se desplazan para que la elegida siempre quede a la vista
+        let first_row = (self.selected / columns).saturating_sub(visible_rows - 1);
+
+        for (index, entry) in self.entries.iter().enumerate().skip(first_row * columns).take(visible_rows * columns) {
+            let x = MARGIN + (index % columns) * CARD_WIDTH;
+            let y = TITLE_HEIGHT + (index / columns - first_row) * CARD_HEIGHT;
+            let selected = index == self.selected;
+
+            let border = if selected { 0x80FF80 } else { 0x404040 };
+            for dy in 0..THUMBNAIL_HEIGHT + 4 {
+                for dx in 0..THUMBNAIL_WIDTH + 4 {
+                    let edge = dx < 2 || dy < 2 || dx >= THUMBNAIL_WIDTH + 2 || dy >= THUMBNAIL_HEIGHT + 2;
+                    let color = if edge { border } else { entry.thumbnail[(dy - 2) * THUMBNAIL_WIDTH + dx - 2] };
+                    framebuffer.overlay_pixel(x + dx, y + dy, color);
+                }
+            }
+
+            let text_y = y + THUMBNAIL_HEIGHT + 8;
+            let name_color = if selected { 0x80FF80 } else { 0xE0E0E0 };
+            let marker = if entry.recent { "*" } else { "" };
+            framebuffer.draw_text(x, text_y, &format!("{}{}", marker, entry.name), name_color);
+            match &entry.info {
+                Ok(info) => {
+                    framebuffer.draw_text(x, text_y + text::LINE_HEIGHT, &format!("{} cuerpos", info.bodies), 0xA0A0A0);
+                    let seed = info.seed.map_or("-".to_string(), |seed| seed.to_string());
+                    framebuffer.draw_text(x, text_y + 2 * text::LINE_HEIGHT, &format!("Semilla: {}", seed), 0xA0A0A0);
+                }
+                Err(_) => framebuffer.draw_text(x, text_y + text::LINE_HEIGHT, "Con errores", 0xFF6060),
+            }
+        }
+        framebuffer.draw_text(
+            MARGIN,
+            framebuffer.height.saturating_sub(30),
+            "Flechas: elegir   Enter: abrir   ESC: salir   *: reciente",
+            0xE0E0E0,
+        );
+    }
+}
+
+// Abre una ventana con el menú de las escenas de `directory` y devuelve la
+// elegida, o None si se cerró sin elegir
+pub fn choose(directory: &Path, width: usize, height: usize) -> Option<PathBuf> {
+    let mut menu = SceneMenu::new(directory);
+    if menu.is_empty() {
+        eprintln!("No hay escenas en {}", directory.display());
+        return None;
+    }
+    let mut window = match Window::new("Proyecto 3 - Cargar escena", width, height, WindowOptions::default()) {
+        Ok(window) => window,
+        Err(e) => {
+            eprintln!("No se pudo abrir el menú de escenas: {}", e);
+            return None;
+        }
+    };
+    let mut framebuffer = Framebuffer::new(width, height);
+    while window.is_open() && !window.is_key_down(Key::Escape) {
+        if let Some(path) = menu.update(&window, width) {
+            return Some(path);
+        }
+        framebuffer.clear();
+        menu.render(&mut framebuffer);
+        window.update_with_buffer(framebuffer.present(), width, height).ok()?;
+    }
+    None
+}
@@ -0,0 +1,91 @@
+// Iluminación en pantalla para la calidad baja (`--quality low`): los cuerpos
+// lejanos se rasterizan con el color del shader sin iluminar y, al terminar,
+// un solo pase reconstruye la normal de cada uno de esos píxeles a partir del
+// z-buffer (posición de los vecinos en el espacio de la cámara) y aplica un
+// Lambert simple con la luz ambiental. No hay especular, reflejos, atmósfera
+// ni sombra de la nave: a esa distancia apenas se notan.
+
+use nalgebra_glm::{inverse, Mat4, Vec3, Vec4};
+
+use crate::framebuffer::{pack_color, Framebuffer};
+use crate::lighting::Light;
+
+// Con calidad baja, los cuerpos de radio en pantalla menor a este (píxeles)
+// se iluminan en el pase en pantalla
+pub const SCREEN_LIGHTING_RADIUS: f32 = 60.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Quality {
+    #[default]
+    High,
+    Low,
+}
+
+impl Quality {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "high" => Some(Quality::High),
+            "low" => Some(Quality::Low),
+            _ => None,
+        }
+    }
+}
+
+pub fn apply(framebuffer: &mut Framebuffer, view_matrix: &Mat4, projection_matrix: &Mat4, light: &Light) {
+    let (width, height) = (framebuffer.width, framebuffer.height);
+    let inverse_projection = inverse(projection_matrix);
+    let zbuffer = &framebuffer.zbuffer;
+    let index_of = framebuffer.indexer();
+    // Posición en el espacio de la cámara del píxel (x, y), si tiene geometría
+    let view_position = |x: usize, y: usize| -> Option<Vec3> {
+        let depth = zbuffer[index_of(x, y)];
+        if !depth.is_finite() {
+            return None;
+        }
+        let ndc_x = (x as f32 + 0.5) / width as f32 * 2.0 - 1.0;
+        let ndc_y = 1.0 - (y as f32 + 0.5) / height as f32 * 2.0;
+        let view = inverse_projection * Vec4::new(ndc_x, ndc_y, depth, 1.0);
+        Some(view.xyz() / view.w)
+    };
+    // Diferencia hacia el vecino más parecido en profundidad de los dos
+    // lados, para no mezclar el cuerpo con lo que tiene detrás en el borde
+    let tangent = |center: Vec3, before: Option<Vec3>, after: Option<Vec3>| -> Option<Vec3> {
+        match (before.map(|p| center - p), after.map(|p| p - center)) {
+            (Some(a), Some(b)) => Some(if a.z.abs() < b.z.abs() { a } else { b }),
+            (a, b) => a.or(b),
+        }
+    };
+    let light_view = (view_matrix * Vec4::new(light.position.x, light.position.y, light.position.z, 1.0)).xyz();
+
+    for y in 0..height {
+        for x in 0..width {
+            let index = index_of(x, y);
+            if !framebuffer.unlit[index] {
+                continue;
+            }
+            let Some(center) = view_position(x, y) else {
+                continue;
+            };
+            let left = (x > 0).then(|| view_position(x - 1, y)).flatten();
+            let right = (x + 1 < width).then(|| view_position(x + 1, y)).flatten();
+            let up = (y > 0).then(|| view_position(x, y - 1)).flatten();
+            let down = (y + 1 < height).then(|| view_position(x, y + 1)).flatten();
+            let to_camera = -center.normalize();
+            let normal = match (tangent(center, left, right), tangent(center, up, down)) {
+                (Some(horizontal), Some(vertical)) => horizontal.cross(&vertical).try_normalize(1e-9).unwrap_or(to_camera),
+                _ => to_camera,
+            };
+            // El signo del producto cruz depende del vecino elegido: la
+            // normal visible siempre mira a la cámara
+            let normal = if normal.dot(&to_camera) < 0.0 { -normal } else { normal };
+
+            let albedo = framebuffer.hdr[index];
+            let to_light = (light_view - center).normalize();
+            let diffuse = normal.dot(&to_light).max(0.0);
+            let color = albedo.component_mul(&light.color) * diffuse + albedo.component_mul(&light.ambient);
+            framebuffer.buffer[index] = pack_color(color);
+            framebuffer.hdr[index] = color;
+            framebuffer.unlit[index] = false;
+        }
+    }
+}
@@ -0,0 +1,185 @@
+// Sombreado por lotes: los shaders cuyo ruido solo depende de la dirección
+// y del tiempo (ver `NoiseLayer`) evalúan sus capas de fbm para LANES
+// fragmentos a la vez, con los datos separados por componente (SoA) en
+// arreglos fijos que el compilador puede vectorizar. El resto de cada shader
+// sigue siendo escalar, y los que no tienen camino por lotes (`PlanetShader::batch`)
+// sombrean cada fragmento por separado.
+
+use std::array;
+use std::ops::{Add, Mul, Sub};
+
+use nalgebra_glm::Vec3;
+
+use crate::shaders::{octave_weight, sin_approx, NoiseLayer};
+
+pub const LANES: usize = 8;
+
+#[derive(Clone, Copy)]
+struct Lanes([f32; LANES]);
+
+impl Lanes {
+    fn splat(value: f32) -> Self {
+        Lanes([value; LANES])
+    }
+
+    fn map(self, f: impl Fn(f32) -> f32) -> Self {
+        Lanes(self.0.map(f))
+    }
+
+    // a + t * (b - a), carril por carril
+    fn mix(a: Lanes, b: Lanes, t: Lanes) -> Lanes {
+        a + t * (b - a)
+    }
+}
+
+impl Add for Lanes {
+    type Output = Lanes;
+    fn add(self, other: Lanes) -> Lanes {
+        Lanes(array::from_fn(|i| self.0[i] + other.0[i]))
+    }
+}
+
+impl Sub for Lanes {
+    type Output = Lanes;
+    fn sub(self, other: Lanes) -> Lanes {
+        Lanes(array::from_fn(|i| self.0[i] - other.0[i]))
+    }
+}
+
+impl Mul for Lanes {
+    type Output = Lanes;
+    fn mul(self, other: Lanes) -> Lanes {
+        Lanes(array::from_fn(|i| self.0[i] * other.0[i]))
+    }
+}
+
+impl Mul<f32> for Lanes {
+    type Output = Lanes;
+    fn mul(self, factor: f32) -> Lanes {
+        self.map(|x| x * factor)
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Vec3Lanes {
+    x: Lanes,
+    y: Lanes,
+    z: Lanes,
+}
+
+impl Vec3Lanes {
+    fn from_points(points: &[Vec3; LANES]) -> Self {
+        Vec3Lanes {
+            x: Lanes(points.map(|p| p.x)),
+            y: Lanes(points.map(|p| p.y)),
+            z: Lanes(points.map(|p| p.z)),
+        }
+    }
+
+    fn map(self, f: impl Fn(f32) -> f32 + Copy) -> Self {
+        Vec3Lanes { x: self.x.map(f), y: self.y.map(f), z: self.z.map(f) }
+    }
+
+    // Suma el mismo vector a todos los carriles
+    fn offset(self, v: Vec3) -> Self {
+        Vec3Lanes { x: self.x + Lanes::splat(v.x), y: self.y + Lanes::splat(v.y), z: self.z + Lanes::splat(v.z) }
+    }
+
+    fn scale(self, factor: f32) -> Self {
+        Vec3Lanes { x: self.x * factor, y: self.y * factor, z: self.z * factor }
+    }
+}
+
+// `rand` de los shaders, para ocho esquinas a la vez
+fn rand_lanes(p: Vec3Lanes) -> Lanes {
+    let dot = p.x * 12.9898 + p.y * 78.233 + p.z * 45.5432;
+    dot.map(|x| {
+        let hash = sin_approx(x) * 43758.547;
+        hash - hash as i32 as f32
+    })
+}
+
+// Igual que `noise` en los shaders: interpolación suave entre las esquinas de la celda
+fn noise_lanes(p: Vec3Lanes) -> Lanes {
+    // Parte entera por conversión a i32 (exacta en el rango del ruido), que a
+    // diferencia de `floor` no necesita llamar a la biblioteca matemática
+    let truncated = p.map(|x| x as i32 as f32);
+    let floor = |x: f32, t: f32| if t > x { t - 1.0 } else { t };
+    let i = Vec3Lanes {
+        x: Lanes(array::from_fn(|k| floor(p.x.0[k], truncated.x.0[k]))),
+        y: Lanes(array::from_fn(|k| floor(p.y.0[k], truncated.y.0[k]))),
+        z: Lanes(array::from_fn(|k| floor(p.z.0[k], truncated.z.0[k]))),
+    };
+    let f = Vec3Lanes { x: p.x - truncated.x, y: p.y - truncated.y, z: p.z - truncated.z };
+    // Misma curva que el escalar: se aplica sobre f²
+    let u = Vec3Lanes { x: f.x * f.x, y: f.y * f.y, z: f.z * f.z }.map(|x| x * (3.0 - 2.0 * x));
+    let corner = |x: f32, y: f32, z: f32| rand_lanes(i.offset(Vec3::new(x, y, z)));
+
+    Lanes::mix(
+        Lanes::mix(
+            Lanes::mix(corner(0.0, 0.0, 0.0), corner(1.0, 0.0, 0.0), u.x),
+            Lanes::mix(corner(0.0, 1.0, 0.0), corner(1.0, 1.0, 0.0), u.x),
+            u.y,
+        ),
+        Lanes::mix(
+            Lanes::mix(corner(0.0, 0.0, 1.0), corner(1.0, 0.0, 1.0), u.x),
+            Lanes::mix(corner(0.0, 1.0, 1.0), corner(1.0, 1.0, 1.0), u.x),
+            u.y,
+        ),
+        u.z,
+    )
+}
+
+// Igual que `fbm_filtered`, con el tamaño del píxel de cada carril; una
+// octava solo se salta cuando todos los carriles la tienen apagada
+fn fbm_lanes(p: Vec3Lanes, octaves: i32, persistence: f32, lacunarity: f32, footprint: Lanes) -> Lanes {
+    let mut total = Lanes::splat(0.0);
+    let mut frequency = 1.0;
+    let mut amplitude = 1.0;
+    let mut max_value = 0.0;
+
+    for _ in 0..octaves {
+        let weight = footprint.map(|footprint| octave_weight(frequency, footprint));
+        if weight.0.iter().any(|&w| w > 0.0) {
+            total = total + noise_lanes(p.scale(frequency)) * amplitude * weight;
+        }
+        max_value += amplitude;
+        amplitude *= persistence;
+        frequency *= lacunarity;
+    }
+
+    total.map(|x| x / max_value)
+}
+
+fn sample_layer(layer: &NoiseLayer, uv: Vec3Lanes, time: f32, footprint: Lanes) -> Lanes {
+    let point = uv.scale(layer.scale).offset(layer.drift * time);
+    fbm_lanes(point, layer.octaves, layer.persistence, layer.lacunarity, footprint * layer.scale)
+}
+
+type Finish = fn(Vec3, f32, f32, &[f32]) -> Vec3;
+
+// Capas de ruido y final escalar de un shader con camino por lotes
+pub type BatchShader = (&'static [NoiseLayer], Finish);
+
+// Colores de `points` (ya rotados por la semilla) con el camino por lotes
+// de un shader, con el tamaño del píxel de cada uno en `footprints`. El
+// último grupo se completa repitiendo su último punto.
+pub fn shade_batch((layers, finish): BatchShader, points: &[Vec3], footprints: &[f32], time: f32, colors: &mut Vec<Vec3>) {
+    let mut noise = vec![[0.0; LANES]; layers.len()];
+    let mut lane_noise = vec![0.0; layers.len()];
+    for (chunk, footprints) in points.chunks(LANES).zip(footprints.chunks(LANES)) {
+        let uv: [Vec3; LANES] = array::from_fn(|i| chunk[i.min(chunk.len() - 1)].normalize());
+        let uv_lanes = Vec3Lanes::from_points(&uv);
+        let footprint = Lanes(array::from_fn(|i| footprints[i.min(footprints.len() - 1)]));
+        for (values, layer) in noise.iter_mut().zip(layers) {
+            *values = sample_layer(layer, uv_lanes, time, footprint).0;
+        }
+        for (lane, uv) in uv.iter().enumerate().take(chunk.len()) {
+            for (value, values) in lane_noise.iter_mut().zip(&noise) {
+                *value = values[lane];
+            }
+            colors.push(finish(*uv, time, footprint.0[lane], &lane_noise));
+        }
+    }
+}
+
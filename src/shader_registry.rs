@@ -0,0 +1,97 @@
+// Registro de shaders de planeta adicionales: además de los de `shaders.rs`,
+// el programa puede registrar al arrancar shaders propios con un nombre (el
+// que usan las escenas en `shader = "..."`), una función de color y la lista
+// de parámetros numéricos que aceptan (`params = { ... }` en cada cuerpo).
+// Así se suman tipos de planeta sin tocar `shaders.rs`: cada shader
+// registrado es un `PlanetShader` más.
+
+use std::fmt;
+use std::sync::OnceLock;
+
+use nalgebra_glm::Vec3;
+
+use crate::scene::builtin_shader;
+use crate::shaders::{FragmentInput, PlanetShader, Surface};
+use crate::Uniforms;
+
+// Color de un punto de la esfera unitaria en un instante, con el tamaño del
+// píxel sobre la esfera (para `fbm_filtered`); los parámetros del cuerpo
+// llegan en `surface.params`, en el orden en que se declararon
+pub type ShadeFn = Box<dyn Fn(Vec3, f32, f32, &Surface) -> Vec3 + Send + Sync>;
+
+// Un parámetro que acepta el shader, con su valor por defecto y su rango válido
+#[derive(Debug, Clone, Copy)]
+pub struct ShaderParam {
+    pub name: &'static str,
+    pub default: f32,
+    pub min: f32,
+    pub max: f32,
+}
+
+impl ShaderParam {
+    pub const fn new(name: &'static str, default: f32, min: f32, max: f32) -> Self {
+        ShaderParam { name, default, min, max }
+    }
+}
+
+pub struct CustomShader {
+    pub name: String,
+    pub params: Vec<ShaderParam>,
+    shade: ShadeFn,
+}
+
+impl fmt::Debug for CustomShader {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CustomShader").field("name", &self.name).field("params", &self.params).finish()
+    }
+}
+
+impl PlanetShader for CustomShader {
+    fn shade(&self, fragment: &FragmentInput, uniforms: &Uniforms) -> Vec3 {
+        (self.shade)(fragment.point, uniforms.shader_time, fragment.footprint, uniforms.surface)
+    }
+}
+
+#[derive(Default)]
+pub struct ShaderRegistry {
+    shaders: Vec<CustomShader>,
+}
+
+impl ShaderRegistry {
+    // Agrega un shader; el nombre no puede repetir el de otro registrado ni
+    // el de uno de `shaders.rs`
+    pub fn register(
+        &mut self,
+        name: &str,
+        params: Vec<ShaderParam>,
+        shade: impl Fn(Vec3, f32, f32, &Surface) -> Vec3 + Send + Sync + 'static,
+    ) -> Result<(), String> {
+        if builtin_shader(name).is_some() || self.get(name).is_some() {
+            return Err(format!("ya hay un shader llamado '{}'", name));
+        }
+        self.shaders.push(CustomShader { name: name.to_string(), params, shade: Box::new(shade) });
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&CustomShader> {
+        self.shaders.iter().find(|shader| shader.name == name)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.shaders.iter().map(|shader| shader.name.as_str())
+    }
+}
+
+static REGISTRY: OnceLock<ShaderRegistry> = OnceLock::new();
+
+// Fija los shaders registrados para todo el programa. Debe llamarse antes de
+// cargar cualquier escena; si el registro ya se había fijado (o leído) se
+// devuelve sin cambios.
+pub fn install(registry: ShaderRegistry) -> Result<(), ShaderRegistry> {
+    REGISTRY.set(registry)
+}
+
+// Registro del programa (vacío si nadie llamó a `install`)
+pub fn registry() -> &'static ShaderRegistry {
+    REGISTRY.get_or_init(ShaderRegistry::default)
+}
@@ -1,29 +1,87 @@
-use nalgebra_glm::{Vec3, Vec4, Mat3};
+use std::fmt;
+
+use nalgebra_glm::{Vec2, Vec3, Vec4, Mat3, Mat4};
+use serde::{Deserialize, Serialize};
 use crate::vertex::Vertex;
+use crate::texture::Texture;
 use crate::Uniforms;
+use crate::shade_batch::BatchShader;
 
-pub fn vertex_shader(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
-    let position = Vec4::new(vertex.position.x, vertex.position.y, vertex.position.z, 1.0);
-    
-    // Aplicar transformación completa: Model -> View -> Projection
-    let transformed = uniforms.projection_matrix * uniforms.view_matrix * uniforms.model_matrix * position;
+// Lo que el rasterizado sabe de cada fragmento que se va a sombrear
+pub struct FragmentInput {
+    // Posición en espacio de objeto, ya rotada por la semilla del cuerpo
+    pub point: Vec3,
+    pub tex_coords: Vec2,
+    // Tamaño del píxel sobre la esfera unitaria (0 = todo el detalle)
+    pub footprint: f32,
+}
+
+// Un fragment shader. Cada tipo de cuerpo (y cada sombreado interno:
+// textura, anillos, circuito) es una implementación, y `Uniforms::shader`
+// dice cuál usar al dibujar; así un shader nuevo no toca el rasterizado.
+pub trait PlanetShader: fmt::Debug + Sync {
+    fn shade(&self, fragment: &FragmentInput, uniforms: &Uniforms) -> Vec3;
 
-    // La normal se transforma solo con la matriz del modelo
-    let model_mat3 = Mat3::from_columns(&[
-        uniforms.model_matrix.column(0).xyz(),
-        uniforms.model_matrix.column(1).xyz(),
-        uniforms.model_matrix.column(2).xyz(),
-    ]);
-    let normal_matrix = model_mat3.transpose().try_inverse().unwrap_or_else(Mat3::identity);
-    let transformed_normal = (normal_matrix * vertex.normal).normalize();
+    // Color y densidad (0..1) que multiplica la opacidad del fragmento; solo
+    // los anillos planetarios la usan
+    fn shade_with_density(&self, fragment: &FragmentInput, uniforms: &Uniforms) -> (Vec3, f32) {
+        (self.shade(fragment, uniforms), 1.0)
+    }
+
+    // Capas de ruido y final escalar para sombrear de a grupos de
+    // fragmentos (ver `shade_batch`), si el shader lo permite
+    fn batch(&self) -> Option<BatchShader> {
+        None
+    }
 
-    Vertex {
-        position: vertex.position,
-        normal: vertex.normal,
-        tex_coords: vertex.tex_coords,
-        color: vertex.color,
-        transformed_position: transformed,
-        transformed_normal,
+    // Si deja fragmentos translúcidos aunque se dibuje con alpha 1: sus
+    // triángulos se ordenan de atrás hacia adelante (ver `render_model`)
+    fn translucent(&self) -> bool {
+        false
+    }
+}
+
+// El vertex shader con las matrices de un modelo ya combinadas: se arma una
+// vez por modelo y se aplica a cada vértice (ver `vertex_stage`)
+#[derive(Clone, Copy)]
+pub struct VertexShader {
+    model_matrix: Mat4,
+    view_projection: Mat4,
+    normal_matrix: Mat3,
+}
+
+impl VertexShader {
+    pub fn new(uniforms: &Uniforms) -> Self {
+        // La normal se transforma solo con la matriz del modelo
+        let model_mat3 = Mat3::from_columns(&[
+            uniforms.model_matrix.column(0).xyz(),
+            uniforms.model_matrix.column(1).xyz(),
+            uniforms.model_matrix.column(2).xyz(),
+        ]);
+        VertexShader {
+            model_matrix: uniforms.model_matrix,
+            view_projection: uniforms.projection_matrix * uniforms.view_matrix,
+            normal_matrix: model_mat3.transpose().try_inverse().unwrap_or_else(Mat3::identity),
+        }
+    }
+
+    pub fn shade(&self, vertex: &Vertex) -> Vertex {
+        let position = Vec4::new(vertex.position.x, vertex.position.y, vertex.position.z, 1.0);
+
+        // Aplicar transformación completa: Model -> View -> Projection
+        let world = self.model_matrix * position;
+        let transformed = self.view_projection * world;
+        let transformed_normal = (self.normal_matrix * vertex.normal).normalize();
+
+        Vertex {
+            position: vertex.position,
+            normal: vertex.normal,
+            tex_coords: vertex.tex_coords,
+            color: vertex.color,
+            transformed_position: transformed,
+            transformed_normal,
+            world_position: world.xyz(),
+        }
     }
 }
 
@@ -50,18 +108,66 @@ fn noise(p: Vec3) -> f32 {
     )
 }
 
+// Seno sin ramas, para que se vectorice: se lleva x a [-π, π] restando
+// vueltas completas (2π partido en dos constantes para no perder precisión),
+// se refleja a [-π/2, π/2] y se evalúa un polinomio de Taylor de grado 11.
+// El error ronda 1e-7, como el de `f32::sin`; lo usa el hash del ruido
+// para que el camino por lotes (`shade_batch`) dé lo mismo que el escalar.
+pub fn sin_approx(x: f32) -> f32 {
+    const TAU_HIGH: f32 = 6.28125;
+    const TAU_LOW: f32 = 0.001_935_307_2;
+    // Redondeo con una conversión a entero, que también se vectoriza
+    let turns = x * std::f32::consts::FRAC_1_PI * 0.5;
+    let turns = (turns + 0.5f32.copysign(turns)) as i32 as f32;
+    let r = x - turns * TAU_HIGH - turns * TAU_LOW;
+    let half_pi = std::f32::consts::FRAC_PI_2;
+    let r = if r.abs() > half_pi { std::f32::consts::PI.copysign(r) - r } else { r };
+    let r2 = r * r;
+    let poly = 1.0 + r2 * (-1.0 / 6.0 + r2 * (1.0 / 120.0 + r2 * (-1.0 / 5040.0 + r2 * (1.0 / 362_880.0 + r2 * (-1.0 / 39_916_800.0)))));
+    r * poly
+}
+
 fn rand(p: Vec3) -> f32 {
-    (p.dot(&Vec3::new(12.9898, 78.233, 45.5432)).sin() * 43758.5453).fract()
+    let dot = p.x * 12.9898 + p.y * 78.233 + p.z * 45.5432;
+    (sin_approx(dot) * 43758.547).fract()
+}
+
+// Ruido "ridged": vale 1 sobre las líneas donde el ruido cruza 0.5, útil
+// para cauces y crestas finas
+fn ridged(p: Vec3) -> f32 {
+    1.0 - (noise(p) * 2.0 - 1.0).abs()
 }
 
-fn fbm(p: Vec3, octaves: i32, persistence: f32, lacunarity: f32) -> f32 {
+pub fn fbm(p: Vec3, octaves: i32, persistence: f32, lacunarity: f32) -> f32 {
+    fbm_filtered(p, octaves, persistence, lacunarity, 0.0)
+}
+
+// Tamaño de un píxel, en celdas de una octava, entre los que esa octava se
+// desvanece: con celdas de menos de dos píxeles el detalle ya no se distingue
+// y solo parpadea al moverse la cámara
+pub const FADE_START: f32 = 0.25;
+pub const FADE_END: f32 = 0.5;
+
+// Peso 0..1 de una octava de frecuencia `frequency` con píxeles de `footprint`
+pub fn octave_weight(frequency: f32, footprint: f32) -> f32 {
+    ((FADE_END - frequency * footprint) / (FADE_END - FADE_START)).clamp(0.0, 1.0)
+}
+
+// fbm filtrado como un mipmap procedural: `footprint` es el tamaño de un
+// píxel en las unidades de `p`, y las octavas más finas que eso se apagan
+// (sin evaluarse) en lugar de sumar ruido que no se puede mostrar. La
+// normalización no cambia, así el valor medio del patrón se mantiene.
+pub fn fbm_filtered(p: Vec3, octaves: i32, persistence: f32, lacunarity: f32, footprint: f32) -> f32 {
     let mut total = 0.0;
     let mut frequency = 1.0;
     let mut amplitude = 1.0;
     let mut max_value = 0.0;
 
     for _ in 0..octaves {
-        total += noise(p * frequency) * amplitude;
+        let weight = octave_weight(frequency, footprint);
+        if weight > 0.0 {
+            total += noise(p * frequency) * amplitude * weight;
+        }
         max_value += amplitude;
         amplitude *= persistence;
         frequency *= lacunarity;
@@ -70,9 +176,189 @@ fn fbm(p: Vec3, octaves: i32, persistence: f32, lacunarity: f32) -> f32 {
     total / max_value
 }
 
+// `noise` con el mismo filtrado (una sola octava)
+fn noise_filtered(p: Vec3, footprint: f32) -> f32 {
+    fbm_filtered(p, 1, 1.0, 1.0, footprint)
+}
+
+// Capa de ruido que solo depende de la dirección `uv` y del tiempo: fbm de
+// `uv * scale + drift * time`. Los shaders que la usan declaran sus capas
+// como constantes, así el camino por lotes (`shade_batch`) evalúa exactamente
+// el mismo ruido que el escalar.
+pub struct NoiseLayer {
+    pub scale: f32,
+    pub drift: Vec3,
+    pub octaves: i32,
+    pub persistence: f32,
+    pub lacunarity: f32,
+}
+
+impl NoiseLayer {
+    // Con una sola octava, fbm equivale a `noise`
+    const fn single(scale: f32, drift: Vec3) -> Self {
+        NoiseLayer { scale, drift, octaves: 1, persistence: 1.0, lacunarity: 1.0 }
+    }
+
+    // `footprint` es el tamaño de un píxel sobre la esfera unitaria
+    fn sample(&self, uv: Vec3, time: f32, footprint: f32) -> f32 {
+        fbm_filtered(uv * self.scale + self.drift * time, self.octaves, self.persistence, self.lacunarity, footprint * self.scale)
+    }
+}
+
+// Evalúa todas las capas de un shader para un punto
+fn sample_layers<const N: usize>(layers: &[NoiseLayer; N], uv: Vec3, time: f32, footprint: f32) -> [f32; N] {
+    std::array::from_fn(|index| layers[index].sample(uv, time, footprint))
+}
+
+// Gira el punto alrededor del eje Y según la semilla, para que dos cuerpos
+// con el mismo shader no muestren el mismo patrón
+pub fn seeded_point(point: Vec3, seed: u32) -> Vec3 {
+    if seed == 0 {
+        return point;
+    }
+    let angle = seed as f32 * 2.399_963; // ángulo áureo
+    let (sin_a, cos_a) = angle.sin_cos();
+    Vec3::new(
+        point.x * cos_a + point.z * sin_a,
+        point.y,
+        -point.x * sin_a + point.z * cos_a,
+    )
+}
+
+// Parámetros de superficie de cada cuerpo que usan algunos shaders
+#[derive(Debug, Clone, Default)]
+pub struct Surface {
+    pub craters: Vec<Crater>,
+    pub climate: Climate,
+    // Colores de las bandas de sus anillos, si tiene
+    pub ring_palette: Vec<Vec3>,
+    // Parámetros de un shader registrado, en el orden en que los declara
+    pub params: Vec<f32>,
+}
+
+// Superficie sin cráteres para lo que no es un cuerpo (nave, anillos, props)
+pub static BARE_SURFACE: Surface = Surface {
+    craters: Vec::new(),
+    climate: Climate::TEMPERATE,
+    ring_palette: Vec::new(),
+    params: Vec::new(),
+};
+
+// Clima del planeta rocoso: la temperatura depende de la latitud, la
+// altura y la inclinación del eje, y de ella salen los biomas
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Climate {
+    // Temperatura media al nivel del mar en el ecuador y en los polos (°C)
+    pub equator_temperature: f32,
+    pub pole_temperature: f32,
+    // Grados que pierden las cumbres más altas respecto a la costa
+    pub lapse_rate: f32,
+    // Inclinación del eje en grados: cuanto mayor, más anchos los trópicos
+    pub axial_tilt: f32,
+}
+
+impl Climate {
+    pub const TEMPERATE: Climate = Climate {
+        equator_temperature: 28.0,
+        pole_temperature: -30.0,
+        lapse_rate: 35.0,
+        axial_tilt: 23.5,
+    };
+
+    // `latitude` es el seno de la latitud (0 en el ecuador, 1 en los polos) y
+    // `elevation` la altura sobre el mar en 0..1
+    fn temperature(&self, latitude: f32, elevation: f32) -> f32 {
+        let tropics = self.axial_tilt.to_radians().sin().abs() * 0.5;
+        let band = ((latitude.abs() - tropics) / (1.0 - tropics)).max(0.0);
+        self.equator_temperature + (self.pole_temperature - self.equator_temperature) * band.powf(1.3)
+            - self.lapse_rate * elevation
+    }
+}
+
+impl Default for Climate {
+    fn default() -> Self {
+        Climate::TEMPERATE
+    }
+}
+
+// Cráter sobre la esfera unitaria: centro y radio (distancia de cuerda)
+#[derive(Debug, Clone, Copy)]
+pub struct Crater {
+    center: Vec3,
+    radius: f32,
+}
+
+// Cráteres sembrados de forma uniforme sobre la esfera; abundan los pequeños
+pub fn crater_field(seed: u32, count: u32) -> Vec<Crater> {
+    let base = (seed % 10_007) as f32;
+    (0..count)
+        .map(|i| {
+            let cell = Vec3::new(base, i as f32, base * 0.5 + i as f32 * 1.7);
+            let u = rand(cell);
+            let v = rand(cell + Vec3::new(3.1, 7.7, 1.3));
+            let size = rand(cell + Vec3::new(9.2, 2.4, 5.6));
+            let z = u * 2.0 - 1.0;
+            let ring = (1.0 - z * z).max(0.0).sqrt();
+            let angle = v * std::f32::consts::TAU;
+            Crater {
+                center: Vec3::new(ring * angle.cos(), z, ring * angle.sin()),
+                radius: 0.04 + 0.16 * size * size,
+            }
+        })
+        .collect()
+}
+
+// Oscurece el fondo de cada cráter y aclara su borde. La sombra del cuenco
+// se corre hacia un lado, como si el borde tapara la luz rasante.
+fn apply_craters(color: Vec3, point: Vec3, craters: &[Crater]) -> Vec3 {
+    let uv = point.normalize();
+    let mut factor = 1.0;
+    for crater in craters {
+        // Distancia al punto más cercano del centro, relativa al radio
+        let offset = uv - crater.center;
+        let distance = offset.magnitude() / crater.radius;
+        if distance >= 1.3 {
+            continue;
+        }
+        if distance < 1.0 {
+            let side = (offset.y / crater.radius).clamp(-1.0, 1.0);
+            let bowl = (1.0 - distance * distance) * (0.3 + 0.2 * side);
+            factor *= 1.0 - bowl;
+        }
+        let rim = (1.0 - (distance - 1.05).abs() / 0.25).max(0.0);
+        factor *= 1.0 + rim * 0.25;
+    }
+    color * factor
+}
+
+// Detalle fino para ver un planeta de cerca (al aterrizar): octavas de ruido
+// más allá de las del shader que aclaran y oscurecen su color sin cambiar el
+// tono. `detail` va de 0 (sin efecto) a 1.
+const DETAIL_SCALE: f32 = 48.0;
+const DETAIL_OCTAVES: i32 = 4;
+const DETAIL_CONTRAST: f32 = 0.3;
+
+pub fn apply_surface_detail(color: Vec3, point: Vec3, detail: f32, footprint: f32) -> Vec3 {
+    let grain = fbm_filtered(point.normalize() * DETAIL_SCALE, DETAIL_OCTAVES, 0.55, 2.1, footprint * DETAIL_SCALE);
+    color * (1.0 + grain * DETAIL_CONTRAST * detail)
+}
+
+// Manchas solares, turbulencia del plasma y llamaradas
+pub const STAR_NOISE: [NoiseLayer; 3] = [
+    NoiseLayer { scale: 4.0, drift: Vec3::new(0.1, 0.0, 0.0), octaves: 3, persistence: 0.6, lacunarity: 2.0 },
+    NoiseLayer { scale: 8.0, drift: Vec3::new(0.3, 0.2, 0.0), octaves: 4, persistence: 0.5, lacunarity: 2.5 },
+    NoiseLayer::single(15.0, Vec3::new(0.8, 0.0, 0.0)),
+];
+
 // Shaders para los cuerpos celestes
-pub fn shade_star(point: Vec3, time: f32) -> Vec3 {
+pub fn shade_star(point: Vec3, time: f32, footprint: f32) -> Vec3 {
     let uv = point.normalize();
+    finish_star(uv, time, footprint, &sample_layers(&STAR_NOISE, uv, time, footprint))
+}
+
+// El resto del shader de la estrella, con las capas de `STAR_NOISE` ya evaluadas
+pub fn finish_star(uv: Vec3, time: f32, _footprint: f32, noise: &[f32]) -> Vec3 {
     let dist_to_center = uv.magnitude();
     
     // Núcleo ultra brillante con gradiente suave
@@ -97,28 +383,21 @@ pub fn shade_star(point: Vec3, time: f32) -> Vec3 {
     }
     
     // Manchas solares (sunspots) - regiones más oscuras
-    let sunspot_freq = 4.0;
-    let sunspot_pattern = fbm(uv * sunspot_freq + Vec3::new(time * 0.1, 0.0, 0.0), 3, 0.6, 2.0);
+    let sunspot_pattern = noise[0];
     if sunspot_pattern > 0.65 {
         let spot_intensity = (sunspot_pattern - 0.65) * 2.0;
         color *= 1.0 - (spot_intensity * 0.4);
     }
     
     // Turbulencia de plasma solar
-    let turbulence_freq = 8.0;
-    let turbulence = fbm(
-        uv * turbulence_freq + Vec3::new(time * 0.3, time * 0.2, 0.0),
-        4,
-        0.5,
-        2.5
-    );
+    let turbulence = noise[1];
     let plasma_color = Vec3::new(1.4, 0.6, 0.05);
     color = color.lerp(&plasma_color, turbulence * 0.25);
     
     // Llamaradas solares (solar flares)
     let flare_angle = (uv.y.atan2(uv.x) + time * 0.5).sin();
     let flare_distance = dist_to_center + flare_angle * 0.1;
-    let flare_noise = noise(uv * 15.0 + Vec3::new(time * 0.8, 0.0, 0.0));
+    let flare_noise = noise[2];
     if flare_noise > 0.8 && flare_distance > 0.85 {
         let flare_intensity = (flare_noise - 0.8) * 5.0;
         color = color.lerp(&Vec3::new(1.6, 0.8, 0.2), flare_intensity * 0.5);
@@ -139,15 +418,15 @@ pub fn shade_star(point: Vec3, time: f32) -> Vec3 {
     // Aumentar brillo cerca del núcleo
     color *= 1.0 + core_brightness * 0.8;
 
-    color.map(|x| x.max(0.0).min(2.0)) // Permitir valores muy brillantes
+    color.map(|x| x.clamp(0.0, 2.0)) // Permitir valores muy brillantes
 }
 
-pub fn shade_rocky(point: Vec3, time: f32) -> Vec3 {
+pub fn shade_rocky(point: Vec3, time: f32, footprint: f32, climate: &Climate) -> Vec3 {
     let uv = point.normalize();
 
     // Generación mejorada de continentes
     let continent_freq = 2.5;
-    let continent_noise = fbm(uv * continent_freq, 4, 0.55, 2.1);
+    let continent_noise = fbm_filtered(uv * continent_freq, 4, 0.55, 2.1, footprint * continent_freq);
     
     let threshold = 0.48;
     let is_land = continent_noise > threshold;
@@ -161,31 +440,48 @@ pub fn shade_rocky(point: Vec3, time: f32) -> Vec3 {
     let land_forest = Vec3::new(0.08, 0.35, 0.10);
     let land_mountain = Vec3::new(0.45, 0.45, 0.47);
     let land_snow = Vec3::new(0.92, 0.95, 0.98);
+    let land_tundra = Vec3::new(0.50, 0.50, 0.40);
+    let land_desert = Vec3::new(0.84, 0.70, 0.42);
 
     let mut color;
     if is_land {
         let elevation = (continent_noise - threshold) / (1.0 - threshold);
-        
-        // Biomas basados en altura
-        if elevation < 0.1 {
-            // Playa
-            color = land_beach;
-        } else if elevation < 0.4 {
-            // Pradera
-            color = land_grass;
-            let grass_detail = noise(uv * 20.0);
-            color = color.lerp(&land_forest, grass_detail * 0.3);
-        } else if elevation < 0.7 {
-            // Bosque/colinas
-            color = land_forest.lerp(&land_mountain, (elevation - 0.4) / 0.3);
-        } else {
-            // Montañas nevadas
-            color = land_mountain.lerp(&land_snow, (elevation - 0.7) / 0.3);
+        let temperature = climate.temperature(uv.y, elevation);
+
+        // Biomas según la temperatura: desierto donde hace calor y falta
+        // humedad, pradera y bosque en zonas templadas, tundra y nieve en el frío
+        let moisture = noise_filtered(uv * 5.0 + Vec3::new(3.7, 1.9, 8.1), footprint * 5.0);
+        let temperate = land_grass.lerp(&land_forest, (moisture + noise_filtered(uv * 20.0, footprint * 20.0) * 0.3).min(1.0));
+        let cold = ((5.0 - temperature) / 15.0).clamp(0.0, 1.0);
+        let frozen = ((-8.0 - temperature) / 8.0).clamp(0.0, 1.0);
+        let arid = ((temperature - 20.0) / 8.0).clamp(0.0, 1.0) * (1.3 - moisture).min(1.0);
+        color = temperate.lerp(&land_tundra, cold).lerp(&land_desert, arid);
+
+        // Roca desnuda en las montañas, salvo donde la cubre la nieve
+        if elevation > 0.55 {
+            color = color.lerp(&land_mountain, ((elevation - 0.55) / 0.45).min(1.0) * 0.8);
+        }
+        color = color.lerp(&land_snow, frozen);
+        // Playas en la costa, excepto en las costas heladas
+        if elevation < 0.06 {
+            color = land_beach.lerp(&color, (elevation / 0.06).max(frozen));
         }
         
         // Detalle de terreno
-        let terrain_detail = fbm(uv * 15.0, 2, 0.5, 2.0);
+        let terrain_detail = fbm_filtered(uv * 15.0, 2, 0.5, 2.0, footprint * 15.0);
         color *= 0.85 + terrain_detail * 0.3;
+
+        // Ríos: crestas finas de ruido ridged (con el dominio deformado para
+        // que serpenteen) que bajan de las montañas hasta la costa; se
+        // ensanchan hacia la desembocadura y no llegan a las cumbres nevadas
+        let warp = Vec3::new(noise_filtered(uv * 4.0, footprint * 4.0), noise_filtered(uv * 4.0 + Vec3::new(5.2, 1.3, 2.8), footprint * 4.0), 0.0);
+        let channel = ridged(uv * 7.0 + warp * 0.8);
+        let width = 0.04 + (0.7 - elevation).max(0.0) * 0.05;
+        if elevation < 0.7 && frozen < 1.0 && channel > 1.0 - width {
+            let river = ((channel - (1.0 - width)) / width).min(1.0);
+            let fade = ((0.7 - elevation) / 0.15).min(1.0);
+            color = color.lerp(&Vec3::new(0.05, 0.18, 0.32), river * fade * (1.0 - frozen) * 0.85);
+        }
     } else {
         // Océanos con profundidad variable
         let depth = 1.0 - (continent_noise / threshold);
@@ -196,30 +492,52 @@ pub fn shade_rocky(point: Vec3, time: f32) -> Vec3 {
         } else {
             color = ocean_deep;
         }
+
+        // Aguas someras: un degradado turquesa que abraza la costa
+        let coast = Vec3::new(0.25, 0.65, 0.70);
+        if depth < 0.12 {
+            color = color.lerp(&coast, (1.0 - depth / 0.12).powf(1.5) * 0.8);
+        }
         
         // Olas y corrientes oceánicas
-        let wave_pattern = fbm(uv * 25.0 + Vec3::new(time * 0.5, time * 0.3, 0.0), 2, 0.6, 2.0);
+        let wave_pattern = fbm_filtered(uv * 25.0 + Vec3::new(time * 0.5, time * 0.3, 0.0), 2, 0.6, 2.0, footprint * 25.0);
         color = color.lerp(&ocean_shallow, wave_pattern * 0.15);
+
+        // Mar congelado en las latitudes más frías
+        let sea_ice = ((-10.0 - climate.temperature(uv.y, 0.0)) / 6.0).clamp(0.0, 1.0);
+        color = color.lerp(&Vec3::new(0.85, 0.90, 0.95), sea_ice);
     }
     
     // Nubes atmosféricas
     let cloud_freq = 6.0;
-    let cloud_pattern = fbm(uv * cloud_freq + Vec3::new(time * 0.15, 0.0, 0.0), 3, 0.5, 2.0);
+    let cloud_pattern = fbm_filtered(uv * cloud_freq + Vec3::new(time * 0.15, 0.0, 0.0), 3, 0.5, 2.0, footprint * cloud_freq);
     if cloud_pattern > 0.62 {
         let cloud_density = (cloud_pattern - 0.62) * 2.5;
         let cloud_color = Vec3::new(0.95, 0.95, 1.0);
         color = color.lerp(&cloud_color, cloud_density.min(0.85));
     }
 
-    color.map(|x| x.max(0.0).min(1.0))
+    color.map(|x| x.clamp(0.0, 1.0))
 }
 
-pub fn shade_gas_giant(point: Vec3, time: f32) -> Vec3 {
+// Turbulencia de las bandas, detalle del gas y vórtices
+pub const GAS_GIANT_NOISE: [NoiseLayer; 3] = [
+    NoiseLayer { scale: 18.0, drift: Vec3::new(0.25, 0.0, 0.0), octaves: 3, persistence: 0.6, lacunarity: 2.0 },
+    NoiseLayer { scale: 30.0, drift: Vec3::new(0.4, 0.0, 0.0), octaves: 2, persistence: 0.5, lacunarity: 2.0 },
+    NoiseLayer::single(22.0, Vec3::new(0.35, 0.2, 0.0)),
+];
+
+pub fn shade_gas_giant(point: Vec3, time: f32, footprint: f32) -> Vec3 {
     let uv = point.normalize();
+    finish_gas_giant(uv, time, footprint, &sample_layers(&GAS_GIANT_NOISE, uv, time, footprint))
+}
 
+// El resto del shader del gigante gaseoso, con las capas de `GAS_GIANT_NOISE`
+// ya evaluadas; la tormenta solo cubre una zona y se calcula aparte
+pub fn finish_gas_giant(uv: Vec3, time: f32, footprint: f32, noise: &[f32]) -> Vec3 {
     // Bandas atmosféricas múltiples
     let band_freq = 10.0;
-    let band_turbulence = fbm(uv * 18.0 + Vec3::new(time * 0.25, 0.0, 0.0), 3, 0.6, 2.0);
+    let band_turbulence = noise[0];
     let band_position = uv.y * band_freq + band_turbulence * 1.5;
     let bands = (band_position.sin() + 1.0) * 0.5;
     
@@ -241,11 +559,11 @@ pub fn shade_gas_giant(point: Vec3, time: f32) -> Vec3 {
     }
     
     // Turbulencia de gas atmosférico
-    let turbulence_detail = fbm(uv * 30.0 + Vec3::new(time * 0.4, 0.0, 0.0), 2, 0.5, 2.0);
+    let turbulence_detail = noise[1];
     color *= 0.88 + turbulence_detail * 0.24;
     
     // Vórtices y remolinos
-    let vortex_pattern = noise(uv * 22.0 + Vec3::new(time * 0.35, time * 0.2, 0.0));
+    let vortex_pattern = noise[2];
     if vortex_pattern > 0.7 {
         let vortex_intensity = (vortex_pattern - 0.7) * 3.0;
         color = color.lerp(&Vec3::new(0.95, 0.85, 0.7), vortex_intensity * 0.25);
@@ -256,11 +574,12 @@ pub fn shade_gas_giant(point: Vec3, time: f32) -> Vec3 {
     let dist_to_storm = (uv - storm_center).magnitude();
     if dist_to_storm < 0.28 {
         let storm_factor = 1.0 - (dist_to_storm / 0.28);
-        let storm_swirl = fbm(
+        let storm_swirl = fbm_filtered(
             (uv - storm_center) * 15.0 + Vec3::new(time * 0.8, 0.0, 0.0),
             3,
             0.6,
-            2.0
+            2.0,
+            footprint * 15.0
         );
         
         let storm_color_center = Vec3::new(0.95, 0.25, 0.12); // Rojo intenso
@@ -270,7 +589,19 @@ pub fn shade_gas_giant(point: Vec3, time: f32) -> Vec3 {
         color = color.lerp(&storm_color, storm_factor.powf(2.5) * 0.75);
     }
 
-    color.map(|x| x.max(0.0).min(1.0))
+    color.map(|x| x.clamp(0.0, 1.0))
+}
+
+pub fn shade_textured(texture: &Texture, tex_coords: Vec2) -> Vec3 {
+    texture.sample(tex_coords)
+}
+
+// Anillo emisivo con pulsos de luz que recorren el toro
+pub fn shade_checkpoint(point: Vec3, time: f32) -> Vec3 {
+    let angle = point.z.atan2(point.x);
+    let pulse = ((angle * 6.0 - time * 8.0).sin() * 0.5 + 0.5).powf(4.0);
+    let base = Vec3::new(0.1, 0.8, 1.0) * 0.7;
+    base.lerp(&Vec3::new(1.0, 1.0, 1.0), pulse)
 }
 
 pub fn shade_spaceship(_point: Vec3, _time: f32) -> Vec3 {
@@ -278,11 +609,11 @@ pub fn shade_spaceship(_point: Vec3, _time: f32) -> Vec3 {
     Vec3::new(0.5, 0.5, 0.5)
 }
 
-pub fn shade_ice_planet(point: Vec3, time: f32) -> Vec3 {
+pub fn shade_ice_planet(point: Vec3, time: f32, footprint: f32) -> Vec3 {
     let uv = point.normalize();
     
     // Base de hielo con variación
-    let ice_base = fbm(uv * 4.0 + Vec3::new(time * 0.03, 0.0, 0.0), 4, 0.55, 2.0);
+    let ice_base = fbm_filtered(uv * 4.0 + Vec3::new(time * 0.03, 0.0, 0.0), 4, 0.55, 2.0, footprint * 4.0);
     
     let ice_bright = Vec3::new(0.92, 0.95, 1.0);  // Hielo brillante
     let ice_normal = Vec3::new(0.75, 0.85, 0.95); // Hielo azulado
@@ -303,14 +634,14 @@ pub fn shade_ice_planet(point: Vec3, time: f32) -> Vec3 {
     }
     
     // Capas de nieve fresca
-    let snow_pattern = fbm(uv * 12.0, 2, 0.6, 2.0);
+    let snow_pattern = fbm_filtered(uv * 12.0, 2, 0.6, 2.0, footprint * 12.0);
     if snow_pattern > 0.65 {
         let snow_intensity = (snow_pattern - 0.65) * 2.8;
         color = color.lerp(&Vec3::new(0.98, 0.99, 1.0), snow_intensity.min(0.7));
     }
     
     // Grietas profundas con detalle
-    let crack_detail = fbm(uv * 18.0 + Vec3::new(time * 0.05, 0.0, 0.0), 2, 0.5, 2.0);
+    let crack_detail = fbm_filtered(uv * 18.0 + Vec3::new(time * 0.05, 0.0, 0.0), 2, 0.5, 2.0, footprint * 18.0);
     if crack_detail < 0.25 {
         let crack_depth = 1.0 - (crack_detail / 0.25);
         color = color.lerp(&Vec3::new(0.15, 0.25, 0.45), crack_depth * 0.6);
@@ -324,39 +655,58 @@ pub fn shade_ice_planet(point: Vec3, time: f32) -> Vec3 {
     }
     
     // Cristales de hielo brillantes
-    let crystal_noise = noise(uv * 35.0 + Vec3::new(0.0, time * 0.1, 0.0));
+    let crystal_noise = noise_filtered(uv * 35.0 + Vec3::new(0.0, time * 0.1, 0.0), footprint * 35.0);
     if crystal_noise > 0.82 {
         let sparkle = (crystal_noise - 0.82) * 5.0;
         color = color.lerp(&Vec3::new(1.0, 1.0, 1.0), sparkle.min(0.4));
     }
     
-    color.map(|x| x.max(0.0).min(1.0))
+    color.map(|x| x.clamp(0.0, 1.0))
 }
 
-pub fn shade_desert_planet(point: Vec3, time: f32) -> Vec3 {
+pub fn shade_desert_planet(point: Vec3, time: f32, footprint: f32) -> Vec3 {
     let uv = point.normalize();
     
     // Planeta desértico con dunas
     let base_freq = 4.0;
-    let n = fbm(uv * base_freq + Vec3::new(time * 0.02, 0.0, 0.0), 2, 0.6, 2.0);
+    let n = fbm_filtered(uv * base_freq + Vec3::new(time * 0.02, 0.0, 0.0), 2, 0.6, 2.0, footprint * base_freq);
     
     let sand_light = Vec3::new(0.9, 0.7, 0.3);
     let sand_dark = Vec3::new(0.6, 0.4, 0.1);
     
-    let mut color = sand_dark.lerp(&sand_light, n.powf(0.8));
+    // El ruido filtrado puede bajar un poco de 0: powf daría NaN
+    let mut color = sand_dark.lerp(&sand_light, n.max(0.0).powf(0.8));
     
     // Dunas de arena
-    let dunes = (uv.y * 10.0 + noise(uv * 6.0) * 2.0).sin() * 0.5 + 0.5;
+    let dunes = (uv.y * 10.0 + noise_filtered(uv * 6.0, footprint * 6.0) * 2.0).sin() * 0.5 + 0.5;
     color = color.lerp(&Vec3::new(0.95, 0.8, 0.4), dunes * 0.3);
     
-    color.map(|x| x.max(0.0).min(1.0))
+    color.map(|x| x.clamp(0.0, 1.0))
+}
+
+// Dirección cuesta abajo sobre la esfera: gradiente del relieve (diferencias
+// finitas) proyectado al plano tangente y limitado a longitud 1
+fn downhill(uv: Vec3, height: f32) -> Vec3 {
+    let step = 0.02;
+    let relief = |p: Vec3| fbm(p * 3.5, 2, 0.55, 2.0);
+    let gradient = Vec3::new(
+        relief(uv + Vec3::new(step, 0.0, 0.0)) - height,
+        relief(uv + Vec3::new(0.0, step, 0.0)) - height,
+        relief(uv + Vec3::new(0.0, 0.0, step)) - height,
+    ) / step;
+    let tangent = gradient - uv * gradient.dot(&uv);
+    let strength = tangent.magnitude();
+    if strength < 1e-5 {
+        return Vec3::zeros();
+    }
+    -tangent / strength * strength.min(1.0)
 }
 
-pub fn shade_volcanic_planet(point: Vec3, time: f32) -> Vec3 {
+pub fn shade_volcanic_planet(point: Vec3, time: f32, footprint: f32) -> Vec3 {
     let uv = point.normalize();
     
     // Terreno volcánico base
-    let terrain = fbm(uv * 3.5, 4, 0.55, 2.0);
+    let terrain = fbm_filtered(uv * 3.5, 4, 0.55, 2.0, footprint * 3.5);
     
     let rock_dark = Vec3::new(0.12, 0.10, 0.08);    // Roca volcánica oscura
     let rock_normal = Vec3::new(0.25, 0.20, 0.15);  // Roca gris
@@ -372,13 +722,18 @@ pub fn shade_volcanic_planet(point: Vec3, time: f32) -> Vec3 {
         // Zonas de lava activa
         let lava_intensity = (terrain - threshold) / (1.0 - threshold);
         
-        // Flujo de lava con turbulencia
-        let lava_flow = fbm(
-            uv * 8.0 + Vec3::new(time * 0.5, time * 0.3, 0.0),
-            3,
-            0.6,
-            2.0
-        );
+        // Flujo de lava: el ruido se arrastra cuesta abajo desde los puntos
+        // calientes (flow map con dos fases desfasadas que se alternan para
+        // que el patrón no se estire indefinidamente)
+        let relief = fbm(uv * 3.5, 2, 0.55, 2.0);
+        let flow = downhill(uv, relief);
+        let cycle = time * 0.4;
+        let phase_a = cycle.fract();
+        let phase_b = (cycle + 0.5).fract();
+        let advect = |phase: f32, offset: Vec3| fbm_filtered(uv * 8.0 - flow * (phase * 1.5) + offset, 3, 0.6, 2.0, footprint * 8.0);
+        let weight_a = 1.0 - (phase_a * 2.0 - 1.0).abs();
+        let lava_flow = advect(phase_a, Vec3::zeros()) * weight_a
+            + advect(phase_b, Vec3::new(17.3, 5.1, 9.7)) * (1.0 - weight_a);
         
         if lava_intensity > 0.7 {
             // Lava ultra caliente (núcleo)
@@ -391,8 +746,9 @@ pub fn shade_volcanic_planet(point: Vec3, time: f32) -> Vec3 {
             color = rock_hot.lerp(&lava_dark, lava_intensity / 0.4);
         }
         
-        // Pulsación de lava (efecto de flujo)
-        let pulse = (time * 2.5 + uv.x * 8.0 + uv.y * 6.0).sin() * 0.5 + 0.5;
+        // Pulsos de lava que bajan por la pendiente (fase constante a lo largo
+        // de las curvas de nivel del relieve)
+        let pulse = (time * 2.5 + relief * 40.0).sin() * 0.5 + 0.5;
         let pulse_color = Vec3::new(1.4, 0.6, 0.05);
         color = color.lerp(&pulse_color, pulse * lava_intensity * 0.35);
         
@@ -407,11 +763,11 @@ pub fn shade_volcanic_planet(point: Vec3, time: f32) -> Vec3 {
         }
         
         // Textura de roca volcánica
-        let rock_detail = fbm(uv * 15.0, 2, 0.5, 2.0);
+        let rock_detail = fbm_filtered(uv * 15.0, 2, 0.5, 2.0, footprint * 15.0);
         color *= 0.8 + rock_detail * 0.4;
         
         // Grietas con resplandor de lava
-        let crack_pattern = noise(uv * 20.0 + Vec3::new(time * 0.2, 0.0, 0.0));
+        let crack_pattern = noise_filtered(uv * 20.0 + Vec3::new(time * 0.2, 0.0, 0.0), footprint * 20.0);
         if crack_pattern < 0.15 {
             let crack_glow = (0.15 - crack_pattern) * 6.0;
             color = color.lerp(&Vec3::new(1.0, 0.35, 0.0), crack_glow.min(0.5));
@@ -419,22 +775,22 @@ pub fn shade_volcanic_planet(point: Vec3, time: f32) -> Vec3 {
     }
     
     // Ceniza volcánica flotante
-    let ash_pattern = noise(uv * 25.0 + Vec3::new(time * 0.4, time * 0.6, 0.0));
+    let ash_pattern = noise_filtered(uv * 25.0 + Vec3::new(time * 0.4, time * 0.6, 0.0), footprint * 25.0);
     if ash_pattern > 0.78 {
         let ash_density = (ash_pattern - 0.78) * 4.0;
         color = color.lerp(&Vec3::new(0.35, 0.30, 0.28), ash_density.min(0.3));
     }
     
-    color.map(|x| x.max(0.0).min(1.5))
+    color.map(|x| x.clamp(0.0, 1.5))
 }
 
-pub fn shade_ocean_planet(point: Vec3, time: f32) -> Vec3 {
+pub fn shade_ocean_planet(point: Vec3, time: f32, footprint: f32) -> Vec3 {
     let uv = point.normalize();
     
     // Planeta oceánico con olas
     let wave_freq = 15.0;
     let wave_speed = 0.5;
-    let waves = fbm(uv * wave_freq + Vec3::new(time * wave_speed, time * wave_speed * 0.5, 0.0), 3, 0.6, 2.0);
+    let waves = fbm_filtered(uv * wave_freq + Vec3::new(time * wave_speed, time * wave_speed * 0.5, 0.0), 3, 0.6, 2.0, footprint * wave_freq);
     
     let deep_ocean = Vec3::new(0.0, 0.2, 0.5);
     let shallow_ocean = Vec3::new(0.1, 0.5, 0.8);
@@ -447,15 +803,15 @@ pub fn shade_ocean_planet(point: Vec3, time: f32) -> Vec3 {
         color = color.lerp(&foam, (waves - 0.7) * 3.0);
     }
     
-    color.map(|x| x.max(0.0).min(1.0))
+    color.map(|x| x.clamp(0.0, 1.0))
 }
 
-pub fn shade_purple_planet(point: Vec3, time: f32) -> Vec3 {
+pub fn shade_purple_planet(point: Vec3, time: f32, footprint: f32) -> Vec3 {
     let uv = point.normalize();
     
     // Planeta alienígena púrpura con cristales
     let crystal_freq = 8.0;
-    let n = fbm(uv * crystal_freq + Vec3::new(0.0, time * 0.1, 0.0), 4, 0.5, 2.5);
+    let n = fbm_filtered(uv * crystal_freq + Vec3::new(0.0, time * 0.1, 0.0), 4, 0.5, 2.5, footprint * crystal_freq);
     
     let dark_purple = Vec3::new(0.3, 0.1, 0.5);
     let bright_purple = Vec3::new(0.7, 0.2, 0.9);
@@ -464,20 +820,20 @@ pub fn shade_purple_planet(point: Vec3, time: f32) -> Vec3 {
     let mut color = dark_purple.lerp(&bright_purple, n);
     
     // Cristales brillantes
-    let crystal_noise = noise(uv * 20.0);
+    let crystal_noise = noise_filtered(uv * 20.0, footprint * 20.0);
     if crystal_noise > 0.75 {
         color = color.lerp(&crystal_color, (crystal_noise - 0.75) * 4.0);
     }
     
-    color.map(|x| x.max(0.0).min(1.0))
+    color.map(|x| x.clamp(0.0, 1.0))
 }
 
-pub fn shade_ringed_planet(point: Vec3, time: f32) -> Vec3 {
+pub fn shade_ringed_planet(point: Vec3, time: f32, footprint: f32) -> Vec3 {
     let uv = point.normalize();
     
     // Planeta con atmósfera turquesa
     let base_freq = 5.0;
-    let n = fbm(uv * base_freq + Vec3::new(time * 0.15, 0.0, 0.0), 3, 0.5, 2.0);
+    let n = fbm_filtered(uv * base_freq + Vec3::new(time * 0.15, 0.0, 0.0), 3, 0.5, 2.0, footprint * base_freq);
     
     let turquoise_dark = Vec3::new(0.1, 0.4, 0.5);
     let turquoise_light = Vec3::new(0.3, 0.8, 0.9);
@@ -486,15 +842,295 @@ pub fn shade_ringed_planet(point: Vec3, time: f32) -> Vec3 {
     let mut color = turquoise_dark.lerp(&turquoise_light, n);
     
     // Nubes brillantes
-    let cloud_noise = fbm(uv * 10.0 + Vec3::new(time * 0.2, 0.0, 0.0), 2, 0.6, 2.0);
+    let cloud_noise = fbm_filtered(uv * 10.0 + Vec3::new(time * 0.2, 0.0, 0.0), 2, 0.6, 2.0, footprint * 10.0);
     if cloud_noise > 0.6 {
         color = color.lerp(&white_clouds, (cloud_noise - 0.6) * 2.5);
     }
     
-    color.map(|x| x.max(0.0).min(1.0))
+    color.map(|x| x.clamp(0.0, 1.0))
 }
 
-pub fn shade_starfield(_point: Vec3, _time: f32) -> Vec3 {
-    // Fondo negro del espacio
-    Vec3::new(0.0, 0.0, 0.0)
+// Color y densidad (0..1) de los anillos en la posición radial `radial`
+// (0 = borde interior, 1 = exterior). Las bandas recorren la paleta de
+// adentro hacia afuera; un ruido lento abre huecos casi vacíos entre ellas.
+pub fn shade_ring(radial: f32, seed: u32, palette: &[Vec3]) -> (Vec3, f32) {
+    let offset = (seed % 1000) as f32 * 7.31;
+    let bands = noise(Vec3::new(radial * 60.0 + offset, 0.0, 0.0));
+    let gaps = fbm(Vec3::new(radial * 9.0 + offset, 0.5, 0.0), 2, 0.5, 2.0);
+
+    let color = match palette.len() {
+        0 => Vec3::new(0.8, 0.75, 0.65),
+        1 => palette[0],
+        count => {
+            let position = (radial + (bands - 0.5) * 0.08).clamp(0.0, 1.0) * (count - 1) as f32;
+            let index = (position.floor() as usize).min(count - 2);
+            palette[index].lerp(&palette[index + 1], position - index as f32)
+        }
+    };
+    let color = color * (0.75 + bands * 0.35);
+
+    // Bordes suaves y huecos donde el ruido lento cae bajo el umbral
+    let edges = (radial * 12.0).min((1.0 - radial) * 12.0).clamp(0.0, 1.0);
+    let gap = ((gaps - 0.3) * 6.0).clamp(0.1, 1.0);
+    let density = (0.45 + bands * 0.55) * gap * edges;
+
+    (color.map(|x| x.clamp(0.0, 1.0)), density)
+}
+
+pub fn shade_moon(point: Vec3, _time: f32, footprint: f32) -> Vec3 {
+    let uv = point.normalize();
+
+    // Luna gris sin atmósfera: mares oscuros y cráteres
+    let maria = fbm_filtered(uv * 2.0, 3, 0.5, 2.0, footprint * 2.0);
+    let highland = Vec3::new(0.62, 0.61, 0.58);
+    let mare = Vec3::new(0.28, 0.28, 0.30);
+    let mut color = mare.lerp(&highland, ((maria - 0.35) * 3.0).clamp(0.0, 1.0));
+
+    // Cráteres: bordes claros alrededor de fondos oscuros
+    let craters = noise_filtered(uv * 14.0, footprint * 14.0);
+    if craters > 0.7 {
+        let depth = (craters - 0.7) / 0.3;
+        color *= 1.0 - depth * 0.45;
+    } else if craters > 0.62 {
+        color *= 1.12;
+    }
+    color *= 0.9 + fbm_filtered(uv * 30.0, 2, 0.5, 2.0, footprint * 30.0) * 0.2;
+
+    color.map(|x| x.clamp(0.0, 1.0))
+}
+
+pub fn shade_comet(point: Vec3, time: f32, footprint: f32) -> Vec3 {
+    let uv = point.normalize();
+
+    // Núcleo de hielo sucio: costra oscura con placas de hielo expuesto
+    let crust = fbm_filtered(uv * 3.0, 3, 0.5, 2.0, footprint * 3.0);
+    let dust = Vec3::new(0.16, 0.15, 0.14);
+    let ice = Vec3::new(0.7, 0.8, 0.9);
+    let mut color = dust.lerp(&ice, ((crust - 0.5) * 4.0).clamp(0.0, 1.0));
+
+    // Zonas activas donde el hielo sublima: brillan por encima de 1.0 para
+    // que el bloom las haga resplandecer, y laten lentamente
+    let vents = fbm_filtered(uv * 6.0 + Vec3::new(0.0, time * 0.2, 0.0), 2, 0.5, 2.0, footprint * 6.0);
+    let activity = ((vents - 0.6) * 5.0).clamp(0.0, 1.0) * (0.75 + 0.25 * (time * 1.5).sin());
+    color += Vec3::new(0.8, 1.1, 1.4) * activity * 1.5;
+
+    color
+}
+
+// Agujero de gusano visto de frente (ver `anomaly::render_wormhole`: el eje
+// Z del punto apunta hacia la cámara): brazos en espiral que giran hacia una
+// garganta negra, con un anillo brillante que el bloom hace resplandecer
+pub fn shade_wormhole(point: Vec3, time: f32, footprint: f32) -> Vec3 {
+    let uv = point.normalize();
+    let radius = uv.x.hypot(uv.y);
+
+    // La espiral se cierra hacia el centro y gira con el tiempo
+    let swirl = uv.y.atan2(uv.x) + 2.5 / (radius + 0.25) - time * 1.5;
+    let arms = (swirl * 3.0).sin() * 0.5 + 0.5;
+    let turbulence = fbm_filtered(Vec3::new(swirl.cos(), swirl.sin(), radius * 4.0) * 3.0, 3, 0.5, 2.0, footprint * 12.0);
+
+    let violet = Vec3::new(0.9, 0.3, 1.6);
+    let blue = Vec3::new(0.3, 0.7, 1.8);
+    let mut color = violet.lerp(&blue, (arms + turbulence * 0.5).clamp(0.0, 1.0));
+
+    // Anillo de luz alrededor de la garganta; afuera el remolino se disuelve
+    let ring = (1.0 - (radius - 0.35).abs() / 0.12).max(0.0);
+    let glow = (arms * 0.6 + 0.4 + turbulence * 0.3).max(0.0) * (1.0 - radius).powf(1.5);
+    color = color * glow + Vec3::new(1.8, 1.5, 2.2) * ring * ring;
+
+    // Garganta: negro absoluto en el centro
+    let throat = ((radius - 0.18) / 0.12).clamp(0.0, 1.0);
+    color * throat
+}
+
+// Casco de una nave abandonada: metal gris oscuro con placas de óxido,
+// quemaduras y una luz de emergencia roja que parpadea
+pub fn shade_derelict(point: Vec3, time: f32, footprint: f32) -> Vec3 {
+    let hull = Vec3::new(0.3, 0.3, 0.32);
+    let rust = Vec3::new(0.38, 0.2, 0.1);
+    let rust_amount = fbm_filtered(point * 4.0, 3, 0.5, 2.0, footprint * 4.0);
+    let mut color = hull.lerp(&rust, ((rust_amount - 0.1) * 3.0).clamp(0.0, 1.0));
+
+    let scorch = fbm_filtered(point * 9.0 + Vec3::new(3.1, 7.4, 1.7), 2, 0.5, 2.0, footprint * 9.0);
+    color *= 1.0 - ((scorch - 0.2) * 2.5).clamp(0.0, 0.7);
+
+    // La luz de emergencia está en el borde superior de los paneles (el
+    // modelo de la nave llega hasta y ≈ 2.9)
+    let beacon = ((point.y - 2.5) * 4.0).clamp(0.0, 1.0);
+    let blink = if (time * 3.0).sin() > 0.6 { 1.0 } else { 0.0 };
+    color + Vec3::new(3.0, 0.2, 0.1) * beacon * blink
+}
+
+// Implementaciones de `PlanetShader`. Los cráteres solo se dibujan sobre los
+// planetas rocosos y desérticos.
+
+#[derive(Debug)]
+pub struct StarShader;
+
+impl PlanetShader for StarShader {
+    fn shade(&self, fragment: &FragmentInput, uniforms: &Uniforms) -> Vec3 {
+        shade_star(fragment.point, uniforms.shader_time, fragment.footprint)
+    }
+
+    fn batch(&self) -> Option<BatchShader> {
+        Some((&STAR_NOISE, finish_star))
+    }
+}
+
+#[derive(Debug)]
+pub struct RockyShader;
+
+impl PlanetShader for RockyShader {
+    fn shade(&self, fragment: &FragmentInput, uniforms: &Uniforms) -> Vec3 {
+        let surface = uniforms.surface;
+        let color = shade_rocky(fragment.point, uniforms.shader_time, fragment.footprint, &surface.climate);
+        apply_craters(color, fragment.point, &surface.craters).map(|x| x.clamp(0.0, 1.0))
+    }
+}
+
+#[derive(Debug)]
+pub struct GasGiantShader;
+
+impl PlanetShader for GasGiantShader {
+    fn shade(&self, fragment: &FragmentInput, uniforms: &Uniforms) -> Vec3 {
+        shade_gas_giant(fragment.point, uniforms.shader_time, fragment.footprint)
+    }
+
+    fn batch(&self) -> Option<BatchShader> {
+        Some((&GAS_GIANT_NOISE, finish_gas_giant))
+    }
+}
+
+// Gris uniforme: la nave sin textura y las piezas de los objetos animados
+#[derive(Debug)]
+pub struct SpaceshipShader;
+
+impl PlanetShader for SpaceshipShader {
+    fn shade(&self, fragment: &FragmentInput, uniforms: &Uniforms) -> Vec3 {
+        shade_spaceship(fragment.point, uniforms.shader_time)
+    }
+}
+
+#[derive(Debug)]
+pub struct IceShader;
+
+impl PlanetShader for IceShader {
+    fn shade(&self, fragment: &FragmentInput, uniforms: &Uniforms) -> Vec3 {
+        shade_ice_planet(fragment.point, uniforms.shader_time, fragment.footprint)
+    }
+}
+
+#[derive(Debug)]
+pub struct DesertShader;
+
+impl PlanetShader for DesertShader {
+    fn shade(&self, fragment: &FragmentInput, uniforms: &Uniforms) -> Vec3 {
+        let color = shade_desert_planet(fragment.point, uniforms.shader_time, fragment.footprint);
+        apply_craters(color, fragment.point, &uniforms.surface.craters).map(|x| x.clamp(0.0, 1.0))
+    }
+}
+
+#[derive(Debug)]
+pub struct VolcanicShader;
+
+impl PlanetShader for VolcanicShader {
+    fn shade(&self, fragment: &FragmentInput, uniforms: &Uniforms) -> Vec3 {
+        shade_volcanic_planet(fragment.point, uniforms.shader_time, fragment.footprint)
+    }
+}
+
+#[derive(Debug)]
+pub struct OceanShader;
+
+impl PlanetShader for OceanShader {
+    fn shade(&self, fragment: &FragmentInput, uniforms: &Uniforms) -> Vec3 {
+        shade_ocean_planet(fragment.point, uniforms.shader_time, fragment.footprint)
+    }
+}
+
+#[derive(Debug)]
+pub struct PurpleShader;
+
+impl PlanetShader for PurpleShader {
+    fn shade(&self, fragment: &FragmentInput, uniforms: &Uniforms) -> Vec3 {
+        shade_purple_planet(fragment.point, uniforms.shader_time, fragment.footprint)
+    }
+}
+
+#[derive(Debug)]
+pub struct RingedShader;
+
+impl PlanetShader for RingedShader {
+    fn shade(&self, fragment: &FragmentInput, uniforms: &Uniforms) -> Vec3 {
+        shade_ringed_planet(fragment.point, uniforms.shader_time, fragment.footprint)
+    }
+}
+
+#[derive(Debug)]
+pub struct MoonShader;
+
+impl PlanetShader for MoonShader {
+    fn shade(&self, fragment: &FragmentInput, uniforms: &Uniforms) -> Vec3 {
+        shade_moon(fragment.point, uniforms.shader_time, fragment.footprint)
+    }
+}
+
+#[derive(Debug)]
+pub struct CometShader;
+
+impl PlanetShader for CometShader {
+    fn shade(&self, fragment: &FragmentInput, uniforms: &Uniforms) -> Vec3 {
+        shade_comet(fragment.point, uniforms.shader_time, fragment.footprint)
+    }
+}
+
+// Textura difusa del modelo; sin textura, el gris de la nave
+#[derive(Debug)]
+pub struct TexturedShader;
+
+impl PlanetShader for TexturedShader {
+    fn shade(&self, fragment: &FragmentInput, uniforms: &Uniforms) -> Vec3 {
+        match uniforms.texture {
+            Some(texture) => shade_textured(texture, fragment.tex_coords),
+            None => shade_spaceship(fragment.point, uniforms.shader_time),
+        }
+    }
+}
+
+// Anillos de control del circuito
+#[derive(Debug)]
+pub struct CheckpointShader;
+
+impl PlanetShader for CheckpointShader {
+    fn shade(&self, fragment: &FragmentInput, uniforms: &Uniforms) -> Vec3 {
+        shade_checkpoint(fragment.point, uniforms.shader_time)
+    }
+}
+
+// Anillos planetarios (ver `rings`): más o menos densos según la banda
+#[derive(Debug)]
+pub struct RingShader;
+
+impl PlanetShader for RingShader {
+    fn shade(&self, fragment: &FragmentInput, uniforms: &Uniforms) -> Vec3 {
+        self.shade_with_density(fragment, uniforms).0
+    }
+
+    fn shade_with_density(&self, fragment: &FragmentInput, uniforms: &Uniforms) -> (Vec3, f32) {
+        shade_ring(fragment.tex_coords.x, uniforms.seed, &uniforms.surface.ring_palette)
+    }
+
+    fn translucent(&self) -> bool {
+        true
+    }
+}
+
+// Casco de las naves abandonadas (ver `anomaly`)
+#[derive(Debug)]
+pub struct DerelictShader;
+
+impl PlanetShader for DerelictShader {
+    fn shade(&self, fragment: &FragmentInput, uniforms: &Uniforms) -> Vec3 {
+        shade_derelict(fragment.point, uniforms.shader_time, fragment.footprint)
+    }
 }
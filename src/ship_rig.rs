@@ -0,0 +1,183 @@
+// Animación por partes de la nave: algunos objetos del OBJ giran o se
+// desplazan alrededor de un pivote según el estado de vuelo (las alas se
+// pliegan al aterrizar y se echan hacia atrás al acelerar). Las piezas que
+// tienen una mitad a cada lado del eje Z se separan para moverlas en espejo.
+
+use nalgebra_glm::{Mat4, Vec3};
+
+use crate::obj::SubMesh;
+use crate::vertex::Vertex;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShipState {
+    Cruising,
+    Boosting,
+    Landed,
+}
+
+// Distancia a la superficie de colisión por debajo de la cual una nave
+// detenida se considera posada
+const LANDING_RANGE: f32 = 1.0;
+// Tiempo (en unidades de simulación) que tarda una transición entre poses
+const BLEND_TIME: f32 = 0.3;
+
+impl ShipState {
+    // `velocity` es el desplazamiento por unidad de tiempo; `surface_distance`
+    // la holgura hasta el límite de colisión del cuerpo más cercano
+    pub fn from_motion(velocity: Vec3, forward: Vec3, surface_distance: f32) -> Self {
+        let speed = velocity.magnitude();
+        if speed < 1e-3 && surface_distance < LANDING_RANGE {
+            ShipState::Landed
+        } else if velocity.dot(&forward) > speed * 0.5 {
+            ShipState::Boosting
+        } else {
+            ShipState::Cruising
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            ShipState::Cruising => 0,
+            ShipState::Boosting => 1,
+            ShipState::Landed => 2,
+        }
+    }
+}
+
+// Valores de una pista para cada estado: crucero, impulso, aterrizado
+struct PartRule {
+    // Prefijo del nombre del objeto en el OBJ
+    mesh: &'static str,
+    // Pivote de la mitad +Z; la mitad -Z usa su reflejo y giros opuestos
+    pivot: [f32; 3],
+    // Giro en grados alrededor del eje vertical (barrido hacia atrás)
+    yaw: [f32; 3],
+    // Giro en grados alrededor del eje hacia adelante (pliegue)
+    roll: [f32; 3],
+    // Desplazamiento hacia abajo (tren de aterrizaje)
+    drop: [f32; 3],
+}
+
+// El frente del CazaTie es +X; las alas son los objetos `Plane*` en z = ±2.
+// Al acelerar se echan hacia atrás y al posarse se abren por abajo como
+// soportes. El modelo no incluye tren de aterrizaje: un objeto
+// `TrenAterrizaje` añadido al OBJ bajaría al posarse.
+const PART_RULES: [PartRule; 2] = [
+    PartRule {
+        mesh: "Plane",
+        pivot: [0.0, 0.0, 1.6],
+        yaw: [0.0, 12.0, 0.0],
+        roll: [0.0, 0.0, -20.0],
+        drop: [0.0; 3],
+    },
+    PartRule {
+        mesh: "TrenAterrizaje",
+        pivot: [0.0; 3],
+        yaw: [0.0; 3],
+        roll: [0.0; 3],
+        drop: [0.0, 0.0, 0.6],
+    },
+];
+
+fn rule_for(name: &str) -> Option<usize> {
+    PART_RULES.iter().position(|rule| name.starts_with(rule.mesh))
+}
+
+// Separa por el signo de Z las piezas animadas que tienen geometría a ambos lados
+pub fn split_animated_parts(parts: Vec<SubMesh>) -> Vec<SubMesh> {
+    let mut result = Vec::with_capacity(parts.len());
+    for part in parts {
+        if rule_for(&part.name).is_none() {
+            result.push(part);
+            continue;
+        }
+        let halves: Vec<SubMesh> = [("+z", 1.0f32), ("-z", -1.0)]
+            .iter()
+            .map(|(suffix, side)| {
+                let mut remap = vec![u32::MAX; part.vertices.len()];
+                let mut vertices: Vec<Vertex> = Vec::new();
+                let mut indices = Vec::new();
+                for triangle in part.indices.chunks(3) {
+                    let centroid_z: f32 = triangle.iter().map(|&i| part.vertices[i as usize].position.z).sum();
+                    if centroid_z * side < 0.0 {
+                        continue;
+                    }
+                    for &i in triangle {
+                        if remap[i as usize] == u32::MAX {
+                            remap[i as usize] = vertices.len() as u32;
+                            vertices.push(part.vertices[i as usize].clone());
+                        }
+                        indices.push(remap[i as usize]);
+                    }
+                }
+                SubMesh {
+                    name: format!("{}{}", part.name, suffix),
+                    vertices,
+                    indices,
+                    diffuse_texture: part.diffuse_texture.clone(),
+                    roughness: part.roughness,
+                }
+            })
+            .filter(|half| !half.indices.is_empty())
+            .collect();
+        result.extend(halves);
+    }
+    result
+}
+
+struct Binding {
+    rule: usize,
+    // +1 o -1 según el lado de la pieza; -1 refleja pivote y giro
+    side: f32,
+}
+
+pub struct ShipRig {
+    bindings: Vec<Option<Binding>>,
+    // Peso de cada estado en la pose actual, para transiciones suaves
+    weights: [f32; 3],
+}
+
+impl ShipRig {
+    pub fn new<'a>(parts: impl IntoIterator<Item = &'a SubMesh>) -> Self {
+        let bindings = parts
+            .into_iter()
+            .map(|part| {
+                rule_for(&part.name).map(|rule| {
+                    let mean_z = part.vertices.iter().map(|v| v.position.z).sum::<f32>() / part.vertices.len().max(1) as f32;
+                    Binding { rule, side: if mean_z < 0.0 { -1.0 } else { 1.0 } }
+                })
+            })
+            .collect();
+        ShipRig { bindings, weights: [1.0, 0.0, 0.0] }
+    }
+
+    pub fn update(&mut self, state: ShipState, dt: f32) {
+        let step = (dt / BLEND_TIME).min(1.0);
+        for (index, weight) in self.weights.iter_mut().enumerate() {
+            let target = if index == state.index() { 1.0 } else { 0.0 };
+            *weight += (target - *weight) * step;
+        }
+    }
+
+    // Transformación local de la pieza `index` (identidad si no está animada)
+    pub fn part_matrix(&self, index: usize) -> Mat4 {
+        let Some(binding) = self.bindings.get(index).and_then(Option::as_ref) else {
+            return Mat4::identity();
+        };
+        let rule = &PART_RULES[binding.rule];
+        let blend = |values: [f32; 3]| -> f32 {
+            values.iter().zip(&self.weights).map(|(value, weight)| value * weight).sum()
+        };
+
+        // Reflejo en el plano Z = 0 para la mitad -Z: los giros cambian de signo
+        let pivot = Vec3::new(rule.pivot[0], rule.pivot[1], rule.pivot[2] * binding.side);
+        let yaw = blend(rule.yaw).to_radians() * binding.side;
+        let roll = blend(rule.roll).to_radians() * binding.side;
+        let drop = blend(rule.drop);
+
+        let transform = nalgebra_glm::translation(&(pivot - Vec3::new(0.0, drop, 0.0)))
+            * nalgebra_glm::rotation(yaw, &Vec3::new(0.0, 1.0, 0.0))
+            * nalgebra_glm::rotation(roll, &Vec3::new(1.0, 0.0, 0.0));
+        transform * nalgebra_glm::translation(&-pivot)
+    }
+}
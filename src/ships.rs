@@ -0,0 +1,138 @@
+// Registro de naves (`assets/naves.toml`, o el archivo de `--ships`): cada
+// `[[ship]]` tiene su modelo (OBJ o glTF), escala, radio de colisión, punto
+// de la cámara de la cabina, un shader opcional en lugar de sus texturas y
+// sus factores de vuelo. Se cargan todas al empezar; en el hangar se elige
+// una con las flechas y en vuelo N pasa a la siguiente.
+
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::culling::bounding_radius;
+use crate::obj::{Obj, SubMesh};
+use crate::scene::shader_from_name;
+use crate::shaders::{PlanetShader, TexturedShader};
+use crate::ship_rig::split_animated_parts;
+use crate::texture::Texture;
+
+pub const DEFAULT_REGISTRY: &str = "assets/naves.toml";
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ShipConfig {
+    pub name: String,
+    pub description: String,
+    pub model: String,
+    pub scale: f32,
+    // Radio de la nave para los choques; sin él, el de la malla escalada.
+    // Los cuerpos con un `collision_margin` menor lo agrandan hasta este.
+    pub collision_radius: Option<f32>,
+    // Ubicación de la cámara de la cabina: adelante y arriba del centro de
+    // la nave, en unidades del mundo
+    pub camera_anchor: [f32; 2],
+    // Salida de los motores, donde empieza la distorsión por calor: atrás y
+    // arriba del centro de la nave, en unidades del mundo
+    pub engine_anchor: [f32; 2],
+    // Shader de planeta para toda la nave (p. ej. "spaceship"); sin él se
+    // usan las texturas del modelo
+    pub shader: Option<String>,
+    // Factores sobre la velocidad máxima y el empuje de la escena (`[flight]`)
+    pub speed: f32,
+    pub thrust: f32,
+}
+
+impl Default for ShipConfig {
+    fn default() -> Self {
+        ShipConfig {
+            name: "Nave".to_string(),
+            description: String::new(),
+            model: "assets/CazaTie.obj".to_string(),
+            scale: 0.3,
+            collision_radius: None,
+            camera_anchor: [0.4, 0.1],
+            engine_anchor: [0.5, 0.0],
+            shader: None,
+            speed: 1.0,
+            thrust: 1.0,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Registry {
+    #[serde(rename = "ship", default)]
+    ships: Vec<ShipConfig>,
+}
+
+pub fn load_registry<P: AsRef<Path>>(path: P) -> Result<Vec<ShipConfig>, String> {
+    let source = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let registry: Registry = toml::from_str(&source).map_err(|e| e.to_string())?;
+    if registry.ships.is_empty() {
+        return Err("el registro no tiene ninguna [[ship]]".to_string());
+    }
+    for (index, ship) in registry.ships.iter().enumerate() {
+        let name = &ship.name;
+        if ship.scale <= 0.0 {
+            return Err(format!("nave {} ({}): scale tiene que ser positivo", index + 1, name));
+        }
+        if ship.collision_radius.is_some_and(|radius| radius <= 0.0) {
+            return Err(format!("nave {} ({}): collision_radius tiene que ser positivo", index + 1, name));
+        }
+        if ship.speed <= 0.0 || ship.thrust <= 0.0 {
+            return Err(format!("nave {} ({}): speed y thrust tienen que ser positivos", index + 1, name));
+        }
+        if let Some(shader) = &ship.shader {
+            if shader_from_name(shader).is_none() {
+                return Err(format!("nave {} ({}): shader desconocido '{}'", index + 1, name, shader));
+            }
+        }
+    }
+    Ok(registry.ships)
+}
+
+// Una nave del registro con su malla cargada, una pieza por material; las
+// piezas animadas se separan para que cada lado se mueva por su cuenta
+pub struct Ship {
+    pub config: ShipConfig,
+    pub parts: Vec<(SubMesh, Option<Texture>)>,
+    // Radio de la malla sin escalar
+    pub radius: f32,
+    shader: Option<&'static dyn PlanetShader>,
+}
+
+impl Ship {
+    pub fn load(config: ShipConfig) -> Result<Self, String> {
+        let model = Obj::load(&config.model).map_err(|e| format!("No se pudo cargar el modelo {}: {}", config.model, e))?;
+        let shader = config.shader.as_deref().and_then(shader_from_name);
+        let parts: Vec<(SubMesh, Option<Texture>)> = split_animated_parts(model.get_submeshes())
+            .into_iter()
+            .map(|part| {
+                // Con un shader propio las texturas no se usan
+                let texture = part.diffuse_texture.as_ref().filter(|_| shader.is_none()).and_then(|path| match Texture::load(path) {
+                    Ok(texture) => Some(texture),
+                    Err(e) => {
+                        eprintln!("No se pudo cargar la textura {}: {} (se usará el shader gris)", path.display(), e);
+                        None
+                    }
+                });
+                (part, texture)
+            })
+            .collect();
+        let radius = parts.iter().map(|(part, _)| bounding_radius(&part.vertices)).fold(0.0, f32::max);
+        Ok(Ship { config, parts, radius, shader })
+    }
+
+    pub fn collision_radius(&self) -> f32 {
+        self.config.collision_radius.unwrap_or(self.radius * self.config.scale)
+    }
+
+    // Shader y textura con que se dibuja una pieza: la textura del modelo
+    // con el shader texturizado (gris si no tiene), o el shader de la nave
+    pub fn look<'a>(&self, texture: &'a Option<Texture>) -> (&'static dyn PlanetShader, Option<&'a Texture>) {
+        match self.shader {
+            Some(shader) => (shader, None),
+            None => (&TexturedShader, texture.as_ref()),
+        }
+    }
+}
@@ -0,0 +1,236 @@
+// Fondo del espacio fijo a direcciones del mundo: se proyecta solo con la
+// rotación de la cámara, así las estrellas giran con ella pero nunca se
+// acercan. Puede usar un cubemap (seis imágenes) bajo las estrellas.
+//
+// Las estrellas salen de un catálogo generado con una semilla
+// (`[starfield]`): direcciones uniformes sobre la esfera, así la cantidad en
+// pantalla depende solo del campo de visión, brillo con muchas más débiles
+// que brillantes, color según su temperatura y un centelleo suave. Las más
+// brillantes se dibujan de 2x2 píxeles.
+
+use std::f32::consts::TAU;
+use std::path::Path;
+
+use nalgebra_glm::{Mat4, Vec2, Vec3, Vec4};
+use serde::{Deserialize, Serialize};
+
+use crate::daily::mix_seed;
+use crate::framebuffer::{pack_color, unpack_color, Framebuffer};
+use crate::texture::Texture;
+
+// Caras del cubemap en el orden +X, -X, +Y, -Y, +Z, -Z
+const FACE_NAMES: [&str; 6] = ["px", "nx", "py", "ny", "pz", "nz"];
+
+// Catálogo de estrellas del fondo (`[starfield]` en la escena)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct StarfieldConfig {
+    pub count: usize,
+    pub seed: u32,
+    // Reparto del brillo entre `min_brightness` y 1: con un exponente mayor
+    // hay más estrellas débiles por cada brillante
+    pub brightness_exponent: f32,
+    pub min_brightness: f32,
+    // Temperaturas de color en kelvin, de las más rojizas a las más azules
+    pub temperature: [f32; 2],
+    // Cuánto baja el brillo en el centelleo (0 = fijas)
+    pub twinkle: f32,
+    // Desde este brillo las estrellas ocupan 2x2 píxeles
+    pub large_brightness: f32,
+}
+
+impl Default for StarfieldConfig {
+    fn default() -> Self {
+        StarfieldConfig {
+            count: 2500,
+            seed: 0,
+            brightness_exponent: 2.5,
+            min_brightness: 0.25,
+            temperature: [3000.0, 12000.0],
+            twinkle: 0.2,
+            large_brightness: 0.85,
+        }
+    }
+}
+
+struct Star {
+    direction: Vec3,
+    brightness: f32,
+    color: Vec3,
+    // Frecuencia y fase del centelleo
+    frequency: f32,
+    phase: f32,
+}
+
+// Color de un cuerpo negro a `kelvin` (aproximación de Tanner Helland),
+// normalizado para que el canal más fuerte valga 1
+pub fn temperature_color(kelvin: f32) -> Vec3 {
+    let t = (kelvin / 100.0).clamp(10.0, 400.0);
+    let red = if t <= 66.0 { 255.0 } else { 329.7 * (t - 60.0).powf(-0.1332) };
+    let green = if t <= 66.0 { 99.47 * t.ln() - 161.12 } else { 288.12 * (t - 60.0).powf(-0.0755) };
+    let blue = if t >= 66.0 {
+        255.0
+    } else if t <= 19.0 {
+        0.0
+    } else {
+        138.52 * (t - 10.0).ln() - 305.04
+    };
+    let color = Vec3::new(red, green, blue).map(|c| c.clamp(0.0, 255.0));
+    color / color.max().max(1.0)
+}
+
+pub struct Cubemap {
+    faces: Vec<Texture>,
+}
+
+impl Cubemap {
+    // Carga `px.png`, `nx.png`, ... (o `.jpg`) desde `directory`
+    pub fn load<P: AsRef<Path>>(directory: P) -> Result<Self, String> {
+        let directory = directory.as_ref();
+        let faces = FACE_NAMES
+            .iter()
+            .map(|name| {
+                ["png", "jpg"]
+                    .iter()
+                    .map(|extension| directory.join(format!("{}.{}", name, extension)))
+                    .find(|path| path.exists())
+                    .ok_or_else(|| format!("falta la cara '{}' en {}", name, directory.display()))
+                    .and_then(|path| Texture::load(&path).map_err(|e| format!("{}: {}", path.display(), e)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Cubemap { faces })
+    }
+
+    // Color en la dirección `direction` (convención de cubemaps de OpenGL)
+    fn sample(&self, direction: Vec3) -> Vec3 {
+        let abs = direction.abs();
+        let (face, s, t, major) = if abs.x >= abs.y && abs.x >= abs.z {
+            if direction.x > 0.0 { (0, -direction.z, -direction.y, abs.x) } else { (1, direction.z, -direction.y, abs.x) }
+        } else if abs.y >= abs.z {
+            if direction.y > 0.0 { (2, direction.x, direction.z, abs.y) } else { (3, direction.x, -direction.z, abs.y) }
+        } else if direction.z > 0.0 {
+            (4, direction.x, -direction.y, abs.z)
+        } else {
+            (5, -direction.x, -direction.y, abs.z)
+        };
+        let uv = Vec2::new((s / major + 1.0) * 0.5, (t / major + 1.0) * 0.5);
+        self.faces[face].sample(uv)
+    }
+}
+
+pub struct Skybox {
+    stars: Vec<Star>,
+    twinkle: f32,
+    large_brightness: f32,
+    cubemap: Option<Cubemap>,
+}
+
+impl Skybox {
+    pub fn new(config: &StarfieldConfig, cubemap: Option<Cubemap>) -> Self {
+        let mut skybox = Skybox { stars: Vec::new(), twinkle: 0.0, large_brightness: 0.0, cubemap };
+        skybox.set_starfield(config);
+        skybox
+    }
+
+    // Estrellas del catálogo repartidas de forma uniforme sobre la esfera; al
+    // viajar a otro sistema se cambian por las suyas y el cubemap queda
+    pub fn set_starfield(&mut self, config: &StarfieldConfig) {
+        self.stars = (0..config.count as u32)
+            .map(|i| {
+                // Seis valores independientes por estrella
+                let random = |k: u32| mix_seed(config.seed, i * 6 + k) as f32 / u32::MAX as f32;
+                let z = random(0) * 2.0 - 1.0;
+                let ring = (1.0 - z * z).max(0.0).sqrt();
+                let angle = random(1) * TAU;
+                let brightness = config.min_brightness + (1.0 - config.min_brightness) * random(2).powf(config.brightness_exponent);
+                // Las frías son más comunes que las calientes
+                let [cool, hot] = config.temperature;
+                let kelvin = cool + (hot - cool) * random(3).powi(2);
+                Star {
+                    direction: Vec3::new(ring * angle.cos(), ring * angle.sin(), z),
+                    brightness,
+                    color: temperature_color(kelvin),
+                    frequency: 1.5 + random(4) * 4.0,
+                    phase: random(5) * TAU,
+                }
+            })
+            .collect();
+        self.twinkle = config.twinkle;
+        self.large_brightness = config.large_brightness;
+    }
+
+    // Dibuja el fondo sin escribir profundidad, para que todo lo demás quede
+    // encima; `time` mueve el centelleo
+    pub fn render(&self, framebuffer: &mut Framebuffer, view_matrix: &Mat4, projection_matrix: &Mat4, time: f32) {
+        // Solo la rotación de la vista: el fondo está infinitamente lejos
+        let mut rotation = *view_matrix;
+        rotation.set_column(3, &Vec4::new(0.0, 0.0, 0.0, 1.0));
+
+        if let Some(cubemap) = &self.cubemap {
+            // Rayo de cada píxel en espacio de vista, llevado al mundo con la
+            // rotación inversa (la transpuesta)
+            let inverse = rotation.transpose();
+            let (scale_x, scale_y) = (projection_matrix[(0, 0)], projection_matrix[(1, 1)]);
+            for y in 0..framebuffer.height {
+                let ndc_y = 1.0 - (y as f32 + 0.5) / framebuffer.height as f32 * 2.0;
+                for x in 0..framebuffer.width {
+                    let ndc_x = (x as f32 + 0.5) / framebuffer.width as f32 * 2.0 - 1.0;
+                    let ray = inverse * Vec4::new(ndc_x / scale_x, ndc_y / scale_y, -1.0, 0.0);
+                    let color = cubemap.sample(ray.xyz().normalize());
+                    let index = framebuffer.index(x, y);
+                    framebuffer.buffer[index] = pack_color(color);
+                }
+            }
+        }
+
+        let view_projection = projection_matrix * rotation;
+        for star in &self.stars {
+            let clip = view_projection * Vec4::new(star.direction.x, star.direction.y, star.direction.z, 1.0);
+            // Detrás de la cámara
+            if clip.w <= 0.0 {
+                continue;
+            }
+            let x = (clip.x / clip.w * 0.5 + 0.5) * framebuffer.width as f32;
+            let y = (1.0 - (clip.y / clip.w * 0.5 + 0.5)) * framebuffer.height as f32;
+            if x < 0.0 || y < 0.0 || x >= framebuffer.width as f32 || y >= framebuffer.height as f32 {
+                continue;
+            }
+            let (x, y) = (x as usize, y as usize);
+            let twinkle = 1.0 - self.twinkle * (0.5 + 0.5 * (time * star.frequency + star.phase).sin());
+            let light = star.color * (star.brightness * twinkle);
+            let size = if star.brightness >= self.large_brightness { 2 } else { 1 };
+            for y in y..(y + size).min(framebuffer.height) {
+                for x in x..(x + size).min(framebuffer.width) {
+                    // Las estrellas aclaran el cubemap en lugar de taparlo
+                    let index = framebuffer.index(x, y);
+                    framebuffer.buffer[index] = pack_color(unpack_color(framebuffer.buffer[index]).sup(&light));
+                }
+            }
+        }
+    }
+}
+
+// Cielo procedural aproximado en la dirección `direction`, para los reflejos
+// del entorno: fondo oscuro con una nebulosa suave, estrellas sueltas y el
+// sol. Con más `roughness` (0..1) el reflejo se vuelve borroso: la nebulosa
+// tiende a su promedio, las estrellas se pierden y el brillo del sol se abre.
+pub fn environment(direction: Vec3, sun_direction: Vec3, sun_color: Vec3, roughness: f32) -> Vec3 {
+    let roughness = roughness.clamp(0.0, 1.0);
+    let space = Vec3::new(0.01, 0.012, 0.03);
+    let nebula_color = Vec3::new(0.25, 0.1, 0.35);
+
+    let bands = ((direction.x * 3.1 + (direction.y * 2.3).sin()).sin() * (direction.z * 2.7 + direction.x).cos()).max(0.0);
+    let nebula = bands * (1.0 - roughness) + 0.3 * roughness;
+    let mut color = space + nebula_color * nebula * 0.4;
+
+    // Estrellas: celdas de la esfera con un valor pseudoaleatorio alto
+    let cell = (direction * 60.0).map(f32::floor);
+    let hash = (cell.dot(&Vec3::new(12.9898, 78.233, 37.719)).sin() * 43758.547).fract().abs();
+    if hash > 0.985 {
+        color += Vec3::repeat((hash - 0.985) / 0.015 * (1.0 - roughness).powi(2));
+    }
+
+    let exponent = 2000.0 * (1.0 - roughness).powi(3) + 8.0;
+    let sun = direction.dot(&sun_direction).max(0.0).powf(exponent) * (2.5 - roughness * 1.8);
+    color + sun_color * sun
+}
@@ -0,0 +1,104 @@
+// Puntos de partida con nombre (`[[spawn]]` en la escena): dónde aparece la
+// nave, hacia dónde mira y cómo queda la cámara que la sigue. Se eligen con
+// `--spawn <nombre>`; sin la opción se usa el primero de la escena.
+
+use nalgebra_glm::Vec3;
+use serde::{Deserialize, Serialize};
+
+use crate::scene::{Collider, Scene};
+
+// Posición de la nave cuando la escena no declara puntos de partida
+pub const DEFAULT_POSITION: Vec3 = Vec3::new(35.0, 15.0, 40.0);
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SpawnPreset {
+    pub name: String,
+    // Posición en el mundo, o relativa al cuerpo `near` si se indica
+    pub position: [f32; 3],
+    // Cuerpo junto al que se aparece, según dónde esté al empezar
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub near: Option<String>,
+    // Cuerpo hacia el que mira la nave; si falta se usa `heading`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub look_at: Option<String>,
+    // Rumbo en grados sobre el plano XZ (0 = +X, 90 = +Z)
+    #[serde(default)]
+    pub heading: f32,
+    // Distancia y elevación (grados) de la cámara que sigue a la nave
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub camera_distance: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub camera_pitch: Option<f32>,
+}
+
+// Punto de partida ya resuelto contra las posiciones iniciales de la escena
+#[derive(Clone)]
+pub struct Spawn {
+    pub position: Vec3,
+    // Rumbo en radianes, como `Spaceship::heading`
+    pub heading: Option<f32>,
+    pub camera_distance: Option<f32>,
+    pub camera_pitch: Option<f32>,
+}
+
+fn body_position(scene: &Scene, name: &str) -> Option<Vec3> {
+    let index = scene.bodies.iter().position(|body| body.name == name)?;
+    Some(scene.states[index].position)
+}
+
+impl SpawnPreset {
+    // Los nombres de cuerpos ya se validaron al cargar la escena
+    pub fn resolve(&self, scene: &Scene) -> Spawn {
+        let origin = self.near.as_deref().and_then(|name| body_position(scene, name)).unwrap_or_else(Vec3::zeros);
+        let position = origin + Vec3::from(self.position);
+        let heading = match self.look_at.as_deref().and_then(|name| body_position(scene, name)) {
+            Some(target) => {
+                let direction = target - position;
+                direction.z.atan2(direction.x)
+            }
+            None => self.heading.to_radians(),
+        };
+        Spawn {
+            position,
+            heading: Some(heading),
+            camera_distance: self.camera_distance,
+            camera_pitch: self.camera_pitch,
+        }
+    }
+}
+
+// `--spawn <nombre>` elige un punto de partida; sin él, el primero de la
+// escena, y si no hay ninguno la posición de siempre con el rumbo inicial
+pub fn choose(scene: &Scene, name: Option<&str>) -> Result<Spawn, String> {
+    let preset = match name {
+        Some(name) => match scene.spawns.iter().find(|preset| preset.name == name) {
+            Some(preset) => Some(preset),
+            None => {
+                let available: Vec<&str> = scene.spawns.iter().map(|preset| preset.name.as_str()).collect();
+                let available = if available.is_empty() { "ninguno".to_string() } else { available.join(", ") };
+                return Err(format!("no existe el punto de partida '{}' (disponibles: {})", name, available));
+            }
+        },
+        None => scene.spawns.first(),
+    };
+    Ok(match preset {
+        Some(preset) => preset.resolve(scene),
+        None => Spawn { position: DEFAULT_POSITION, heading: None, camera_distance: None, camera_pitch: None },
+    })
+}
+
+// Punto de partida más cercano a `point` que ahora mismo queda fuera del
+// margen de colisión de todos los cuerpos (los relativos a un cuerpo se
+// resuelven donde está en este momento), con su nombre. Sin puntos en la escena se prueba la
+// posición de siempre.
+pub fn nearest_safe<'a>(scene: &'a Scene, point: Vec3, colliders: &[Collider]) -> Option<(&'a str, Spawn)> {
+    let candidates: Vec<(&str, Spawn)> = if scene.spawns.is_empty() {
+        vec![("inicial", Spawn { position: DEFAULT_POSITION, heading: None, camera_distance: None, camera_pitch: None })]
+    } else {
+        scene.spawns.iter().map(|preset| (preset.name.as_str(), preset.resolve(scene))).collect()
+    };
+    candidates
+        .into_iter()
+        .filter(|(_, spawn)| colliders.iter().all(|collider| collider.clearance(spawn.position) >= collider.margin))
+        .min_by(|a, b| (a.1.position - point).magnitude().total_cmp(&(b.1.position - point).magnitude()))
+}
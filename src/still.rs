@@ -0,0 +1,70 @@
+// Imagen fija de una escena sin ventana: vista general del sistema desde
+// arriba del plano orbital, con las órbitas y cada cuerpo como impostor
+// sombreado con su shader. Los cuerpos se agrandan hasta un tamaño mínimo en
+// pantalla para que los planetas no se pierdan en una imagen chica. La usan
+// las miniaturas del menú de escenas.
+
+use nalgebra_glm::{look_at, perspective, Vec3, Vec4};
+
+use crate::culling::Frustum;
+use crate::debug_view::DebugView;
+use crate::framebuffer::{pack_color, Framebuffer};
+use crate::lighting::Light;
+use crate::scene::Scene;
+use crate::{create_scaled_model_matrix, create_viewport_matrix, projected_radius, render_impostor, render_orbit, Uniforms};
+
+const FOV_DEGREES: f32 = 40.0;
+// Radio mínimo de cada cuerpo en la imagen, en píxeles
+const MIN_RADIUS: f32 = 3.5;
+
+pub fn render_still(scene: &Scene, width: usize, height: usize) -> Framebuffer {
+    let mut framebuffer = Framebuffer::new(width, height);
+    framebuffer.set_background_color(pack_color(scene.lighting.background()));
+    framebuffer.clear();
+
+    // Cámara a unos 30° sobre el plano, a la distancia que deja ver todo el sistema
+    let center = scene.star_position();
+    let extent = scene
+        .bodies
+        .iter()
+        .zip(&scene.states)
+        .map(|(body, state)| (state.position - center).magnitude() + body.scale)
+        .fold(1.0f32, f32::max);
+    let eye = center + Vec3::new(0.0, 0.5, 0.87) * extent * 1.9;
+    let view_matrix = look_at(&eye, &center, &Vec3::new(0.0, 1.0, 0.0));
+    let projection_matrix = perspective(width as f32 / height as f32, FOV_DEGREES.to_radians(), 0.1, extent * 6.0);
+    let viewport_matrix = create_viewport_matrix(width as f32, height as f32);
+    let frustum = Frustum::from_matrix(&(projection_matrix * view_matrix));
+    let light = Light::new(center).with_ambient(scene.lighting.ambient());
+
+    for (index, body) in scene.bodies.iter().enumerate().filter(|(_, body)| body.orbit_radius > 0.0) {
+        render_orbit(&mut framebuffer, scene.orbit_center(index), body, &view_matrix, &projection_matrix, &viewport_matrix);
+    }
+    for (body, state) in scene.bodies.iter().zip(&scene.states) {
+        let position = state.position;
+        let view_depth = -(view_matrix * Vec4::new(position.x, position.y, position.z, 1.0)).z;
+        let radius_px = projected_radius(body.scale, view_depth, &projection_matrix, &viewport_matrix);
+        let radius = if radius_px > 0.0 { body.scale * (MIN_RADIUS / radius_px).max(1.0) } else { body.scale };
+        let uniforms = Uniforms {
+            model_matrix: create_scaled_model_matrix(position, body.scale_vector(), state.rotation),
+            view_matrix,
+            projection_matrix,
+            viewport_matrix,
+            frustum,
+            shader_time: 0.0,
+            shader: body.fragment_shader(),
+            seed: body.shader_seed(),
+            alpha: 1.0,
+            texture: None,
+            surface: &body.surface,
+            light,
+            material: body.material(),
+            camera_position: eye,
+            debug_view: DebugView::Shaded,
+            screen_lighting: false,
+            detail: 0.0,
+        };
+        render_impostor(&mut framebuffer, &uniforms, position, radius);
+    }
+    framebuffer
+}
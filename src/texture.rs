@@ -0,0 +1,55 @@
+use std::path::Path;
+
+use nalgebra_glm::{Vec2, Vec3};
+
+// Imagen cargada en memoria con colores en rango 0.0-1.0
+pub struct Texture {
+    width: usize,
+    height: usize,
+    pixels: Vec<Vec3>,
+}
+
+impl Texture {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, image::ImageError> {
+        let image = image::open(path)?.to_rgb8();
+        let (width, height) = image.dimensions();
+
+        let pixels = image
+            .pixels()
+            .map(|p| Vec3::new(p[0] as f32 / 255.0, p[1] as f32 / 255.0, p[2] as f32 / 255.0))
+            .collect();
+
+        Ok(Texture {
+            width: width as usize,
+            height: height as usize,
+            pixels,
+        })
+    }
+
+    fn texel(&self, x: usize, y: usize) -> Vec3 {
+        self.pixels[y * self.width + x]
+    }
+
+    // Muestreo bilineal con repetición (wrap) de las coordenadas UV
+    pub fn sample(&self, uv: Vec2) -> Vec3 {
+        let u = uv.x - uv.x.floor();
+        let v = uv.y - uv.y.floor();
+
+        let x = u * self.width as f32 - 0.5;
+        let y = v * self.height as f32 - 0.5;
+        let x0 = x.floor();
+        let y0 = y.floor();
+        let tx = x - x0;
+        let ty = y - y0;
+
+        let wrap = |value: f32, size: usize| -> usize { (value as i32).rem_euclid(size as i32) as usize };
+        let x0i = wrap(x0, self.width);
+        let x1i = wrap(x0 + 1.0, self.width);
+        let y0i = wrap(y0, self.height);
+        let y1i = wrap(y0 + 1.0, self.height);
+
+        let top = self.texel(x0i, y0i).lerp(&self.texel(x1i, y0i), tx);
+        let bottom = self.texel(x0i, y1i).lerp(&self.texel(x1i, y1i), tx);
+        top.lerp(&bottom, ty)
+    }
+}
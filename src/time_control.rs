@@ -0,0 +1,65 @@
+// Control del tiempo de la simulación: las órbitas y la rotación de los
+// cuerpos avanzan con su propio reloj, que se puede pausar (P), acelerar o
+// frenar por pasos (+/-, hasta x100) e invertir (R). La nave, los controles y
+// la animación de los shaders siguen con el tiempo real, así acelerar el
+// sistema no acelera el vuelo ni hace saltar las nubes o la lava.
+
+// Escalas disponibles con +/-; la normal es la de índice `NORMAL`
+const SCALES: [f32; 9] = [0.25, 0.5, 1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0];
+const NORMAL: usize = 2;
+
+pub struct TimeControl {
+    scale: usize,
+    paused: bool,
+    reversed: bool,
+}
+
+impl Default for TimeControl {
+    fn default() -> Self {
+        TimeControl { scale: NORMAL, paused: false, reversed: false }
+    }
+}
+
+impl TimeControl {
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    pub fn toggle_reverse(&mut self) {
+        self.reversed = !self.reversed;
+    }
+
+    pub fn faster(&mut self) {
+        self.scale = (self.scale + 1).min(SCALES.len() - 1);
+    }
+
+    pub fn slower(&mut self) {
+        self.scale = self.scale.saturating_sub(1);
+    }
+
+    // Factor sobre el paso de tiempo de las órbitas: 0 en pausa y negativo
+    // hacia atrás
+    pub fn factor(&self) -> f32 {
+        if self.paused {
+            0.0
+        } else if self.reversed {
+            -SCALES[self.scale]
+        } else {
+            SCALES[self.scale]
+        }
+    }
+
+    // Estado para la consola y el HUD
+    pub fn label(&self) -> String {
+        let direction = if self.reversed { ", invertido" } else { "" };
+        if self.paused {
+            format!("órbitas en pausa (x{}{})", SCALES[self.scale], direction)
+        } else {
+            format!("x{}{}", SCALES[self.scale], direction)
+        }
+    }
+
+    pub fn is_normal(&self) -> bool {
+        !self.paused && !self.reversed && self.scale == NORMAL
+    }
+}
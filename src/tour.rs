@@ -0,0 +1,273 @@
+// Recorridos cinemáticos (`--tour archivo.toml`, tecla T): la cámara sigue
+// una curva Catmull-Rom por los puntos clave del archivo, y cada punto fija
+// además la exposición, el campo de visión y la velocidad del tiempo de la
+// simulación, que se interpolan entre puntos. Así un recorrido puede
+// acercarse a oscuras a un planeta y abrir la exposición cuando asoma el sol
+// por su borde, sin tocar el código.
+//
+// Con `--record-tour archivo.toml` la tecla K agrega un punto clave con la
+// cámara de ese momento (posición, hacia dónde mira y campo de visión) y
+// reescribe el archivo, que queda listo para `--tour`. Cerca de un cuerpo el
+// punto se guarda relativo a él, así el recorrido lo sigue en su órbita
+// aunque se reproduzca en otro momento de la simulación.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use nalgebra_glm::Vec3;
+use serde::{Deserialize, Serialize};
+
+use crate::course::catmull_rom;
+use crate::scene::Scene;
+use crate::FOV_DEGREES;
+
+// Distancia al punto al que mira la cámara en los puntos grabados
+const RECORD_LOOK_DISTANCE: f32 = 10.0;
+// Un punto grabado a menos de estos radios de un cuerpo se guarda relativo a él
+const RECORD_BODY_RANGE: f32 = 6.0;
+// Separación entre el último punto de un archivo existente y el primero que se agrega
+const RECORD_RESUME_GAP: f32 = 3.0;
+
+fn default_fov() -> f32 {
+    FOV_DEGREES
+}
+
+fn default_time_scale() -> f32 {
+    1.0
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Keyframe {
+    // Instante del punto desde el inicio del recorrido, en unidades de tiempo
+    // de la simulación a velocidad normal
+    pub time: f32,
+    pub position: [f32; 3],
+    #[serde(default)]
+    pub look_at: [f32; 3],
+    // Cuerpo al que se refieren `position` y `look_at`: son desplazamientos
+    // desde su centro, que sigue su órbita. Sin cuerpo son coordenadas del mundo.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    // Con `body`, los desplazamientos se miden respecto al sol: +X apunta del
+    // sol al cuerpo, +Y hacia arriba y +Z hacia el costado. Un punto en +X
+    // queda del lado oscuro sin importar dónde vaya el cuerpo en su órbita.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub sun_relative: bool,
+    #[serde(default = "default_fov")]
+    pub fov: f32,
+    // Exposición en pasos: +1 duplica el brillo y -1 lo reduce a la mitad
+    #[serde(default)]
+    pub exposure: f32,
+    // Velocidad del tiempo de la simulación (1 normal, 0 detenido)
+    #[serde(default = "default_time_scale")]
+    pub time_scale: f32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Tour {
+    // Sin nombre se usa el del archivo
+    #[serde(default)]
+    pub name: String,
+    // Volver a empezar al terminar
+    #[serde(default, rename = "loop")]
+    pub looping: bool,
+    #[serde(rename = "keyframe")]
+    pub keyframes: Vec<Keyframe>,
+}
+
+// La cámara del recorrido en un instante
+pub struct TourFrame {
+    pub position: Vec3,
+    pub look_at: Vec3,
+    pub fov: f32,
+    pub exposure: f32,
+}
+
+// Interpolación suave (con aceleración y frenado) entre dos valores
+fn ease(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t * t * (3.0 - 2.0 * t)
+}
+
+impl Tour {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let mut tour: Tour = toml::from_str(&source).map_err(|e| e.to_string())?;
+        if tour.name.is_empty() {
+            tour.name = path.file_stem().map_or_else(String::new, |stem| stem.to_string_lossy().into_owned());
+        }
+        if tour.keyframes.len() < 2 {
+            return Err("un recorrido necesita al menos dos puntos clave".to_string());
+        }
+        for (index, pair) in tour.keyframes.windows(2).enumerate() {
+            if pair[1].time <= pair[0].time {
+                return Err(format!("el punto clave {} no va después del anterior en el tiempo", index + 2));
+            }
+        }
+        for (index, keyframe) in tour.keyframes.iter().enumerate() {
+            if !(1.0..=170.0).contains(&keyframe.fov) {
+                return Err(format!("punto clave {}: fov {} fuera de 1..170", index + 1, keyframe.fov));
+            }
+            if keyframe.time_scale < 0.0 {
+                return Err(format!("punto clave {}: time_scale no puede ser negativo", index + 1));
+            }
+            if keyframe.sun_relative && keyframe.body.is_none() {
+                return Err(format!("punto clave {}: sun_relative necesita un body", index + 1));
+            }
+        }
+        Ok(tour)
+    }
+
+    // Comprueba que los cuerpos nombrados existan en la escena
+    pub fn check(&self, scene: &Scene) -> Result<(), String> {
+        for (index, keyframe) in self.keyframes.iter().enumerate() {
+            if let Some(name) = &keyframe.body {
+                if !scene.bodies.iter().any(|body| &body.name == name) {
+                    return Err(format!("punto clave {}: la escena no tiene un cuerpo '{}'", index + 1, name));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map_or(0.0, |last| last.time)
+    }
+
+    // ¿Terminó el recorrido a los `elapsed`? En bucle nunca termina
+    pub fn finished(&self, elapsed: f32) -> bool {
+        !self.looping && elapsed > self.duration()
+    }
+
+    // Segmento entre puntos clave en que cae `elapsed` y el avance 0..1 dentro de él
+    fn segment(&self, elapsed: f32) -> (usize, f32) {
+        let elapsed = if self.looping { elapsed.rem_euclid(self.duration()) } else { elapsed };
+        let last = self.keyframes.len() - 2;
+        let index = self.keyframes[1..=last].iter().take_while(|keyframe| keyframe.time <= elapsed).count();
+        let (a, b) = (&self.keyframes[index], &self.keyframes[index + 1]);
+        (index, ((elapsed - a.time) / (b.time - a.time)).clamp(0.0, 1.0))
+    }
+
+    // Velocidad del tiempo de la simulación a los `elapsed` del recorrido
+    pub fn time_scale(&self, elapsed: f32) -> f32 {
+        let (index, t) = self.segment(elapsed);
+        ease(self.keyframes[index].time_scale, self.keyframes[index + 1].time_scale, t)
+    }
+
+    // Cámara a los `elapsed` del recorrido, con los cuerpos donde están ahora
+    pub fn sample(&self, scene: &Scene, elapsed: f32) -> TourFrame {
+        let (index, t) = self.segment(elapsed);
+        let count = self.keyframes.len();
+        // Vecinos para la curva; en los extremos se repite el punto
+        let neighbours = [index.saturating_sub(1), index, index + 1, (index + 2).min(count - 1)];
+        let points = neighbours.map(|i| self.world_points(scene, &self.keyframes[i]));
+        let position = catmull_rom(points[0].0, points[1].0, points[2].0, points[3].0, t);
+        let look_at = catmull_rom(points[0].1, points[1].1, points[2].1, points[3].1, t);
+
+        let (a, b) = (&self.keyframes[index], &self.keyframes[index + 1]);
+        TourFrame {
+            position,
+            look_at,
+            fov: ease(a.fov, b.fov, t),
+            exposure: ease(a.exposure, b.exposure, t),
+        }
+    }
+
+    // Posición de la cámara y punto al que mira de un punto clave, en el mundo
+    fn world_points(&self, scene: &Scene, keyframe: &Keyframe) -> (Vec3, Vec3) {
+        let (position, look_at) = (Vec3::from(keyframe.position), Vec3::from(keyframe.look_at));
+        let Some(index) = keyframe.body.as_ref().and_then(|name| scene.bodies.iter().position(|body| &body.name == name)) else {
+            return (position, look_at);
+        };
+        let center = scene.states[index].position;
+        if !keyframe.sun_relative {
+            return (center + position, center + look_at);
+        }
+        let up = Vec3::new(0.0, 1.0, 0.0);
+        let away = (center - scene.star_position()).try_normalize(1e-6).unwrap_or_else(|| Vec3::new(1.0, 0.0, 0.0));
+        let side = away.cross(&up).try_normalize(1e-6).unwrap_or_else(|| Vec3::new(0.0, 0.0, 1.0));
+        let up = side.cross(&away);
+        let to_world = |offset: Vec3| center + away * offset.x + up * offset.y + side * offset.z;
+        (to_world(position), to_world(look_at))
+    }
+}
+
+// Grabación de un recorrido con la tecla K (`--record-tour`)
+pub struct TourRecorder {
+    path: PathBuf,
+    pub tour: Tour,
+    // Instante (tiempo del programa) del primer punto grabado en esta sesión
+    start: Option<f32>,
+    // Instante del recorrido en que empieza lo grabado en esta sesión
+    offset: f32,
+}
+
+impl TourRecorder {
+    // Si el archivo ya tiene un recorrido, los puntos nuevos van después
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref();
+        let tour = if path.exists() {
+            Tour::load(path)?
+        } else {
+            Tour {
+                name: path.file_stem().map_or_else(String::new, |stem| stem.to_string_lossy().into_owned()),
+                looping: false,
+                keyframes: Vec::new(),
+            }
+        };
+        let offset = if tour.keyframes.is_empty() { 0.0 } else { tour.duration() + RECORD_RESUME_GAP };
+        Ok(TourRecorder { path: path.to_path_buf(), tour, start: None, offset })
+    }
+
+    // Agrega un punto con la cámara en `position` mirando hacia `forward` a
+    // los `time` del programa y guarda el archivo si ya se puede reproducir.
+    // Devuelve el cuerpo al que quedó referido el punto, si lo hay.
+    pub fn add(&mut self, scene: &Scene, time: f32, position: Vec3, forward: Vec3, fov: f32) -> Result<Option<String>, String> {
+        let start = *self.start.get_or_insert(time);
+        let time = self.offset + time - start;
+        // Dos toques en el mismo cuadro no pueden repetir el instante
+        if self.tour.keyframes.last().is_some_and(|last| time <= last.time) {
+            return Err("el punto clave tiene que ir después del anterior en el tiempo".to_string());
+        }
+        let look_at = position + forward * RECORD_LOOK_DISTANCE;
+        let nearest = scene
+            .bodies
+            .iter()
+            .zip(&scene.states)
+            .map(|(body, state)| (body, state.position, (state.position - position).magnitude() / body.scale))
+            .filter(|(_, _, radii)| *radii < RECORD_BODY_RANGE)
+            .min_by(|a, b| a.2.total_cmp(&b.2));
+        let (body, origin) = match nearest {
+            Some((body, center, _)) => (Some(body.name.clone()), center),
+            None => (None, Vec3::zeros()),
+        };
+        let keyframe = Keyframe {
+            time,
+            position: (position - origin).into(),
+            look_at: (look_at - origin).into(),
+            body: body.clone(),
+            sun_relative: false,
+            fov,
+            exposure: 0.0,
+            time_scale: default_time_scale(),
+        };
+        self.tour.keyframes.push(keyframe);
+        if self.tour.keyframes.len() >= 2 {
+            self.save()?;
+        }
+        Ok(body)
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let source = toml::to_string(&self.tour).map_err(|e| e.to_string())?;
+        fs::write(&self.path, source).map_err(|e| e.to_string())
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
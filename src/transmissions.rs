@@ -0,0 +1,229 @@
+// Transmisiones de radio: mensajes de texto de las estaciones, del control
+// de la misión y de las señales de socorro. Llegan al acercarse a una
+// estación o a una anomalía y con los hitos del vuelo (aterrizar, investigar
+// una anomalía); se encolan y se entregan de a una, cada una con un pitido
+// propio de su canal, y las últimas quedan en un registro en la esquina
+// inferior derecha, con un retrato provisional (la inicial de quien habla en
+// un recuadro del color del canal) hasta que se desvanecen.
+
+use std::collections::VecDeque;
+
+use nalgebra_glm::Vec3;
+
+use crate::anomaly::AnomalyKind;
+use crate::audio::{Tone, Waveform};
+use crate::framebuffer::{pack_color, unpack_color, Framebuffer};
+use crate::scene::Scene;
+use crate::text::{CHAR_ADVANCE, LINE_HEIGHT};
+
+// Distancia desde la que llaman las estaciones y, sumada al tamaño de cada
+// anomalía, desde la que se captan sus señales
+const HAIL_RANGE: f32 = 30.0;
+// Tiempo mínimo entre dos mensajes entregados
+const DELIVERY_GAP: f32 = 1.5;
+// Tiempo que un mensaje queda en el registro; la última fracción se desvanece
+const DISPLAY_TIME: f32 = 12.0;
+const FADE_FRACTION: f32 = 0.25;
+const LOG_SIZE: usize = 3;
+
+// Registro en pantalla: columnas de texto, márgenes y lado del retrato
+const COLUMNS: usize = 30;
+const MARGIN: usize = 10;
+const PADDING: usize = 6;
+const PORTRAIT_SIZE: usize = LINE_HEIGHT * 2;
+const BACKGROUND_DIM: f32 = 0.35;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Channel {
+    Station,
+    Control,
+    Distress,
+}
+
+impl Channel {
+    fn color(self) -> Vec3 {
+        match self {
+            Channel::Station => Vec3::new(0.4, 0.9, 1.0),
+            Channel::Control => Vec3::new(0.5, 1.0, 0.5),
+            Channel::Distress => Vec3::new(1.0, 0.35, 0.3),
+        }
+    }
+
+    // Pitido de llegada: dos tonos cortos para el control, uno para las
+    // estaciones y uno entrecortado para las señales de socorro
+    fn tone(self) -> Tone {
+        match self {
+            Channel::Station => Tone::new(1320.0, 0.08, 0.15, Waveform::Sine),
+            Channel::Control => Tone::new(990.0, 0.12, 0.15, Waveform::Sine).with_tremolo(16.0),
+            Channel::Distress => Tone::new(700.0, 0.3, 0.15, Waveform::Square).with_tremolo(8.0),
+        }
+    }
+}
+
+struct Message {
+    channel: Channel,
+    sender: String,
+    text: String,
+}
+
+struct Received {
+    message: Message,
+    age: f32,
+}
+
+#[derive(Default)]
+pub struct Transmissions {
+    queue: VecDeque<Message>,
+    // Los últimos entregados, el más nuevo al final
+    log: VecDeque<Received>,
+    // Tiempo hasta que se pueda entregar el siguiente
+    gap: f32,
+    // Estaciones y anomalías que estaban al alcance en el cuadro anterior:
+    // solo llaman al entrar en él
+    stations_in_range: Vec<bool>,
+    anomalies_in_range: Vec<bool>,
+}
+
+// Parte el texto en líneas de hasta `columns` caracteres, por palabras
+fn wrap(text: &str, columns: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > columns {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+// Sube `in_range[index]` y devuelve si acaba de entrar al alcance
+fn entered(in_range: &mut Vec<bool>, count: usize, index: usize, inside: bool) -> bool {
+    in_range.resize(count, false);
+    let was_inside = std::mem::replace(&mut in_range[index], inside);
+    inside && !was_inside
+}
+
+impl Transmissions {
+    pub fn send(&mut self, channel: Channel, sender: &str, text: impl Into<String>) {
+        self.queue.push_back(Message { channel, sender: sender.to_string(), text: text.into() });
+    }
+
+    // Disparadores por cercanía: las estaciones saludan y las anomalías
+    // emiten al entrar la nave en su alcance
+    pub fn watch(&mut self, scene: &Scene, ship_position: Vec3) {
+        for (index, (prop, state)) in scene.props.iter().zip(&scene.prop_states).enumerate() {
+            let inside = (state.position - ship_position).magnitude() < HAIL_RANGE;
+            if entered(&mut self.stations_in_range, scene.props.len(), index, inside) {
+                let greeting = prop.greeting.clone().unwrap_or_else(|| {
+                    if prop.dockable {
+                        format!("Aquí {}. Lo tenemos en el radar; entre despacio a la zona de acoplamiento.", prop.name)
+                    } else {
+                        format!("Aquí {}. Mantenga la distancia, por favor.", prop.name)
+                    }
+                });
+                self.send(Channel::Station, &prop.name, greeting);
+            }
+        }
+        for (index, (anomaly, state)) in scene.anomalies.iter().zip(&scene.anomaly_states).enumerate() {
+            let inside = (state.position - ship_position).magnitude() < HAIL_RANGE + anomaly.size;
+            if entered(&mut self.anomalies_in_range, scene.anomalies.len(), index, inside) {
+                match anomaly.kind {
+                    AnomalyKind::Derelict => self.send(
+                        Channel::Distress,
+                        &anomaly.name,
+                        "...baliza automática... tripulación evacuada... no responde el soporte vital...",
+                    ),
+                    AnomalyKind::Wormhole => self.send(
+                        Channel::Control,
+                        "Control",
+                        format!("Lecturas gravitatorias extrañas cerca de {}. Acérquese con cuidado.", anomaly.name),
+                    ),
+                }
+            }
+        }
+    }
+
+    // Envejece el registro y entrega el siguiente mensaje si ya toca;
+    // devuelve su pitido
+    pub fn update(&mut self, dt: f32) -> Option<Tone> {
+        for received in &mut self.log {
+            received.age += dt;
+        }
+        self.log.retain(|received| received.age < DISPLAY_TIME);
+        self.gap = (self.gap - dt).max(0.0);
+        if self.gap > 0.0 {
+            return None;
+        }
+        let message = self.queue.pop_front()?;
+        println!("Transmisión de {}: {}", message.sender, message.text);
+        let tone = message.channel.tone();
+        self.log.push_back(Received { message, age: 0.0 });
+        if self.log.len() > LOG_SIZE {
+            self.log.pop_front();
+        }
+        self.gap = DELIVERY_GAP;
+        Some(tone)
+    }
+
+    // Registro en la esquina inferior derecha, el mensaje más nuevo abajo
+    pub fn render(&self, framebuffer: &mut Framebuffer, text_color: u32) {
+        let text_left = PADDING * 2 + PORTRAIT_SIZE;
+        let width = text_left + COLUMNS * CHAR_ADVANCE + PADDING;
+        if framebuffer.width < width + MARGIN * 2 {
+            return;
+        }
+        let left = framebuffer.width - width - MARGIN;
+        let mut bottom = framebuffer.height.saturating_sub(MARGIN);
+        for received in self.log.iter().rev() {
+            let message = &received.message;
+            let lines = wrap(&message.text, COLUMNS);
+            let height = ((lines.len() + 1) * LINE_HEIGHT).max(PORTRAIT_SIZE) + PADDING * 2;
+            if bottom < height + MARGIN {
+                break;
+            }
+            let top = bottom - height;
+            let fade = ((DISPLAY_TIME - received.age) / (DISPLAY_TIME * FADE_FRACTION)).clamp(0.0, 1.0);
+            let color = message.channel.color();
+
+            for y in top..bottom {
+                for x in left..left + width {
+                    let border = x == left || y == top || x == left + width - 1 || y == bottom - 1;
+                    let background = unpack_color(framebuffer.buffer[framebuffer.index(x, y)]);
+                    let dimmed = if border { color * 0.6 } else { background * BACKGROUND_DIM };
+                    framebuffer.overlay_pixel(x, y, pack_color(background.lerp(&dimmed, fade)));
+                }
+            }
+
+            // Retrato provisional: recuadro del color del canal con la inicial
+            let (portrait_x, portrait_y) = (left + PADDING, top + PADDING);
+            for y in portrait_y..portrait_y + PORTRAIT_SIZE {
+                for x in portrait_x..portrait_x + PORTRAIT_SIZE {
+                    let edge = x == portrait_x || y == portrait_y || x == portrait_x + PORTRAIT_SIZE - 1 || y == portrait_y + PORTRAIT_SIZE - 1;
+                    framebuffer.overlay_blend(x, y, pack_color(if edge { color } else { color * 0.3 }), fade);
+                }
+            }
+            bottom = top - 4;
+            // El texto no se mezcla: desaparece a mitad del desvanecido
+            if fade < 0.5 {
+                continue;
+            }
+            let initial: String = message.sender.chars().take(1).collect();
+            let initial_x = portrait_x + (PORTRAIT_SIZE - CHAR_ADVANCE) / 2 + 1;
+            let initial_y = portrait_y + (PORTRAIT_SIZE - LINE_HEIGHT) / 2 + 2;
+            framebuffer.draw_text(initial_x, initial_y, &initial, pack_color(color));
+
+            let text_x = left + text_left;
+            framebuffer.draw_text(text_x, top + PADDING, &message.sender, pack_color(color));
+            for (row, line) in lines.iter().enumerate() {
+                framebuffer.draw_text(text_x, top + PADDING + (row + 1) * LINE_HEIGHT, line, text_color);
+            }
+        }
+    }
+}
@@ -1,19 +1,32 @@
-use nalgebra_glm::{Vec3, dot};
+use std::ops::{Add, Mul};
+
+use nalgebra_glm::{Vec3, Vec4};
 use crate::fragment::Fragment;
 use crate::vertex::Vertex;
 use crate::color::Color;
 use crate::Uniforms;
 
-pub fn triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex, uniforms: &Uniforms) -> Vec<Fragment> {
-  let mut fragments = Vec::new();
+// Triángulo ya proyectado a pantalla: lo que comparten todos los bloques
+// del framebuffer en los que se rasteriza
+pub struct ScreenTriangle {
+  screen: [Vec3; 3],
+  w: [f32; 3],
+  area: f32,
+  // Rectángulo de píxeles que cubre (min_x, min_y, max_x, max_y), con los
+  // máximos incluidos y recortado a la pantalla
+  pub bounds: (usize, usize, usize, usize),
+}
 
+// Proyecta el triángulo a pantalla; `None` si no hay nada que rasterizar
+// (fuera de pantalla, degenerado o de espaldas)
+pub fn setup_triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex, uniforms: &Uniforms) -> Option<ScreenTriangle> {
   // Perform perspective division to get screen-space coordinates
   let a_w = v1.transformed_position.w;
   let b_w = v2.transformed_position.w;
   let c_w = v3.transformed_position.w;
 
   if a_w.abs() < 1e-6 || b_w.abs() < 1e-6 || c_w.abs() < 1e-6 {
-      return fragments;
+      return None;
   }
 
   let a = Vec3::new(
@@ -32,12 +45,14 @@ pub fn triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex, uniforms: &Uniforms) -> V
       v3.transformed_position.z / c_w,
   );
 
-  // Apply viewport transformation
+  // Apply viewport transformation (the viewport matrix carries the framebuffer size)
+  let viewport = &uniforms.viewport_matrix;
   let transform_to_screen = |pos: Vec3| -> Vec3 {
-      let screen_x = (pos.x * 0.5 + 0.5) * 800.0;
-      let screen_y = (1.0 - (pos.y * 0.5 + 0.5)) * 600.0;
-      Vec3::new(screen_x, screen_y, pos.z)
+      let screen = viewport * Vec4::new(pos.x, pos.y, pos.z, 1.0);
+      Vec3::new(screen.x, screen.y, pos.z)
   };
+  let max_screen_x = (viewport[(0, 3)] * 2.0) as i32 - 1;
+  let max_screen_y = (viewport[(1, 3)] * 2.0) as i32 - 1;
 
   let a_screen = transform_to_screen(a);
   let b_screen = transform_to_screen(b);
@@ -48,51 +63,101 @@ pub fn triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex, uniforms: &Uniforms) -> V
   // Clamp to screen bounds
   let min_x = min_x.max(0);
   let min_y = min_y.max(0);
-  let max_x = max_x.min(799);
-  let max_y = max_y.min(599);
+  let max_x = max_x.min(max_screen_x);
+  let max_y = max_y.min(max_screen_y);
 
   // Skip if completely outside screen
-  if min_x > 799 || min_y > 599 || max_x < 0 || max_y < 0 {
-      return fragments;
+  if min_x > max_x || min_y > max_y {
+      return None;
   }
 
   let triangle_area = edge_function(&a_screen, &b_screen, &c_screen);
 
   if triangle_area.abs() < 1e-6 {
-      return fragments;
+      return None;
   }
 
   // Backface culling
   if triangle_area < 0.0 {
-      return fragments;
+      return None;
+  }
+
+  Some(ScreenTriangle {
+      screen: [a_screen, b_screen, c_screen],
+      w: [a_w, b_w, c_w],
+      area: triangle_area,
+      bounds: (min_x as usize, min_y as usize, max_x as usize, max_y as usize),
+  })
+}
+
+impl ScreenTriangle {
+  // Distancia media de los vértices a la cámara (el `w` de la proyección),
+  // para ordenar los triángulos translúcidos de atrás hacia adelante
+  pub fn view_depth(&self) -> f32 {
+      (self.w[0] + self.w[1] + self.w[2]) / 3.0
   }
+}
+
+// Rasteriza la parte del triángulo que cae en `rect` (un bloque del
+// framebuffer, con los máximos incluidos) dejando sus fragmentos en
+// `fragments`, que se vacía primero: quien llama reutiliza el mismo vector
+// para todos los triángulos y así no se pide memoria nueva por cada uno
+pub fn triangle(vertices: [&Vertex; 3], setup: &ScreenTriangle, rect: (usize, usize, usize, usize), fragments: &mut Vec<Fragment>) {
+  fragments.clear();
+
+  let [v1, v2, v3] = vertices;
+  let [a_screen, b_screen, c_screen] = &setup.screen;
+  let [a_w, b_w, c_w] = setup.w;
+  let triangle_area = setup.area;
+  let (min_x, min_y, max_x, max_y) = setup.bounds;
+  let (min_x, min_y) = (min_x.max(rect.0), min_y.max(rect.1));
+  let (max_x, max_y) = (max_x.min(rect.2), max_y.min(rect.3));
 
   for y in min_y..=max_y {
     for x in min_x..=max_x {
       let point = Vec3::new(x as f32 + 0.5, y as f32 + 0.5, 0.0);
 
-      let (w1, w2, w3) = barycentric_coordinates(&point, &a_screen, &b_screen, &c_screen, triangle_area);
+      let (w1, w2, w3) = barycentric_coordinates(&point, a_screen, b_screen, c_screen, triangle_area);
 
       if w1 >= 0.0 && w2 >= 0.0 && w3 >= 0.0 {
         let inv_w = 1.0/a_w * w1 + 1.0/b_w * w2 + 1.0/c_w * w3;
         let w = 1.0/inv_w;
-
-        let vertex_position = (v1.position * (w1 / a_w) + v2.position * (w2 / b_w) + v3.position * (w3 / c_w)) * w;
+        let weights = [w1 / a_w * w, w2 / b_w * w, w3 / c_w * w];
+
+        let vertex_position = perspective_interpolate([v1.position, v2.position, v3.position], weights);
+        let tex_coords = perspective_interpolate([v1.tex_coords, v2.tex_coords, v3.tex_coords], weights);
+        let world_position = perspective_interpolate([v1.world_position, v2.world_position, v3.world_position], weights);
+        // Interpolar normales unitarias las acorta; se renormalizan para la iluminación
+        let normal = perspective_interpolate([v1.transformed_normal, v2.transformed_normal, v3.transformed_normal], weights)
+            .try_normalize(1e-6)
+            .unwrap_or_else(Vec3::zeros);
         
         let depth = a_screen.z * w1 + b_screen.z * w2 + c_screen.z * w3;
 
-        fragments.push(Fragment::new_with_vertex_position(
+        let mut fragment = Fragment::new_with_vertex_position(
             x as f32, 
             y as f32, 
             Color::new(255, 255, 255),
             depth,
             vertex_position
-        ));
+        );
+        fragment.tex_coords = tex_coords;
+        fragment.normal = normal;
+        fragment.world_position = world_position;
+        fragments.push(fragment);
       }
     }
   }
+}
 
-  fragments
+// Interpolación con corrección de perspectiva: `weights` son las
+// coordenadas baricéntricas de pantalla divididas por el w de cada vértice
+// y multiplicadas por el w interpolado del píxel
+fn perspective_interpolate<T>(values: [T; 3], weights: [f32; 3]) -> T
+where
+    T: Copy + Mul<f32, Output = T> + Add<Output = T>,
+{
+    values[0] * weights[0] + values[1] * weights[1] + values[2] * weights[2]
 }
 
 fn calculate_bounding_box(v1: &Vec3, v2: &Vec3, v3: &Vec3) -> (i32, i32, i32, i32) {
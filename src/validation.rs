@@ -0,0 +1,525 @@
+// Validación de archivos de escena: recolecta todos los problemas en lugar
+// de detenerse en el primero, indicando cuerpo, campo y línea cuando es posible.
+
+use std::fmt;
+
+use crate::anomaly::AnomalyKind;
+use crate::camera::{MAX_PITCH, MIN_PITCH};
+use crate::scene::{shader_params, shader_from_name, Scene};
+
+// Cada cráter se evalúa en cada fragmento del cuerpo
+const MAX_CRATERS: u32 = 200;
+// Cada estrella del fondo se proyecta en cada cuadro
+const MAX_STARS: usize = 20000;
+
+#[derive(Debug, Clone)]
+pub struct ValidationIssue {
+    pub body: String,
+    pub field: &'static str,
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "línea {}: ", line)?,
+            None => write!(f, "  ")?,
+        }
+        // Los problemas fuera de un `[[body]]` llevan el nombre de su sección, p. ej. "[race]"
+        if self.body.starts_with('[') {
+            write!(f, "sección {}, campo `{}`: {}", self.body, self.field, self.message)
+        } else {
+            write!(f, "cuerpo '{}', campo `{}`: {}", self.body, self.field, self.message)
+        }
+    }
+}
+
+// Ubica en el texto fuente la línea de cada `[[body]]` y de sus campos.
+// Solo aplica a TOML; para JSON se reporta cuerpo y campo sin línea.
+struct SourceMap<'a> {
+    lines: Vec<&'a str>,
+    body_starts: Vec<usize>,
+    is_json: bool,
+}
+
+impl<'a> SourceMap<'a> {
+    fn new(source: &'a str, is_json: bool) -> Self {
+        let lines: Vec<&str> = source.lines().collect();
+        let body_starts = if is_json {
+            Vec::new()
+        } else {
+            lines.iter()
+                .enumerate()
+                .filter(|(_, line)| line.trim() == "[[body]]")
+                .map(|(i, _)| i)
+                .collect()
+        };
+        SourceMap { lines, body_starts, is_json }
+    }
+
+    // Línea (base 1) del campo dentro de la sección del cuerpo `index`,
+    // o la del encabezado `[[body]]` si el campo no aparece
+    fn locate(&self, index: usize, field: &str) -> Option<usize> {
+        let start = *self.body_starts.get(index)?;
+        let end = self.body_starts.get(index + 1).copied().unwrap_or(self.lines.len());
+        Some(self.find_field(start, end, field).unwrap_or(start) + 1)
+    }
+
+    // Igual que `locate`, para una tabla única como `[race]`
+    fn locate_section(&self, header: &str, field: &str) -> Option<usize> {
+        if self.is_json {
+            return None;
+        }
+        let start = self.lines.iter().position(|line| line.trim() == header)?;
+        let end = (start + 1..self.lines.len())
+            .find(|&i| self.lines[i].trim_start().starts_with('['))
+            .unwrap_or(self.lines.len());
+        Some(self.find_field(start, end, field).unwrap_or(start) + 1)
+    }
+
+    fn find_field(&self, start: usize, end: usize, field: &str) -> Option<usize> {
+        (start..end).find(|&i| {
+            let line = self.lines[i].trim_start();
+            line.strip_prefix(field)
+                .is_some_and(|rest| rest.trim_start().starts_with('='))
+        })
+    }
+}
+
+pub fn validate_scene(scene: &Scene, source: &str, is_json: bool) -> Vec<ValidationIssue> {
+    let map = SourceMap::new(source, is_json);
+    let mut issues = Vec::new();
+
+    let mut report = |index: usize, body: &str, field: &'static str, message: String| {
+        issues.push(ValidationIssue {
+            body: body.to_string(),
+            field,
+            line: map.locate(index, field),
+            message,
+        });
+    };
+
+    if scene.bodies.is_empty() {
+        report(0, "-", "body", "la escena no define ningún cuerpo".to_string());
+    }
+
+    for (index, body) in scene.bodies.iter().enumerate() {
+        let name = body.name.as_str();
+
+        if body.name.trim().is_empty() {
+            report(index, name, "name", "el nombre no puede estar vacío".to_string());
+        }
+        if scene.bodies[..index].iter().any(|other| other.name == body.name) {
+            report(index, name, "name", "nombre duplicado".to_string());
+        }
+        if shader_from_name(&body.shader).is_none() {
+            report(index, name, "shader", format!("shader desconocido '{}'", body.shader));
+        }
+        let schema = shader_params(&body.shader);
+        for (param, value) in &body.params {
+            match schema.iter().find(|spec| spec.name == param) {
+                Some(spec) if !(spec.min..=spec.max).contains(value) => report(
+                    index,
+                    name,
+                    "params",
+                    format!("`{}` debe estar entre {} y {} (es {})", param, spec.min, spec.max, value),
+                ),
+                Some(_) => {}
+                None if schema.is_empty() => {
+                    report(index, name, "params", format!("el shader '{}' no acepta parámetros", body.shader));
+                    break;
+                }
+                None => {
+                    let known: Vec<&str> = schema.iter().map(|spec| spec.name).collect();
+                    report(index, name, "params", format!("parámetro desconocido '{}' (acepta: {})", param, known.join(", ")));
+                }
+            }
+        }
+        if body.seed.is_none() {
+            report(index, name, "seed", "falta la semilla del shader procedural".to_string());
+        }
+        if body.scale <= 0.0 {
+            report(index, name, "scale", format!("debe ser positiva (es {})", body.scale));
+        }
+        if body.collision_radius <= 0.0 {
+            report(index, name, "collision_radius", format!("debe ser positivo (es {})", body.collision_radius));
+        }
+        if let Some(margin) = body.collision_margin.filter(|margin| *margin < 0.0) {
+            report(index, name, "collision_margin", format!("no puede ser negativo (es {})", margin));
+        }
+        if !(0.0..=0.5).contains(&body.oblateness) {
+            report(index, name, "oblateness", format!("debe estar entre 0 y 0.5 (es {})", body.oblateness));
+        }
+        if body.orbit_radius < 0.0 {
+            report(index, name, "orbit_radius", format!("no puede ser negativo (es {})", body.orbit_radius));
+        }
+        if body.orbit_radius > 0.0 && body.orbit_period == 0.0 {
+            report(index, name, "orbit_period", "un cuerpo en órbita necesita un período distinto de 0".to_string());
+        }
+        if !(0.0..1.0).contains(&body.eccentricity) {
+            report(index, name, "eccentricity", format!("debe estar entre 0 y 1, sin incluir 1 (es {})", body.eccentricity));
+        }
+        if !(-90.0..=90.0).contains(&body.inclination) {
+            report(index, name, "inclination", format!("debe estar entre -90 y 90 grados (es {})", body.inclination));
+        }
+        if body.day_length < 0.0 {
+            report(index, name, "day_length", "no puede ser negativo; usa `retrograde = true`".to_string());
+        }
+        if !(0.0..=180.0).contains(&body.axial_tilt) {
+            report(index, name, "axial_tilt", format!("debe estar entre 0 y 180 grados (es {})", body.axial_tilt));
+        }
+        if body.mass < 0.0 {
+            report(index, name, "mass", format!("no puede ser negativa (es {})", body.mass));
+        }
+        if let Some(atmosphere) = &body.atmosphere {
+            if atmosphere.color.iter().any(|c| *c < 0.0) {
+                report(index, name, "atmosphere", "el color no puede tener componentes negativas".to_string());
+            }
+            if atmosphere.intensity < 0.0 {
+                report(index, name, "atmosphere", format!("la intensidad no puede ser negativa (es {})", atmosphere.intensity));
+            }
+            if atmosphere.falloff <= 0.0 {
+                report(index, name, "atmosphere", format!("`falloff` debe ser positivo (es {})", atmosphere.falloff));
+            }
+            if atmosphere.density < 0.0 {
+                report(index, name, "atmosphere", format!("la densidad no puede ser negativa (es {})", atmosphere.density));
+            }
+        }
+        if let Some(rings) = &body.rings {
+            if rings.inner_radius < 1.0 {
+                report(index, name, "rings", format!("`inner_radius` no puede quedar dentro del cuerpo (es {}, mínimo 1)", rings.inner_radius));
+            }
+            if rings.outer_radius <= rings.inner_radius {
+                report(index, name, "rings", format!(
+                    "`outer_radius` ({}) debe ser mayor que `inner_radius` ({})",
+                    rings.outer_radius, rings.inner_radius
+                ));
+            }
+            if !(-90.0..=90.0).contains(&rings.tilt) {
+                report(index, name, "rings", format!("`tilt` debe estar entre -90 y 90 grados (es {})", rings.tilt));
+            }
+            if !(0.0..=1.0).contains(&rings.opacity) {
+                report(index, name, "rings", format!("`opacity` debe estar entre 0 y 1 (es {})", rings.opacity));
+            }
+            if rings.palette.is_empty() {
+                report(index, name, "rings", "la paleta necesita al menos un color".to_string());
+            }
+            if rings.palette.iter().flatten().any(|c| *c < 0.0) {
+                report(index, name, "rings", "la paleta no puede tener componentes negativas".to_string());
+            }
+        }
+        if let Some(tail) = &body.tail {
+            if tail.length <= 0.0 {
+                report(index, name, "tail", format!("`length` debe ser positivo (es {})", tail.length));
+            }
+            if tail.active_distance <= 0.0 {
+                report(index, name, "tail", format!("`active_distance` debe ser positivo (es {})", tail.active_distance));
+            }
+            if tail.color.iter().any(|c| *c < 0.0) {
+                report(index, name, "tail", "el color no puede tener componentes negativas".to_string());
+            }
+        }
+        if let Some(lod) = &body.lod {
+            if lod.impostor_radius <= 0.0 {
+                report(index, name, "lod", format!("`impostor_radius` debe ser positivo (es {})", lod.impostor_radius));
+            }
+            if lod.mesh_radius < lod.impostor_radius {
+                report(index, name, "lod", format!("`mesh_radius` ({}) no puede ser menor que `impostor_radius` ({})", lod.mesh_radius, lod.impostor_radius));
+            }
+        }
+        if body.craters > 0 && !matches!(body.shader.as_str(), "rocky" | "desert") {
+            report(index, name, "craters", format!("el shader '{}' no dibuja cráteres (solo rocky y desert)", body.shader));
+        }
+        if let Some(climate) = &body.climate {
+            if body.shader != "rocky" {
+                report(index, name, "climate", format!("el shader '{}' no usa clima (solo rocky)", body.shader));
+            }
+            if climate.pole_temperature > climate.equator_temperature {
+                report(index, name, "climate", "los polos no pueden ser más cálidos que el ecuador".to_string());
+            }
+            if climate.lapse_rate < 0.0 {
+                report(index, name, "climate", format!("`lapse_rate` no puede ser negativo (es {})", climate.lapse_rate));
+            }
+            if !(0.0..=90.0).contains(&climate.axial_tilt) {
+                report(index, name, "climate", format!("`axial_tilt` debe estar entre 0 y 90 grados (es {})", climate.axial_tilt));
+            }
+        }
+        if body.craters > MAX_CRATERS {
+            report(index, name, "craters", format!("como máximo {} cráteres (hay {})", MAX_CRATERS, body.craters));
+        }
+
+        // Una luna orbita a un cuerpo definido antes que ella
+        let parent = match &body.parent {
+            Some(parent_name) => match scene.bodies.iter().position(|other| &other.name == parent_name) {
+                Some(parent_index) if parent_index < index => Some(&scene.bodies[parent_index]),
+                Some(parent_index) if parent_index == index => {
+                    report(index, name, "parent", "un cuerpo no puede orbitarse a sí mismo".to_string());
+                    None
+                }
+                Some(_) => {
+                    report(index, name, "parent", format!("'{}' debe definirse antes que sus lunas", parent_name));
+                    None
+                }
+                None => {
+                    report(index, name, "parent", format!("no existe el cuerpo '{}'", parent_name));
+                    None
+                }
+            },
+            None => None,
+        };
+
+        // La órbita no debe atravesar a los cuerpos centrales (o al padre, en una luna)
+        if body.orbit_radius > 0.0 {
+            // Si el padre no es válido ya se reportó arriba y no se revisa la órbita
+            let centrals: Vec<_> = match (parent, &body.parent) {
+                (Some(parent), _) => vec![parent],
+                (None, Some(_)) => Vec::new(),
+                (None, None) => scene.bodies.iter().filter(|b| b.orbit_radius == 0.0 && b.parent.is_none()).collect(),
+            };
+            for central in centrals {
+                // En una elipse el punto más cercano es el periapsis
+                let min_radius = central.collision_radius + body.collision_radius;
+                if body.periapsis() <= min_radius {
+                    let (field, closest) = if body.eccentricity > 0.0 {
+                        ("eccentricity", format!("su periapsis ({:.1})", body.periapsis()))
+                    } else {
+                        ("orbit_radius", format!("la órbita ({})", body.orbit_radius))
+                    };
+                    report(
+                        index,
+                        name,
+                        field,
+                        format!("{} atraviesa a '{}'; debe ser mayor que {}", closest, central.name, min_radius),
+                    );
+                }
+            }
+        }
+    }
+
+    let mut report_ambient = |field: &'static str, message: String| {
+        issues.push(ValidationIssue {
+            body: "[ambient]".to_string(),
+            field,
+            line: map.locate_section("[ambient]", field),
+            message,
+        });
+    };
+    if scene.ambient.radius <= 0.0 {
+        report_ambient("radius", format!("debe ser positivo (es {})", scene.ambient.radius));
+    }
+    if scene.ambient.volume < 0.0 {
+        report_ambient("volume", format!("no puede ser negativo (es {})", scene.ambient.volume));
+    }
+    if scene.ambient.fade < 0.0 {
+        report_ambient("fade", format!("no puede ser negativo (es {})", scene.ambient.fade));
+    }
+
+    let mut report_flight = |field: &'static str, message: String| {
+        issues.push(ValidationIssue {
+            body: "[flight]".to_string(),
+            field,
+            line: map.locate_section("[flight]", field),
+            message,
+        });
+    };
+    for (field, value) in [("max_speed", scene.flight.max_speed), ("thrust", scene.flight.thrust), ("brake", scene.flight.brake)] {
+        if value <= 0.0 {
+            report_flight(field, format!("debe ser positivo (es {})", value));
+        }
+    }
+    if scene.flight.drag < 0.0 {
+        report_flight("drag", format!("no puede ser negativo (es {})", scene.flight.drag));
+    }
+
+    let mut report_lighting = |field: &'static str, message: String| {
+        issues.push(ValidationIssue {
+            body: "[lighting]".to_string(),
+            field,
+            line: map.locate_section("[lighting]", field),
+            message,
+        });
+    };
+    for (field, color) in [("ambient_color", scene.lighting.ambient_color), ("background", scene.lighting.background)] {
+        if color.iter().any(|c| *c < 0.0) {
+            report_lighting(field, "el color no puede tener componentes negativas".to_string());
+        }
+    }
+    if scene.lighting.ambient_intensity < 0.0 {
+        report_lighting("ambient_intensity", format!("no puede ser negativa (es {})", scene.lighting.ambient_intensity));
+    }
+
+    let mut report_starfield = |field: &'static str, message: String| {
+        issues.push(ValidationIssue {
+            body: "[starfield]".to_string(),
+            field,
+            line: map.locate_section("[starfield]", field),
+            message,
+        });
+    };
+    let starfield = &scene.starfield;
+    if starfield.count > MAX_STARS {
+        report_starfield("count", format!("no puede pasar de {} (es {})", MAX_STARS, starfield.count));
+    }
+    if starfield.brightness_exponent <= 0.0 {
+        report_starfield("brightness_exponent", format!("debe ser positivo (es {})", starfield.brightness_exponent));
+    }
+    for (field, value) in [("min_brightness", starfield.min_brightness), ("twinkle", starfield.twinkle)] {
+        if !(0.0..=1.0).contains(&value) {
+            report_starfield(field, format!("debe estar entre 0 y 1 (es {})", value));
+        }
+    }
+    let [cool, hot] = starfield.temperature;
+    if cool <= 0.0 || hot < cool {
+        report_starfield("temperature", format!("tiene que ser [mínima, máxima] en kelvin, positivas (es [{}, {}])", cool, hot));
+    }
+
+    for prop in &scene.props {
+        let section = format!("[[prop]] '{}'", prop.name);
+        let mut report_prop = |field: &'static str, message: String| {
+            issues.push(ValidationIssue {
+                body: section.clone(),
+                field,
+                line: None,
+                message,
+            });
+        };
+        if prop.scale <= 0.0 {
+            report_prop("scale", format!("debe ser positiva (es {})", prop.scale));
+        }
+        if prop.speed < 0.0 {
+            report_prop("speed", format!("no puede ser negativa (es {})", prop.speed));
+        }
+        if let Some(parent) = &prop.parent {
+            if !scene.bodies.iter().any(|body| &body.name == parent) {
+                report_prop("parent", format!("no existe el cuerpo '{}'", parent));
+            }
+        }
+        if prop.orbit_period < 0.0 {
+            report_prop("orbit_period", format!("no puede ser negativo (es {})", prop.orbit_period));
+        }
+        if prop.dock_radius <= 0.0 {
+            report_prop("dock_radius", format!("debe ser positivo (es {})", prop.dock_radius));
+        }
+    }
+
+    for (index, anomaly) in scene.anomalies.iter().enumerate() {
+        let section = format!("[[anomaly]] '{}'", anomaly.name);
+        let mut report_anomaly = |field: &'static str, message: String| {
+            issues.push(ValidationIssue {
+                body: section.clone(),
+                field,
+                line: None,
+                message,
+            });
+        };
+        if anomaly.name.trim().is_empty() {
+            report_anomaly("name", "el nombre no puede estar vacío".to_string());
+        }
+        if scene.anomalies[..index].iter().any(|other| other.name == anomaly.name) {
+            report_anomaly("name", "nombre duplicado".to_string());
+        }
+        if anomaly.size <= 0.0 {
+            report_anomaly("size", format!("debe ser positivo (es {})", anomaly.size));
+        }
+        if anomaly.drift < 0.0 {
+            report_anomaly("drift", format!("no puede ser negativa (es {})", anomaly.drift));
+        }
+        if let Some(exit) = &anomaly.exit {
+            let target = scene.anomalies.iter().find(|other| &other.name == exit);
+            if anomaly.kind != AnomalyKind::Wormhole {
+                report_anomaly("exit", "solo los agujeros de gusano tienen salida".to_string());
+            } else if exit == &anomaly.name {
+                report_anomaly("exit", "un agujero de gusano no puede salir por sí mismo".to_string());
+            } else {
+                match target {
+                    None => report_anomaly("exit", format!("no existe la anomalía '{}'", exit)),
+                    Some(target) if target.kind != AnomalyKind::Wormhole => {
+                        report_anomaly("exit", format!("'{}' no es un agujero de gusano", exit))
+                    }
+                    // El par ya funciona en los dos sentidos
+                    Some(target) if target.exit.is_some() => {
+                        report_anomaly("exit", format!("'{}' ya tiene su propia salida", exit))
+                    }
+                    Some(_) => {}
+                }
+            }
+            if scene.anomalies[..index].iter().any(|other| other.exit.as_ref() == Some(exit)) {
+                report_anomaly("exit", format!("'{}' ya es la salida de otro agujero de gusano", exit));
+            }
+        }
+    }
+
+    for (index, spawn) in scene.spawns.iter().enumerate() {
+        let section = format!("[[spawn]] '{}'", spawn.name);
+        let mut report_spawn = |field: &'static str, message: String| {
+            issues.push(ValidationIssue {
+                body: section.clone(),
+                field,
+                line: None,
+                message,
+            });
+        };
+        if spawn.name.trim().is_empty() {
+            report_spawn("name", "el nombre no puede estar vacío".to_string());
+        }
+        if scene.spawns[..index].iter().any(|other| other.name == spawn.name) {
+            report_spawn("name", "nombre duplicado".to_string());
+        }
+        for (field, body) in [("near", &spawn.near), ("look_at", &spawn.look_at)] {
+            if let Some(body) = body {
+                if !scene.bodies.iter().any(|other| &other.name == body) {
+                    report_spawn(field, format!("no existe el cuerpo '{}'", body));
+                }
+            }
+        }
+        if spawn.camera_distance.is_some_and(|distance| distance <= 0.0) {
+            report_spawn("camera_distance", "debe ser positiva".to_string());
+        }
+        if spawn.camera_pitch.is_some_and(|pitch| !(MIN_PITCH..=MAX_PITCH).contains(&pitch)) {
+            report_spawn("camera_pitch", format!("debe estar entre {} y {} grados", MIN_PITCH, MAX_PITCH));
+        }
+    }
+
+    if let Some(race) = &scene.race {
+        let mut report_race = |field: &'static str, message: String| {
+            issues.push(ValidationIssue {
+                body: "[race]".to_string(),
+                field,
+                line: map.locate_section("[race]", field),
+                message,
+            });
+        };
+        if race.waypoints.len() < 2 {
+            report_race("waypoints", "el circuito necesita al menos 2 puntos (salida y meta)".to_string());
+        }
+        if race.radius <= 0.0 {
+            report_race("radius", format!("debe ser positivo (es {})", race.radius));
+        }
+    }
+
+    if let Some(course) = &scene.course {
+        let mut report_course = |field: &'static str, message: String| {
+            issues.push(ValidationIssue {
+                body: "[course]".to_string(),
+                field,
+                line: map.locate_section("[course]", field),
+                message,
+            });
+        };
+        if course.rings < 2 {
+            report_course("rings", format!("se necesitan al menos 2 anillos (hay {})", course.rings));
+        }
+        if course.laps == 0 {
+            report_course("laps", "debe haber al menos una vuelta".to_string());
+        }
+        if course.ring_radius <= 0.0 {
+            report_course("ring_radius", format!("debe ser positivo (es {})", course.ring_radius));
+        }
+        if scene.bodies.iter().filter(|b| b.orbit_radius > 0.0 && b.parent.is_none()).count() < 2 {
+            report_course("rings", "el circuito necesita al menos 2 planetas en órbita".to_string());
+        }
+    }
+
+    issues
+}
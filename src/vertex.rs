@@ -9,8 +9,10 @@ pub struct Vertex {
   pub color: Color,
   pub transformed_position: Vec4,
   pub transformed_normal: Vec3,
+  pub world_position: Vec3,
 }
 
+#[allow(dead_code)]
 impl Vertex {
   pub fn new(position: Vec3, normal: Vec3, tex_coords: Vec2) -> Self {
     Vertex {
@@ -20,6 +22,7 @@ impl Vertex {
       color: Color::black(),
       transformed_position: Vec4::new(position.x, position.y, position.z, 1.0),
       transformed_normal: normal,
+      world_position: position,
     }
   }
 
@@ -31,6 +34,7 @@ impl Vertex {
       color,
       transformed_position: Vec4::new(0.0, 0.0, 0.0, 1.0),
       transformed_normal: Vec3::new(0.0, 0.0, 0.0),
+      world_position: position,
     }
   }
 
@@ -49,6 +53,7 @@ impl Default for Vertex {
       color: Color::black(),
       transformed_position: Vec4::new(0.0, 0.0, 0.0, 1.0),
       transformed_normal: Vec3::new(0.0, 1.0, 0.0),
+      world_position: Vec3::new(0.0, 0.0, 0.0),
     }
   }
 }
\ No newline at end of file
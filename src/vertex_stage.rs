@@ -0,0 +1,66 @@
+// Etapa de vértices de `render_model`: el vertex shader se aplica a los
+// vértices del modelo repartidos entre los núcleos (rayon) y el resultado se
+// escribe en un buffer que se reutiliza. Los cuerpos guardan además su buffer
+// entre cuadros (`VertexCache`): si ni su malla ni sus matrices cambiaron
+// (el sol sin rotación con la cámara quieta, el cuadro congelado) se dibujan
+// con los vértices del cuadro anterior sin volver a transformarlos.
+
+use nalgebra_glm::Mat4;
+use rayon::prelude::*;
+
+use crate::shaders::VertexShader;
+use crate::vertex::Vertex;
+use crate::Uniforms;
+
+// Vértices mínimos por tarea: las mallas chicas (anillos, piezas de la nave)
+// se transforman en un solo hilo, donde repartir cuesta más de lo que ahorra
+const MIN_VERTICES_PER_TASK: usize = 1024;
+
+// Transforma `vertices` con las matrices de `uniforms` y deja el resultado en
+// `transformed`, reutilizando su memoria
+pub fn transform_vertices(vertices: &[Vertex], uniforms: &Uniforms, transformed: &mut Vec<Vertex>) {
+    let shader = VertexShader::new(uniforms);
+    vertices
+        .par_iter()
+        .with_min_len(MIN_VERTICES_PER_TASK)
+        .map(|vertex| shader.shade(vertex))
+        .collect_into_vec(transformed);
+}
+
+// Lo que decide si los vértices transformados siguen valiendo: la malla
+// (dirección y largo) y las tres matrices del vertex shader
+#[derive(PartialEq)]
+struct CacheKey {
+    mesh: (usize, usize),
+    model_matrix: Mat4,
+    view_matrix: Mat4,
+    projection_matrix: Mat4,
+}
+
+// Vértices transformados de un modelo, guardados entre cuadros
+#[derive(Default)]
+pub struct VertexCache {
+    key: Option<CacheKey>,
+    vertices: Vec<Vertex>,
+}
+
+impl VertexCache {
+    // Vértices de `vertices` transformados con `uniforms`, del cuadro anterior
+    // si la clave coincide. El rasterizado agrega al final los vértices de los
+    // triángulos recortados; se descartan antes de reutilizar el buffer.
+    pub fn transform(&mut self, vertices: &[Vertex], uniforms: &Uniforms) -> &mut Vec<Vertex> {
+        let key = CacheKey {
+            mesh: (vertices.as_ptr() as usize, vertices.len()),
+            model_matrix: uniforms.model_matrix,
+            view_matrix: uniforms.view_matrix,
+            projection_matrix: uniforms.projection_matrix,
+        };
+        if self.key.as_ref() == Some(&key) {
+            self.vertices.truncate(vertices.len());
+        } else {
+            transform_vertices(vertices, uniforms, &mut self.vertices);
+            self.key = Some(key);
+        }
+        &mut self.vertices
+    }
+}
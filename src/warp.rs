@@ -0,0 +1,103 @@
+// Viaje instantáneo entre cuerpos (teclas 1-9): la nave se desplaza con una
+// curva suave hasta un punto seguro junto al cuerpo elegido mientras el
+// campo de visión se abre y las estrellas se estiran en líneas.
+
+use nalgebra_glm::Vec3;
+
+use crate::framebuffer::Framebuffer;
+use crate::scene::Scene;
+
+// Duración del salto en unidades de tiempo de simulación
+const WARP_DURATION: f32 = 0.6;
+// Distancia extra a la superficie de colisión al llegar
+const ARRIVAL_MARGIN: f32 = 6.0;
+// Apertura máxima adicional del campo de visión durante el salto (grados)
+const MAX_FOV_BOOST: f32 = 35.0;
+
+pub struct Warp {
+    from: Vec3,
+    target: usize,
+    elapsed: f32,
+}
+
+impl Warp {
+    pub fn new(from: Vec3, target: usize) -> Self {
+        Warp { from, target, elapsed: 0.0 }
+    }
+
+    pub fn target(&self) -> usize {
+        self.target
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= WARP_DURATION
+    }
+
+    fn progress(&self) -> f32 {
+        (self.elapsed / WARP_DURATION).min(1.0)
+    }
+
+    // Intensidad del efecto: sube y baja a lo largo del salto
+    pub fn intensity(&self) -> f32 {
+        (self.progress() * std::f32::consts::PI).sin()
+    }
+
+    pub fn fov_boost(&self) -> f32 {
+        self.intensity() * MAX_FOV_BOOST
+    }
+
+    // Punto de llegada junto al cuerpo, del lado desde el que viene la nave.
+    // Se recalcula cada cuadro porque el cuerpo sigue en órbita.
+    pub fn destination(&self, scene: &Scene) -> Vec3 {
+        let body = &scene.bodies[self.target];
+        let center = scene.states[self.target].position;
+        let mut direction = self.from - center;
+        if direction.magnitude() < 1e-3 {
+            direction = Vec3::new(0.0, 0.0, 1.0);
+        }
+        let direction = (direction.normalize() + Vec3::new(0.0, 0.3, 0.0)).normalize();
+        // Afuera del margen que usa la detección de colisiones de la nave
+        center + direction * (body.collision_radius + body.collision_margin() + ARRIVAL_MARGIN)
+    }
+
+    // Avanza el salto y devuelve la nueva posición de la nave
+    pub fn update(&mut self, dt: f32, scene: &Scene) -> Vec3 {
+        self.elapsed += dt;
+        let t = self.progress();
+        let eased = t * t * (3.0 - 2.0 * t);
+        self.from.lerp(&self.destination(scene), eased)
+    }
+}
+
+// Líneas radiales desde el centro de la pantalla, más largas y brillantes
+// cuanto mayor es la intensidad del salto
+pub fn render_streaks(framebuffer: &mut Framebuffer, intensity: f32, time: f32) {
+    if intensity <= 0.01 {
+        return;
+    }
+    let center_x = framebuffer.width as f32 * 0.5;
+    let center_y = framebuffer.height as f32 * 0.5;
+    let max_radius = center_x.hypot(center_y);
+
+    for i in 0..120 {
+        let seed = i as f32 * 12.9898;
+        let angle = (seed.sin() * 43758.547).fract() * std::f32::consts::TAU;
+        // Cada línea avanza hacia afuera con el tiempo
+        let start = ((seed * 1.7).cos() * 43758.547).fract().abs();
+        let start = (start + time * 4.0).fract() * max_radius;
+        let length = intensity * 120.0;
+        let (sin, cos) = angle.sin_cos();
+
+        let steps = length as usize;
+        for step in 0..steps {
+            let radius = start + step as f32;
+            let x = center_x + cos * radius;
+            let y = center_y + sin * radius;
+            if x < 0.0 || y < 0.0 {
+                continue;
+            }
+            let fade = 1.0 - step as f32 / steps as f32;
+            framebuffer.point_blend(x as usize, y as usize, 0.0, 0xCCE6FF, intensity * fade * 0.8);
+        }
+    }
+}
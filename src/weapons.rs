@@ -0,0 +1,155 @@
+// Láser de la nave: cada disparo lanza un proyectil que avanza en línea recta
+// por el rumbo de la nave. En cada cuadro el tramo recorrido se prueba contra
+// los cuerpos (rayo contra esfera); al acertar, el proyectil desaparece y
+// deja un destello breve en el punto de impacto.
+
+use nalgebra_glm::{Mat4, Vec3, Vec4};
+
+use crate::framebuffer::Framebuffer;
+use crate::scene::Collider;
+
+// Unidades del mundo por unidad de tiempo de simulación
+const BOLT_SPEED: f32 = 120.0;
+// Alcance máximo antes de que el proyectil se apague
+const BOLT_RANGE: f32 = 150.0;
+// Largo del trazo dibujado detrás de la punta
+const BOLT_LENGTH: f32 = 1.5;
+// Tiempo mínimo entre disparos
+const FIRE_COOLDOWN: f32 = 0.12;
+// Rojo muy por encima de 1.0 para que el bloom lo haga brillar
+const BOLT_COLOR: Vec3 = Vec3::new(4.0, 0.6, 0.4);
+const IMPACT_COLOR: Vec3 = Vec3::new(3.0, 2.2, 1.2);
+const IMPACT_DURATION: f32 = 0.3;
+// Radio final del destello en unidades del mundo
+const IMPACT_RADIUS: f32 = 0.8;
+
+struct Bolt {
+    position: Vec3,
+    direction: Vec3,
+    traveled: f32,
+}
+
+struct Impact {
+    position: Vec3,
+    age: f32,
+}
+
+#[derive(Default)]
+pub struct Weapons {
+    bolts: Vec<Bolt>,
+    impacts: Vec<Impact>,
+    cooldown: f32,
+}
+
+impl Weapons {
+    // Dispara desde `origin` hacia `direction` si el arma ya se enfrió;
+    // devuelve si salió el disparo (para el sonido). `rate` es la cadencia
+    // relativa que da la energía de las armas (1 = normal).
+    pub fn fire(&mut self, origin: Vec3, direction: Vec3, rate: f32) -> bool {
+        if self.cooldown > 0.0 {
+            return false;
+        }
+        let Some(direction) = direction.try_normalize(1e-6) else {
+            return false;
+        };
+        self.bolts.push(Bolt { position: origin, direction, traveled: 0.0 });
+        self.cooldown = FIRE_COOLDOWN / rate;
+        true
+    }
+
+    // Avanza los proyectiles y devuelve los índices de los cuerpos alcanzados
+    pub fn update(&mut self, dt: f32, colliders: &[Collider]) -> Vec<usize> {
+        self.cooldown = (self.cooldown - dt).max(0.0);
+        self.impacts.retain_mut(|impact| {
+            impact.age += dt;
+            impact.age < IMPACT_DURATION
+        });
+
+        let mut hits = Vec::new();
+        let impacts = &mut self.impacts;
+        self.bolts.retain_mut(|bolt| {
+            let step = BOLT_SPEED * dt;
+            let next = bolt.position + bolt.direction * step;
+            // El primer cuerpo que cruza el tramo de este cuadro
+            let hit = colliders
+                .iter()
+                .enumerate()
+                .filter_map(|(index, collider)| collider.segment_hit(bolt.position, next).map(|t| (index, t)))
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+            if let Some((index, t)) = hit {
+                impacts.push(Impact { position: bolt.position + bolt.direction * step * t, age: 0.0 });
+                hits.push(index);
+                return false;
+            }
+            bolt.position = next;
+            bolt.traveled += step;
+            bolt.traveled < BOLT_RANGE
+        });
+        hits
+    }
+
+    pub fn render(&self, framebuffer: &mut Framebuffer, view_matrix: &Mat4, projection_matrix: &Mat4, viewport_matrix: &Mat4) {
+        // Posición en pantalla con la profundidad NDC del z-buffer
+        let to_screen = |point: Vec3| {
+            let clip = projection_matrix * view_matrix * Vec4::new(point.x, point.y, point.z, 1.0);
+            if clip.w <= 0.1 {
+                return None;
+            }
+            let ndc = clip / clip.w;
+            if ndc.z <= -1.0 || ndc.z >= 1.0 {
+                return None;
+            }
+            let screen = viewport_matrix * Vec4::new(ndc.x, ndc.y, ndc.z, 1.0);
+            Some(Vec3::new(screen.x, screen.y, ndc.z))
+        };
+
+        for bolt in &self.bolts {
+            // La cola no pasa del punto de disparo
+            let tail = bolt.position - bolt.direction * BOLT_LENGTH.min(bolt.traveled);
+            if let (Some(from), Some(to)) = (to_screen(tail), to_screen(bolt.position)) {
+                draw_glow_line(framebuffer, from, to);
+            }
+        }
+
+        for impact in &self.impacts {
+            let Some(center) = to_screen(impact.position) else {
+                continue;
+            };
+            let life = impact.age / IMPACT_DURATION;
+            // Distancia a la cámara para pasar el radio del mundo a píxeles
+            let view = view_matrix * Vec4::new(impact.position.x, impact.position.y, impact.position.z, 1.0);
+            let radius = IMPACT_RADIUS * (0.3 + 0.7 * life) * projection_matrix[(1, 1)] / -view.z * -viewport_matrix[(1, 1)];
+            let radius = radius.clamp(1.0, 80.0);
+            let alpha = 1.0 - life;
+            let min_x = (center.x - radius).max(0.0) as usize;
+            let max_x = (center.x + radius).min(framebuffer.width as f32 - 1.0).max(0.0) as usize;
+            let min_y = (center.y - radius).max(0.0) as usize;
+            let max_y = (center.y + radius).min(framebuffer.height as f32 - 1.0).max(0.0) as usize;
+            for y in min_y..=max_y {
+                for x in min_x..=max_x {
+                    let distance = ((x as f32 - center.x).powi(2) + (y as f32 - center.y).powi(2)).sqrt() / radius;
+                    if distance < 1.0 {
+                        // Núcleo blanco que se enfría hacia el borde
+                        let falloff = 1.0 - distance * distance;
+                        framebuffer.point_hdr(x, y, center.z, IMPACT_COLOR * falloff, alpha * falloff);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Trazo de dos píxeles de ancho en color HDR, con la profundidad interpolada
+fn draw_glow_line(framebuffer: &mut Framebuffer, from: Vec3, to: Vec3) {
+    let steps = (to.x - from.x).abs().max((to.y - from.y).abs()).ceil().min(2048.0) as usize;
+    for step in 0..=steps {
+        let t = if steps == 0 { 0.0 } else { step as f32 / steps as f32 };
+        let point = from.lerp(&to, t);
+        if point.x < 0.0 || point.y < 0.0 {
+            continue;
+        }
+        let (x, y) = (point.x as usize, point.y as usize);
+        framebuffer.point_hdr(x, y, point.z, BOLT_COLOR, 1.0);
+        framebuffer.point_hdr(x + 1, y, point.z, BOLT_COLOR * 0.5, 0.6);
+    }
+}
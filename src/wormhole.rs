@@ -0,0 +1,120 @@
+// Travesía de un agujero de gusano: al entrar en la garganta de uno que
+// tiene par la nave cae hacia el centro, reaparece en la boca del otro y sale
+// por delante de él con el mismo rumbo. Mientras tanto la imagen se retuerce
+// en espiral alrededor del centro de la pantalla y se tiñe de violeta, con el
+// máximo justo en el salto.
+
+use nalgebra_glm::Vec3;
+
+use crate::anomaly::{wormhole_exit, AnomalyKind};
+use crate::framebuffer::{pack_color, unpack_color, Framebuffer};
+use crate::scene::Scene;
+
+// Radio de la garganta en fracción del radio del agujero: al entrar ahí la
+// nave lo atraviesa
+const THROAT_FRACTION: f32 = 0.3;
+// Distancia a la que queda la nave de la boca de salida, en radios del
+// agujero; afuera de la garganta, para no volver a entrar
+const EXIT_CLEARANCE: f32 = 1.5;
+// Duración de la travesía en unidades de tiempo de simulación; el salto
+// ocurre a la mitad
+const TRANSIT_DURATION: f32 = 0.6;
+// Vueltas de la espiral en el centro de la pantalla con el efecto al máximo
+const SWIRL_TURNS: f32 = 1.5;
+const TINT: Vec3 = Vec3::new(0.75, 0.4, 1.0);
+
+pub struct Transit {
+    entry: usize,
+    exit: usize,
+    from: Vec3,
+    // Rumbo con el que entró la nave, el mismo con el que sale
+    direction: Vec3,
+    elapsed: f32,
+}
+
+impl Transit {
+    // Empieza la travesía si la nave está en la garganta de un agujero de
+    // gusano con par; `forward` es su rumbo
+    pub fn check(scene: &Scene, ship_position: Vec3, forward: Vec3) -> Option<Self> {
+        let (entry, exit) = scene
+            .anomalies
+            .iter()
+            .zip(&scene.anomaly_states)
+            .enumerate()
+            .filter(|(_, (anomaly, state))| {
+                anomaly.kind == AnomalyKind::Wormhole
+                    && (state.position - ship_position).magnitude() < anomaly.size * THROAT_FRACTION
+            })
+            .find_map(|(index, _)| Some((index, wormhole_exit(&scene.anomalies, index)?)))?;
+        Some(Transit { entry, exit, from: ship_position, direction: forward, elapsed: 0.0 })
+    }
+
+    pub fn exit(&self) -> usize {
+        self.exit
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= TRANSIT_DURATION
+    }
+
+    fn progress(&self) -> f32 {
+        (self.elapsed / TRANSIT_DURATION).min(1.0)
+    }
+
+    // Intensidad del efecto: sube hasta el salto y vuelve a bajar
+    pub fn intensity(&self) -> f32 {
+        (self.progress() * std::f32::consts::PI).sin()
+    }
+
+    // Avanza la travesía y devuelve la nueva posición de la nave y si en
+    // este cuadro pasó de una boca a la otra
+    pub fn update(&mut self, dt: f32, scene: &Scene) -> (Vec3, bool) {
+        let before = self.progress();
+        self.elapsed += dt;
+        let t = self.progress();
+        let jumped = before < 0.5 && t >= 0.5;
+        // Cada mitad con su propia curva suave: hacia el centro de la boca de
+        // entrada y desde el centro de la de salida hacia afuera
+        let half = if t < 0.5 { t * 2.0 } else { t * 2.0 - 1.0 };
+        let eased = half * half * (3.0 - 2.0 * half);
+        let position = if t < 0.5 {
+            self.from.lerp(&scene.anomaly_states[self.entry].position, eased)
+        } else {
+            let center = scene.anomaly_states[self.exit].position;
+            let outside = center + self.direction * scene.anomalies[self.exit].size * EXIT_CLEARANCE;
+            center.lerp(&outside, eased)
+        };
+        (position, jumped)
+    }
+}
+
+// Retuerce la imagen en espiral alrededor del centro de la pantalla, más
+// cerca del centro y cuanto mayor es la intensidad, y la tiñe de violeta
+pub fn render_swirl(framebuffer: &mut Framebuffer, intensity: f32, time: f32) {
+    if intensity <= 0.01 {
+        return;
+    }
+    let source = framebuffer.buffer.clone();
+    let (width, height) = (framebuffer.width, framebuffer.height);
+    let index_of = framebuffer.indexer();
+    let center_x = width as f32 * 0.5;
+    let center_y = height as f32 * 0.5;
+    let max_radius = center_x.hypot(center_y);
+    let tint = intensity * intensity * 0.6;
+
+    for y in 0..height {
+        for x in 0..width {
+            let dx = x as f32 + 0.5 - center_x;
+            let dy = y as f32 + 0.5 - center_y;
+            let falloff = (1.0 - dx.hypot(dy) / max_radius).max(0.0);
+            // Giro y acercamiento hacia el centro, que además rota con el tiempo
+            let angle = (intensity * SWIRL_TURNS * falloff * falloff + time * intensity * 0.5) * std::f32::consts::TAU;
+            let zoom = 1.0 - intensity * 0.3 * falloff;
+            let (sin, cos) = angle.sin_cos();
+            let sample_x = (center_x + (dx * cos - dy * sin) * zoom).clamp(0.0, width as f32 - 1.0) as usize;
+            let sample_y = (center_y + (dx * sin + dy * cos) * zoom).clamp(0.0, height as f32 - 1.0) as usize;
+            let color = unpack_color(source[index_of(sample_x, sample_y)]);
+            framebuffer.buffer[index_of(x, y)] = pack_color(color.lerp(&color.component_mul(&TINT), tint) + TINT * tint * falloff * 0.3);
+        }
+    }
+}